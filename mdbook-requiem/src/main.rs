@@ -0,0 +1,59 @@
+//! mdBook preprocessor for requirements managed by Requiem.
+//!
+//! Adds a "Traces to / Traced by" panel to every requirement chapter, links
+//! HRID mentions to the requirements' chapters, and warns about suspect
+//! links. Only the book mdBook renders is changed, never the source files.
+
+mod mentions;
+mod preprocessor;
+
+use std::io;
+
+use anyhow::Context;
+use clap::Parser;
+use mdbook::preprocess::{CmdPreprocessor, Preprocessor};
+use preprocessor::Requiem;
+
+#[derive(Debug, Parser)]
+#[command(version, about)]
+struct Cli {
+    #[command(subcommand)]
+    command: Option<Command>,
+}
+
+#[derive(Debug, clap::Subcommand)]
+enum Command {
+    /// Check whether a renderer is supported (used by mdBook)
+    Supports {
+        /// The name of the renderer
+        renderer: String,
+    },
+}
+
+fn main() -> anyhow::Result<()> {
+    let preprocessor = Requiem;
+    match Cli::parse().command {
+        Some(Command::Supports { renderer }) => {
+            if !preprocessor.supports_renderer(&renderer) {
+                std::process::exit(1);
+            }
+            Ok(())
+        }
+        None => {
+            let (ctx, book) = CmdPreprocessor::parse_input(io::stdin())
+                .context("failed to read the book from mdBook")?;
+            if ctx.mdbook_version != mdbook::MDBOOK_VERSION {
+                eprintln!(
+                    "warning: {} was built against mdBook {}, but is being called from mdBook {}",
+                    preprocessor.name(),
+                    mdbook::MDBOOK_VERSION,
+                    ctx.mdbook_version
+                );
+            }
+            let book = preprocessor.run(&ctx, book)?;
+            serde_json::to_writer(io::stdout(), &book)
+                .context("failed to write the book back to mdBook")?;
+            Ok(())
+        }
+    }
+}
@@ -0,0 +1,152 @@
+//! Turning HRID mentions in chapter text into links to the requirements'
+//! chapters.
+//!
+//! Only prose is touched: mentions in code, headings, and the text of
+//! existing links or images are left as they are.
+
+use std::{
+    collections::BTreeMap,
+    fmt::Write,
+    path::{Path, PathBuf},
+};
+
+use pulldown_cmark::{Event, Options, Parser, Tag, TagEnd};
+
+/// Links every mention of a requirement in `markdown` to that requirement's
+/// chapter.
+///
+/// `targets` maps displayed HRIDs to chapter paths relative to the book's
+/// source directory; `chapter` is the path of the chapter being rewritten.
+/// Mentions of `own`, the chapter's own HRID, are not linked.
+pub fn link(
+    markdown: &str,
+    chapter: &Path,
+    targets: &BTreeMap<String, PathBuf>,
+    own: Option<&str>,
+) -> String {
+    let mut output = String::with_capacity(markdown.len());
+    let mut copied = 0;
+    let mut skip = 0usize;
+
+    for (event, range) in Parser::new_ext(markdown, options()).into_offset_iter() {
+        match event {
+            Event::Start(
+                Tag::Link { .. } | Tag::Image { .. } | Tag::Heading { .. } | Tag::CodeBlock(_),
+            ) => skip += 1,
+            Event::End(TagEnd::Link | TagEnd::Image | TagEnd::Heading(_) | TagEnd::CodeBlock) => {
+                skip = skip.saturating_sub(1);
+            }
+            // Text with escapes or entities doesn't match its source, so the
+            // offsets of its words can't be trusted.
+            Event::Text(text) if skip == 0 && markdown[range.clone()] == *text => {
+                for (offset, word) in words(&text) {
+                    if Some(word) == own {
+                        continue;
+                    }
+                    let Some(target) = targets.get(word) else {
+                        continue;
+                    };
+                    let start = range.start + offset;
+                    output.push_str(&markdown[copied..start]);
+                    let _ = write!(output, "[{word}]({})", relative(chapter, target));
+                    copied = start + word.len();
+                }
+            }
+            _ => {}
+        }
+    }
+
+    output.push_str(&markdown[copied..]);
+    output
+}
+
+/// The path of `to` relative to the directory containing `from`, as used in
+/// a Markdown link.
+pub fn relative(from: &Path, to: &Path) -> String {
+    let from: Vec<_> = from
+        .parent()
+        .map(|directory| directory.components().collect())
+        .unwrap_or_default();
+    let to: Vec<_> = to.components().collect();
+    let common = from.iter().zip(&to).take_while(|(a, b)| a == b).count();
+
+    let mut parts = vec!["..".to_string(); from.len() - common];
+    parts.extend(
+        to[common..]
+            .iter()
+            .map(|component| component.as_os_str().to_string_lossy().replace(' ', "%20")),
+    );
+    parts.join("/")
+}
+
+/// The Markdown extensions mdBook enables, so chapters parse the same way
+/// here as when they are rendered.
+fn options() -> Options {
+    Options::ENABLE_TABLES
+        | Options::ENABLE_FOOTNOTES
+        | Options::ENABLE_STRIKETHROUGH
+        | Options::ENABLE_TASKLISTS
+        | Options::ENABLE_HEADING_ATTRIBUTES
+}
+
+/// The words of `text` that could be HRIDs, with their byte offsets.
+fn words(text: &str) -> Vec<(usize, &str)> {
+    let mut words = Vec::new();
+    let mut start = None;
+    for (index, c) in text.char_indices().chain([(text.len(), ' ')]) {
+        let in_word = c.is_ascii_alphanumeric() || c == '-' || c == '_';
+        match (start, in_word) {
+            (None, true) => start = Some(index),
+            (Some(begin), false) => {
+                words.push((begin, &text[begin..index]));
+                start = None;
+            }
+            _ => {}
+        }
+    }
+    words
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn links_mentions_in_prose_only() {
+        let targets = BTreeMap::from([
+            ("USR-001".to_string(), PathBuf::from("USR/001.md")),
+            ("SYS-002".to_string(), PathBuf::from("SYS/002.md")),
+        ]);
+        let markdown = "\
+# SYS-002 Sessions
+
+Refines USR-001, unlike USR-0010 or XUSR-001. See SYS-002.
+
+Already [USR-001](elsewhere.md) and `USR-001`.
+
+```text
+USR-001
+```
+";
+        let linked = link(markdown, Path::new("SYS/002.md"), &targets, Some("SYS-002"));
+        assert_eq!(
+            linked,
+            markdown.replacen("Refines USR-001", "Refines [USR-001](../USR/001.md)", 1)
+        );
+    }
+
+    #[test]
+    fn relative_paths_climb_out_of_the_chapter_directory() {
+        assert_eq!(
+            relative(Path::new("USR-001.md"), Path::new("USR-002.md")),
+            "USR-002.md"
+        );
+        assert_eq!(
+            relative(
+                Path::new("reqs/CORE/USR/001.md"),
+                Path::new("reqs/SYS/my req.md")
+            ),
+            "../../SYS/my%20req.md"
+        );
+    }
+}
@@ -0,0 +1,369 @@
+//! The `requiem` preprocessor.
+//!
+//! Each chapter whose source file is a requirement gets a "Traces to /
+//! Traced by" panel listing its parents and children, and a warning at the
+//! top if any of its parent links are suspect. In every chapter, mentions
+//! of requirements that are part of the book are linked to their chapters.
+
+use std::{
+    collections::{BTreeMap, HashMap, HashSet},
+    fmt::Write,
+    path::{Component, Path, PathBuf},
+};
+
+use anyhow::Context;
+use mdbook::{
+    book::Book,
+    preprocess::{Preprocessor, PreprocessorContext},
+    BookItem,
+};
+use requiem_core::{Directory, Hrid, RequirementView};
+use uuid::Uuid;
+
+use crate::mentions;
+
+/// The preprocessor, configured by the `[preprocessor.requiem]` table of
+/// `book.toml`.
+///
+/// The table's `root` key is the requirements root, relative to the book's
+/// root directory. It defaults to the book's source directory.
+pub struct Requiem;
+
+impl Preprocessor for Requiem {
+    fn name(&self) -> &'static str {
+        "requiem"
+    }
+
+    fn run(&self, ctx: &PreprocessorContext, mut book: Book) -> mdbook::errors::Result<Book> {
+        let src = ctx.root.join(&ctx.config.book.src);
+        let root = ctx
+            .config
+            .get_preprocessor(self.name())
+            .and_then(|table| table.get("root"))
+            .and_then(|root| root.as_str())
+            .map_or_else(|| src.clone(), |root| ctx.root.join(root));
+
+        let root = root
+            .canonicalize()
+            .with_context(|| format!("requirements root {} not found", root.display()))?;
+        let src = src
+            .canonicalize()
+            .with_context(|| format!("book source directory {} not found", src.display()))?;
+        let directory = Directory::new(root.clone())
+            .with_context(|| format!("failed to load requirements from {}", root.display()))?;
+
+        let chapters: HashSet<PathBuf> = book
+            .iter()
+            .filter_map(|item| match item {
+                BookItem::Chapter(chapter) => chapter.source_path.as_deref().map(normalize),
+                _ => None,
+            })
+            .collect();
+        let trace = Trace::new(&directory, &src, &chapters);
+
+        book.for_each_mut(|item| {
+            if let BookItem::Chapter(chapter) = item
+                && let Some(path) = &chapter.source_path
+            {
+                chapter.content = trace.render(&normalize(path), &chapter.content);
+            }
+        });
+        Ok(book)
+    }
+
+    fn supports_renderer(&self, _renderer: &str) -> bool {
+        // The additions are plain Markdown, which every renderer understands.
+        true
+    }
+}
+
+/// The requirements that are chapters of the book, and the links between
+/// them.
+struct Trace<'a> {
+    directory: &'a Directory,
+    digits: usize,
+    /// Requirement chapters by displayed HRID, relative to the source
+    /// directory.
+    chapters: BTreeMap<String, PathBuf>,
+    /// The requirement each requirement chapter holds.
+    requirements: HashMap<PathBuf, Hrid>,
+    /// Suspect links, as (child, parent) pairs.
+    suspect: HashSet<(Uuid, Uuid)>,
+}
+
+/// One end of a link, as shown in a panel.
+struct End {
+    hrid: String,
+    title: Option<String>,
+    link_type: Option<String>,
+    suspect: bool,
+}
+
+impl<'a> Trace<'a> {
+    fn new(directory: &'a Directory, src: &Path, chapters: &HashSet<PathBuf>) -> Self {
+        let digits = directory.config().digits();
+        let mut trace = Self {
+            directory,
+            digits,
+            chapters: BTreeMap::new(),
+            requirements: HashMap::new(),
+            suspect: directory
+                .suspect_links()
+                .into_iter()
+                .map(|link| (link.child_uuid, link.parent_uuid))
+                .collect(),
+        };
+        for requirement in directory.requirements() {
+            let Some(path) = directory
+                .path_for(requirement.hrid)
+                .and_then(|path| path.strip_prefix(src).ok())
+                .map(normalize)
+                .filter(|path| chapters.contains(path))
+            else {
+                continue;
+            };
+            trace
+                .chapters
+                .insert(requirement.hrid.display(digits).to_string(), path.clone());
+            trace.requirements.insert(path, requirement.hrid.clone());
+        }
+        trace
+    }
+
+    /// The content of the chapter at `path` with the trace navigation added.
+    fn render(&self, path: &Path, content: &str) -> String {
+        let requirement = self
+            .requirements
+            .get(path)
+            .and_then(|hrid| self.directory.find_by_hrid(hrid));
+        let own = requirement
+            .as_ref()
+            .map(|requirement| requirement.hrid.display(self.digits).to_string());
+
+        let (frontmatter, body) = split_frontmatter(content);
+        let body = mentions::link(body, path, &self.chapters, own.as_deref());
+        let Some(requirement) = requirement else {
+            return format!("{frontmatter}{body}");
+        };
+
+        let parents = self.parents(&requirement);
+        let children = self.children(&requirement);
+        let mut output = frontmatter.to_string();
+
+        let suspect: Vec<&End> = parents.iter().filter(|link| link.suspect).collect();
+        if suspect.is_empty() {
+            output.push_str(body.trim_end());
+        } else {
+            // The warning goes under the title heading, if the body starts
+            // with one, so it reads as part of the page header.
+            let heading = body.trim_start_matches('\n');
+            let split = if heading.starts_with("# ") {
+                body.len() - heading.len() + heading.find('\n').unwrap_or(heading.len())
+            } else {
+                0
+            };
+            let (head, rest) = body.split_at(split);
+            if !head.is_empty() {
+                output.push_str(head);
+                output.push_str("\n\n");
+            }
+            output.push_str(&self.warning(path, own.as_deref().unwrap_or_default(), &suspect));
+            let rest = rest.trim();
+            if !rest.is_empty() {
+                output.push_str("\n\n");
+                output.push_str(rest);
+            }
+        }
+
+        if !parents.is_empty() || !children.is_empty() {
+            output.push_str("\n\n<div class=\"requiem-trace\">\n\n");
+            for (label, links) in [("Traces to", &parents), ("Traced by", &children)] {
+                if links.is_empty() {
+                    continue;
+                }
+                let _ = writeln!(output, "**{label}**\n");
+                for link in links {
+                    let _ = writeln!(output, "- {}", self.item(path, link));
+                }
+                output.push('\n');
+            }
+            output.push_str("</div>");
+        }
+        output.push('\n');
+        output
+    }
+
+    fn parents(&self, requirement: &RequirementView) -> Vec<End> {
+        let mut parents: Vec<End> = requirement
+            .parents
+            .iter()
+            .map(|(uuid, parent)| {
+                let found = self.directory.find_by_uuid(*uuid);
+                End {
+                    hrid: found
+                        .as_ref()
+                        .map_or(&parent.hrid, |found| found.hrid)
+                        .display(self.digits)
+                        .to_string(),
+                    title: found.map(|found| found.title.to_string()),
+                    link_type: parent.link_type.clone(),
+                    suspect: self.suspect.contains(&(*requirement.uuid, *uuid)),
+                }
+            })
+            .collect();
+        parents.sort_by(|a, b| a.hrid.cmp(&b.hrid));
+        parents
+    }
+
+    fn children(&self, requirement: &RequirementView) -> Vec<End> {
+        let mut children: Vec<End> = requirement
+            .children
+            .iter()
+            .filter_map(|uuid| self.directory.find_by_uuid(*uuid))
+            .map(|child| End {
+                hrid: child.hrid.display(self.digits).to_string(),
+                title: Some(child.title.to_string()),
+                link_type: child
+                    .parents
+                    .iter()
+                    .find(|(parent, _)| parent == requirement.uuid)
+                    .and_then(|(_, parent)| parent.link_type.clone()),
+                suspect: self.suspect.contains(&(*child.uuid, *requirement.uuid)),
+            })
+            .collect();
+        children.sort_by(|a, b| a.hrid.cmp(&b.hrid));
+        children
+    }
+
+    /// A requirement's HRID, linked to its chapter if it has one.
+    fn reference(&self, path: &Path, hrid: &str) -> String {
+        self.chapters.get(hrid).map_or_else(
+            || hrid.to_string(),
+            |target| format!("[{hrid}]({})", mentions::relative(path, target)),
+        )
+    }
+
+    /// A panel list item for one end of a link.
+    fn item(&self, path: &Path, link: &End) -> String {
+        let mut item = self.reference(path, &link.hrid);
+        match &link.title {
+            Some(title) if !title.is_empty() => {
+                let _ = write!(item, " {title}");
+            }
+            Some(_) => {}
+            None => item.push_str(" *(not found)*"),
+        }
+        if let Some(link_type) = &link.link_type {
+            let _ = write!(item, " — *{link_type}*");
+        }
+        if link.suspect {
+            item.push_str(" ⚠️ **suspect**");
+        }
+        item
+    }
+
+    /// The warning shown on a requirement with suspect parent links.
+    fn warning(&self, path: &Path, own: &str, suspect: &[&End]) -> String {
+        let mut warning = String::from(
+            "> ⚠️ **Suspect links:** these parents have changed since this requirement was \
+             last reviewed against them:\n>\n",
+        );
+        for link in suspect {
+            let _ = writeln!(warning, "> - {}", self.reference(path, &link.hrid));
+        }
+        let _ = write!(
+            warning,
+            ">\n> Check that this requirement still holds, then accept each link with \
+             `req review --accept {own} <parent>`."
+        );
+        warning
+    }
+}
+
+/// Splits a chapter's YAML frontmatter, if it still has any, from its body.
+///
+/// The frontmatter is kept as it is, so this preprocessor can run before or
+/// after the one that strips it.
+fn split_frontmatter(content: &str) -> (&str, &str) {
+    let Some(rest) = content.strip_prefix("---\n") else {
+        return ("", content);
+    };
+    rest.find("\n---\n").map_or(("", content), |end| {
+        content.split_at("---\n".len() + end + "\n---\n".len())
+    })
+}
+
+/// A path with its `.` components removed, so that `./USR-001.md` and
+/// `USR-001.md` compare equal.
+fn normalize(path: &Path) -> PathBuf {
+    path.components()
+        .filter(|component| *component != Component::CurDir)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn renders_trace_panels_and_suspect_warnings() {
+        let root = tempfile::tempdir().unwrap();
+        let mut directory = Directory::new(root.path().to_path_buf()).unwrap();
+        let user = directory
+            .add_requirement("USR", "# Log in\n\nUsers shall log in.".to_string())
+            .unwrap();
+        let system = directory
+            .add_requirement("SYS", "# Sessions\n\nRefines USR-001.".to_string())
+            .unwrap();
+        let test = directory
+            .add_requirement("TST", "# Session test".to_string())
+            .unwrap();
+        directory
+            .link_requirement(system.hrid(), user.hrid())
+            .unwrap();
+        directory
+            .link_requirement(test.hrid(), system.hrid())
+            .unwrap();
+        directory
+            .update_requirement(
+                user.hrid(),
+                None,
+                Some("Users shall log in with a password.".to_string()),
+                None,
+            )
+            .unwrap();
+
+        // The test requirement isn't in the book, so it isn't linked.
+        let src = root.path().canonicalize().unwrap();
+        let chapters = HashSet::from([PathBuf::from("USR-001.md"), PathBuf::from("SYS-001.md")]);
+        let trace = Trace::new(&directory, &src, &chapters);
+
+        let page = trace.render(
+            Path::new("SYS-001.md"),
+            "---\nuuid: x\n---\n# SYS-001 Sessions\n\nRefines USR-001.\n",
+        );
+        assert_eq!(
+            page,
+            "---\nuuid: x\n---\n# SYS-001 Sessions\n\n\
+             > ⚠️ **Suspect links:** these parents have changed since this requirement was \
+             last reviewed against them:\n>\n\
+             > - [USR-001](USR-001.md)\n>\n\
+             > Check that this requirement still holds, then accept each link with \
+             `req review --accept SYS-001 <parent>`.\n\n\
+             Refines [USR-001](USR-001.md).\n\n\
+             <div class=\"requiem-trace\">\n\n\
+             **Traces to**\n\n\
+             - [USR-001](USR-001.md) Log in ⚠️ **suspect**\n\n\
+             **Traced by**\n\n\
+             - TST-001 Session test\n\n\
+             </div>\n"
+        );
+
+        let page = trace.render(Path::new("USR-001.md"), "# USR-001 Log in\n");
+        assert!(page.starts_with("# USR-001 Log in\n\n<div"));
+        assert!(page.contains("- [SYS-001](SYS-001.md) Sessions ⚠️ **suspect**\n"));
+
+        let page = trace.render(Path::new("notes.md"), "See USR-001.");
+        assert_eq!(page, "See [USR-001](USR-001.md).");
+    }
+}
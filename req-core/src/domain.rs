@@ -4,16 +4,29 @@
 //! human-readable identifiers (HRIDs), and configuration.
 
 pub mod requirement;
-pub use requirement::Requirement;
+pub use requirement::{Origin, Requirement, TestMetadata, TestOutcome, TestResult};
+
+pub mod attribute;
+pub use attribute::{Attribute, AttributeError, AttributeType, AttributeValue};
+
+pub mod baseline;
+pub use baseline::{Baseline, BaselineDiff};
 
 pub mod config;
-pub use config::{Config, KindMetadata};
+pub use config::{Config, KindMetadata, Lifecycle, LinkType, TestsConfig};
+
+pub mod fingerprint;
+pub use fingerprint::{FingerprintScheme, Normalization};
 
 pub mod hrid;
 pub use hrid::{Error as HridError, FormattedHrid, Hrid};
 
 pub mod tree;
-pub use tree::{AcceptLinkError, LinkRequirementError, SuspectLink, Tree, TreeInsertError};
+pub use tree::{
+    AcceptLinkError, CoverageReport, ImpactedRequirement, KindCoverage, LinkRequirementError,
+    LinkRuleError, LinkRuleViolation, StatusConflict, SuspectLink, Tree, TreeInsertError,
+    Verification,
+};
 
 pub mod requirement_view;
 pub use requirement_view::RequirementView;
@@ -0,0 +1,218 @@
+//! Custom requirement attributes: typed values stored in frontmatter and the
+//! schema in `.req/config.toml` that declares them.
+
+use std::fmt;
+
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+
+/// The value of a custom attribute as stored in frontmatter.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum AttributeValue {
+    /// A whole number, e.g. a risk score.
+    Integer(i64),
+    /// Any other value: free text, an enum member, or an ISO 8601 date.
+    Text(String),
+}
+
+impl fmt::Display for AttributeValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Integer(value) => write!(f, "{value}"),
+            Self::Text(value) => f.write_str(value),
+        }
+    }
+}
+
+/// The value type of a declared attribute.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AttributeType {
+    /// Free text.
+    String,
+    /// A whole number.
+    Integer,
+    /// A calendar date written as `YYYY-MM-DD`.
+    Date,
+    /// One of a fixed list of values.
+    Enum,
+}
+
+impl fmt::Display for AttributeType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::String => "string",
+            Self::Integer => "integer",
+            Self::Date => "date",
+            Self::Enum => "enum",
+        })
+    }
+}
+
+/// The declaration of a custom attribute.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Attribute {
+    /// The type of value the attribute holds.
+    #[serde(rename = "type")]
+    pub value_type: AttributeType,
+    /// The permitted values of an [`AttributeType::Enum`] attribute.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub values: Vec<String>,
+    /// The kinds the attribute applies to.
+    ///
+    /// If this is empty, the attribute applies to every kind.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub kinds: Vec<String>,
+    /// Whether requirements of the applicable kinds must set the attribute.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub required: bool,
+    /// Human-readable description of the attribute.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+}
+
+/// Error type for attribute values that do not match their declaration.
+#[derive(Debug, Clone, thiserror::Error, PartialEq, Eq)]
+pub enum AttributeError {
+    /// The value is not a whole number.
+    #[error("'{0}' is not an integer")]
+    NotInteger(String),
+
+    /// The value is not a `YYYY-MM-DD` date.
+    #[error("'{0}' is not a date (expected YYYY-MM-DD)")]
+    NotDate(String),
+
+    /// The value is not one of the enum's permitted values.
+    #[error("'{value}' is not one of: {allowed}")]
+    NotAllowed {
+        /// The rejected value.
+        value: String,
+        /// The permitted values.
+        allowed: String,
+    },
+}
+
+impl Attribute {
+    /// Checks whether the attribute applies to requirements of a kind.
+    #[must_use]
+    pub fn applies_to(&self, kind: &str) -> bool {
+        self.kinds.is_empty() || self.kinds.iter().any(|k| k.eq_ignore_ascii_case(kind))
+    }
+
+    /// Parses a raw value (e.g. from the command line) into a typed value.
+    ///
+    /// Surrounding whitespace is ignored.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the value does not match the attribute's type.
+    pub fn parse(&self, raw: &str) -> Result<AttributeValue, AttributeError> {
+        let raw = raw.trim();
+        let value = match self.value_type {
+            AttributeType::Integer => AttributeValue::Integer(
+                raw.parse()
+                    .map_err(|_| AttributeError::NotInteger(raw.to_string()))?,
+            ),
+            AttributeType::String | AttributeType::Date | AttributeType::Enum => {
+                AttributeValue::Text(raw.to_string())
+            }
+        };
+        self.check(&value)?;
+        Ok(value)
+    }
+
+    /// Checks a stored value against the attribute's type.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the value does not match the attribute's type.
+    pub fn check(&self, value: &AttributeValue) -> Result<(), AttributeError> {
+        match (self.value_type, value) {
+            (AttributeType::String, _) | (AttributeType::Integer, AttributeValue::Integer(_)) => {
+                Ok(())
+            }
+            (AttributeType::Integer, AttributeValue::Text(text)) => {
+                Err(AttributeError::NotInteger(text.clone()))
+            }
+            (AttributeType::Date, value) => {
+                let text = value.to_string();
+                NaiveDate::parse_from_str(&text, "%Y-%m-%d")
+                    .map(|_| ())
+                    .map_err(|_| AttributeError::NotDate(text))
+            }
+            (AttributeType::Enum, value) => {
+                let text = value.to_string();
+                if self.values.contains(&text) {
+                    Ok(())
+                } else {
+                    Err(AttributeError::NotAllowed {
+                        value: text,
+                        allowed: self.values.join(", "),
+                    })
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn attribute(value_type: AttributeType) -> Attribute {
+        Attribute {
+            value_type,
+            values: Vec::new(),
+            kinds: Vec::new(),
+            required: false,
+            description: None,
+        }
+    }
+
+    #[test]
+    fn parse_checks_value_type() {
+        assert_eq!(
+            attribute(AttributeType::Integer).parse(" 42 "),
+            Ok(AttributeValue::Integer(42))
+        );
+        assert_eq!(
+            attribute(AttributeType::Integer).parse("high"),
+            Err(AttributeError::NotInteger("high".to_string()))
+        );
+        assert_eq!(
+            attribute(AttributeType::Date).parse("2025-02-30"),
+            Err(AttributeError::NotDate("2025-02-30".to_string()))
+        );
+        assert!(attribute(AttributeType::Date).parse("2025-02-28").is_ok());
+
+        let priority = Attribute {
+            values: vec!["low".to_string(), "high".to_string()],
+            ..attribute(AttributeType::Enum)
+        };
+        assert_eq!(
+            priority.parse("high"),
+            Ok(AttributeValue::Text("high".to_string()))
+        );
+        assert_eq!(
+            priority.parse("urgent"),
+            Err(AttributeError::NotAllowed {
+                value: "urgent".to_string(),
+                allowed: "low, high".to_string(),
+            })
+        );
+    }
+
+    #[test]
+    fn applies_to_declared_kinds_only() {
+        let owner = attribute(AttributeType::String);
+        assert!(owner.applies_to("USR"));
+
+        let method = Attribute {
+            kinds: vec!["TST".to_string()],
+            ..owner
+        };
+        assert!(method.applies_to("TST"));
+        assert!(!method.applies_to("USR"));
+    }
+}
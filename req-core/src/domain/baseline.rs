@@ -0,0 +1,380 @@
+//! Named baselines: frozen snapshots of the requirement set, and the
+//! differences between them.
+//!
+//! A [`Baseline`] records each requirement's UUID, HRID, title, fingerprint,
+//! and parent links. Requirements are matched between baselines by UUID, so
+//! renumbering a requirement shows up as a rename rather than a removal and
+//! an addition.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::domain::{requirement::ContentRef, FingerprintScheme, RequirementView};
+
+/// A snapshot of every requirement at a point in time.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(from = "Versions", into = "Versions")]
+pub struct Baseline {
+    /// The baseline's name (e.g. `v2.0`).
+    pub name: String,
+    /// When the snapshot was taken.
+    pub created: DateTime<Utc>,
+    /// The requirements in the snapshot, sorted by HRID.
+    pub requirements: Vec<BaselineRequirement>,
+}
+
+/// A requirement as recorded in a [`Baseline`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BaselineRequirement {
+    /// The requirement's UUID.
+    pub uuid: Uuid,
+    /// The requirement's HRID, as displayed.
+    pub hrid: String,
+    /// The requirement's title.
+    pub title: String,
+    /// The requirement's fingerprint.
+    pub fingerprint: String,
+    /// The requirement's parent links, sorted by parent HRID.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub parents: Vec<BaselineLink>,
+}
+
+/// A parent link as recorded in a [`Baseline`].
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct BaselineLink {
+    /// The parent's HRID, as displayed.
+    pub hrid: String,
+    /// The parent's UUID.
+    pub uuid: Uuid,
+    /// The relationship type declared for the link, if any.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub link_type: Option<String>,
+}
+
+impl Baseline {
+    /// Takes a snapshot of some requirements.
+    ///
+    /// Fingerprints are calculated with the given scheme and HRIDs are
+    /// displayed with the given number of digits.
+    #[must_use]
+    pub fn capture<'a>(
+        name: String,
+        requirements: impl IntoIterator<Item = RequirementView<'a>>,
+        scheme: FingerprintScheme,
+        digits: usize,
+    ) -> Self {
+        let mut requirements: Vec<_> = requirements
+            .into_iter()
+            .map(|view| {
+                let mut parents: Vec<_> = view
+                    .parents
+                    .iter()
+                    .map(|(uuid, parent)| BaselineLink {
+                        hrid: parent.hrid.display(digits).to_string(),
+                        uuid: *uuid,
+                        link_type: parent.link_type.clone(),
+                    })
+                    .collect();
+                parents.sort();
+                BaselineRequirement {
+                    uuid: *view.uuid,
+                    hrid: view.hrid.display(digits).to_string(),
+                    title: view.title.to_string(),
+                    fingerprint: scheme.fingerprint(ContentRef {
+                        title: view.title,
+                        body: view.body,
+                        tags: view.tags,
+                    }),
+                    parents,
+                }
+            })
+            .collect();
+        requirements.sort_by(|a, b| a.hrid.cmp(&b.hrid));
+
+        Self {
+            name,
+            created: Utc::now(),
+            requirements,
+        }
+    }
+
+    /// Compares this baseline with a later one.
+    ///
+    /// Fingerprints are compared as recorded, so baselines taken with
+    /// different fingerprint settings report every requirement as modified.
+    #[must_use]
+    pub fn diff(&self, later: &Self) -> BaselineDiff {
+        let before: BTreeMap<Uuid, &BaselineRequirement> = self
+            .requirements
+            .iter()
+            .map(|requirement| (requirement.uuid, requirement))
+            .collect();
+        let after: BTreeMap<Uuid, &BaselineRequirement> = later
+            .requirements
+            .iter()
+            .map(|requirement| (requirement.uuid, requirement))
+            .collect();
+
+        let mut diff = BaselineDiff::default();
+        for (uuid, old) in &before {
+            let Some(new) = after.get(uuid) else {
+                diff.removed.push((*old).clone());
+                continue;
+            };
+            if old.fingerprint != new.fingerprint {
+                diff.modified.push(Modified {
+                    uuid: *uuid,
+                    hrid: new.hrid.clone(),
+                    title: new.title.clone(),
+                });
+            }
+            if old.hrid != new.hrid {
+                diff.renamed.push(Renamed {
+                    uuid: *uuid,
+                    from: old.hrid.clone(),
+                    to: new.hrid.clone(),
+                });
+            }
+            if let Some(relinked) = Relinked::between(old, new) {
+                diff.relinked.push(relinked);
+            }
+        }
+        diff.added = after
+            .iter()
+            .filter(|(uuid, _)| !before.contains_key(uuid))
+            .map(|(_, new)| (*new).clone())
+            .collect();
+
+        diff.added.sort_by(|a, b| a.hrid.cmp(&b.hrid));
+        diff.removed.sort_by(|a, b| a.hrid.cmp(&b.hrid));
+        diff.modified.sort_by(|a, b| a.hrid.cmp(&b.hrid));
+        diff.renamed.sort_by(|a, b| a.to.cmp(&b.to));
+        diff.relinked.sort_by(|a, b| a.hrid.cmp(&b.hrid));
+        diff
+    }
+}
+
+/// The changes between two baselines.
+///
+/// A requirement may appear in several categories, e.g. when it was both
+/// renamed and modified. Each category is sorted by (current) HRID.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct BaselineDiff {
+    /// Requirements only in the later baseline.
+    pub added: Vec<BaselineRequirement>,
+    /// Requirements only in the earlier baseline.
+    pub removed: Vec<BaselineRequirement>,
+    /// Requirements whose fingerprint changed.
+    pub modified: Vec<Modified>,
+    /// Requirements whose HRID changed.
+    pub renamed: Vec<Renamed>,
+    /// Requirements whose parent links changed.
+    pub relinked: Vec<Relinked>,
+}
+
+impl BaselineDiff {
+    /// Checks whether the baselines are identical.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty()
+            && self.removed.is_empty()
+            && self.modified.is_empty()
+            && self.renamed.is_empty()
+            && self.relinked.is_empty()
+    }
+}
+
+/// A requirement whose fingerprint changed between baselines.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Modified {
+    /// The requirement's UUID.
+    pub uuid: Uuid,
+    /// The requirement's HRID in the later baseline.
+    pub hrid: String,
+    /// The requirement's title in the later baseline.
+    pub title: String,
+}
+
+/// A requirement whose HRID changed between baselines.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Renamed {
+    /// The requirement's UUID.
+    pub uuid: Uuid,
+    /// The HRID in the earlier baseline.
+    pub from: String,
+    /// The HRID in the later baseline.
+    pub to: String,
+}
+
+/// A requirement whose parent links changed between baselines.
+///
+/// Links are matched by parent UUID and link type, so renaming a parent does
+/// not count as re-linking its children, but changing a link's type does.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Relinked {
+    /// The requirement's UUID.
+    pub uuid: Uuid,
+    /// The requirement's HRID in the later baseline.
+    pub hrid: String,
+    /// Links only in the later baseline.
+    pub added: Vec<BaselineLink>,
+    /// Links only in the earlier baseline.
+    pub removed: Vec<BaselineLink>,
+}
+
+impl Relinked {
+    fn between(old: &BaselineRequirement, new: &BaselineRequirement) -> Option<Self> {
+        let key = |link: &BaselineLink| (link.uuid, link.link_type.clone());
+        let old_links: BTreeSet<_> = old.parents.iter().map(key).collect();
+        let new_links: BTreeSet<_> = new.parents.iter().map(key).collect();
+
+        let added: Vec<_> = new
+            .parents
+            .iter()
+            .filter(|link| !old_links.contains(&key(link)))
+            .cloned()
+            .collect();
+        let removed: Vec<_> = old
+            .parents
+            .iter()
+            .filter(|link| !new_links.contains(&key(link)))
+            .cloned()
+            .collect();
+
+        (!added.is_empty() || !removed.is_empty()).then(|| Self {
+            uuid: new.uuid,
+            hrid: new.hrid.clone(),
+            added,
+            removed,
+        })
+    }
+}
+
+/// The serialized versions of a baseline.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(tag = "_version")]
+enum Versions {
+    #[serde(rename = "1")]
+    V1 {
+        name: String,
+        created: DateTime<Utc>,
+        requirements: Vec<BaselineRequirement>,
+    },
+}
+
+impl From<Versions> for Baseline {
+    fn from(versions: Versions) -> Self {
+        match versions {
+            Versions::V1 {
+                name,
+                created,
+                requirements,
+            } => Self {
+                name,
+                created,
+                requirements,
+            },
+        }
+    }
+}
+
+impl From<Baseline> for Versions {
+    fn from(baseline: Baseline) -> Self {
+        let Baseline {
+            name,
+            created,
+            requirements,
+        } = baseline;
+        Self::V1 {
+            name,
+            created,
+            requirements,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn requirement(
+        uuid: u128,
+        hrid: &str,
+        fingerprint: &str,
+        parents: &[(&str, u128)],
+    ) -> BaselineRequirement {
+        BaselineRequirement {
+            uuid: Uuid::from_u128(uuid),
+            hrid: hrid.to_string(),
+            title: format!("{hrid} title"),
+            fingerprint: fingerprint.to_string(),
+            parents: parents
+                .iter()
+                .map(|&(hrid, uuid)| BaselineLink {
+                    hrid: hrid.to_string(),
+                    uuid: Uuid::from_u128(uuid),
+                    link_type: None,
+                })
+                .collect(),
+        }
+    }
+
+    fn baseline(requirements: Vec<BaselineRequirement>) -> Baseline {
+        Baseline {
+            name: "test".to_string(),
+            created: Utc::now(),
+            requirements,
+        }
+    }
+
+    #[test]
+    fn diff_reports_each_kind_of_change() {
+        let kept = requirement(1, "USR-001", "a", &[]);
+        let removed = requirement(2, "USR-002", "b", &[]);
+        let child = requirement(3, "SYS-001", "c", &[("USR-001", 1)]);
+        let before = baseline(vec![kept.clone(), removed.clone(), child.clone()]);
+
+        let renamed = BaselineRequirement {
+            hrid: "USR-010".to_string(),
+            fingerprint: "changed".to_string(),
+            ..kept
+        };
+        let relinked = BaselineRequirement {
+            parents: vec![BaselineLink {
+                hrid: "USR-010".to_string(),
+                uuid: Uuid::from_u128(2),
+                link_type: Some("verifies".to_string()),
+            }],
+            ..child
+        };
+        let added = requirement(4, "TST-001", "d", &[]);
+        let after = baseline(vec![renamed, relinked.clone(), added.clone()]);
+
+        let diff = before.diff(&after);
+        assert_eq!(diff.added, [added]);
+        assert_eq!(diff.removed, [removed]);
+        assert_eq!(diff.modified.len(), 1);
+        assert_eq!(diff.modified[0].hrid, "USR-010");
+        assert_eq!(diff.renamed.len(), 1);
+        assert_eq!(
+            (diff.renamed[0].from.as_str(), diff.renamed[0].to.as_str()),
+            ("USR-001", "USR-010")
+        );
+        assert_eq!(diff.relinked.len(), 1);
+        assert_eq!(diff.relinked[0].added, relinked.parents);
+        assert_eq!(diff.relinked[0].removed.len(), 1);
+
+        assert!(after.diff(&after).is_empty());
+    }
+
+    #[test]
+    fn baselines_round_trip_through_json() {
+        let original = baseline(vec![requirement(3, "SYS-001", "c", &[("USR-001", 1)])]);
+        let json = serde_json::to_string(&original).unwrap();
+        assert!(json.starts_with(r#"{"_version":"1","#));
+        assert_eq!(serde_json::from_str::<Baseline>(&json).unwrap(), original);
+    }
+}
@@ -1,16 +1,22 @@
 //! Repository configuration loaded from `.req/config.toml`: HRID
-//! formatting, allowed kinds, and per-kind metadata.
+//! formatting, allowed kinds, per-kind metadata, link types, lifecycle
+//! state machines, and custom attribute schemas.
 
-use std::{collections::HashMap, path::Path};
+use std::{
+    collections::{BTreeMap, BTreeSet, HashMap},
+    path::Path,
+};
 
 use serde::{Deserialize, Serialize};
 
+use crate::domain::{Attribute, FingerprintScheme};
+
 /// Configuration for requirements management.
 ///
 /// This struct holds settings that control how requirements are managed,
 /// including HRID formatting, directory structure modes, and validation rules.
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
-#[serde(from = "Versions", into = "Versions")]
+#[serde(try_from = "Versions", into = "Versions")]
 pub struct Config {
     /// The kinds of requirements that are allowed.
     ///
@@ -49,8 +55,33 @@ pub struct Config {
     ///   Example: `system/auth/USR/001.md` -> HRID is `system-auth-USR-001`
     ///   (The format is inferred: numeric filename means KIND in parent folder)
     pub subfolders_are_namespaces: bool,
+
+    /// The relationship types links may declare (e.g. `verifies`).
+    ///
+    /// If this is empty, any link type is allowed.
+    link_types: Vec<LinkType>,
+
+    /// Lifecycle state machines keyed by kind.
+    ///
+    /// The [`DEFAULT_LIFECYCLE`] entry applies to kinds without their own.
+    /// If this is empty, requirements have no managed lifecycle.
+    lifecycles: BTreeMap<String, Lifecycle>,
+
+    /// Custom attributes keyed by name (e.g. `priority`).
+    attributes: BTreeMap<String, Attribute>,
+
+    /// The inputs of new fingerprints, used to detect suspect links.
+    fingerprint: FingerprintScheme,
+
+    /// Where annotated tests are found, and how test requirements are
+    /// generated from them.
+    tests: TestsConfig,
 }
 
+/// Key of the lifecycle that applies to kinds without a lifecycle of their
+/// own.
+pub const DEFAULT_LIFECYCLE: &str = "default";
+
 impl Default for Config {
     fn default() -> Self {
         Self {
@@ -59,6 +90,11 @@ impl Default for Config {
             digits: default_digits(),
             allow_unrecognised: false,
             subfolders_are_namespaces: false,
+            link_types: Vec::new(),
+            lifecycles: BTreeMap::new(),
+            attributes: BTreeMap::new(),
+            fingerprint: FingerprintScheme::default(),
+            tests: TestsConfig::default(),
         }
     }
 }
@@ -71,13 +107,29 @@ impl Config {
     /// Returns an error if the file cannot be read or if the TOML content is
     /// invalid.
     pub fn load(path: &Path) -> Result<Self, String> {
+        Self::load_versioned(path).map(|(config, _version)| config)
+    }
+
+    /// Loads the configuration, also returning the version it was stored in.
+    ///
+    /// Files written in an older version are upgraded as they are read.
+    pub(crate) fn load_versioned(path: &Path) -> Result<(Self, u32), String> {
         let content = std::fs::read_to_string(path)
             .map_err(|e| format!("Failed to read config file: {e}"))?;
-        toml::from_str(&content).map_err(|e| format!("Failed to parse config file: {e}"))
+        let mut table: toml::Table =
+            toml::from_str(&content).map_err(|e| format!("Failed to parse config file: {e}"))?;
+        let version = crate::storage::markdown::migrate_config(&mut table)
+            .map_err(|e| format!("Unsupported config file: {e}"))?;
+        let config = table
+            .try_into()
+            .map_err(|e| format!("Failed to parse config file: {e}"))?;
+        Ok((config, version))
     }
 
     /// Saves the configuration to a TOML file at the given path.
     ///
+    /// Parent directories are created automatically if they don't exist.
+    ///
     /// # Errors
     ///
     /// Returns an error if the configuration cannot be serialized to TOML or if
@@ -95,6 +147,7 @@ impl Config {
             .filter(|p| !p.as_os_str().is_empty())
             .unwrap_or_else(|| Path::new("."));
         let write_err = |e: &dyn std::fmt::Display| format!("Failed to write config file: {e}");
+        std::fs::create_dir_all(dir).map_err(|e| write_err(&e))?;
         let mut tmp = tempfile::NamedTempFile::new_in(dir).map_err(|e| write_err(&e))?;
         tmp.write_all(content.as_bytes())
             .map_err(|e| write_err(&e))?;
@@ -153,6 +206,69 @@ impl Config {
         self.allowed_kinds.is_empty() || self.allowed_kinds.iter().any(|k| k == kind)
     }
 
+    /// Returns the declared link types.
+    #[must_use]
+    pub fn link_types(&self) -> &[LinkType] {
+        &self.link_types
+    }
+
+    /// Checks if a link type is allowed by the configuration.
+    ///
+    /// If no link types are declared, all link types are allowed.
+    #[must_use]
+    pub fn is_link_type_allowed(&self, link_type: &str) -> bool {
+        self.link_types.is_empty() || self.link_types.iter().any(|t| t.name == link_type)
+    }
+
+    /// Returns the lifecycle state machine that applies to a kind, if any.
+    ///
+    /// A kind-specific lifecycle takes precedence over the default one.
+    #[must_use]
+    pub fn lifecycle_for_kind(&self, kind: &str) -> Option<&Lifecycle> {
+        self.lifecycles
+            .get(&kind.to_uppercase())
+            .or_else(|| self.lifecycles.get(DEFAULT_LIFECYCLE))
+    }
+
+    /// Returns the declared custom attributes, keyed by name.
+    #[must_use]
+    #[allow(clippy::missing_const_for_fn)]
+    pub fn attributes(&self) -> &BTreeMap<String, Attribute> {
+        &self.attributes
+    }
+
+    /// Returns the custom attributes that apply to a kind.
+    pub fn attributes_for_kind<'a>(
+        &'a self,
+        kind: &'a str,
+    ) -> impl Iterator<Item = (&'a str, &'a Attribute)> + 'a {
+        self.attributes
+            .iter()
+            .filter(move |(_, attribute)| attribute.applies_to(kind))
+            .map(|(name, attribute)| (name.as_str(), attribute))
+    }
+
+    /// Returns the declaration of a named attribute if it applies to a kind.
+    #[must_use]
+    pub fn attribute_for_kind(&self, kind: &str, name: &str) -> Option<&Attribute> {
+        self.attributes
+            .get(name)
+            .filter(|attribute| attribute.applies_to(kind))
+    }
+
+    /// Returns the scheme new fingerprints are calculated with.
+    #[must_use]
+    pub const fn fingerprint(&self) -> FingerprintScheme {
+        self.fingerprint
+    }
+
+    /// Returns the settings for generating test requirements from annotated
+    /// tests.
+    #[must_use]
+    pub const fn tests(&self) -> &TestsConfig {
+        &self.tests
+    }
+
     /// Sets the `subfolders_are_namespaces` configuration option.
     pub const fn set_subfolders_are_namespaces(&mut self, value: bool) {
         self.subfolders_are_namespaces = value;
@@ -192,28 +308,32 @@ impl Config {
 
     /// Sets or clears a description for a kind (stored uppercase).
     ///
-    /// An empty or `None` description removes existing metadata.
+    /// An empty or `None` description clears the existing description; other
+    /// metadata (such as link rules) is kept.
     pub fn set_kind_description(&mut self, kind: &str, description: Option<String>) {
         let key = kind.to_uppercase();
-        let description = description
-            .and_then(|d| {
-                let trimmed = d.trim();
-                if trimmed.is_empty() {
-                    None
-                } else {
-                    Some(trimmed.to_string())
-                }
-            })
-            .map(Some);
+        let description = description.and_then(|d| {
+            let trimmed = d.trim();
+            if trimmed.is_empty() {
+                None
+            } else {
+                Some(trimmed.to_string())
+            }
+        });
 
         match description {
             Some(description) => {
                 // Ensure the kind is explicitly allowed when attaching metadata.
                 self.add_kind(&key);
-                self.kind_metadata.insert(key, KindMetadata { description });
+                self.kind_metadata.entry(key).or_default().description = Some(description);
             }
             None => {
-                self.kind_metadata.remove(&key);
+                if let Some(metadata) = self.kind_metadata.get_mut(&key) {
+                    metadata.description = None;
+                    if *metadata == KindMetadata::default() {
+                        self.kind_metadata.remove(&key);
+                    }
+                }
             }
         }
     }
@@ -229,11 +349,155 @@ pub struct KindMetadata {
     /// Human-readable description of the kind's purpose.
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub description: Option<String>,
+
+    /// The kinds requirements of this kind may link to as parents.
+    ///
+    /// If this is empty, parents of any kind are allowed.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub parent_kinds: Vec<String>,
+
+    /// The minimum number of parents a requirement of this kind must have.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_parents: Option<usize>,
+
+    /// The maximum number of parents a requirement of this kind may have.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_parents: Option<usize>,
+
+    /// The minimum percentage of requirements of this kind that must have
+    /// children of the kinds downstream of it (see `req coverage`), from 0 to
+    /// 100.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_coverage: Option<u8>,
+}
+
+impl KindMetadata {
+    /// Checks whether requirements of this kind may link to a parent of the
+    /// given kind.
+    #[must_use]
+    pub fn allows_parent_kind(&self, kind: &str) -> bool {
+        self.parent_kinds.is_empty() || self.parent_kinds.iter().any(|k| k == kind)
+    }
+}
+
+/// Settings for `req tests sync`, which generates a test requirement for each
+/// annotated test.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct TestsConfig {
+    /// The kind of the generated test requirements.
+    #[serde(default = "default_test_kind")]
+    pub kind: String,
+
+    /// Glob patterns of the source files to scan, relative to the
+    /// requirements directory (e.g. `../src/**/*.rs`).
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub sources: Vec<String>,
+
+    /// The link type of generated links to the verified requirements (e.g.
+    /// `verifies`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub link_type: Option<String>,
+}
+
+impl Default for TestsConfig {
+    fn default() -> Self {
+        Self {
+            kind: default_test_kind(),
+            sources: Vec::new(),
+            link_type: None,
+        }
+    }
+}
+
+impl TestsConfig {
+    fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+fn default_test_kind() -> String {
+    "TEST".to_string()
+}
+
+/// A relationship type that links between requirements may declare.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LinkType {
+    /// The link type identifier (lowercase), e.g. `verifies`.
+    pub name: String,
+    /// Human-readable description of the relationship.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+}
+
+/// A lifecycle state machine for requirement statuses.
+///
+/// The states are the initial status plus every status named in the
+/// transition table.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Lifecycle {
+    /// Status assigned to newly created requirements, e.g. `draft`.
+    pub initial: String,
+    /// Allowed transitions, keyed by the status being left.
+    #[serde(default)]
+    pub transitions: BTreeMap<String, Vec<String>>,
+}
+
+impl Lifecycle {
+    /// Returns every status declared by this lifecycle.
+    #[must_use]
+    pub fn states(&self) -> BTreeSet<&str> {
+        std::iter::once(self.initial.as_str())
+            .chain(self.transitions.iter().flat_map(|(from, to)| {
+                std::iter::once(from.as_str()).chain(to.iter().map(String::as_str))
+            }))
+            .collect()
+    }
+
+    /// Checks whether a status is declared by this lifecycle.
+    #[must_use]
+    pub fn is_state(&self, status: &str) -> bool {
+        self.states().contains(status)
+    }
+
+    /// Checks whether a requirement may move from one status to another.
+    ///
+    /// Requirements without a status may adopt any declared status.
+    #[must_use]
+    pub fn allows_transition(&self, from: Option<&str>, to: &str) -> bool {
+        match from {
+            None => self.is_state(to),
+            Some(from) if from == to => true,
+            Some(from) => self
+                .transitions
+                .get(from)
+                .is_some_and(|targets| targets.iter().any(|target| target == to)),
+        }
+    }
+
+    fn normalized(self) -> Self {
+        Self {
+            initial: self.initial.to_lowercase(),
+            transitions: self
+                .transitions
+                .into_iter()
+                .map(|(from, to)| {
+                    (
+                        from.to_lowercase(),
+                        to.into_iter().map(|t| t.to_lowercase()).collect(),
+                    )
+                })
+                .collect(),
+        }
+    }
 }
 
 /// The serialized versions of the configuration.
 /// This allows for future changes to the configuration format and to the domain
 /// type without breaking compatibility.
+///
+/// Only the latest version is deserialized directly; older versions are
+/// upgraded by the storage layer's migration steps first.
 #[derive(Debug, Serialize, Deserialize)]
 #[serde(tag = "_version")]
 enum Versions {
@@ -261,18 +525,40 @@ enum Versions {
 
         #[serde(default)]
         subfolders_are_namespaces: bool,
+
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        link_types: Vec<LinkTypeEntry>,
+
+        #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+        lifecycles: BTreeMap<String, Lifecycle>,
+
+        #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+        attributes: BTreeMap<String, Attribute>,
+
+        #[serde(default, skip_serializing_if = "FingerprintScheme::is_default")]
+        fingerprint: FingerprintScheme,
+
+        #[serde(default, skip_serializing_if = "TestsConfig::is_default")]
+        tests: TestsConfig,
     },
 }
 
-impl From<Versions> for super::Config {
-    fn from(versions: Versions) -> Self {
-        match versions {
+impl TryFrom<Versions> for super::Config {
+    type Error = String;
+
+    fn try_from(versions: Versions) -> Result<Self, Self::Error> {
+        let config = match versions {
             Versions::V1 {
                 allowed_kinds,
                 digits,
                 allow_unrecognised,
                 allow_invalid: _, // Ignored for backward compatibility
                 subfolders_are_namespaces,
+                link_types,
+                lifecycles,
+                attributes,
+                fingerprint,
+                tests,
             } => Self {
                 // Normalize kinds to uppercase on load: HRID kinds are always
                 // uppercase and is_kind_allowed compares exactly, so a
@@ -285,13 +571,66 @@ impl From<Versions> for super::Config {
                 kind_metadata: allowed_kinds
                     .into_iter()
                     .filter_map(AllowedKindEntry::into_metadata)
-                    .map(|(kind, meta)| (kind.to_uppercase(), meta))
+                    .map(|(kind, meta)| {
+                        let parent_kinds = meta.parent_kinds.iter().map(|k| k.to_uppercase());
+                        let meta = KindMetadata {
+                            parent_kinds: parent_kinds.collect(),
+                            ..meta
+                        };
+                        (kind.to_uppercase(), meta)
+                    })
                     .collect(),
                 digits,
                 allow_unrecognised,
                 subfolders_are_namespaces,
+                // Link types are lowercase by convention, like the values
+                // written to frontmatter.
+                link_types: link_types
+                    .into_iter()
+                    .map(LinkTypeEntry::into_link_type)
+                    .map(|link_type| LinkType {
+                        name: link_type.name.to_lowercase(),
+                        ..link_type
+                    })
+                    .collect(),
+                // Kinds are uppercase and statuses lowercase, matching how
+                // they are compared.
+                lifecycles: lifecycles
+                    .into_iter()
+                    .map(|(key, lifecycle)| {
+                        let key = if key.eq_ignore_ascii_case(DEFAULT_LIFECYCLE) {
+                            DEFAULT_LIFECYCLE.to_string()
+                        } else {
+                            key.to_uppercase()
+                        };
+                        (key, lifecycle.normalized())
+                    })
+                    .collect(),
+                attributes: attributes
+                    .into_iter()
+                    .map(|(name, attribute)| {
+                        let kinds = attribute.kinds.iter().map(|k| k.to_uppercase()).collect();
+                        (name, Attribute { kinds, ..attribute })
+                    })
+                    .collect(),
+                fingerprint,
+                tests: TestsConfig {
+                    kind: tests.kind.to_uppercase(),
+                    link_type: tests.link_type.map(|t| t.to_lowercase()),
+                    ..tests
+                },
             },
+        };
+
+        for (kind, metadata) in &config.kind_metadata {
+            if let Some(min_coverage) = metadata.min_coverage.filter(|&min| min > 100) {
+                return Err(format!(
+                    "the min_coverage of kind {kind} is {min_coverage}, but must be a percentage \
+                     from 0 to 100"
+                ));
+            }
         }
+        Ok(config)
     }
 }
 
@@ -303,6 +642,11 @@ impl From<super::Config> for Versions {
             digits,
             allow_unrecognised,
             subfolders_are_namespaces,
+            link_types,
+            lifecycles,
+            attributes,
+            fingerprint,
+            tests,
         } = config;
 
         let serialized_kinds: Vec<AllowedKindEntry> = allowed_kinds
@@ -310,9 +654,9 @@ impl From<super::Config> for Versions {
             .map(|kind| {
                 kind_metadata.remove(kind).map_or_else(
                     || AllowedKindEntry::Simple(kind.clone()),
-                    |meta| AllowedKindEntry::Detailed {
+                    |metadata| AllowedKindEntry::Detailed {
                         kind: kind.clone(),
-                        description: meta.description,
+                        metadata,
                     },
                 )
             })
@@ -324,6 +668,11 @@ impl From<super::Config> for Versions {
             allow_unrecognised,
             allow_invalid: false, // No longer used
             subfolders_are_namespaces,
+            link_types: link_types.into_iter().map(LinkTypeEntry::from).collect(),
+            lifecycles,
+            attributes,
+            fingerprint,
+            tests,
         }
     }
 }
@@ -338,8 +687,8 @@ enum AllowedKindEntry {
     /// A kind identifier with optional metadata fields.
     Detailed {
         kind: String,
-        #[serde(default, skip_serializing_if = "Option::is_none")]
-        description: Option<String>,
+        #[serde(flatten)]
+        metadata: KindMetadata,
     },
 }
 
@@ -353,7 +702,40 @@ impl AllowedKindEntry {
     fn into_metadata(self) -> Option<(String, KindMetadata)> {
         match self {
             Self::Simple(_) => None,
-            Self::Detailed { kind, description } => Some((kind, KindMetadata { description })),
+            Self::Detailed { kind, metadata } => Some((kind, metadata)),
+        }
+    }
+}
+
+/// Serialization helper for link types that supports either bare strings or
+/// inline tables with a description.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(untagged)]
+enum LinkTypeEntry {
+    /// A bare link type name, e.g. "verifies".
+    Simple(String),
+    /// A link type with a description.
+    Detailed(LinkType),
+}
+
+impl LinkTypeEntry {
+    fn into_link_type(self) -> LinkType {
+        match self {
+            Self::Simple(name) => LinkType {
+                name,
+                description: None,
+            },
+            Self::Detailed(link_type) => link_type,
+        }
+    }
+}
+
+impl From<LinkType> for LinkTypeEntry {
+    fn from(link_type: LinkType) -> Self {
+        if link_type.description.is_none() {
+            Self::Simple(link_type.name)
+        } else {
+            Self::Detailed(link_type)
         }
     }
 }
@@ -469,6 +851,18 @@ allowed_kinds = [
         assert!(config.kind_metadata().get("SYS").is_none());
     }
 
+    #[test]
+    fn load_rejects_min_coverage_above_100() {
+        let mut file = tempfile::NamedTempFile::new().unwrap();
+        file.write_all(
+            b"_version = \"1\"\nallowed_kinds = [{ kind = \"usr\", min_coverage = 150 }]\n",
+        )
+        .unwrap();
+
+        let error = Config::load(file.path()).unwrap_err();
+        assert!(error.contains("min_coverage of kind USR is 150"), "{error}");
+    }
+
     #[test]
     fn set_kind_description_adds_and_removes_metadata() {
         let mut config = Config::default();
@@ -522,6 +916,100 @@ allowed_kinds = [
         );
     }
 
+    #[test]
+    fn load_link_types() {
+        let config: Config = toml::from_str(
+            r#"_version = "1"
+link_types = ["Satisfies", { name = "verifies", description = "Test evidence" }]
+"#,
+        )
+        .unwrap();
+
+        let names: Vec<_> = config
+            .link_types()
+            .iter()
+            .map(|t| t.name.as_str())
+            .collect();
+        assert_eq!(names, ["satisfies", "verifies"]);
+        assert_eq!(
+            config.link_types()[1].description.as_deref(),
+            Some("Test evidence")
+        );
+        assert!(config.is_link_type_allowed("verifies"));
+        assert!(!config.is_link_type_allowed("refines"));
+
+        let round_tripped: Config = toml::from_str(&toml::to_string(&config).unwrap()).unwrap();
+        assert_eq!(round_tripped, config);
+
+        // With nothing declared, any link type is accepted.
+        assert!(Config::default().is_link_type_allowed("refines"));
+    }
+
+    #[test]
+    fn load_lifecycles() {
+        let config: Config = toml::from_str(
+            r#"_version = "1"
+
+[lifecycles.default]
+initial = "Draft"
+transitions = { draft = ["proposed"], proposed = ["approved", "draft"], approved = ["obsolete"] }
+
+[lifecycles.tst]
+initial = "draft"
+transitions = { draft = ["passing"] }
+"#,
+        )
+        .unwrap();
+
+        let usr = config.lifecycle_for_kind("USR").unwrap();
+        assert_eq!(usr.initial, "draft");
+        assert_eq!(
+            usr.states().into_iter().collect::<Vec<_>>(),
+            ["approved", "draft", "obsolete", "proposed"]
+        );
+        assert!(usr.allows_transition(Some("draft"), "proposed"));
+        assert!(!usr.allows_transition(Some("draft"), "approved"));
+        assert!(usr.allows_transition(Some("approved"), "approved"));
+        assert!(usr.allows_transition(None, "approved"));
+        assert!(!usr.allows_transition(None, "passing"));
+
+        let tst = config.lifecycle_for_kind("TST").unwrap();
+        assert!(tst.allows_transition(Some("draft"), "passing"));
+        assert!(!tst.is_state("approved"));
+
+        let round_tripped: Config = toml::from_str(&toml::to_string(&config).unwrap()).unwrap();
+        assert_eq!(round_tripped, config);
+
+        assert!(Config::default().lifecycle_for_kind("USR").is_none());
+    }
+
+    #[test]
+    fn load_attributes() {
+        let config: Config = toml::from_str(
+            r#"_version = "1"
+
+[attributes.priority]
+type = "enum"
+values = ["low", "medium", "high"]
+required = true
+
+[attributes.verification-method]
+type = "string"
+kinds = ["tst"]
+"#,
+        )
+        .unwrap();
+
+        let usr: Vec<_> = config.attributes_for_kind("USR").map(|(n, _)| n).collect();
+        assert_eq!(usr, ["priority"]);
+        let tst: Vec<_> = config.attributes_for_kind("TST").map(|(n, _)| n).collect();
+        assert_eq!(tst, ["priority", "verification-method"]);
+        assert!(config.attributes()["priority"].required);
+
+        let round_tripped: Config = toml::from_str(&toml::to_string(&config).unwrap()).unwrap();
+        assert_eq!(round_tripped, config);
+    }
+
     #[test]
     fn empty_file_returns_default() {
         // Tests that deserialising an empty file returns the default configuration.
@@ -0,0 +1,402 @@
+//! Fingerprint schemes: which parts of a requirement are hashed, and how they
+//! are normalised first.
+//!
+//! Stored fingerprints identify the scheme that produced them, so a link is
+//! always checked against its parent using the scheme it was stamped with.
+//! Changing the configured scheme therefore doesn't make existing links
+//! suspect; links pick up the new scheme when they are next created or
+//! reviewed.
+//!
+//! The default scheme (body and tags, no normalisation) is scheme version 1,
+//! and its fingerprints are bare hex strings. Any other scheme is version 2,
+//! written as `v2+<options>:<hash>` (e.g. `v2+title+whitespace:3f9a…`).
+
+use std::{collections::BTreeSet, fmt::Write as _};
+
+use borsh::BorshSerialize;
+use pulldown_cmark::{Event, Options, Parser, Tag, TagEnd};
+use serde::{Deserialize, Serialize};
+use xxhash_rust::xxh3::xxh3_128;
+
+use crate::domain::requirement::ContentRef;
+
+/// Prefix of version 2 fingerprints.
+const V2: &str = "v2";
+
+/// The inputs of a requirement's fingerprint.
+///
+/// The body is always included.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct FingerprintScheme {
+    /// Whether the title is included.
+    #[serde(default)]
+    pub title: bool,
+
+    /// Whether the tags are included.
+    #[serde(default = "default_tags")]
+    pub tags: bool,
+
+    /// How the title and body are normalised before hashing.
+    #[serde(default)]
+    pub normalize: Normalization,
+}
+
+const fn default_tags() -> bool {
+    true
+}
+
+impl Default for FingerprintScheme {
+    fn default() -> Self {
+        Self {
+            title: false,
+            tags: true,
+            normalize: Normalization::None,
+        }
+    }
+}
+
+/// Normalisation applied to text before it is fingerprinted.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Normalization {
+    /// Text is hashed as written.
+    #[default]
+    None,
+
+    /// Runs of whitespace and line breaks within a paragraph are ignored, so
+    /// reflowing text doesn't change the fingerprint.
+    Whitespace,
+
+    /// As [`Self::Whitespace`], and markdown formatting is ignored too:
+    /// emphasis, strikethrough, and code markers, heading and blockquote
+    /// markers, and the style of list bullets. Characters such as `_` and
+    /// `~` still count where markdown doesn't read them as formatting.
+    Markdown,
+}
+
+impl Normalization {
+    const fn name(self) -> Option<&'static str> {
+        match self {
+            Self::None => None,
+            Self::Whitespace => Some("whitespace"),
+            Self::Markdown => Some("markdown"),
+        }
+    }
+
+    fn apply(self, text: &str) -> String {
+        match self {
+            Self::None => text.to_string(),
+            Self::Whitespace => collapse_whitespace(text.lines()),
+            Self::Markdown => collapse_whitespace(strip_markdown(text).lines()),
+        }
+    }
+}
+
+impl FingerprintScheme {
+    /// Checks whether this is the default (version 1) scheme.
+    #[must_use]
+    pub fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Returns the scheme identifier written before version 2 fingerprints,
+    /// or `None` for the default scheme.
+    #[must_use]
+    pub fn id(&self) -> Option<String> {
+        if self.is_default() {
+            return None;
+        }
+        let options = [
+            self.title.then_some("title"),
+            self.tags.then_some("tags"),
+            self.normalize.name(),
+        ];
+        Some(
+            options
+                .into_iter()
+                .flatten()
+                .fold(V2.to_string(), |id, option| format!("{id}+{option}")),
+        )
+    }
+
+    /// Returns the scheme that produced a stored fingerprint.
+    ///
+    /// Fingerprints without a scheme identifier were produced by the default
+    /// scheme. Returns `None` if the identifier is not recognised.
+    #[must_use]
+    pub fn of(fingerprint: &str) -> Option<Self> {
+        let Some((id, _hash)) = fingerprint.split_once(':') else {
+            return Some(Self::default());
+        };
+        let mut options = id.split('+');
+        if options.next() != Some(V2) {
+            return None;
+        }
+
+        let mut scheme = Self {
+            title: false,
+            tags: false,
+            normalize: Normalization::None,
+        };
+        for option in options {
+            match option {
+                "title" => scheme.title = true,
+                "tags" => scheme.tags = true,
+                "whitespace" => scheme.normalize = Normalization::Whitespace,
+                "markdown" => scheme.normalize = Normalization::Markdown,
+                _ => return None,
+            }
+        }
+        Some(scheme)
+    }
+
+    /// Calculate the fingerprint of some content with this scheme.
+    ///
+    /// The fingerprint is an xxHash3 128-bit hash of the Borsh-serialized
+    /// inputs, prefixed with the scheme identifier unless this is the default
+    /// scheme.
+    ///
+    /// # Panics
+    ///
+    /// Panics if borsh serialization fails (which should never happen for this
+    /// data structure).
+    #[must_use]
+    pub fn fingerprint(&self, content: ContentRef<'_>) -> String {
+        #[derive(BorshSerialize)]
+        struct V1Data<'a> {
+            body: &'a str,
+            tags: &'a BTreeSet<String>,
+        }
+
+        #[derive(BorshSerialize)]
+        struct V2Data<'a> {
+            title: Option<String>,
+            body: String,
+            tags: Option<&'a BTreeSet<String>>,
+        }
+
+        let id = self.id();
+
+        // encode using [borsh](https://borsh.io/)
+        let encoded = if id.is_none() {
+            borsh::to_vec(&V1Data {
+                body: content.body,
+                tags: content.tags,
+            })
+        } else {
+            borsh::to_vec(&V2Data {
+                title: self.title.then(|| self.normalize.apply(content.title)),
+                body: self.normalize.apply(content.body),
+                tags: self.tags.then_some(content.tags),
+            })
+        }
+        .expect("this should never fail");
+
+        // Generate an xxHash3 128-bit fingerprint. This is deterministic and
+        // intentionally non-cryptographic: fingerprints are used for change
+        // detection, not adversarial integrity checks.
+        let hash = xxh3_128(&encoded);
+
+        // Encode the full 128-bit value as a fixed-width (32-character)
+        // lowercase hex string.
+        id.map_or_else(|| format!("{hash:032x}"), |id| format!("{id}:{hash:032x}"))
+    }
+}
+
+/// Joins the words of each paragraph with single spaces, and paragraphs with
+/// blank lines.
+fn collapse_whitespace<'a>(lines: impl Iterator<Item = &'a str>) -> String {
+    let mut paragraphs: Vec<Vec<&str>> = vec![Vec::new()];
+    for line in lines {
+        if line.trim().is_empty() {
+            if paragraphs.last().is_some_and(|words| !words.is_empty()) {
+                paragraphs.push(Vec::new());
+            }
+        } else if let Some(words) = paragraphs.last_mut() {
+            words.extend(line.split_whitespace());
+        }
+    }
+    paragraphs
+        .iter()
+        .filter(|words| !words.is_empty())
+        .map(|words| words.join(" "))
+        .collect::<Vec<_>>()
+        .join("\n\n")
+}
+
+/// The text of some markdown, without the formatting that doesn't change its
+/// meaning: emphasis, strikethrough, and code markers, heading and blockquote
+/// markers, and the style of list bullets. Each block ends with a blank line.
+///
+/// Only markers that markdown reads as formatting are removed, so a `~` or
+/// `_` that is part of the text still counts.
+fn strip_markdown(text: &str) -> String {
+    let mut stripped = String::new();
+    // The next number of each enclosing list, or `None` if it is unordered.
+    let mut lists: Vec<Option<u64>> = Vec::new();
+    let mut destinations = Vec::new();
+    for event in Parser::new_ext(text, Options::ENABLE_STRIKETHROUGH) {
+        match event {
+            Event::Start(Tag::List(start)) => lists.push(start),
+            Event::End(TagEnd::List(_)) => {
+                lists.pop();
+            }
+            Event::Start(Tag::Item) => match lists.last_mut() {
+                Some(Some(number)) => {
+                    let _ = write!(stripped, "{number}. ");
+                    *number += 1;
+                }
+                _ => stripped.push_str("- "),
+            },
+            Event::Start(Tag::Link { dest_url, .. }) => {
+                stripped.push('[');
+                destinations.push(dest_url);
+            }
+            Event::Start(Tag::Image { dest_url, .. }) => {
+                stripped.push_str("![");
+                destinations.push(dest_url);
+            }
+            Event::End(TagEnd::Link | TagEnd::Image) => {
+                if let Some(destination) = destinations.pop() {
+                    let _ = write!(stripped, "]({destination})");
+                }
+            }
+            Event::End(
+                TagEnd::Paragraph
+                | TagEnd::Heading(_)
+                | TagEnd::Item
+                | TagEnd::BlockQuote
+                | TagEnd::CodeBlock
+                | TagEnd::HtmlBlock,
+            ) => stripped.push_str("\n\n"),
+            Event::Text(text) | Event::Code(text) | Event::Html(text) | Event::InlineHtml(text) => {
+                stripped.push_str(&text);
+            }
+            Event::SoftBreak | Event::HardBreak => stripped.push('\n'),
+            Event::Rule => stripped.push_str("---\n\n"),
+            _ => {}
+        }
+    }
+    stripped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn content<'a>(title: &'a str, body: &'a str, tags: &'a BTreeSet<String>) -> ContentRef<'a> {
+        ContentRef { title, body, tags }
+    }
+
+    #[test]
+    fn default_scheme_matches_legacy_fingerprints() {
+        let tags = BTreeSet::from(["security".to_string()]);
+        let content = content("Title", "Body text.", &tags);
+
+        let fingerprint = FingerprintScheme::default().fingerprint(content);
+        assert_eq!(fingerprint, content.fingerprint());
+        assert_eq!(fingerprint.len(), 32);
+        assert_eq!(
+            FingerprintScheme::of(&fingerprint),
+            Some(FingerprintScheme::default())
+        );
+    }
+
+    #[test]
+    fn scheme_ids_round_trip() {
+        let scheme = FingerprintScheme {
+            title: true,
+            tags: false,
+            normalize: Normalization::Markdown,
+        };
+        let tags = BTreeSet::new();
+        let fingerprint = scheme.fingerprint(content("Title", "Body", &tags));
+
+        assert!(fingerprint.starts_with("v2+title+markdown:"));
+        assert_eq!(FingerprintScheme::of(&fingerprint), Some(scheme));
+        assert_eq!(FingerprintScheme::of("v3+title:abc"), None);
+        assert_eq!(FingerprintScheme::of("v2+colour:abc"), None);
+    }
+
+    #[test]
+    fn options_choose_what_is_fingerprinted() {
+        let scheme = FingerprintScheme {
+            title: true,
+            tags: false,
+            normalize: Normalization::None,
+        };
+        let tags = BTreeSet::from(["a".to_string()]);
+        let renamed_tags = BTreeSet::from(["b".to_string()]);
+        let fingerprint = scheme.fingerprint(content("Title", "Body", &tags));
+
+        assert_eq!(
+            scheme.fingerprint(content("Title", "Body", &renamed_tags)),
+            fingerprint
+        );
+        assert_ne!(
+            scheme.fingerprint(content("New title", "Body", &tags)),
+            fingerprint
+        );
+    }
+
+    #[test]
+    fn normalization_ignores_reflows_and_formatting() {
+        let whitespace = FingerprintScheme {
+            normalize: Normalization::Whitespace,
+            ..FingerprintScheme::default()
+        };
+        let markdown = FingerprintScheme {
+            normalize: Normalization::Markdown,
+            ..FingerprintScheme::default()
+        };
+        let tags = BTreeSet::new();
+        let original = "The system shall\nvalidate emails.\n\n* one\n* two";
+        let reflowed = "The  system shall validate\nemails.\n\n\n* one\n* two\n";
+        let formatted = "The system shall *validate* `emails`.\n\n- one\n- two";
+        let reworded = "The system shall validate addresses.\n\n* one\n* two";
+        let fingerprint =
+            |scheme: FingerprintScheme, body| scheme.fingerprint(content("", body, &tags));
+
+        assert_eq!(
+            fingerprint(whitespace, reflowed),
+            fingerprint(whitespace, original)
+        );
+        assert_ne!(
+            fingerprint(whitespace, formatted),
+            fingerprint(whitespace, original)
+        );
+        assert_eq!(
+            fingerprint(markdown, formatted),
+            fingerprint(markdown, original)
+        );
+        assert_ne!(
+            fingerprint(markdown, reworded),
+            fingerprint(markdown, original)
+        );
+    }
+
+    #[test]
+    fn markdown_normalization_keeps_markers_that_are_text() {
+        let markdown = FingerprintScheme {
+            normalize: Normalization::Markdown,
+            ..FingerprintScheme::default()
+        };
+        let tags = BTreeSet::new();
+        let fingerprint = |body| markdown.fingerprint(content("", body, &tags));
+
+        assert_eq!(
+            fingerprint("The ~~old~~ __limit__ is `max_rate`."),
+            fingerprint("The old limit is max_rate.")
+        );
+        for (before, after) in [
+            ("Latency is ~5 ms.", "Latency is 5 ms."),
+            ("Limited by max_rate.", "Limited by maxrate."),
+            ("It takes 2*n steps.", "It takes 2n steps."),
+            ("See [the spec](a.md).", "See [the spec](b.md)."),
+            ("1. first\n2. second", "- first\n- second"),
+        ] {
+            assert_ne!(fingerprint(before), fingerprint(after), "{before}");
+        }
+    }
+}
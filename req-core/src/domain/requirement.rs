@@ -2,18 +2,21 @@
 //! content fingerprinting.
 
 use std::{
-    collections::{BTreeSet, HashMap},
+    collections::{BTreeMap, BTreeSet, HashMap},
     io,
     path::Path,
 };
 
 use borsh::BorshSerialize;
 use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
 use uuid::Uuid;
-use xxhash_rust::xxh3::xxh3_128;
 
 pub use crate::storage::markdown::LoadError;
-use crate::{domain::Hrid, storage::markdown::MarkdownRequirement};
+use crate::{
+    domain::{AttributeValue, FingerprintScheme, Hrid},
+    storage::markdown::MarkdownRequirement,
+};
 
 /// A requirement is a document used to describe a system.
 ///
@@ -24,7 +27,8 @@ use crate::{domain::Hrid, storage::markdown::MarkdownRequirement};
 pub struct Requirement {
     /// The requirement's content (markdown text and tags).
     pub content: Content,
-    /// The requirement's metadata (UUID, HRID, creation time, parents).
+    /// The requirement's metadata (UUID, HRID, creation time, status,
+    /// attributes, parents).
     pub metadata: Metadata,
 }
 
@@ -74,41 +78,16 @@ pub struct ContentRef<'a> {
 }
 
 impl ContentRef<'_> {
-    /// Calculate the fingerprint of this content.
+    /// Calculate the fingerprint of this content with the default
+    /// [`FingerprintScheme`].
     ///
     /// The fingerprint is an xxHash3 128-bit hash of the Borsh-serialized body
     /// and tags. The title and HRID are excluded so that renaming
     /// requirements or updating titles doesn't invalidate child requirement
     /// links.
-    ///
-    /// # Panics
-    ///
-    /// Panics if borsh serialization fails (which should never happen for this
-    /// data structure).
     #[must_use]
     pub fn fingerprint(&self) -> String {
-        #[derive(BorshSerialize)]
-        struct FingerprintData<'a> {
-            body: &'a str,
-            tags: &'a BTreeSet<String>,
-        }
-
-        let data = FingerprintData {
-            body: self.body,
-            tags: self.tags,
-        };
-
-        // encode using [borsh](https://borsh.io/)
-        let encoded = borsh::to_vec(&data).expect("this should never fail");
-
-        // Generate an xxHash3 128-bit fingerprint. This is deterministic and
-        // intentionally non-cryptographic: fingerprints are used for change
-        // detection, not adversarial integrity checks.
-        let hash = xxh3_128(&encoded);
-
-        // Encode the full 128-bit value as a fixed-width (32-character)
-        // lowercase hex string.
-        format!("{hash:032x}")
+        FingerprintScheme::default().fingerprint(*self)
     }
 }
 
@@ -127,10 +106,87 @@ pub struct Metadata {
     pub hrid: Hrid,
     /// Timestamp recording when the requirement was created.
     pub created: DateTime<Utc>,
+    /// Lifecycle status (e.g. `draft`, `approved`), if one has been set.
+    pub status: Option<String>,
+    /// Custom attribute values keyed by attribute name.
+    pub attributes: BTreeMap<String, AttributeValue>,
+    /// The test this requirement describes, if it was generated from an
+    /// annotated test.
+    pub test: Option<TestMetadata>,
+    /// Where the requirement was imported from, if it was imported from
+    /// another tool.
+    pub origin: Option<Origin>,
     /// Parent requirements keyed by UUID.
     pub parents: HashMap<Uuid, Parent>,
 }
 
+/// The test in source code that a test requirement describes.
+///
+/// Test requirements are kept in step with their tests by `req tests sync`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TestMetadata {
+    /// Where the test is defined, as `path/to/file.rs::test_name`.
+    pub location: String,
+    /// The latest imported result of running the test, if any.
+    ///
+    /// Results are recorded by `req tests import`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub result: Option<TestResult>,
+}
+
+/// The result of one run of a test.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TestResult {
+    /// Whether the test passed.
+    pub outcome: TestOutcome,
+    /// When the test was run, or the result imported if the report doesn't
+    /// say.
+    pub timestamp: DateTime<Utc>,
+}
+
+/// Whether a test passed, failed, or was skipped.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TestOutcome {
+    /// The test passed.
+    Passed,
+    /// The test was skipped (for example, an ignored Rust test).
+    Skipped,
+    /// The test failed.
+    Failed,
+}
+
+impl TestOutcome {
+    /// The outcome as a lowercase word, as it is written to frontmatter.
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Passed => "passed",
+            Self::Skipped => "skipped",
+            Self::Failed => "failed",
+        }
+    }
+}
+
+impl std::fmt::Display for TestOutcome {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Where an imported requirement came from.
+///
+/// Re-importing from the same source updates the requirement with the same
+/// identifier instead of creating a new one.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Origin {
+    /// The format the requirement was imported from, such as `reqif`.
+    pub source: String,
+    /// The requirement's identifier in the source, such as a ReqIF
+    /// `IDENTIFIER`.
+    pub identifier: String,
+}
+
 /// Parent requirement metadata stored alongside a requirement.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Parent {
@@ -138,6 +194,10 @@ pub struct Parent {
     pub hrid: Hrid,
     /// Fingerprint snapshot of the parent requirement.
     pub fingerprint: String,
+    /// The kind of relationship (e.g. `satisfies`, `verifies`).
+    ///
+    /// `None` for untyped links.
+    pub link_type: Option<String>,
 }
 
 impl Requirement {
@@ -161,6 +221,10 @@ impl Requirement {
             uuid,
             hrid,
             created: Utc::now(),
+            status: None,
+            attributes: BTreeMap::new(),
+            test: None,
+            origin: None,
             parents: HashMap::new(),
         };
 
@@ -222,6 +286,66 @@ impl Requirement {
         self.metadata.created
     }
 
+    /// The lifecycle status of the requirement, if one has been set.
+    #[must_use]
+    pub fn status(&self) -> Option<&str> {
+        self.metadata.status.as_deref()
+    }
+
+    /// Set the lifecycle status of the requirement.
+    ///
+    /// The status is metadata and does not affect the fingerprint.
+    pub fn set_status(&mut self, status: Option<String>) {
+        self.metadata.status = status;
+    }
+
+    /// The custom attribute values of the requirement, keyed by name.
+    #[must_use]
+    pub const fn attributes(&self) -> &BTreeMap<String, AttributeValue> {
+        &self.metadata.attributes
+    }
+
+    /// Set or clear a custom attribute value.
+    ///
+    /// Attributes are metadata and do not affect the fingerprint. Returns the
+    /// previous value.
+    pub fn set_attribute(
+        &mut self,
+        name: String,
+        value: Option<AttributeValue>,
+    ) -> Option<AttributeValue> {
+        match value {
+            Some(value) => self.metadata.attributes.insert(name, value),
+            None => self.metadata.attributes.remove(&name),
+        }
+    }
+
+    /// The test this requirement describes, if any.
+    #[must_use]
+    pub const fn test(&self) -> Option<&TestMetadata> {
+        self.metadata.test.as_ref()
+    }
+
+    /// Set or clear the test this requirement describes.
+    ///
+    /// Test metadata does not affect the fingerprint.
+    pub fn set_test(&mut self, test: Option<TestMetadata>) {
+        self.metadata.test = test;
+    }
+
+    /// Where the requirement was imported from, if anywhere.
+    #[must_use]
+    pub const fn origin(&self) -> Option<&Origin> {
+        self.metadata.origin.as_ref()
+    }
+
+    /// Set or clear where the requirement was imported from.
+    ///
+    /// The origin does not affect the fingerprint.
+    pub fn set_origin(&mut self, origin: Option<Origin>) {
+        self.metadata.origin = origin;
+    }
+
     /// Returns a value generated by hashing the content of the Requirement.
     ///
     /// Any change to the requirement will change the fingerprint. This is used
@@ -272,6 +396,16 @@ impl Requirement {
         Ok(MarkdownRequirement::load(root, hrid, config)?.try_into()?)
     }
 
+    /// Reads a requirement from a specific file path.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file does not exist, cannot be read from, or has
+    /// malformed YAML frontmatter.
+    pub fn load_from_path(path: &Path) -> Result<Self, LoadError> {
+        Ok(MarkdownRequirement::load_from_path(path)?.try_into()?)
+    }
+
     /// Writes the requirement using the given configuration.
     ///
     /// The path construction respects the `subfolders_are_namespaces` setting:
@@ -1,11 +1,17 @@
 //! Decomposed requirement data: the content-and-timestamp payload stored
 //! per UUID by the [`Tree`](crate::domain::Tree).
 
-use std::collections::BTreeSet;
+use std::collections::{BTreeMap, BTreeSet};
 
 use chrono::{DateTime, Utc};
 
-use crate::Requirement;
+use crate::{
+    domain::{
+        requirement::{Origin, TestMetadata},
+        AttributeValue,
+    },
+    Requirement,
+};
 
 /// The core data of a requirement, excluding identity and relationship
 /// information.
@@ -23,6 +29,14 @@ pub struct RequirementData {
     pub tags: BTreeSet<String>,
     /// When the requirement was created.
     pub created: DateTime<Utc>,
+    /// Lifecycle status, if one has been set.
+    pub status: Option<String>,
+    /// Custom attribute values keyed by attribute name.
+    pub attributes: BTreeMap<String, AttributeValue>,
+    /// The test the requirement describes, if any.
+    pub test: Option<TestMetadata>,
+    /// Where the requirement was imported from, if anywhere.
+    pub origin: Option<Origin>,
 }
 
 impl From<Requirement> for RequirementData {
@@ -32,6 +46,10 @@ impl From<Requirement> for RequirementData {
             body: req.content.body,
             tags: req.content.tags,
             created: req.metadata.created,
+            status: req.metadata.status,
+            attributes: req.metadata.attributes,
+            test: req.metadata.test,
+            origin: req.metadata.origin,
         }
     }
 }
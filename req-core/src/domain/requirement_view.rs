@@ -1,15 +1,15 @@
 //! [`RequirementView`]: a borrowed view of a requirement for cheap read
 //! access and serialization.
 
-use std::collections::BTreeSet;
+use std::collections::{BTreeMap, BTreeSet};
 
 use chrono::{DateTime, Utc};
 use uuid::Uuid;
 
 use crate::{
     domain::{
-        requirement::{ContentRef, Parent},
-        Hrid,
+        requirement::{ContentRef, Origin, Parent, TestMetadata},
+        AttributeValue, Hrid,
     },
     Requirement,
 };
@@ -32,6 +32,14 @@ pub struct RequirementView<'a> {
     pub body: &'a str,
     /// The requirement's tags.
     pub tags: &'a BTreeSet<String>,
+    /// The requirement's lifecycle status, if one has been set.
+    pub status: Option<&'a str>,
+    /// The requirement's custom attribute values, keyed by name.
+    pub attributes: &'a BTreeMap<String, AttributeValue>,
+    /// The test the requirement describes, if any.
+    pub test: Option<&'a TestMetadata>,
+    /// Where the requirement was imported from, if anywhere.
+    pub origin: Option<&'a Origin>,
     /// Parent requirements (UUID → Parent info).
     ///
     /// Note: This is constructed on-demand from the graph, so it owns the data.
@@ -74,6 +82,10 @@ impl RequirementView<'_> {
                 uuid: *self.uuid,
                 hrid: self.hrid.clone(),
                 created: *self.created,
+                status: self.status.map(str::to_string),
+                attributes: self.attributes.clone(),
+                test: self.test.cloned(),
+                origin: self.origin.cloned(),
                 parents: self
                     .parents
                     .iter()
@@ -11,6 +11,11 @@
 //! - `link`: creating, removing, and renaming parent-child links
 //! - `cycle`: cycle detection and prevention
 //! - `suspect`: fingerprint-based change detection and HRID drift repair
+//! - `lifecycle`: status consistency checks across links
+//! - `rules`: kind-level link rules (allowed parent kinds and cardinality)
+//! - `impact`: which descendants a change would make suspect
+//! - `coverage`: how completely requirements are traced downstream
+//! - `verification`: rolling test results up the parent graph
 
 use std::{
     collections::{BTreeMap, BTreeSet, HashMap, VecDeque},
@@ -23,22 +28,32 @@ use uuid::Uuid;
 use crate::{
     domain::{
         hrid::{KindString, NamespaceSegment},
-        requirement::Parent,
+        requirement::{Origin, Parent, TestMetadata},
         requirement_data::RequirementData,
         requirement_view::RequirementView,
-        Hrid,
+        AttributeValue, FingerprintScheme, Hrid,
     },
     Requirement,
 };
 
+mod coverage;
 mod cycle;
 mod error;
+mod impact;
+mod lifecycle;
 mod link;
+mod rules;
 mod suspect;
+mod verification;
 
-pub use error::{AcceptLinkError, LinkError, LinkRequirementError, TreeInsertError};
+pub use coverage::{CoverageReport, KindCoverage};
+pub use error::{AcceptLinkError, LinkError, LinkRequirementError, LinkRuleError, TreeInsertError};
+pub use impact::ImpactedRequirement;
+pub use lifecycle::StatusConflict;
 pub use link::LinkOutcome;
+pub use rules::LinkRuleViolation;
 pub use suspect::SuspectLink;
+pub use verification::Verification;
 
 /// Data stored on each edge in the dependency graph.
 ///
@@ -55,6 +70,9 @@ struct EdgeData {
     /// created. Used to detect if the parent has been modified since the
     /// link was established.
     fingerprint: String,
+
+    /// The relationship type declared for the link, if any.
+    link_type: Option<String>,
 }
 
 /// An in-memory representation of the set of requirements with decomposed
@@ -83,6 +101,9 @@ pub struct Tree {
     /// Edge data contains parent HRID and fingerprint for change detection.
     /// This is the sole source of truth for parent relationships.
     graph: DiGraphMap<Uuid, EdgeData>,
+
+    /// The scheme new link fingerprints are calculated with.
+    fingerprint_scheme: FingerprintScheme,
 }
 
 impl Default for Tree {
@@ -92,6 +113,7 @@ impl Default for Tree {
             hrids: HashMap::new(),
             hrid_to_uuid: BTreeMap::new(),
             graph: DiGraphMap::new(),
+            fingerprint_scheme: FingerprintScheme::default(),
         }
     }
 }
@@ -106,9 +128,19 @@ impl Tree {
             hrids: HashMap::with_capacity(capacity),
             hrid_to_uuid: BTreeMap::new(),
             graph: DiGraphMap::with_capacity(capacity, capacity * 2),
+            fingerprint_scheme: FingerprintScheme::default(),
         }
     }
 
+    /// Sets the scheme fingerprints are calculated with when links are
+    /// created or accepted.
+    ///
+    /// Existing fingerprints are still checked with the scheme they were
+    /// produced with.
+    pub const fn set_fingerprint_scheme(&mut self, scheme: FingerprintScheme) {
+        self.fingerprint_scheme = scheme;
+    }
+
     /// Inserts a requirement into the tree.
     ///
     /// # Errors
@@ -145,6 +177,7 @@ impl Tree {
             let edge_data = EdgeData {
                 parent_hrid: parent_info.hrid.clone(),
                 fingerprint: parent_info.fingerprint.clone(),
+                link_type: parent_info.link_type.clone(),
             };
             self.graph.add_edge(uuid, *parent_uuid, edge_data);
         }
@@ -191,6 +224,10 @@ impl Tree {
                 uuid,
                 hrid: hrid.clone(),
                 created: data.created,
+                status: data.status.clone(),
+                attributes: data.attributes.clone(),
+                test: data.test.clone(),
+                origin: data.origin.clone(),
                 parents,
             },
         })
@@ -233,6 +270,69 @@ impl Tree {
         Some(changed)
     }
 
+    /// Sets the lifecycle status of the requirement with the given UUID.
+    ///
+    /// Returns `None` when no requirement with this UUID exists, otherwise
+    /// whether the status actually changed.
+    pub fn set_requirement_status(&mut self, uuid: Uuid, status: Option<String>) -> Option<bool> {
+        let data = self.requirements.get_mut(&uuid)?;
+        if data.status == status {
+            return Some(false);
+        }
+        data.status = status;
+        Some(true)
+    }
+
+    /// Sets or clears a custom attribute of the requirement with the given
+    /// UUID.
+    ///
+    /// Returns `None` when no requirement with this UUID exists, otherwise
+    /// whether the attribute actually changed.
+    pub fn set_requirement_attribute(
+        &mut self,
+        uuid: Uuid,
+        name: &str,
+        value: Option<AttributeValue>,
+    ) -> Option<bool> {
+        let data = self.requirements.get_mut(&uuid)?;
+        if data.attributes.get(name) == value.as_ref() {
+            return Some(false);
+        }
+        match value {
+            Some(value) => data.attributes.insert(name.to_string(), value),
+            None => data.attributes.remove(name),
+        };
+        Some(true)
+    }
+
+    /// Sets or clears the test described by the requirement with the given
+    /// UUID.
+    ///
+    /// Returns `None` when no requirement with this UUID exists, otherwise
+    /// whether the test metadata actually changed.
+    pub fn set_requirement_test(&mut self, uuid: Uuid, test: Option<TestMetadata>) -> Option<bool> {
+        let data = self.requirements.get_mut(&uuid)?;
+        if data.test == test {
+            return Some(false);
+        }
+        data.test = test;
+        Some(true)
+    }
+
+    /// Sets or clears where the requirement with the given UUID was imported
+    /// from.
+    ///
+    /// Returns `None` when no requirement with this UUID exists, otherwise
+    /// whether the origin actually changed.
+    pub fn set_requirement_origin(&mut self, uuid: Uuid, origin: Option<Origin>) -> Option<bool> {
+        let data = self.requirements.get_mut(&uuid)?;
+        if data.origin == origin {
+            return Some(false);
+        }
+        data.origin = origin;
+        Some(true)
+    }
+
     /// Retrieves a requirement by UUID as a borrowed view.
     ///
     /// Note: Since UUID is passed by value, we need to find a way to get a
@@ -257,6 +357,10 @@ impl Tree {
             title: &data.title,
             body: &data.body,
             tags: &data.tags,
+            status: data.status.as_deref(),
+            attributes: &data.attributes,
+            test: data.test.as_ref(),
+            origin: data.origin.as_ref(),
             parents,
             children,
         })
@@ -309,6 +413,10 @@ impl Tree {
                 title: &data.title,
                 body: &data.body,
                 tags: &data.tags,
+                status: data.status.as_deref(),
+                attributes: &data.attributes,
+                test: data.test.as_ref(),
+                origin: data.origin.as_ref(),
                 parents: self.parent_links(*uuid),
                 children: self.child_uuids(*uuid),
             })
@@ -325,6 +433,7 @@ impl Tree {
                     Parent {
                         hrid: edge_data.parent_hrid.clone(),
                         fingerprint: edge_data.fingerprint.clone(),
+                        link_type: edge_data.link_type.clone(),
                     },
                 )
             })
@@ -380,6 +489,68 @@ impl Tree {
         Ok(())
     }
 
+    /// Replaces a requirement with a new version of it (e.g. reloaded from
+    /// disk), inserting it if it isn't in the tree.
+    ///
+    /// Unlike removing and re-inserting, the links from its children are
+    /// kept.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if another requirement already has the new HRID.
+    pub fn replace(&mut self, requirement: Requirement) -> Result<(), TreeInsertError> {
+        let uuid = requirement.metadata.uuid;
+        if let Some(&existing_uuid) = self
+            .hrid_to_uuid
+            .get(&requirement.metadata.hrid)
+            .filter(|&&existing_uuid| existing_uuid != uuid)
+        {
+            return Err(TreeInsertError::DuplicateHrid {
+                hrid: requirement.metadata.hrid,
+                new_uuid: uuid,
+                existing_uuid,
+            });
+        }
+        self.unload(uuid);
+        self.insert(requirement)
+    }
+
+    /// Unloads a requirement (e.g. because its file was deleted), returning
+    /// its HRID.
+    ///
+    /// Unlike [`Tree::remove_requirement`], the links from its children are
+    /// kept, so they point at a parent that doesn't exist, just as if the
+    /// tree had been loaded without it.
+    pub fn unload(&mut self, uuid: Uuid) -> Option<Hrid> {
+        self.requirements.remove(&uuid)?;
+        let hrid = self.hrids.remove(&uuid)?;
+        self.hrid_to_uuid.remove(&hrid);
+
+        let parents: Vec<Uuid> = self
+            .graph
+            .neighbors_directed(uuid, petgraph::Direction::Outgoing)
+            .collect();
+        for parent in parents {
+            self.graph.remove_edge(uuid, parent);
+            self.remove_if_unreferenced(parent);
+        }
+        self.remove_if_unreferenced(uuid);
+        Some(hrid)
+    }
+
+    /// Removes the node of a requirement that isn't in the tree, once no
+    /// child links to it.
+    fn remove_if_unreferenced(&mut self, uuid: Uuid) {
+        let referenced = self
+            .graph
+            .neighbors_directed(uuid, petgraph::Direction::Incoming)
+            .next()
+            .is_some();
+        if !referenced && !self.requirements.contains_key(&uuid) {
+            self.graph.remove_node(uuid);
+        }
+    }
+
     /// Get all children of a requirement.
     #[must_use]
     pub fn children(&self, uuid: Uuid) -> Vec<Uuid> {
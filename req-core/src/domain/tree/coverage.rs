@@ -0,0 +1,263 @@
+//! Coverage analysis: how completely requirements are traced to the kinds
+//! downstream of them.
+//!
+//! A kind's *downstream kinds* are the kinds expected to link to it as
+//! parents. They are taken from the link rules when any kind lists it in
+//! `parent_kinds`, and are otherwise inferred from the links that exist. A
+//! requirement is *covered* when it has a child of a downstream kind. Kinds
+//! without downstream kinds (such as tests) are leaves, and aren't measured.
+//!
+//! Similarly, a requirement is an *orphan* when it has no parents but its
+//! kind is expected to have them: its kind has link rules for parents, or
+//! other requirements of its kind are linked to parents.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use petgraph::Direction;
+
+use super::Tree;
+use crate::domain::{Config, Hrid};
+
+/// Coverage of the requirements of one kind.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KindCoverage {
+    /// The kind.
+    pub kind: String,
+    /// The number of requirements of this kind.
+    pub total: usize,
+    /// The kinds expected to link to this kind as parents. Empty for leaf
+    /// kinds.
+    pub downstream_kinds: Vec<String>,
+    /// Requirements with no children of a downstream kind, sorted by HRID.
+    pub uncovered: Vec<Hrid>,
+    /// Requirements with no parents although their kind is expected to have
+    /// them, sorted by HRID.
+    pub orphans: Vec<Hrid>,
+}
+
+impl KindCoverage {
+    /// The number of requirements with children of a downstream kind.
+    #[must_use]
+    pub fn covered(&self) -> usize {
+        self.total - self.uncovered.len()
+    }
+
+    /// Checks whether this kind is a leaf (has no downstream kinds), in which
+    /// case its coverage isn't measured.
+    #[must_use]
+    pub fn is_leaf(&self) -> bool {
+        self.downstream_kinds.is_empty()
+    }
+
+    /// The percentage of requirements that are covered, or `None` for leaf
+    /// kinds.
+    #[must_use]
+    pub fn percentage(&self) -> Option<f64> {
+        (!self.is_leaf()).then(|| percentage(self.covered(), self.total))
+    }
+
+    /// Checks whether at least `min` percent of the requirements are
+    /// covered. Leaf kinds always meet the threshold.
+    #[must_use]
+    pub fn meets(&self, min: u8) -> bool {
+        self.is_leaf() || self.covered() * 100 >= min as usize * self.total
+    }
+}
+
+/// The coverage of every kind of requirement.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CoverageReport {
+    /// Coverage by kind, sorted by kind.
+    pub kinds: Vec<KindCoverage>,
+}
+
+impl CoverageReport {
+    /// The percentage of requirements of measured (non-leaf) kinds that are
+    /// covered, or `None` if no kind is measured.
+    #[must_use]
+    pub fn overall(&self) -> Option<f64> {
+        let measured = self.kinds.iter().filter(|kind| !kind.is_leaf());
+        let (covered, total) = measured.fold((0, 0), |(covered, total), kind| {
+            (covered + kind.covered(), total + kind.total)
+        });
+        (total > 0).then(|| percentage(covered, total))
+    }
+}
+
+#[allow(clippy::cast_precision_loss)] // requirement counts are far below 2^52
+fn percentage(covered: usize, total: usize) -> f64 {
+    if total == 0 {
+        return 100.0;
+    }
+    covered as f64 * 100.0 / total as f64
+}
+
+impl Tree {
+    /// Analyse how completely each kind of requirement is traced to the
+    /// kinds downstream of it.
+    #[must_use]
+    pub fn coverage(&self, config: &Config) -> CoverageReport {
+        let kind_of = |uuid| self.hrids.get(&uuid).map(Hrid::kind);
+
+        let mut explicit: BTreeMap<&str, BTreeSet<&str>> = BTreeMap::new();
+        for (kind, metadata) in config.kind_metadata() {
+            for parent_kind in &metadata.parent_kinds {
+                explicit.entry(parent_kind).or_default().insert(kind);
+            }
+        }
+
+        let mut inferred: BTreeMap<&str, BTreeSet<&str>> = BTreeMap::new();
+        for (child, parent, _) in self.graph.all_edges() {
+            if let (Some(child_kind), Some(parent_kind)) = (kind_of(child), kind_of(parent)) {
+                inferred.entry(parent_kind).or_default().insert(child_kind);
+            }
+        }
+        let linked_child_kinds: BTreeSet<&str> = inferred.values().flatten().copied().collect();
+
+        let mut by_kind: BTreeMap<&str, Vec<(uuid::Uuid, &Hrid)>> = BTreeMap::new();
+        for (uuid, hrid) in &self.hrids {
+            by_kind.entry(hrid.kind()).or_default().push((*uuid, hrid));
+        }
+
+        let kinds = by_kind
+            .into_iter()
+            .map(|(kind, requirements)| {
+                let downstream = explicit
+                    .get(kind)
+                    .or_else(|| inferred.get(kind))
+                    .cloned()
+                    .unwrap_or_default();
+                let expects_parents = linked_child_kinds.contains(kind)
+                    || config.metadata_for_kind(kind).is_some_and(|metadata| {
+                        !metadata.parent_kinds.is_empty() || metadata.min_parents > Some(0)
+                    });
+
+                let mut uncovered = Vec::new();
+                let mut orphans = Vec::new();
+                for (uuid, hrid) in &requirements {
+                    let covered = self
+                        .graph
+                        .neighbors_directed(*uuid, Direction::Incoming)
+                        .any(|child| kind_of(child).is_some_and(|k| downstream.contains(k)));
+                    if !downstream.is_empty() && !covered {
+                        uncovered.push((*hrid).clone());
+                    }
+                    if expects_parents && self.graph.edges(*uuid).next().is_none() {
+                        orphans.push((*hrid).clone());
+                    }
+                }
+                uncovered.sort();
+                orphans.sort();
+
+                KindCoverage {
+                    kind: kind.to_string(),
+                    total: requirements.len(),
+                    downstream_kinds: downstream.into_iter().map(str::to_string).collect(),
+                    uncovered,
+                    orphans,
+                }
+            })
+            .collect();
+
+        CoverageReport { kinds }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::num::NonZeroUsize;
+
+    use super::*;
+    use crate::{
+        domain::{hrid::KindString, requirement::Parent},
+        Requirement,
+    };
+
+    fn hrid(kind: &str, id: usize) -> Hrid {
+        Hrid::new(
+            KindString::new(kind.to_string()).unwrap(),
+            NonZeroUsize::new(id).unwrap(),
+        )
+    }
+
+    fn requirement(kind: &str, id: usize, parents: &[&Requirement]) -> Requirement {
+        let mut requirement = Requirement::new(hrid(kind, id), String::new(), String::new());
+        for parent in parents {
+            requirement.add_parent(
+                parent.uuid(),
+                Parent {
+                    hrid: parent.hrid().clone(),
+                    fingerprint: parent.fingerprint(),
+                    link_type: None,
+                },
+            );
+        }
+        requirement
+    }
+
+    fn tree() -> Tree {
+        let usr1 = requirement("USR", 1, &[]);
+        let usr2 = requirement("USR", 2, &[]);
+        let sys1 = requirement("SYS", 1, &[&usr1]);
+        let sys2 = requirement("SYS", 2, &[]);
+        let tst1 = requirement("TST", 1, &[&sys1]);
+
+        let mut tree = Tree::default();
+        for requirement in [usr1, usr2, sys1, sys2, tst1] {
+            tree.insert(requirement).unwrap();
+        }
+        tree
+    }
+
+    #[test]
+    fn coverage_infers_downstream_kinds_from_links() {
+        let report = tree().coverage(&Config::default());
+        let kinds: Vec<_> = report
+            .kinds
+            .iter()
+            .map(|kind| {
+                (
+                    kind.kind.as_str(),
+                    kind.downstream_kinds.clone(),
+                    kind.uncovered.clone(),
+                    kind.orphans.clone(),
+                )
+            })
+            .collect();
+
+        assert_eq!(
+            kinds,
+            [
+                (
+                    "SYS",
+                    vec!["TST".to_string()],
+                    vec![hrid("SYS", 2)],
+                    vec![hrid("SYS", 2)]
+                ),
+                ("TST", vec![], vec![], vec![]),
+                ("USR", vec!["SYS".to_string()], vec![hrid("USR", 2)], vec![]),
+            ]
+        );
+        assert_eq!(report.kinds[0].percentage(), Some(50.0));
+        assert_eq!(report.kinds[1].percentage(), None);
+        assert!(report.kinds[0].meets(50));
+        assert!(!report.kinds[0].meets(51));
+        assert_eq!(report.overall(), Some(50.0));
+    }
+
+    #[test]
+    fn link_rules_declare_downstream_kinds() {
+        let config: Config = toml::from_str(
+            r#"_version = "1"
+allowed_kinds = ["USR", "SYS", { kind = "TST", parent_kinds = ["SYS", "USR"] }]
+"#,
+        )
+        .unwrap();
+        let report = tree().coverage(&config);
+
+        let usr = &report.kinds[2];
+        assert_eq!(usr.downstream_kinds, ["TST"]);
+        assert_eq!(usr.uncovered, [hrid("USR", 1), hrid("USR", 2)]);
+        assert_eq!(usr.percentage(), Some(0.0));
+    }
+}
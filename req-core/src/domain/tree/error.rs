@@ -98,4 +98,54 @@ pub enum LinkRequirementError {
     /// The link would create a cycle in the requirement graph.
     #[error("{0}")]
     WouldCreateCycle(String),
+
+    /// The link breaks the link rules of the child's kind.
+    #[error(transparent)]
+    BreaksLinkRule(#[from] LinkRuleError),
+
+    /// The link type is not declared in the configuration.
+    #[error("link type '{link_type}' is not allowed (allowed link types: {allowed_link_types})")]
+    DisallowedLinkType {
+        /// The link type that was rejected.
+        link_type: String,
+        /// The list of allowed link types.
+        allowed_link_types: String,
+    },
+}
+
+/// A way a requirement can break the link rules of its kind.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum LinkRuleError {
+    /// The parent's kind is not an allowed parent kind.
+    #[error("{kind} requirements may only have parents of kind {allowed} (not {parent_kind})")]
+    DisallowedParentKind {
+        /// The kind of the child requirement.
+        kind: String,
+        /// The kind of the rejected parent.
+        parent_kind: String,
+        /// The allowed parent kinds, comma separated.
+        allowed: String,
+    },
+
+    /// The requirement has more parents than its kind allows.
+    #[error("{kind} requirements may have at most {max} parent(s) (got {count})")]
+    TooManyParents {
+        /// The kind of the requirement.
+        kind: String,
+        /// The maximum number of parents.
+        max: usize,
+        /// The number of parents the requirement has (or would have).
+        count: usize,
+    },
+
+    /// The requirement has fewer parents than its kind requires.
+    #[error("{kind} requirements must have at least {min} parent(s) (got {count})")]
+    TooFewParents {
+        /// The kind of the requirement.
+        kind: String,
+        /// The minimum number of parents.
+        min: usize,
+        /// The number of parents the requirement has.
+        count: usize,
+    },
 }
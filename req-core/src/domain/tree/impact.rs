@@ -0,0 +1,157 @@
+//! Impact analysis: which requirements a change would make suspect.
+//!
+//! Changing a requirement makes the links from its children suspect. Each
+//! child may then need updating, which in turn makes the links from its own
+//! children suspect, so the impact of a change reaches every descendant,
+//! level by level.
+
+use std::collections::{BTreeSet, VecDeque};
+
+use petgraph::Direction;
+use uuid::Uuid;
+
+use super::Tree;
+use crate::domain::{requirement::ContentRef, FingerprintScheme, Hrid};
+
+/// A requirement that would become suspect after a change to an ancestor.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ImpactedRequirement {
+    /// The UUID of the impacted requirement.
+    pub uuid: Uuid,
+    /// The HRID of the impacted requirement.
+    pub hrid: Hrid,
+    /// The number of links between the changed requirement and this one.
+    ///
+    /// Links from requirements at depth 1 become suspect as soon as the
+    /// change is made; deeper requirements become suspect once the level
+    /// above is updated in response.
+    pub depth: usize,
+}
+
+impl Tree {
+    /// Find the descendants of a requirement that a change to it would make
+    /// suspect.
+    ///
+    /// If `content` is given, it is the changed content of the requirement,
+    /// and only children whose stored fingerprints don't match it are
+    /// impacted directly (e.g. a reflow under whitespace normalisation
+    /// impacts nothing). Otherwise any change is assumed.
+    ///
+    /// Each descendant is reported once, at its shortest depth. Results are
+    /// sorted by depth, then HRID.
+    #[must_use]
+    pub fn impact_of(
+        &self,
+        uuid: Uuid,
+        content: Option<ContentRef<'_>>,
+    ) -> Vec<ImpactedRequirement> {
+        if !self.graph.contains_node(uuid) {
+            return Vec::new();
+        }
+
+        let direct = self
+            .graph
+            .edges_directed(uuid, Direction::Incoming)
+            .filter(|(_, _, edge)| {
+                content.is_none_or(|content| {
+                    FingerprintScheme::of(&edge.fingerprint)
+                        .unwrap_or(self.fingerprint_scheme)
+                        .fingerprint(content)
+                        != edge.fingerprint
+                })
+            })
+            .map(|(child, _, _)| (child, 1));
+
+        let mut visited = BTreeSet::from([uuid]);
+        let mut queue: VecDeque<(Uuid, usize)> = direct.collect();
+        let mut impacted = Vec::new();
+
+        while let Some((next, depth)) = queue.pop_front() {
+            if !visited.insert(next) {
+                continue;
+            }
+            if let Some(hrid) = self.hrids.get(&next) {
+                impacted.push(ImpactedRequirement {
+                    uuid: next,
+                    hrid: hrid.clone(),
+                    depth,
+                });
+            }
+            for child in self.graph.neighbors_directed(next, Direction::Incoming) {
+                queue.push_back((child, depth + 1));
+            }
+        }
+
+        impacted.sort_by(|a, b| (a.depth, &a.hrid).cmp(&(b.depth, &b.hrid)));
+        impacted
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::BTreeSet, num::NonZeroUsize};
+
+    use super::*;
+    use crate::{
+        domain::{hrid::KindString, requirement::Parent},
+        Requirement,
+    };
+
+    fn hrid(kind: &str, id: usize) -> Hrid {
+        Hrid::new(
+            KindString::new(kind.to_string()).unwrap(),
+            NonZeroUsize::new(id).unwrap(),
+        )
+    }
+
+    fn link(child: &mut Requirement, parent: &Requirement) {
+        child.add_parent(
+            parent.uuid(),
+            Parent {
+                hrid: parent.hrid().clone(),
+                fingerprint: parent.fingerprint(),
+                link_type: None,
+            },
+        );
+    }
+
+    #[test]
+    fn impact_reaches_descendants_by_shortest_depth() {
+        let usr = Requirement::new(hrid("USR", 1), "User".into(), "Body".into());
+        let mut sys = Requirement::new(hrid("SYS", 1), "System".into(), String::new());
+        let mut tst1 = Requirement::new(hrid("TST", 1), "Test".into(), String::new());
+        let mut tst2 = Requirement::new(hrid("TST", 2), "Test".into(), String::new());
+        link(&mut sys, &usr);
+        link(&mut tst1, &sys);
+        link(&mut tst2, &sys);
+        link(&mut tst2, &usr);
+        let usr_uuid = usr.uuid();
+
+        let mut tree = Tree::default();
+        for requirement in [usr, sys, tst1, tst2] {
+            tree.insert(requirement).unwrap();
+        }
+
+        let impact: Vec<_> = tree
+            .impact_of(usr_uuid, None)
+            .into_iter()
+            .map(|impacted| (impacted.hrid, impacted.depth))
+            .collect();
+        assert_eq!(
+            impact,
+            [
+                (hrid("SYS", 1), 1),
+                (hrid("TST", 2), 1),
+                (hrid("TST", 1), 2)
+            ]
+        );
+
+        let tags = BTreeSet::new();
+        let unchanged = ContentRef {
+            title: "New title",
+            body: "Body",
+            tags: &tags,
+        };
+        assert!(tree.impact_of(usr_uuid, Some(unchanged)).is_empty());
+    }
+}
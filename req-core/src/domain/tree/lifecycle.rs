@@ -0,0 +1,122 @@
+//! Lifecycle consistency checks across parent-child links.
+//!
+//! A link is a *status conflict* when an approved child depends on a parent
+//! that is still a draft: the child was signed off against content that has
+//! not itself been agreed.
+
+use uuid::Uuid;
+
+use super::Tree;
+use crate::domain::Hrid;
+
+/// Status of a requirement that has been signed off.
+const APPROVED: &str = "approved";
+
+/// Status of a requirement that is still being written.
+const DRAFT: &str = "draft";
+
+/// A link from an approved child to a draft parent.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StatusConflict {
+    /// The UUID of the child requirement.
+    pub child_uuid: Uuid,
+    /// The HRID of the child requirement.
+    pub child_hrid: Hrid,
+    /// The UUID of the parent requirement.
+    pub parent_uuid: Uuid,
+    /// The HRID of the parent requirement.
+    pub parent_hrid: Hrid,
+}
+
+impl Tree {
+    /// Find all links from approved children to draft parents.
+    ///
+    /// Results are sorted by child HRID, then parent HRID.
+    #[must_use]
+    pub fn status_conflicts(&self) -> Vec<StatusConflict> {
+        let mut conflicts = Vec::new();
+
+        for (child_uuid, data) in &self.requirements {
+            if data.status.as_deref() != Some(APPROVED) {
+                continue;
+            }
+            let Some(child_hrid) = self.hrids.get(child_uuid) else {
+                continue;
+            };
+
+            for (_, parent_uuid, _) in self.graph.edges(*child_uuid) {
+                let is_draft = self
+                    .requirements
+                    .get(&parent_uuid)
+                    .is_some_and(|parent| parent.status.as_deref() == Some(DRAFT));
+                let Some(parent_hrid) = self.hrids.get(&parent_uuid) else {
+                    continue;
+                };
+                if is_draft {
+                    conflicts.push(StatusConflict {
+                        child_uuid: *child_uuid,
+                        child_hrid: child_hrid.clone(),
+                        parent_uuid,
+                        parent_hrid: parent_hrid.clone(),
+                    });
+                }
+            }
+        }
+
+        conflicts.sort_by(|a, b| {
+            a.child_hrid
+                .cmp(&b.child_hrid)
+                .then_with(|| a.parent_hrid.cmp(&b.parent_hrid))
+        });
+        conflicts
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::num::NonZeroUsize;
+
+    use super::*;
+    use crate::{
+        domain::{hrid::KindString, requirement::Parent},
+        Requirement,
+    };
+
+    fn hrid(kind: &str, id: usize) -> Hrid {
+        Hrid::new(
+            KindString::new(kind.to_string()).unwrap(),
+            NonZeroUsize::new(id).unwrap(),
+        )
+    }
+
+    #[test]
+    fn approved_child_of_draft_parent_conflicts() {
+        let mut tree = Tree::default();
+
+        let mut parent = Requirement::new(hrid("USR", 1), "Parent".into(), String::new());
+        parent.set_status(Some("draft".to_string()));
+        let parent_uuid = parent.uuid();
+
+        let mut child = Requirement::new(hrid("SYS", 1), "Child".into(), String::new());
+        child.set_status(Some("approved".to_string()));
+        child.add_parent(
+            parent_uuid,
+            Parent {
+                hrid: parent.hrid().clone(),
+                fingerprint: parent.fingerprint(),
+                link_type: None,
+            },
+        );
+
+        tree.insert(parent).unwrap();
+        tree.insert(child).unwrap();
+
+        let conflicts = tree.status_conflicts();
+        assert_eq!(conflicts.len(), 1);
+        assert_eq!(conflicts[0].child_hrid, hrid("SYS", 1));
+        assert_eq!(conflicts[0].parent_hrid, hrid("USR", 1));
+
+        tree.set_requirement_status(parent_uuid, Some("approved".to_string()));
+        assert!(tree.status_conflicts().is_empty());
+    }
+}
@@ -18,6 +18,8 @@ pub struct LinkOutcome {
     pub parent_hrid: Hrid,
     /// Whether the relationship already existed prior to linking.
     pub already_linked: bool,
+    /// The relationship type recorded on the link after linking.
+    pub link_type: Option<String>,
 }
 
 impl Tree {
@@ -32,6 +34,26 @@ impl Tree {
         &mut self,
         child: &Hrid,
         parent: &Hrid,
+    ) -> Result<LinkOutcome, LinkRequirementError> {
+        self.link_requirement_with_type(child, parent, None)
+    }
+
+    /// Link two requirements identified by their HRIDs with a typed
+    /// relationship.
+    ///
+    /// When `link_type` is `None` and the link already exists, the existing
+    /// type is kept; otherwise the given type replaces it.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when either HRID does not exist in the tree, when
+    /// the parent/child UUIDs cannot be linked, or when the link would create a
+    /// cycle.
+    pub fn link_requirement_with_type(
+        &mut self,
+        child: &Hrid,
+        parent: &Hrid,
+        link_type: Option<String>,
     ) -> Result<LinkOutcome, LinkRequirementError> {
         let (child_uuid, child_hrid) = {
             let view = self
@@ -40,12 +62,15 @@ impl Tree {
             (*view.uuid, view.hrid.clone())
         };
 
-        let (parent_uuid, parent_hrid, parent_fingerprint) = {
+        let (parent_uuid, parent_hrid) = {
             let view = self
                 .find_by_hrid(parent)
                 .ok_or_else(|| LinkRequirementError::ParentNotFound(parent.clone()))?;
-            (*view.uuid, view.hrid.clone(), view.fingerprint())
+            (*view.uuid, view.hrid.clone())
         };
+        let parent_fingerprint = self
+            .fingerprint_with(parent_uuid, self.fingerprint_scheme)
+            .ok_or_else(|| LinkRequirementError::ParentNotFound(parent.clone()))?;
 
         // Check if this link would create a cycle
         self.check_would_create_cycle(child_uuid, parent_uuid)
@@ -63,12 +88,22 @@ impl Tree {
                 }
             })?;
 
+        let edge = self
+            .graph
+            .edge_weight_mut(child_uuid, parent_uuid)
+            .ok_or_else(|| LinkRequirementError::ParentNotFound(parent_hrid.clone()))?;
+        if link_type.is_some() {
+            edge.link_type = link_type;
+        }
+        let link_type = edge.link_type.clone();
+
         Ok(LinkOutcome {
             child_uuid,
             child_hrid,
             parent_uuid,
             parent_hrid,
             already_linked,
+            link_type,
         })
     }
 
@@ -108,6 +143,24 @@ impl Tree {
         Ok(child_uuid)
     }
 
+    /// Sets or clears the type of an existing link, keeping its fingerprint.
+    ///
+    /// Returns `None` when the requirements aren't linked, otherwise whether
+    /// the type actually changed.
+    pub fn set_link_type(
+        &mut self,
+        child: Uuid,
+        parent: Uuid,
+        link_type: Option<String>,
+    ) -> Option<bool> {
+        let edge = self.graph.edge_weight_mut(child, parent)?;
+        if edge.link_type == link_type {
+            return Some(false);
+        }
+        edge.link_type = link_type;
+        Some(true)
+    }
+
     /// Renames a requirement by changing its HRID.
     ///
     /// This updates:
@@ -170,7 +223,7 @@ impl Tree {
     /// Insert or update a parent link for the given child UUID.
     ///
     /// Returns `Ok(true)` if an existing link was replaced, or `Ok(false)` if a
-    /// new link was created.
+    /// new link was created. Replacing a link keeps its relationship type.
     ///
     /// # Errors
     ///
@@ -199,9 +252,15 @@ impl Tree {
             .get(&parent_uuid)
             .ok_or(LinkError::ParentNotFound(parent_uuid))?;
 
+        let link_type = self
+            .graph
+            .edge_weight(child_uuid, parent_uuid)
+            .and_then(|edge| edge.link_type.clone());
+
         let edge = EdgeData {
             parent_hrid: parent_hrid.clone(),
             fingerprint,
+            link_type,
         };
 
         Ok(self.graph.add_edge(child_uuid, parent_uuid, edge).is_some())
@@ -322,4 +381,26 @@ mod tests {
         assert_eq!(parents.len(), 1, "Child should have one parent");
         assert_eq!(parents[0].0, parent_uuid, "Parent UUID should match");
     }
+
+    #[test]
+    fn relinking_without_type_keeps_existing_type() {
+        let mut tree = Tree::default();
+        let parent = Requirement::new("SYS-001".parse().unwrap(), String::new(), String::new());
+        let child = Requirement::new("TST-001".parse().unwrap(), String::new(), String::new());
+        let child_uuid = child.uuid();
+        tree.insert(parent.clone()).unwrap();
+        tree.insert(child.clone()).unwrap();
+
+        let outcome = tree
+            .link_requirement_with_type(child.hrid(), parent.hrid(), Some("verifies".to_string()))
+            .unwrap();
+        assert_eq!(outcome.link_type.as_deref(), Some("verifies"));
+
+        let outcome = tree.link_requirement(child.hrid(), parent.hrid()).unwrap();
+        assert!(outcome.already_linked);
+        assert_eq!(outcome.link_type.as_deref(), Some("verifies"));
+
+        let view = tree.requirement(child_uuid).unwrap();
+        assert_eq!(view.parents[0].1.link_type.as_deref(), Some("verifies"));
+    }
 }
@@ -0,0 +1,274 @@
+//! Kind-level link rules.
+//!
+//! A kind may restrict which kinds its requirements link to as parents, and
+//! how many parents they have (see [`KindMetadata`]). New links are checked
+//! before they are made; existing links are reported as violations.
+
+use super::{LinkRequirementError, LinkRuleError, Tree};
+use crate::domain::{Config, Hrid, KindMetadata};
+
+/// A requirement that breaks the link rules of its kind.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LinkRuleViolation {
+    /// The HRID of the offending requirement.
+    pub hrid: Hrid,
+    /// The parent whose link breaks the rules, if the rule concerns a single
+    /// link.
+    pub parent: Option<Hrid>,
+    /// The rule that is broken.
+    pub error: LinkRuleError,
+}
+
+impl Tree {
+    /// Checks a new link against the link rules of the child's kind.
+    ///
+    /// Relinking an existing parent does not count towards the maximum number
+    /// of parents. The minimum number of parents is not checked here: links
+    /// are added one at a time, so it can only be checked once linking is
+    /// done (see [`Self::link_rule_violations`]).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if either requirement does not exist, or if the link
+    /// would break the rules.
+    pub fn check_link_rules(
+        &self,
+        child: &Hrid,
+        parent: &Hrid,
+        config: &Config,
+    ) -> Result<(), LinkRequirementError> {
+        let child_uuid = *self
+            .hrid_to_uuid
+            .get(child)
+            .ok_or_else(|| LinkRequirementError::ChildNotFound(child.clone()))?;
+        let parent_uuid = *self
+            .hrid_to_uuid
+            .get(parent)
+            .ok_or_else(|| LinkRequirementError::ParentNotFound(parent.clone()))?;
+
+        let Some(rules) = config.metadata_for_kind(child.kind()) else {
+            return Ok(());
+        };
+
+        if let Some(error) = disallowed_parent(child.kind(), parent.kind(), rules) {
+            return Err(error.into());
+        }
+
+        if !self.graph.contains_edge(child_uuid, parent_uuid) {
+            let count = self.graph.edges(child_uuid).count() + 1;
+            if let Some(max) = rules.max_parents.filter(|&max| count > max) {
+                return Err(LinkRuleError::TooManyParents {
+                    kind: child.kind().to_string(),
+                    max,
+                    count,
+                }
+                .into());
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Find all requirements that break the link rules of their kind.
+    ///
+    /// Results are sorted by HRID, then parent HRID.
+    #[must_use]
+    pub fn link_rule_violations(&self, config: &Config) -> Vec<LinkRuleViolation> {
+        let mut violations = Vec::new();
+
+        for (uuid, hrid) in &self.hrids {
+            let kind = hrid.kind();
+            let Some(rules) = config.metadata_for_kind(kind) else {
+                continue;
+            };
+
+            let parents: Vec<&Hrid> = self
+                .graph
+                .edges(*uuid)
+                .map(|(_, parent_uuid, edge)| {
+                    self.hrids.get(&parent_uuid).unwrap_or(&edge.parent_hrid)
+                })
+                .collect();
+
+            for parent in &parents {
+                if let Some(error) = disallowed_parent(kind, parent.kind(), rules) {
+                    violations.push(LinkRuleViolation {
+                        hrid: hrid.clone(),
+                        parent: Some((*parent).clone()),
+                        error,
+                    });
+                }
+            }
+
+            let count = parents.len();
+            let too_few = rules.min_parents.filter(|&min| count < min).map(|min| {
+                LinkRuleError::TooFewParents {
+                    kind: kind.to_string(),
+                    min,
+                    count,
+                }
+            });
+            let too_many = || {
+                rules.max_parents.filter(|&max| count > max).map(|max| {
+                    LinkRuleError::TooManyParents {
+                        kind: kind.to_string(),
+                        max,
+                        count,
+                    }
+                })
+            };
+            let error = too_few.or_else(too_many);
+            if let Some(error) = error {
+                violations.push(LinkRuleViolation {
+                    hrid: hrid.clone(),
+                    parent: None,
+                    error,
+                });
+            }
+        }
+
+        violations.sort_by(|a, b| (&a.hrid, &a.parent).cmp(&(&b.hrid, &b.parent)));
+        violations
+    }
+}
+
+/// Returns the error for a parent kind the rules don't allow.
+fn disallowed_parent(kind: &str, parent_kind: &str, rules: &KindMetadata) -> Option<LinkRuleError> {
+    (!rules.allows_parent_kind(parent_kind)).then(|| LinkRuleError::DisallowedParentKind {
+        kind: kind.to_string(),
+        parent_kind: parent_kind.to_string(),
+        allowed: rules.parent_kinds.join(", "),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::num::NonZeroUsize;
+
+    use super::*;
+    use crate::{
+        domain::{hrid::KindString, requirement::Parent},
+        Requirement,
+    };
+
+    fn hrid(kind: &str, id: usize) -> Hrid {
+        Hrid::new(
+            KindString::new(kind.to_string()).unwrap(),
+            NonZeroUsize::new(id).unwrap(),
+        )
+    }
+
+    fn config() -> Config {
+        toml::from_str(
+            r#"_version = "1"
+allowed_kinds = [
+    "USR",
+    { kind = "SYS", parent_kinds = ["usr"], min_parents = 1, max_parents = 1 },
+]
+"#,
+        )
+        .unwrap()
+    }
+
+    fn link(child: &mut Requirement, parent: &Requirement) {
+        child.add_parent(
+            parent.uuid(),
+            Parent {
+                hrid: parent.hrid().clone(),
+                fingerprint: parent.fingerprint(),
+                link_type: None,
+            },
+        );
+    }
+
+    #[test]
+    fn check_link_rules_rejects_disallowed_kinds_and_extra_parents() {
+        let mut tree = Tree::default();
+        let usr1 = Requirement::new(hrid("USR", 1), "User".into(), String::new());
+        let usr2 = Requirement::new(hrid("USR", 2), "User".into(), String::new());
+        let sys1 = Requirement::new(hrid("SYS", 1), "System".into(), String::new());
+        let mut sys2 = Requirement::new(hrid("SYS", 2), "System".into(), String::new());
+        link(&mut sys2, &usr1);
+        for requirement in [usr1, usr2, sys1, sys2] {
+            tree.insert(requirement).unwrap();
+        }
+        let config = config();
+
+        assert!(tree
+            .check_link_rules(&hrid("SYS", 1), &hrid("USR", 1), &config)
+            .is_ok());
+        assert!(tree
+            .check_link_rules(&hrid("SYS", 2), &hrid("USR", 1), &config)
+            .is_ok());
+        assert!(matches!(
+            tree.check_link_rules(&hrid("SYS", 1), &hrid("SYS", 2), &config),
+            Err(LinkRequirementError::BreaksLinkRule(
+                LinkRuleError::DisallowedParentKind { .. }
+            ))
+        ));
+        assert!(matches!(
+            tree.check_link_rules(&hrid("SYS", 2), &hrid("USR", 2), &config),
+            Err(LinkRequirementError::BreaksLinkRule(
+                LinkRuleError::TooManyParents {
+                    max: 1,
+                    count: 2,
+                    ..
+                }
+            ))
+        ));
+        assert!(tree
+            .check_link_rules(&hrid("USR", 1), &hrid("SYS", 1), &config)
+            .is_ok());
+    }
+
+    #[test]
+    fn link_rule_violations_report_existing_links() {
+        let mut tree = Tree::default();
+        let usr = Requirement::new(hrid("USR", 1), "User".into(), String::new());
+        let orphan = Requirement::new(hrid("SYS", 1), "Orphan".into(), String::new());
+        let mut nested = Requirement::new(hrid("SYS", 2), "Nested".into(), String::new());
+        link(&mut nested, &orphan);
+        link(&mut nested, &usr);
+        for requirement in [usr, orphan, nested] {
+            tree.insert(requirement).unwrap();
+        }
+
+        let violations: Vec<_> = tree
+            .link_rule_violations(&config())
+            .into_iter()
+            .map(|violation| (violation.hrid, violation.parent, violation.error))
+            .collect();
+        assert_eq!(
+            violations,
+            [
+                (
+                    hrid("SYS", 1),
+                    None,
+                    LinkRuleError::TooFewParents {
+                        kind: "SYS".to_string(),
+                        min: 1,
+                        count: 0,
+                    }
+                ),
+                (
+                    hrid("SYS", 2),
+                    None,
+                    LinkRuleError::TooManyParents {
+                        kind: "SYS".to_string(),
+                        max: 1,
+                        count: 2,
+                    }
+                ),
+                (
+                    hrid("SYS", 2),
+                    Some(hrid("SYS", 1)),
+                    LinkRuleError::DisallowedParentKind {
+                        kind: "SYS".to_string(),
+                        parent_kind: "SYS".to_string(),
+                        allowed: "USR".to_string(),
+                    }
+                ),
+            ]
+        );
+    }
+}
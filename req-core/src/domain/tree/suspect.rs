@@ -2,13 +2,15 @@
 //!
 //! A link is *suspect* when the fingerprint recorded on the edge no longer
 //! matches the parent's current fingerprint, meaning the parent has changed
-//! since the link was created or last reviewed.
+//! since the link was created or last reviewed. Stored fingerprints are
+//! compared using the [`FingerprintScheme`] that produced them, so changing
+//! the configured scheme doesn't make existing links suspect.
 
 use tracing::instrument;
 use uuid::Uuid;
 
 use super::{AcceptLinkError, Tree};
-use crate::domain::Hrid;
+use crate::domain::{requirement::ContentRef, FingerprintScheme, Hrid};
 
 /// A suspect link in the requirement graph.
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -28,6 +30,8 @@ pub struct SuspectLink {
     /// If empty, indicates the parent requirement is missing (failed to load or
     /// was deleted).
     pub current_fingerprint: String,
+    /// The relationship type of the link, if any.
+    pub link_type: Option<String>,
 }
 
 impl Tree {
@@ -127,8 +131,6 @@ impl Tree {
     /// skipped.
     #[must_use]
     pub fn suspect_links(&self) -> Vec<SuspectLink> {
-        use crate::domain::requirement::ContentRef;
-
         let mut suspect = Vec::new();
 
         for child_uuid in self.graph.nodes() {
@@ -138,20 +140,17 @@ impl Tree {
             };
 
             for (_, parent_uuid, edge_data) in self.graph.edges(child_uuid) {
-                // Access RequirementData directly to avoid full RequirementView construction
-                let parent_data = self.requirements.get(&parent_uuid);
+                // Compare using the scheme the stored fingerprint was produced
+                // with; an unrecognised scheme can never match.
+                let scheme = FingerprintScheme::of(&edge_data.fingerprint)
+                    .unwrap_or(self.fingerprint_scheme);
 
                 // Calculate current fingerprint, or use empty string if parent is missing
                 // Empty string indicates a dangling/broken reference (parent failed to load or
                 // was deleted)
-                let current_fingerprint = parent_data.map_or_else(String::new, |data| {
-                    ContentRef {
-                        title: &data.title,
-                        body: &data.body,
-                        tags: &data.tags,
-                    }
-                    .fingerprint()
-                });
+                let current_fingerprint = self
+                    .fingerprint_with(parent_uuid, scheme)
+                    .unwrap_or_default();
 
                 // Report as suspect if fingerprints don't match, OR if parent is missing
                 if edge_data.fingerprint != current_fingerprint {
@@ -162,6 +161,7 @@ impl Tree {
                         parent_hrid: edge_data.parent_hrid.clone(),
                         stored_fingerprint: edge_data.fingerprint.clone(),
                         current_fingerprint,
+                        link_type: edge_data.link_type.clone(),
                     });
                 }
             }
@@ -170,10 +170,27 @@ impl Tree {
         suspect
     }
 
+    /// Calculate the current fingerprint of a requirement with the given
+    /// scheme, or `None` if the requirement doesn't exist.
+    ///
+    /// Reads the stored data directly; building a full `RequirementView`
+    /// would clone every parent and child link just to hash the content.
+    pub(super) fn fingerprint_with(&self, uuid: Uuid, scheme: FingerprintScheme) -> Option<String> {
+        self.requirements.get(&uuid).map(|data| {
+            scheme.fingerprint(ContentRef {
+                title: &data.title,
+                body: &data.body,
+                tags: &data.tags,
+            })
+        })
+    }
+
     /// Update the fingerprint for a specific parent link.
     ///
-    /// Returns `Ok(true)` if the fingerprint was updated, `Ok(false)` if
-    /// already up to date.
+    /// The new fingerprint uses the tree's fingerprint scheme. Returns
+    /// `Ok(true)` if the fingerprint was updated, `Ok(false)` if the stored
+    /// fingerprint still matches the parent (with whichever scheme produced
+    /// it).
     ///
     /// # Errors
     ///
@@ -184,32 +201,26 @@ impl Tree {
         child_uuid: Uuid,
         parent_uuid: Uuid,
     ) -> Result<bool, AcceptLinkError> {
-        use crate::domain::requirement::ContentRef;
-
         // Check if child exists in graph
         if !self.graph.contains_node(child_uuid) {
             return Err(AcceptLinkError::ChildNotFound(child_uuid));
         }
 
-        // Compute the parent's current fingerprint directly from its stored
-        // data; building a full RequirementView here would clone every parent
-        // and child link just to hash the content.
+        // The new fingerprint uses the configured scheme, but a stored
+        // fingerprint that still matches under its own scheme is kept as is.
         let current_fingerprint = self
-            .requirements
-            .get(&parent_uuid)
-            .map(|data| {
-                ContentRef {
-                    title: &data.title,
-                    body: &data.body,
-                    tags: &data.tags,
-                }
-                .fingerprint()
-            })
+            .fingerprint_with(parent_uuid, self.fingerprint_scheme)
             .ok_or(AcceptLinkError::ParentNotFound(parent_uuid))?;
+        let stored_scheme = self
+            .graph
+            .edge_weight(child_uuid, parent_uuid)
+            .and_then(|edge_data| FingerprintScheme::of(&edge_data.fingerprint));
+        let stored_fingerprint =
+            stored_scheme.and_then(|scheme| self.fingerprint_with(parent_uuid, scheme));
 
         // Find and update the edge
         if let Some(edge_data) = self.graph.edge_weight_mut(child_uuid, parent_uuid) {
-            if edge_data.fingerprint == current_fingerprint {
+            if stored_fingerprint.as_ref() == Some(&edge_data.fingerprint) {
                 return Ok(false); // Already up to date
             }
 
@@ -0,0 +1,193 @@
+//! Verification rollup: how well each requirement is verified by tests.
+//!
+//! Test requirements take their verification from the latest recorded result
+//! of their test. Every other requirement rolls up the verification of its
+//! children: it has failed if any test beneath it failed, is incomplete if
+//! any test beneath it was skipped or hasn't run, and has passed only when
+//! every test beneath it passed. Requirements with no tests beneath them are
+//! unverified and have no verification at all.
+
+use std::collections::{HashMap, HashSet};
+
+use petgraph::Direction;
+use uuid::Uuid;
+
+use super::Tree;
+use crate::domain::TestOutcome;
+
+/// The rolled-up verification of a requirement.
+///
+/// Variants are ordered from best to worst, so the verification of a group
+/// of requirements is the maximum of theirs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Verification {
+    /// Every test beneath the requirement passed.
+    Passed,
+    /// No test beneath the requirement failed, but some were skipped or have
+    /// no recorded result.
+    Incomplete,
+    /// A test beneath the requirement failed.
+    Failed,
+}
+
+impl Verification {
+    /// The verification as a lowercase word.
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Passed => "passed",
+            Self::Incomplete => "incomplete",
+            Self::Failed => "failed",
+        }
+    }
+}
+
+impl std::fmt::Display for Verification {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl From<Option<TestOutcome>> for Verification {
+    fn from(outcome: Option<TestOutcome>) -> Self {
+        match outcome {
+            Some(TestOutcome::Passed) => Self::Passed,
+            Some(TestOutcome::Failed) => Self::Failed,
+            Some(TestOutcome::Skipped) | None => Self::Incomplete,
+        }
+    }
+}
+
+impl Tree {
+    /// Roll up the verification of every requirement from the recorded
+    /// results of the tests beneath it.
+    ///
+    /// Unverified requirements (with no tests beneath them) are left out.
+    #[must_use]
+    pub fn verification(&self) -> HashMap<Uuid, Verification> {
+        let mut rollup = HashMap::new();
+        let mut visiting = HashSet::new();
+        for uuid in self.requirements.keys() {
+            self.verify(*uuid, &mut rollup, &mut visiting);
+        }
+        rollup
+            .into_iter()
+            .filter_map(|(uuid, verification)| Some((uuid, verification?)))
+            .collect()
+    }
+
+    fn verify(
+        &self,
+        uuid: Uuid,
+        rollup: &mut HashMap<Uuid, Option<Verification>>,
+        visiting: &mut HashSet<Uuid>,
+    ) -> Option<Verification> {
+        if let Some(verification) = rollup.get(&uuid) {
+            return *verification;
+        }
+        let data = self.requirements.get(&uuid)?;
+        // Cycles are rejected when links are made, but guard against loading
+        // one from disk.
+        if !visiting.insert(uuid) {
+            return None;
+        }
+
+        let verification = data.test.as_ref().map_or_else(
+            || {
+                self.graph
+                    .neighbors_directed(uuid, Direction::Incoming)
+                    .filter_map(|child| self.verify(child, rollup, visiting))
+                    .max()
+            },
+            |test| {
+                Some(Verification::from(
+                    test.result.as_ref().map(|result| result.outcome),
+                ))
+            },
+        );
+
+        visiting.remove(&uuid);
+        rollup.insert(uuid, verification);
+        verification
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::num::NonZeroUsize;
+
+    use chrono::Utc;
+
+    use super::*;
+    use crate::{
+        domain::{
+            hrid::KindString,
+            requirement::{Parent, TestMetadata, TestResult},
+            Hrid,
+        },
+        Requirement,
+    };
+
+    fn requirement(kind: &str, id: usize, parents: &[&Requirement]) -> Requirement {
+        let hrid = Hrid::new(
+            KindString::new(kind.to_string()).unwrap(),
+            NonZeroUsize::new(id).unwrap(),
+        );
+        let mut requirement = Requirement::new(hrid, String::new(), String::new());
+        for parent in parents {
+            requirement.add_parent(
+                parent.uuid(),
+                Parent {
+                    hrid: parent.hrid().clone(),
+                    fingerprint: parent.fingerprint(),
+                    link_type: None,
+                },
+            );
+        }
+        requirement
+    }
+
+    fn test(id: usize, outcome: Option<TestOutcome>, parents: &[&Requirement]) -> Requirement {
+        let mut requirement = requirement("TEST", id, parents);
+        requirement.set_test(Some(TestMetadata {
+            location: format!("src/lib.rs::test_{id}"),
+            result: outcome.map(|outcome| TestResult {
+                outcome,
+                timestamp: Utc::now(),
+            }),
+        }));
+        requirement
+    }
+
+    #[test]
+    fn verification_rolls_up_the_worst_result() {
+        let usr = requirement("USR", 1, &[]);
+        let sys1 = requirement("SYS", 1, &[&usr]);
+        let sys2 = requirement("SYS", 2, &[&usr]);
+        let sys3 = requirement("SYS", 3, &[]);
+        let passed = test(1, Some(TestOutcome::Passed), &[&sys1]);
+        let skipped = test(2, Some(TestOutcome::Skipped), &[&sys1]);
+        let failed = test(3, Some(TestOutcome::Failed), &[&sys2]);
+        let ids = [&usr, &sys1, &sys2, &sys3, &passed, &skipped, &failed].map(Requirement::uuid);
+
+        let mut tree = Tree::default();
+        for requirement in [usr, sys1, sys2, sys3, passed, skipped, failed] {
+            tree.insert(requirement).unwrap();
+        }
+
+        let rollup = tree.verification();
+        let verification: Vec<_> = ids.iter().map(|uuid| rollup.get(uuid).copied()).collect();
+        assert_eq!(
+            verification,
+            [
+                Some(Verification::Failed),
+                Some(Verification::Incomplete),
+                Some(Verification::Failed),
+                None,
+                Some(Verification::Passed),
+                Some(Verification::Incomplete),
+                Some(Verification::Failed),
+            ]
+        );
+    }
+}
@@ -1,9 +1,26 @@
-//! Filesystem storage: the directory store, markdown serialization, and
-//! HRID/path mapping.
+//! Filesystem storage: the directory store, markdown serialization, HRID/path
+//! mapping, and reading requirements from git history.
+//!
+//! Source files are scanned for annotated tests by `test_scan`, and test
+//! results are read from test reports by `test_report`. Requirements are
+//! exchanged with other tools as ReqIF by `reqif`, migrated from Doorstop
+//! projects by `doorstop`, and read from spreadsheets by `csv`. A whole
+//! repository is exported and restored as a single document by `json`, and
+//! published as a static site by `html` and as sphinx-needs needs by
+//! `sphinx_needs`.
 
+pub mod csv;
 pub mod directory;
+pub mod doorstop;
+pub mod git;
+pub mod html;
+pub mod json;
 pub mod markdown;
 mod path_parser;
+pub mod reqif;
+pub mod sphinx_needs;
+pub mod test_report;
+pub mod test_scan;
 
 pub use directory::{AcceptResult, Directory};
 pub use markdown::{LoadError, MarkdownRequirement};
@@ -0,0 +1,315 @@
+//! Reading requirements from CSV and TSV spreadsheets.
+//!
+//! The first row names the columns. Each later row is a requirement,
+//! identified by an HRID column (to create it with that HRID) or a kind
+//! column (to number it after the existing requirements of that kind, with
+//! an optional namespace written `NS-KIND`). The title column is required;
+//! body, tags, and parents columns are optional. Tags and parents are
+//! separated by commas or semicolons, and parents are HRIDs.
+//!
+//! Column names default to the headers written by `req list --output csv`
+//! and are matched case-insensitively. Fields may be quoted with `"`, with
+//! `""` for a literal quote, and quoted fields may span lines. [`escape`]
+//! quotes fields the same way for writing.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use crate::{
+    domain::Hrid,
+    storage::directory::{NewHrid, NewRequirement, RowError},
+};
+
+/// The names of the columns to read requirements from.
+///
+/// A column left as `None` is read from its default name if the spreadsheet
+/// has it; a named column must be present.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Columns {
+    /// The HRID column (`HRID` by default).
+    pub hrid: Option<String>,
+    /// The kind column (`Kind` by default).
+    pub kind: Option<String>,
+    /// The title column (`Title` by default).
+    pub title: Option<String>,
+    /// The body column (`Body` by default).
+    pub body: Option<String>,
+    /// The tags column (`Tags` by default).
+    pub tags: Option<String>,
+    /// The parents column (`Parents` by default).
+    pub parents: Option<String>,
+}
+
+/// Error type for reading requirements from spreadsheets.
+#[derive(Debug, thiserror::Error)]
+pub enum CsvError {
+    /// A quoted field is never closed.
+    #[error("line {0}: unterminated quoted field")]
+    UnterminatedQuote(usize),
+
+    /// The spreadsheet has no header row.
+    #[error("the spreadsheet is empty")]
+    Empty,
+
+    /// A required column is not in the header row.
+    #[error("no '{0}' column")]
+    MissingColumn(String),
+
+    /// Neither an HRID nor a kind column is in the header row.
+    #[error("no HRID or kind column")]
+    NoIdentifierColumn,
+
+    /// One or more rows are invalid.
+    #[error("{}", .0.iter().map(ToString::to_string).collect::<Vec<_>>().join("\n"))]
+    InvalidRows(Vec<RowError>),
+}
+
+/// Reads the requirements in a spreadsheet whose fields are separated by
+/// `delimiter`.
+///
+/// # Errors
+///
+/// Returns an error if the spreadsheet is malformed, a column is missing, or
+/// any row is invalid.
+pub fn read_requirements(
+    text: &str,
+    delimiter: char,
+    columns: &Columns,
+) -> Result<Vec<NewRequirement>, CsvError> {
+    let mut records = parse(text, delimiter)?.into_iter();
+    let (_, header) = records.next().ok_or(CsvError::Empty)?;
+    let positions: BTreeMap<String, usize> = header
+        .iter()
+        .enumerate()
+        .map(|(position, name)| (name.trim().to_lowercase(), position))
+        .collect();
+    let column = |name: &Option<String>, default: &str| {
+        name.as_ref().map_or_else(
+            || Ok(positions.get(&default.to_lowercase()).copied()),
+            |name| {
+                positions
+                    .get(&name.trim().to_lowercase())
+                    .copied()
+                    .map(Some)
+                    .ok_or_else(|| CsvError::MissingColumn(name.clone()))
+            },
+        )
+    };
+
+    let hrid = column(&columns.hrid, "HRID")?;
+    let kind = column(&columns.kind, "Kind")?;
+    let title = column(&columns.title, "Title")?
+        .ok_or_else(|| CsvError::MissingColumn("Title".to_string()))?;
+    let body = column(&columns.body, "Body")?;
+    let tags = column(&columns.tags, "Tags")?;
+    let parents = column(&columns.parents, "Parents")?;
+    if hrid.is_none() && kind.is_none() {
+        return Err(CsvError::NoIdentifierColumn);
+    }
+
+    let mut requirements = Vec::new();
+    let mut errors = Vec::new();
+    for (line, record) in records {
+        let field = |position: Option<usize>| {
+            position
+                .and_then(|position| record.get(position))
+                .map_or("", |field| field.trim())
+        };
+        let mut error = |message: String| errors.push(RowError { line, message });
+
+        let new_hrid = match (field(hrid), field(kind)) {
+            ("", "") => {
+                error("no HRID or kind".to_string());
+                None
+            }
+            ("", kind) => {
+                let mut segments: Vec<String> = kind
+                    .split('-')
+                    .map(|segment| segment.trim().to_uppercase())
+                    .collect();
+                let kind = segments.pop().unwrap_or_default();
+                Some(NewHrid::Next {
+                    namespace: segments,
+                    kind,
+                })
+            }
+            (hrid, _) => Hrid::try_from(hrid)
+                .map_err(|reason| error(format!("invalid HRID '{hrid}': {reason}")))
+                .ok()
+                .map(NewHrid::Given),
+        };
+
+        let mut parent_hrids = Vec::new();
+        for parent in list(field(parents)) {
+            match Hrid::try_from(parent) {
+                Ok(parent) => parent_hrids.push(parent),
+                Err(reason) => error(format!("invalid parent HRID '{parent}': {reason}")),
+            }
+        }
+
+        let Some(new_hrid) = new_hrid else {
+            continue;
+        };
+        requirements.push(NewRequirement {
+            line,
+            hrid: new_hrid,
+            title: field(Some(title)).to_string(),
+            body: field(body).to_string(),
+            tags: list(field(tags))
+                .map(str::to_string)
+                .collect::<BTreeSet<_>>(),
+            parents: parent_hrids,
+        });
+    }
+
+    if errors.is_empty() {
+        Ok(requirements)
+    } else {
+        Err(CsvError::InvalidRows(errors))
+    }
+}
+
+/// Quotes a field for writing to CSV if it contains a separator, quote, or
+/// line break.
+#[must_use]
+pub fn escape(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+/// Splits a list of tags or HRIDs on commas and semicolons.
+fn list(field: &str) -> impl Iterator<Item = &str> {
+    field
+        .split([',', ';'])
+        .map(str::trim)
+        .filter(|item| !item.is_empty())
+}
+
+/// Splits a spreadsheet into records, each with the line it starts on.
+/// Blank lines are skipped.
+fn parse(text: &str, delimiter: char) -> Result<Vec<(usize, Vec<String>)>, CsvError> {
+    let mut records = Vec::new();
+    let mut record = Vec::new();
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut line = 1;
+    let mut start = 1;
+    let mut chars = text.trim_start_matches('\u{feff}').chars().peekable();
+
+    while let Some(c) = chars.next() {
+        if c == '\n' {
+            line += 1;
+        }
+        if quoted {
+            if c == '"' {
+                if chars.peek() == Some(&'"') {
+                    chars.next();
+                    field.push('"');
+                } else {
+                    quoted = false;
+                }
+            } else {
+                field.push(c);
+            }
+        } else if c == '"' && field.is_empty() {
+            quoted = true;
+        } else if c == delimiter {
+            record.push(std::mem::take(&mut field));
+        } else if c == '\n' || c == '\r' {
+            if c == '\r' && chars.peek() == Some(&'\n') {
+                continue;
+            }
+            record.push(std::mem::take(&mut field));
+            if record.len() > 1 || !record[0].is_empty() {
+                records.push((start, std::mem::take(&mut record)));
+            }
+            record.clear();
+            start = line;
+        } else {
+            field.push(c);
+        }
+    }
+
+    if quoted {
+        return Err(CsvError::UnterminatedQuote(start));
+    }
+    if !field.is_empty() || !record.is_empty() {
+        record.push(field);
+        records.push((start, record));
+    }
+    Ok(records)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_rows_and_reports_every_invalid_one() {
+        let text = "HRID,Kind,Title,Body,Tags,Parents\r\n\
+                    USR-010,,Log in,\"Users shall\n\"\"log in\"\".\",\"auth, ui\",\n\
+                    \n\
+                    ,core-sys,Auth service,,,USR-010; USR-001\n";
+        let requirements = read_requirements(text, ',', &Columns::default()).unwrap();
+        assert_eq!(
+            requirements,
+            [
+                NewRequirement {
+                    line: 2,
+                    hrid: NewHrid::Given(Hrid::try_from("USR-010").unwrap()),
+                    title: "Log in".to_string(),
+                    body: "Users shall\n\"log in\".".to_string(),
+                    tags: BTreeSet::from(["auth".to_string(), "ui".to_string()]),
+                    parents: Vec::new(),
+                },
+                NewRequirement {
+                    line: 5,
+                    hrid: NewHrid::Next {
+                        namespace: vec!["CORE".to_string()],
+                        kind: "SYS".to_string(),
+                    },
+                    title: "Auth service".to_string(),
+                    body: String::new(),
+                    tags: BTreeSet::new(),
+                    parents: vec![
+                        Hrid::try_from("USR-010").unwrap(),
+                        Hrid::try_from("USR-001").unwrap(),
+                    ],
+                },
+            ]
+        );
+
+        let tsv = "Name\tType\tParent\nLog in\tUSR\t\nAuth\t\tUSR-1x\n";
+        let columns = Columns {
+            kind: Some("type".to_string()),
+            title: Some("Name".to_string()),
+            parents: Some("Parent".to_string()),
+            ..Columns::default()
+        };
+        let Err(CsvError::InvalidRows(errors)) = read_requirements(tsv, '\t', &columns) else {
+            panic!("expected invalid rows");
+        };
+        let lines: Vec<_> = errors.iter().map(|error| error.line).collect();
+        assert_eq!(lines, [3, 3]);
+
+        assert!(matches!(
+            read_requirements("Title\n\"open", ',', &Columns::default()),
+            Err(CsvError::UnterminatedQuote(2))
+        ));
+    }
+    #[test]
+    fn escaped_fields_read_back_unchanged() {
+        assert_eq!(escape("simple"), "simple");
+        assert_eq!(escape("needs,comma"), "\"needs,comma\"");
+        assert_eq!(escape("quote\"here"), "\"quote\"\"here\"");
+
+        let fields = ["simple", "needs,comma", "quote\"here", "line\nbreak"];
+        let line = fields.map(escape).join(",");
+        assert_eq!(
+            parse(&line, ',').unwrap(),
+            [(1, fields.map(String::from).to_vec())]
+        );
+    }
+}
@@ -9,8 +9,16 @@
 //!
 //! - `load`: opening a directory and loading requirements from disk
 //! - `edit`: adding, linking, renaming, moving, and deleting requirements
-//! - `maintenance`: drift detection, suspect-link review, and repair
+//! - `maintenance`: drift detection, suspect-link review, format migration,
+//!   and repair
 //! - `flush`: persisting pending changes back to disk
+//! - `reload`: reloading requirements whose files changed on disk
+//! - `baseline`: saving and loading named baselines
+//! - `history`: recovering earlier versions of requirements from git
+//! - `test_sync`: generating test requirements from annotated tests, and
+//!   recording their results
+//! - `import`: importing requirements from other tools, and merging repeated
+//!   imports
 
 use std::{
     collections::{BTreeSet, HashMap, HashSet},
@@ -19,17 +27,33 @@ use std::{
 
 use uuid::Uuid;
 
-use crate::domain::{Config, Hrid, RequirementView, Tree};
+use crate::domain::{
+    requirement::ContentRef, Config, Hrid, ImpactedRequirement, RequirementView, Tree,
+};
 
+mod baseline;
 mod edit;
 mod flush;
+mod history;
+mod import;
 mod load;
 mod maintenance;
+mod reload;
+mod test_sync;
 
-pub use edit::AddRequirementError;
+pub use baseline::BaselineError;
+pub use edit::{AddRequirementError, SetAttributeError, SetStatusError};
 pub use flush::FlushError;
+pub use history::{ParentChanges, ParentHistory};
+pub use import::{
+    Import, ImportChange, ImportEntry, ImportedRequirement, NewHrid, NewRequirement, RowError,
+};
 pub use load::DirectoryLoadError;
-pub use maintenance::{AcceptResult, AcceptSuspectLinkError};
+pub use maintenance::{
+    AcceptResult, AcceptSuspectLinkError, AttributeIssue, AttributeProblem, OutdatedFile,
+};
+pub use reload::Reload;
+pub use test_sync::{TestChange, TestImport, TestSync, TestSyncEntry};
 
 /// A filesystem backed store of requirements.
 pub struct Directory {
@@ -43,6 +67,17 @@ pub struct Directory {
     paths: HashMap<Uuid, PathBuf>,
     /// Paths to delete on flush.
     deletions: HashSet<PathBuf>,
+    /// The version `.req/config.toml` is stored in, if it exists.
+    config_version: Option<u32>,
+    /// Whether `.req/config.toml` must be rewritten on flush.
+    config_dirty: bool,
+    /// Frontmatter versions of requirements stored in an older version than
+    /// is written.
+    outdated: HashMap<Uuid, u32>,
+    /// Hashes of requirement files as last read or written, by absolute
+    /// path. A file whose hash no longer matches was changed by something
+    /// else, and isn't overwritten on flush.
+    hashes: HashMap<PathBuf, u64>,
 }
 
 impl Directory {
@@ -166,6 +201,23 @@ impl Directory {
 
         collected.into_iter().collect()
     }
+
+    /// Find the descendants of a requirement that a change to it would make
+    /// suspect, by depth.
+    ///
+    /// If `content` is given, it is the changed content of the requirement;
+    /// otherwise any change is assumed (see [`Tree::impact_of`]).
+    #[must_use]
+    pub fn impact_of(
+        &self,
+        hrid: &Hrid,
+        content: Option<ContentRef<'_>>,
+    ) -> Vec<ImpactedRequirement> {
+        self.tree
+            .find_by_hrid(hrid)
+            .map(|view| self.tree.impact_of(*view.uuid, content))
+            .unwrap_or_default()
+    }
 }
 
 /// Create a temporary directory backed store for tests.
@@ -0,0 +1,197 @@
+//! Saving and loading named baselines.
+//!
+//! Baselines are stored as JSON in `.req/baselines/<name>.json`.
+
+use std::{io, path::PathBuf};
+
+use super::Directory;
+use crate::{
+    domain::{Baseline, Tree},
+    storage::git::{self, GitError},
+};
+
+/// Error type for baseline storage failures.
+#[derive(Debug, thiserror::Error)]
+pub enum BaselineError {
+    /// The name cannot be used as a file name.
+    #[error("invalid baseline name '{0}': names must be non-empty, must not start with '.', and must not contain path separators")]
+    InvalidName(String),
+
+    /// A baseline with the name already exists.
+    #[error("baseline '{0}' already exists")]
+    AlreadyExists(String),
+
+    /// No baseline with the name exists.
+    #[error("baseline '{0}' not found")]
+    NotFound(String),
+
+    /// The baseline file could not be read or written.
+    #[error("failed to access {}: {source}", path.display())]
+    Io {
+        /// The path of the baseline file.
+        path: PathBuf,
+        /// The underlying IO error.
+        source: io::Error,
+    },
+
+    /// The baseline file is not a valid baseline.
+    #[error("failed to parse {}: {source}", path.display())]
+    Parse {
+        /// The path of the baseline file.
+        path: PathBuf,
+        /// The underlying parse error.
+        source: serde_json::Error,
+    },
+}
+
+impl Directory {
+    /// Take a snapshot of the requirements as they are now.
+    ///
+    /// The snapshot is not saved; see [`Self::create_baseline`].
+    #[must_use]
+    pub fn baseline(&self, name: String) -> Baseline {
+        Baseline::capture(
+            name,
+            self.tree.iter(),
+            self.config.fingerprint(),
+            self.config.digits(),
+        )
+    }
+
+    /// Take a snapshot of the requirements as they were at a git revision.
+    ///
+    /// The snapshot uses the current configuration, so fingerprints are
+    /// comparable with [`Self::baseline`]. The snapshot is named after the
+    /// revision.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the requirements cannot be read from git.
+    pub fn baseline_at_revision(&self, revision: &str) -> Result<Baseline, GitError> {
+        let requirements = git::requirements_at_revision(&self.root, revision)?;
+        let mut tree = Tree::with_capacity(requirements.len());
+        for requirement in requirements {
+            if let Err(error) = tree.insert(requirement) {
+                tracing::debug!("Skipping requirement at {revision}: {error}");
+            }
+        }
+        Ok(Baseline::capture(
+            revision.to_string(),
+            tree.iter(),
+            self.config.fingerprint(),
+            self.config.digits(),
+        ))
+    }
+
+    /// Returns the path a named baseline is stored at.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the name cannot be used as a file name.
+    pub fn baseline_path(&self, name: &str) -> Result<PathBuf, BaselineError> {
+        if name.is_empty() || name.starts_with('.') || name.contains(['/', '\\']) {
+            return Err(BaselineError::InvalidName(name.to_string()));
+        }
+        Ok(self
+            .root
+            .join(".req")
+            .join("baselines")
+            .join(format!("{name}.json")))
+    }
+
+    /// Snapshot the requirements as they are now and save the snapshot as a
+    /// named baseline.
+    ///
+    /// Existing baselines are only replaced if `overwrite` is set.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the name is invalid, a baseline with the name
+    /// already exists, or the file cannot be written.
+    ///
+    /// # Panics
+    ///
+    /// Panics if JSON serialization fails (which should never happen for this
+    /// data structure).
+    pub fn create_baseline(&self, name: &str, overwrite: bool) -> Result<Baseline, BaselineError> {
+        let path = self.baseline_path(name)?;
+        if !overwrite && path.exists() {
+            return Err(BaselineError::AlreadyExists(name.to_string()));
+        }
+
+        let baseline = self.baseline(name.to_string());
+        let json = serde_json::to_string_pretty(&baseline).expect("this should never fail");
+        let write = || {
+            if let Some(parent) = path.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+            std::fs::write(&path, json + "\n")
+        };
+        write().map_err(|source| BaselineError::Io {
+            path: path.clone(),
+            source,
+        })?;
+        Ok(baseline)
+    }
+
+    /// Load a named baseline.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the name is invalid, the baseline doesn't exist, or
+    /// it cannot be read or parsed.
+    pub fn load_baseline(&self, name: &str) -> Result<Baseline, BaselineError> {
+        let path = self.baseline_path(name)?;
+        let json = std::fs::read_to_string(&path).map_err(|source| {
+            if source.kind() == io::ErrorKind::NotFound {
+                BaselineError::NotFound(name.to_string())
+            } else {
+                BaselineError::Io {
+                    path: path.clone(),
+                    source,
+                }
+            }
+        })?;
+        serde_json::from_str(&json).map_err(|source| BaselineError::Parse { path, source })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{super::setup_temp_directory, *};
+
+    #[test]
+    fn baselines_are_saved_and_compared_with_later_changes() {
+        let (_tmp, mut dir) = setup_temp_directory();
+        let parent = dir.add_requirement("USR", "# Parent".to_string()).unwrap();
+        let child = dir.add_requirement("SYS", "# Child".to_string()).unwrap();
+        dir.link_requirement(child.hrid(), parent.hrid()).unwrap();
+        dir.flush().unwrap();
+
+        dir.create_baseline("v1.0", false).unwrap();
+        assert!(matches!(
+            dir.create_baseline("v1.0", false),
+            Err(BaselineError::AlreadyExists(_))
+        ));
+        assert!(matches!(
+            dir.create_baseline("../escape", false),
+            Err(BaselineError::InvalidName(_))
+        ));
+        assert!(matches!(
+            dir.load_baseline("v2.0"),
+            Err(BaselineError::NotFound(_))
+        ));
+
+        dir.update_requirement(parent.hrid(), None, Some("New body".to_string()), None)
+            .unwrap();
+        dir.add_requirement("USR", "# Another".to_string()).unwrap();
+
+        let saved = dir.load_baseline("v1.0").unwrap();
+        assert_eq!(saved.requirements.len(), 2);
+        let diff = saved.diff(&dir.baseline("current".to_string()));
+        assert_eq!(diff.added.len(), 1);
+        assert_eq!(diff.modified.len(), 1);
+        assert_eq!(diff.modified[0].hrid, "USR-001");
+        assert!(diff.removed.is_empty() && diff.renamed.is_empty() && diff.relinked.is_empty());
+    }
+}
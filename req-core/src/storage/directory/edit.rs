@@ -9,7 +9,7 @@ use super::Directory;
 use crate::{
     domain::{
         hrid::{KindString, NamespaceSegment},
-        Hrid, LinkRequirementError, RequirementView,
+        AttributeError, AttributeValue, Hrid, LinkRequirementError, RequirementView,
     },
     storage::markdown::trim_empty_lines,
     Requirement,
@@ -36,6 +36,65 @@ pub enum AddRequirementError {
     },
 }
 
+/// Error type for changing a requirement's lifecycle status.
+#[derive(Debug, thiserror::Error)]
+pub enum SetStatusError {
+    /// The requirement does not exist.
+    #[error("requirement {0} not found")]
+    NotFound(String),
+
+    /// The status is not declared by the kind's lifecycle.
+    #[error("status '{status}' is not defined (allowed statuses: {allowed_statuses})")]
+    UnknownStatus {
+        /// The status that was rejected.
+        status: String,
+        /// The statuses declared by the lifecycle.
+        allowed_statuses: String,
+    },
+
+    /// The lifecycle does not allow moving from the current status to the
+    /// requested one.
+    #[error("cannot change status from '{from}' to '{to}' (allowed: {allowed})")]
+    InvalidTransition {
+        /// The current status.
+        from: String,
+        /// The requested status.
+        to: String,
+        /// The statuses reachable from the current one.
+        allowed: String,
+    },
+}
+
+/// Error type for setting a requirement's custom attribute.
+#[derive(Debug, thiserror::Error)]
+pub enum SetAttributeError {
+    /// The requirement does not exist.
+    #[error("requirement {0} not found")]
+    NotFound(String),
+
+    /// The attribute is not declared for the requirement's kind.
+    #[error(
+        "attribute '{name}' is not declared for kind {kind} (declared attributes: {declared})"
+    )]
+    UndeclaredAttribute {
+        /// The attribute that was rejected.
+        name: String,
+        /// The requirement's kind.
+        kind: String,
+        /// The attributes declared for the kind.
+        declared: String,
+    },
+
+    /// The value does not match the attribute's declared type.
+    #[error("invalid value for attribute '{name}': {source}")]
+    InvalidValue {
+        /// The attribute being set.
+        name: String,
+        /// Why the value was rejected.
+        source: AttributeError,
+    },
+}
+
 impl Directory {
     /// Add a new requirement to the directory.
     ///
@@ -76,12 +135,40 @@ impl Directory {
         kind: &str,
         content: String,
     ) -> Result<Requirement, AddRequirementError> {
-        let tree = &mut self.tree;
-
         // Validate kind (CLI already normalized to uppercase)
         let kind_string =
             KindString::new(kind.to_string()).map_err(crate::domain::hrid::Error::from)?;
 
+        // Validate namespace segments (allow lowercase/mixed-case)
+        let namespace_strings: Result<Vec<_>, _> = namespace
+            .into_iter()
+            .map(|seg| {
+                NamespaceSegment::new(seg)
+                    .map_err(|e| crate::domain::hrid::Error::Namespace(String::new(), e))
+            })
+            .collect();
+        let namespace_strings = namespace_strings?;
+
+        let id = self.tree.next_index(&namespace_strings, &kind_string);
+        let hrid = Hrid::new_with_namespace(namespace_strings, kind_string, id);
+        self.add_requirement_with_hrid(hrid, content)
+    }
+
+    /// Add a new requirement to the directory with a given HRID.
+    ///
+    /// # Errors
+    ///
+    /// This method can fail if:
+    ///
+    /// - the HRID's kind is not allowed by the configuration
+    /// - a requirement with this HRID already exists
+    pub fn add_requirement_with_hrid(
+        &mut self,
+        hrid: Hrid,
+        content: String,
+    ) -> Result<Requirement, AddRequirementError> {
+        let kind = hrid.kind();
+
         // Check if kind is allowed by configuration
         if !self.config.is_kind_allowed(kind) {
             let allowed_kinds = if self.config.allowed_kinds().is_empty() {
@@ -95,19 +182,6 @@ impl Directory {
             });
         }
 
-        // Validate namespace segments (allow lowercase/mixed-case)
-        let namespace_strings: Result<Vec<_>, _> = namespace
-            .into_iter()
-            .map(|seg| {
-                NamespaceSegment::new(seg)
-                    .map_err(|e| crate::domain::hrid::Error::Namespace(String::new(), e))
-            })
-            .collect();
-        let namespace_strings = namespace_strings?;
-
-        let id = tree.next_index(&namespace_strings, &kind_string);
-        let hrid = Hrid::new_with_namespace(namespace_strings, kind_string, id);
-
         // Parse content to extract title and body
         // If no content is provided via CLI, check for a template
         let (title, body) = if content.is_empty() {
@@ -118,9 +192,12 @@ impl Directory {
             split_title_and_body(content)
         };
 
-        let requirement = Requirement::new(hrid, title, body);
+        let mut requirement = Requirement::new(hrid, title, body);
+        if let Some(lifecycle) = self.config.lifecycle_for_kind(requirement.hrid().kind()) {
+            requirement.set_status(Some(lifecycle.initial.clone()));
+        }
 
-        tree.insert(requirement.clone())?;
+        self.tree.insert(requirement.clone())?;
         let canonical = self.canonical_path_for(requirement.hrid());
         self.paths.insert(requirement.uuid(), canonical);
         self.mark_dirty(requirement.uuid());
@@ -135,9 +212,10 @@ impl Directory {
 
     /// Update the title, body, and/or tags of an existing requirement.
     ///
-    /// Fields passed as `None` are left unchanged. Changing the body or tags
-    /// changes the requirement's fingerprint, so links from its children
-    /// become suspect and need review.
+    /// Fields passed as `None` are left unchanged. Changing the fingerprinted
+    /// fields (by default the body and tags) changes the requirement's
+    /// fingerprint, so links from its children become suspect and need
+    /// review.
     ///
     /// Values are normalized to what the markdown format round-trips: the
     /// title is trimmed (it lives on the single heading line) and blank lines
@@ -189,6 +267,129 @@ impl Directory {
         Ok(changed)
     }
 
+    /// Change the lifecycle status of a requirement.
+    ///
+    /// The status is normalized to lowercase. When the requirement's kind has
+    /// a lifecycle configured, the status must be one of its states and the
+    /// transition from the current status must be allowed. Kinds without a
+    /// lifecycle accept any status.
+    ///
+    /// Returns the previous status.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the requirement does not exist, or the lifecycle
+    /// rejects the status or transition.
+    pub fn set_status(
+        &mut self,
+        hrid: &Hrid,
+        status: &str,
+    ) -> Result<Option<String>, SetStatusError> {
+        let status = status.trim().to_lowercase();
+        let digits = self.config.digits();
+
+        let Some(view) = self.tree.find_by_hrid(hrid) else {
+            return Err(SetStatusError::NotFound(hrid.display(digits).to_string()));
+        };
+        let uuid = *view.uuid;
+        let previous = view.status.map(str::to_string);
+
+        if let Some(lifecycle) = self.config.lifecycle_for_kind(hrid.kind()) {
+            if !lifecycle.is_state(&status) {
+                return Err(SetStatusError::UnknownStatus {
+                    status,
+                    allowed_statuses: lifecycle
+                        .states()
+                        .into_iter()
+                        .collect::<Vec<_>>()
+                        .join(", "),
+                });
+            }
+            if !lifecycle.allows_transition(previous.as_deref(), &status) {
+                let from = previous.unwrap_or_default();
+                let allowed = lifecycle
+                    .transitions
+                    .get(&from)
+                    .filter(|targets| !targets.is_empty())
+                    .map_or_else(|| "none".to_string(), |targets| targets.join(", "));
+                return Err(SetStatusError::InvalidTransition {
+                    from,
+                    to: status,
+                    allowed,
+                });
+            }
+        } else if status.is_empty() {
+            return Err(SetStatusError::UnknownStatus {
+                status,
+                allowed_statuses: "any non-empty status".to_string(),
+            });
+        }
+
+        if self.tree.set_requirement_status(uuid, Some(status.clone())) == Some(true) {
+            self.mark_dirty(uuid);
+            tracing::info!("Set status of {} to {status}", hrid.display(digits));
+        }
+
+        Ok(previous)
+    }
+
+    /// Set or clear a custom attribute of a requirement.
+    ///
+    /// The attribute must be declared in the configuration for the
+    /// requirement's kind, and the raw value is parsed according to its
+    /// declared type. Passing `None` removes the attribute, which is allowed
+    /// even for undeclared attributes so stale values can be cleaned up.
+    ///
+    /// Returns the previous value.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the requirement does not exist, the attribute is
+    /// not declared for its kind, or the value does not match the declared
+    /// type.
+    pub fn set_attribute(
+        &mut self,
+        hrid: &Hrid,
+        name: &str,
+        value: Option<&str>,
+    ) -> Result<Option<AttributeValue>, SetAttributeError> {
+        let digits = self.config.digits();
+
+        let Some(view) = self.tree.find_by_hrid(hrid) else {
+            return Err(SetAttributeError::NotFound(
+                hrid.display(digits).to_string(),
+            ));
+        };
+        let uuid = *view.uuid;
+        let previous = view.attributes.get(name).cloned();
+
+        let value = value
+            .map(|raw| {
+                let attribute = self
+                    .config
+                    .attribute_for_kind(hrid.kind(), name)
+                    .ok_or_else(|| SetAttributeError::UndeclaredAttribute {
+                        name: name.to_string(),
+                        kind: hrid.kind().to_string(),
+                        declared: declared_attributes(&self.config, hrid.kind()),
+                    })?;
+                attribute
+                    .parse(raw)
+                    .map_err(|source| SetAttributeError::InvalidValue {
+                        name: name.to_string(),
+                        source,
+                    })
+            })
+            .transpose()?;
+
+        if self.tree.set_requirement_attribute(uuid, name, value) == Some(true) {
+            self.mark_dirty(uuid);
+            tracing::info!("Set attribute {name} of {}", hrid.display(digits));
+        }
+
+        Ok(previous)
+    }
+
     /// Link two requirements together with a parent-child relationship.
     ///
     /// # Errors
@@ -204,15 +405,64 @@ impl Directory {
         child: &Hrid,
         parent: &Hrid,
     ) -> Result<RequirementView<'_>, LinkRequirementError> {
-        let outcome = self.tree.link_requirement(child, parent)?;
+        self.link_requirement_with_type(child, parent, None)
+    }
+
+    /// Link two requirements together with a typed relationship.
+    ///
+    /// The link type is normalized to lowercase and must be declared in the
+    /// configuration (unless no link types are declared). Passing `None`
+    /// relinks without changing an existing link's type.
+    ///
+    /// # Errors
+    ///
+    /// This method can fail if:
+    ///
+    /// - the link type is not allowed by the configuration
+    /// - the link breaks the link rules of the child's kind
+    /// - either the child or parent requirement cannot be found
+    /// - the parent/child UUIDs cannot be linked
+    pub fn link_requirement_with_type(
+        &mut self,
+        child: &Hrid,
+        parent: &Hrid,
+        link_type: Option<&str>,
+    ) -> Result<RequirementView<'_>, LinkRequirementError> {
+        let link_type = link_type
+            .map(|t| t.trim().to_lowercase())
+            .filter(|t| !t.is_empty());
+        if let Some(link_type) = &link_type {
+            if !self.config.is_link_type_allowed(link_type) {
+                return Err(LinkRequirementError::DisallowedLinkType {
+                    link_type: link_type.clone(),
+                    allowed_link_types: self
+                        .config
+                        .link_types()
+                        .iter()
+                        .map(|t| t.name.as_str())
+                        .collect::<Vec<_>>()
+                        .join(", "),
+                });
+            }
+        }
+
+        self.tree.check_link_rules(child, parent, &self.config)?;
+
+        let outcome = self
+            .tree
+            .link_requirement_with_type(child, parent, link_type)?;
         self.mark_dirty(outcome.child_uuid);
 
         if !outcome.already_linked {
             let digits = self.config.digits();
             tracing::info!(
-                "Linked {} ← {}",
+                "Linked {} ← {}{}",
                 outcome.child_hrid.display(digits),
-                outcome.parent_hrid.display(digits)
+                outcome.parent_hrid.display(digits),
+                outcome
+                    .link_type
+                    .as_deref()
+                    .map_or_else(String::new, |t| format!(" ({t})"))
             );
         }
 
@@ -507,6 +757,20 @@ impl Directory {
     }
 }
 
+/// Comma-separated names of the attributes declared for a kind, for error
+/// messages.
+fn declared_attributes(config: &crate::domain::Config, kind: &str) -> String {
+    let names: Vec<_> = config
+        .attributes_for_kind(kind)
+        .map(|(name, _)| name)
+        .collect();
+    if names.is_empty() {
+        "none".to_string()
+    } else {
+        names.join(", ")
+    }
+}
+
 /// Split user-provided content into a title (from a leading `#` heading, if
 /// any) and a body.
 fn split_title_and_body(content: String) -> (String, String) {
@@ -622,7 +886,7 @@ mod tests {
             .unwrap();
         reloaded.flush().unwrap();
 
-        let config = load_config(&dir.root).unwrap();
+        let (config, _) = load_config(&dir.root).unwrap();
         let updated =
             Requirement::load(&dir.root, child.hrid(), &config).expect("should load child");
 
@@ -632,6 +896,115 @@ mod tests {
         assert_eq!(&parents[0].1.hrid, parent.hrid());
     }
 
+    #[test]
+    fn typed_link_persists_and_respects_config() {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path();
+        std::fs::create_dir_all(root.join(".req")).unwrap();
+        std::fs::write(
+            root.join(".req/config.toml"),
+            "_version = \"1\"\nlink_types = [\"satisfies\", \"verifies\"]\n",
+        )
+        .unwrap();
+
+        let mut dir = Directory::new(root.to_path_buf()).unwrap();
+        let parent = dir.add_requirement("SYS", "# Parent".to_string()).unwrap();
+        let child = dir.add_requirement("TST", "# Test".to_string()).unwrap();
+
+        let error = dir
+            .link_requirement_with_type(child.hrid(), parent.hrid(), Some("refines"))
+            .unwrap_err();
+        assert!(matches!(
+            error,
+            LinkRequirementError::DisallowedLinkType { ref link_type, .. } if link_type == "refines"
+        ));
+
+        dir.link_requirement_with_type(child.hrid(), parent.hrid(), Some("Verifies"))
+            .unwrap();
+        dir.flush().unwrap();
+
+        let reloaded = Directory::new(root.to_path_buf()).unwrap();
+        let view = reloaded.find_by_hrid(child.hrid()).unwrap();
+        assert_eq!(view.parents[0].1.link_type.as_deref(), Some("verifies"));
+    }
+
+    #[test]
+    fn set_status_follows_lifecycle() {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path();
+        std::fs::create_dir_all(root.join(".req")).unwrap();
+        std::fs::write(
+            root.join(".req/config.toml"),
+            "_version = \"1\"\n\n[lifecycles.default]\ninitial = \"draft\"\ntransitions = { \
+             draft = [\"approved\"], approved = [\"obsolete\"] }\n",
+        )
+        .unwrap();
+
+        let mut dir = Directory::new(root.to_path_buf()).unwrap();
+        let req = dir.add_requirement("USR", "# Test".to_string()).unwrap();
+        assert_eq!(req.status(), Some("draft"));
+
+        let error = dir.set_status(req.hrid(), "obsolete").unwrap_err();
+        assert!(matches!(error, SetStatusError::InvalidTransition { .. }));
+        let error = dir.set_status(req.hrid(), "bogus").unwrap_err();
+        assert!(matches!(error, SetStatusError::UnknownStatus { .. }));
+
+        let previous = dir.set_status(req.hrid(), "Approved").unwrap();
+        assert_eq!(previous.as_deref(), Some("draft"));
+        dir.flush().unwrap();
+
+        let reloaded = Directory::new(root.to_path_buf()).unwrap();
+        let view = reloaded.find_by_hrid(req.hrid()).unwrap();
+        assert_eq!(view.status, Some("approved"));
+    }
+
+    #[test]
+    fn set_attribute_checks_schema() {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path();
+        std::fs::create_dir_all(root.join(".req")).unwrap();
+        std::fs::write(
+            root.join(".req/config.toml"),
+            "_version = \"1\"\n\n[attributes.risk]\ntype = \"integer\"\n\n[attributes.method]\ntype \
+             = \"string\"\nkinds = [\"TST\"]\n",
+        )
+        .unwrap();
+
+        let mut dir = Directory::new(root.to_path_buf()).unwrap();
+        let req = dir.add_requirement("USR", "# Test".to_string()).unwrap();
+
+        let error = dir
+            .set_attribute(req.hrid(), "method", Some("test"))
+            .unwrap_err();
+        assert!(matches!(
+            error,
+            SetAttributeError::UndeclaredAttribute { .. }
+        ));
+        let error = dir
+            .set_attribute(req.hrid(), "risk", Some("high"))
+            .unwrap_err();
+        assert!(matches!(error, SetAttributeError::InvalidValue { .. }));
+
+        let previous = dir.set_attribute(req.hrid(), "risk", Some("3")).unwrap();
+        assert_eq!(previous, None);
+        dir.flush().unwrap();
+
+        let mut reloaded = Directory::new(root.to_path_buf()).unwrap();
+        let view = reloaded.find_by_hrid(req.hrid()).unwrap();
+        assert_eq!(
+            view.attributes.get("risk"),
+            Some(&AttributeValue::Integer(3))
+        );
+
+        let previous = reloaded.set_attribute(req.hrid(), "risk", None).unwrap();
+        assert_eq!(previous, Some(AttributeValue::Integer(3)));
+        assert!(reloaded
+            .find_by_hrid(req.hrid())
+            .unwrap()
+            .attributes
+            .is_empty());
+    }
+
     #[test]
     fn add_requirement_rejects_disallowed_kind() {
         let tmp = tempfile::tempdir().unwrap();
@@ -735,7 +1108,7 @@ mod tests {
         assert!(changed);
         dir.flush().unwrap();
 
-        let config = load_config(&dir.root).unwrap();
+        let (config, _) = load_config(&dir.root).unwrap();
         let updated = Requirement::load(&dir.root, req.hrid(), &config).unwrap();
         assert_eq!(updated.title(), "New title");
         assert_eq!(updated.body(), "New body");
@@ -758,7 +1131,7 @@ mod tests {
         assert!(changed);
         dir.flush().unwrap();
 
-        let config = load_config(&dir.root).unwrap();
+        let (config, _) = load_config(&dir.root).unwrap();
         let updated = Requirement::load(&dir.root, req.hrid(), &config).unwrap();
         assert_eq!(updated.title(), "Renamed");
         assert_eq!(updated.body(), "Body");
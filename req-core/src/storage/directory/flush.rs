@@ -1,41 +1,70 @@
 //! Persisting pending changes back to disk.
 
-use std::{collections::HashSet, fmt, io, path::PathBuf};
+use std::{
+    collections::HashSet,
+    fmt, io,
+    path::{Path, PathBuf},
+};
 
 use nonempty::NonEmpty;
 use uuid::Uuid;
 
-use super::Directory;
-use crate::{domain::Hrid, Requirement};
+use super::{
+    load::{content_hash, file_hash, lexical_absolute},
+    Directory,
+};
+use crate::{
+    domain::Hrid,
+    storage::markdown::{write_file, MarkdownRequirement, CONFIG_VERSION},
+    Requirement,
+};
 
 /// Error type for flush failures.
 #[derive(Debug, thiserror::Error)]
-pub struct FlushError {
-    failures: NonEmpty<(PathBuf, io::Error)>,
+pub enum FlushError {
+    /// Requirement files were changed by something else since they were read.
+    /// Nothing was written, so that those changes aren't overwritten.
+    Conflict(NonEmpty<(Hrid, PathBuf)>),
+    /// Files could not be written or deleted.
+    Io(NonEmpty<(PathBuf, io::Error)>),
 }
 
 impl fmt::Display for FlushError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        const MAX_DISPLAY: usize = 5;
-
-        write!(f, "failed to flush requirements: ")?;
-
-        let total = self.failures.len();
+        match self {
+            Self::Conflict(conflicts) => {
+                write!(
+                    f,
+                    "requirement files changed on disk since they were read: "
+                )?;
+                write_paths(f, conflicts.iter().map(|(_, p)| p.display().to_string()))
+            }
+            Self::Io(failures) => {
+                write!(f, "failed to flush requirements: ")?;
+                write_paths(
+                    f,
+                    failures
+                        .iter()
+                        .map(|(p, e)| format!("{} ({e})", p.display())),
+                )
+            }
+        }
+    }
+}
 
-        let displayed_paths: Vec<String> = self
-            .failures
-            .iter()
-            .take(MAX_DISPLAY)
-            .map(|(p, e)| format!("{} ({e})", p.display()))
-            .collect();
+fn write_paths(
+    f: &mut fmt::Formatter<'_>,
+    paths: impl ExactSizeIterator<Item = String>,
+) -> fmt::Result {
+    const MAX_DISPLAY: usize = 5;
 
-        let msg = displayed_paths.join(", ");
+    let total = paths.len();
+    let msg = paths.take(MAX_DISPLAY).collect::<Vec<_>>().join(", ");
 
-        if total <= MAX_DISPLAY {
-            write!(f, "{msg}")
-        } else {
-            write!(f, "{msg}... (and {} more)", total - MAX_DISPLAY)
-        }
+    if total <= MAX_DISPLAY {
+        write!(f, "{msg}")
+    } else {
+        write!(f, "{msg}... (and {} more)", total - MAX_DISPLAY)
     }
 }
 
@@ -46,8 +75,10 @@ impl Directory {
     ///
     /// # Errors
     ///
-    /// Returns an error containing the paths that failed to flush alongside the
-    /// underlying IO error.
+    /// Returns [`FlushError::Conflict`] without writing anything if a file
+    /// to be written was changed by something else since it was read (see
+    /// [`Directory::reload`]). Otherwise, returns an error containing the
+    /// paths that failed to flush alongside the underlying IO error.
     pub fn flush(&mut self) -> Result<Vec<Hrid>, FlushError> {
         let digits = self.config.digits();
         let mut failures: Vec<(PathBuf, io::Error)> = Vec::new();
@@ -71,13 +102,45 @@ impl Directory {
         // Deterministic write order (and hence deterministic flushed output).
         writes.sort_by(|a, b| a.2.cmp(&b.2));
 
+        // Overwriting a file that changed since it was read would silently
+        // discard that change, so nothing is written until it is reloaded.
+        let conflicts: Vec<(Hrid, PathBuf)> = writes
+            .iter()
+            .filter(|(_, _, path)| self.changed_on_disk(path))
+            .map(|(_, requirement, path)| (requirement.hrid().clone(), path.clone()))
+            .collect();
+        if let Some(conflicts) = NonEmpty::from_vec(conflicts) {
+            return Err(FlushError::Conflict(conflicts));
+        }
+
+        // A migrated config is written first: requirements never depend on
+        // its version, so a failure here must not block them.
+        if self.config_dirty {
+            let path = self.root.join(".req/config.toml");
+            match self.config.save(&path) {
+                Ok(()) => {
+                    self.config_dirty = false;
+                    self.config_version = Some(CONFIG_VERSION);
+                }
+                Err(message) => failures.push((path, io::Error::other(message))),
+            }
+        }
+
         // Phase 2: perform all writes (each atomic). A failure must not abort
         // the flush: skipping the reconciliation phase after a rename would
         // leave two files with the same UUID and wedge the next load.
         for (uuid, requirement, path) in &writes {
-            match requirement.save_to_path(path, digits) {
-                Ok(()) => {
+            let written = MarkdownRequirement::from(requirement.clone())
+                .render(digits)
+                .and_then(|text| {
+                    write_file(path, &text)?;
+                    Ok(content_hash(text.as_bytes()))
+                });
+            match written {
+                Ok(hash) => {
+                    self.hashes.insert(lexical_absolute(path), hash);
                     self.dirty.remove(uuid);
+                    self.outdated.remove(uuid);
                     flushed.push(requirement.hrid().clone());
                 }
                 Err(err) => {
@@ -98,9 +161,13 @@ impl Directory {
                     continue;
                 }
                 match std::fs::remove_file(&path) {
-                    Ok(()) => {}
                     // Already gone: the deletion's goal is achieved.
-                    Err(e) if e.kind() == io::ErrorKind::NotFound => {}
+                    Ok(()) => {
+                        self.hashes.remove(&lexical_absolute(&path));
+                    }
+                    Err(e) if e.kind() == io::ErrorKind::NotFound => {
+                        self.hashes.remove(&lexical_absolute(&path));
+                    }
                     Err(e) => {
                         failures.push((path.clone(), e));
                         deferred.insert(path);
@@ -118,10 +185,20 @@ impl Directory {
         }
 
         if let Some(failures) = NonEmpty::from_vec(failures) {
-            return Err(FlushError { failures });
+            return Err(FlushError::Io(failures));
         }
         Ok(flushed)
     }
+
+    /// Whether the file at `path` was changed or created since it was last
+    /// read or written. A deleted file is simply written again, and a file
+    /// that can't be read is left for the write to report.
+    fn changed_on_disk(&self, path: &Path) -> bool {
+        matches!(
+            file_hash(path),
+            Ok(Some(hash)) if self.hashes.get(&lexical_absolute(path)) != Some(&hash)
+        )
+    }
 }
 
 #[cfg(test)]
@@ -256,4 +333,43 @@ mod tests {
         assert!(child_path.exists());
         assert!(Directory::new(dir.root.clone()).is_ok());
     }
+
+    #[test]
+    fn flush_refuses_to_overwrite_files_changed_on_disk() {
+        let (_tmp, mut dir) = setup_temp_directory();
+        let edited = dir.add_requirement("REQ", String::new()).unwrap();
+        let other = dir.add_requirement("REQ", String::new()).unwrap();
+        dir.flush().unwrap();
+        let path = dir.root.join("REQ-001.md");
+        let text = std::fs::read_to_string(&path).unwrap() + "\nEdited on disk.\n";
+        std::fs::write(&path, &text).unwrap();
+
+        dir.update_requirement(
+            edited.hrid(),
+            None,
+            Some("Edited in memory.".to_string()),
+            None,
+        )
+        .unwrap();
+        dir.update_requirement(other.hrid(), None, Some("Other.".to_string()), None)
+            .unwrap();
+        let Err(FlushError::Conflict(conflicts)) = dir.flush() else {
+            panic!("expected a conflict");
+        };
+        assert_eq!(conflicts.head, (edited.hrid().clone(), path.clone()));
+        assert_eq!(conflicts.len(), 1);
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), text);
+        let other_path = dir.root.join("REQ-002.md");
+        assert!(!std::fs::read_to_string(&other_path)
+            .unwrap()
+            .contains("Other."));
+
+        // Once reloaded, the rest is written.
+        dir.reload(std::slice::from_ref(&path));
+        assert_eq!(dir.flush().unwrap(), [other.hrid().clone()]);
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), text);
+        assert!(std::fs::read_to_string(&other_path)
+            .unwrap()
+            .contains("Other."));
+    }
 }
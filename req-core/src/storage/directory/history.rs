@@ -0,0 +1,186 @@
+//! Recovering earlier versions of requirements from git.
+
+use std::path::PathBuf;
+
+use similar::TextDiff;
+
+use super::Directory;
+use crate::{
+    domain::{Hrid, SuspectLink},
+    storage::git::{self, GitError},
+    Requirement,
+};
+
+/// What changed in a parent requirement since a suspect link was last
+/// reviewed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParentChanges {
+    /// The commit holding the parent as it was when the link was reviewed.
+    pub commit: String,
+    /// A unified diff of the parent's title, body, and tags, from the
+    /// reviewed version to the current one.
+    pub diff: String,
+}
+
+/// The parent of a suspect link, with what is needed to recover its earlier
+/// versions from git.
+///
+/// It is captured from a [`Directory`] so that git can be run without
+/// borrowing the directory (e.g. without holding a lock on it).
+#[derive(Debug, Clone)]
+pub struct ParentHistory {
+    root: PathBuf,
+    path: PathBuf,
+    parent: Requirement,
+    link: SuspectLink,
+    digits: usize,
+}
+
+impl ParentHistory {
+    /// Recovers what changed in the parent since the link was last reviewed.
+    ///
+    /// The parent as it was when the link was reviewed is found by walking
+    /// the git history of the parent's file for a version matching the
+    /// stored fingerprint. Returns `None` if no committed version matches
+    /// (e.g. the reviewed content was never committed).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the directory is not in a git repository or `git`
+    /// cannot be run.
+    pub fn changes(&self) -> Result<Option<ParentChanges>, GitError> {
+        let reviewed = git::find_fingerprint(
+            &self.root,
+            &self.path,
+            self.link.parent_uuid,
+            &self.link.stored_fingerprint,
+        )?;
+        Ok(reviewed.map(|(commit, reviewed)| ParentChanges {
+            commit,
+            diff: content_diff(&self.link.parent_hrid, self.digits, &reviewed, &self.parent),
+        }))
+    }
+}
+
+impl Directory {
+    /// Captures the parent of a suspect link, to recover what changed in it
+    /// since the link was last reviewed. Returns `None` if the parent is
+    /// missing.
+    #[must_use]
+    pub fn parent_history(&self, link: &SuspectLink) -> Option<ParentHistory> {
+        let parent = self.requirement_by_hrid(&link.parent_hrid)?;
+        let path = self
+            .path_for(&link.parent_hrid)
+            .map_or_else(|| self.canonical_path_for(&link.parent_hrid), Into::into);
+        Some(ParentHistory {
+            root: self.root.clone(),
+            path,
+            parent,
+            link: link.clone(),
+            digits: self.config.digits(),
+        })
+    }
+
+    /// Recovers what changed in the parent of a suspect link since the link
+    /// was last reviewed.
+    ///
+    /// Returns `None` if the parent is missing, or if no committed version
+    /// matches (see [`ParentHistory::changes`]).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the directory is not in a git repository or `git`
+    /// cannot be run.
+    pub fn parent_changes(&self, link: &SuspectLink) -> Result<Option<ParentChanges>, GitError> {
+        self.parent_history(link)
+            .map_or(Ok(None), |history| history.changes())
+    }
+}
+
+/// Renders a unified diff between two versions of a requirement.
+fn content_diff(hrid: &Hrid, digits: usize, old: &Requirement, new: &Requirement) -> String {
+    let old = render(old);
+    let new = render(new);
+    let hrid = hrid.display(digits);
+    TextDiff::from_lines(&old, &new)
+        .unified_diff()
+        .header(&format!("{hrid} (reviewed)"), &format!("{hrid} (current)"))
+        .to_string()
+}
+
+/// Renders the fingerprinted content of a requirement as text.
+fn render(requirement: &Requirement) -> String {
+    let mut text = format!("# {}\n\n{}\n", requirement.title(), requirement.body());
+    if !requirement.tags().is_empty() {
+        let tags: Vec<&str> = requirement.tags().iter().map(String::as_str).collect();
+        text.push_str("\nTags: ");
+        text.push_str(&tags.join(", "));
+        text.push('\n');
+    }
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{path::Path, process::Command};
+
+    use tempfile::TempDir;
+
+    use super::*;
+
+    fn git(dir: &Path, args: &[&str]) {
+        let status = Command::new("git")
+            .arg("-C")
+            .arg(dir)
+            .args(["-c", "user.name=Test", "-c", "user.email=test@example.com"])
+            .args(args)
+            .output()
+            .unwrap()
+            .status;
+        assert!(status.success(), "git {args:?} failed");
+    }
+
+    #[test]
+    fn parent_changes_diff_against_reviewed_version() {
+        let tmp = TempDir::new().unwrap();
+        let root = tmp.path();
+        git(root, &["init", "-q"]);
+
+        let mut dir = Directory::new(root.to_path_buf()).unwrap();
+        let parent = dir
+            .add_requirement("USR", "# Login\n\nUsers log in.".to_string())
+            .unwrap();
+        let child = dir.add_requirement("SYS", "# Auth".to_string()).unwrap();
+        dir.link_requirement(child.hrid(), parent.hrid()).unwrap();
+        dir.flush().unwrap();
+        git(root, &["add", "."]);
+        git(root, &["commit", "-q", "-m", "initial"]);
+
+        // An uncommitted intermediate edit, then a rename that is committed.
+        dir.update_requirement(
+            parent.hrid(),
+            None,
+            Some("Users log in with a password.".to_string()),
+            None,
+        )
+        .unwrap();
+        dir.flush().unwrap();
+        let renamed = Hrid::try_from("USR-010").unwrap();
+        dir.rename_requirement(parent.hrid(), &renamed).unwrap();
+        dir.flush().unwrap();
+        git(root, &["add", "-A"]);
+        git(root, &["commit", "-q", "-m", "rename"]);
+
+        let dir = Directory::new(root.to_path_buf()).unwrap();
+        let links = dir.suspect_links();
+        assert_eq!(links.len(), 1);
+        let changes = dir.parent_changes(&links[0]).unwrap().unwrap();
+        assert_eq!(changes.commit.len(), 40);
+        assert!(changes.diff.contains("-Users log in.\n"));
+        assert!(changes.diff.contains("+Users log in with a password.\n"));
+
+        let mut unknown = links[0].clone();
+        unknown.stored_fingerprint = "0".repeat(32);
+        assert_eq!(dir.parent_changes(&unknown).unwrap(), None);
+    }
+}
@@ -0,0 +1,632 @@
+//! Importing requirements from other tools, and merging repeated imports.
+//!
+//! Requirements drafted elsewhere, such as in a spreadsheet, can instead be
+//! created in bulk with [`Directory::add_requirements`], and a whole
+//! repository exported by Requiem itself is rebuilt with
+//! [`Directory::restore`].
+
+use std::{
+    collections::{BTreeMap, BTreeSet, HashMap, HashSet},
+    fmt,
+    path::{Component, PathBuf},
+};
+
+use anyhow::bail;
+use uuid::Uuid;
+
+use super::Directory;
+use crate::{
+    domain::{requirement::Origin, Config, Hrid},
+    Requirement,
+};
+
+/// A requirement read from another tool, ready to be imported.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ImportedRequirement {
+    /// The requirement's identifier in the source. Re-importing a
+    /// requirement with the same identifier updates it in place.
+    pub identifier: String,
+    /// The kind of requirement to create, such as `CUS`.
+    pub kind: String,
+    /// The namespace to create the requirement in.
+    pub namespace: Vec<String>,
+    /// The requirement's title.
+    pub title: String,
+    /// The requirement's body.
+    pub body: String,
+    /// The requirement's tags, or `None` to leave the tags of an existing
+    /// requirement alone.
+    pub tags: Option<BTreeSet<String>>,
+    /// The requirement's parents, as (source identifier, link type).
+    pub parents: Vec<(String, Option<String>)>,
+}
+
+/// What happened to a requirement during an import.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImportChange {
+    /// A requirement was created for a new source requirement.
+    Created,
+    /// The requirement's title, body, tags, or links were updated.
+    Updated,
+    /// The requirement already matched the source.
+    Unchanged,
+}
+
+/// A requirement touched by an import.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ImportEntry {
+    /// The requirement.
+    pub hrid: Hrid,
+    /// The requirement's identifier in the source.
+    pub identifier: String,
+    /// What happened to the requirement.
+    pub change: ImportChange,
+}
+
+/// The outcome of [`Directory::import_requirements`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Import {
+    /// Every imported requirement, in import order.
+    pub entries: Vec<ImportEntry>,
+    /// Requirements imported from the same source before that are absent
+    /// from this import, sorted by HRID. They are kept.
+    pub missing: Vec<Hrid>,
+    /// Parents that match no imported requirement, as (child identifier,
+    /// parent identifier). These links are not made.
+    pub unresolved: Vec<(String, String)>,
+}
+
+impl Import {
+    /// Counts the entries with a given change.
+    #[must_use]
+    pub fn count(&self, change: ImportChange) -> usize {
+        self.entries
+            .iter()
+            .filter(|entry| entry.change == change)
+            .count()
+    }
+}
+
+/// A requirement to create in bulk, such as a row of a spreadsheet.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NewRequirement {
+    /// The line the requirement was read from, for reporting errors.
+    pub line: usize,
+    /// The requirement's HRID.
+    pub hrid: NewHrid,
+    /// The requirement's title.
+    pub title: String,
+    /// The requirement's body.
+    pub body: String,
+    /// The requirement's tags.
+    pub tags: BTreeSet<String>,
+    /// The requirement's parents: existing requirements, or others being
+    /// created with a given HRID.
+    pub parents: Vec<Hrid>,
+}
+
+/// How a requirement created in bulk is identified.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum NewHrid {
+    /// The requirement takes this HRID, which must be free.
+    Given(Hrid),
+    /// The requirement takes the next free HRID of a kind.
+    Next {
+        /// The namespace to create the requirement in.
+        namespace: Vec<String>,
+        /// The kind of requirement to create.
+        kind: String,
+    },
+}
+
+/// Why a requirement could not be created in bulk.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RowError {
+    /// The line the requirement was read from.
+    pub line: usize,
+    /// What is wrong with it.
+    pub message: String,
+}
+
+impl fmt::Display for RowError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+impl Directory {
+    /// Creates requirements in bulk, then links each to its parents.
+    ///
+    /// Every requirement is checked before any is created: given HRIDs must
+    /// be free and unique, kinds allowed, and parents must exist or be
+    /// created with a given HRID. Links are made once every requirement has
+    /// been created, so requirements may name parents that come after them.
+    ///
+    /// Returns the HRIDs of the created requirements, in order. Changes are
+    /// made in memory; call [`Self::flush`] to write them.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error for each invalid requirement. Nothing is written, but
+    /// a failure while creating or linking can leave some requirements
+    /// created in memory, so the directory should be dropped without
+    /// flushing.
+    pub fn add_requirements(
+        &mut self,
+        requirements: &[NewRequirement],
+    ) -> Result<Vec<Hrid>, Vec<RowError>> {
+        self.check_new_requirements(requirements)?;
+
+        let mut errors = Vec::new();
+        let mut created = Vec::new();
+        for requirement in requirements {
+            let content = format!("# {}\n\n{}", requirement.title, requirement.body);
+            let result = match &requirement.hrid {
+                NewHrid::Given(hrid) => self.add_requirement_with_hrid(hrid.clone(), content),
+                NewHrid::Next { namespace, kind } => {
+                    self.add_requirement_with_namespace(namespace.clone(), kind, content)
+                }
+            };
+            let hrid = result.map_err(anyhow::Error::from).and_then(|added| {
+                let hrid = added.hrid().clone();
+                self.update_requirement(&hrid, None, None, Some(requirement.tags.clone()))?;
+                Ok(hrid)
+            });
+            match hrid {
+                Ok(hrid) => created.push(hrid),
+                Err(error) => errors.push(RowError {
+                    line: requirement.line,
+                    message: error.to_string(),
+                }),
+            }
+        }
+        if !errors.is_empty() {
+            return Err(errors);
+        }
+
+        for (requirement, hrid) in requirements.iter().zip(&created) {
+            for parent in &requirement.parents {
+                if let Err(error) = self.link_requirement(hrid, parent) {
+                    errors.push(RowError {
+                        line: requirement.line,
+                        message: error.to_string(),
+                    });
+                }
+            }
+        }
+        if errors.is_empty() {
+            Ok(created)
+        } else {
+            Err(errors)
+        }
+    }
+
+    /// Checks requirements to be created in bulk against the directory and
+    /// each other.
+    fn check_new_requirements(&self, requirements: &[NewRequirement]) -> Result<(), Vec<RowError>> {
+        let digits = self.config.digits();
+        let mut errors = Vec::new();
+        let mut given = BTreeSet::new();
+        for requirement in requirements {
+            let mut error = |message: String| {
+                errors.push(RowError {
+                    line: requirement.line,
+                    message,
+                });
+            };
+            let kind = match &requirement.hrid {
+                NewHrid::Given(hrid) => {
+                    if self.tree.find_by_hrid(hrid).is_some() {
+                        error(format!("{} already exists", hrid.display(digits)));
+                    } else if !given.insert(hrid) {
+                        error(format!("{} is repeated", hrid.display(digits)));
+                    }
+                    hrid.kind()
+                }
+                NewHrid::Next { kind, .. } => kind,
+            };
+            if !self.config.is_kind_allowed(kind) {
+                error(format!("kind '{kind}' is not allowed"));
+            }
+            if requirement.title.trim().is_empty() {
+                error("the title is empty".to_string());
+            }
+        }
+
+        for requirement in requirements {
+            for parent in &requirement.parents {
+                if self.tree.find_by_hrid(parent).is_none() && !given.contains(parent) {
+                    errors.push(RowError {
+                        line: requirement.line,
+                        message: format!("parent {} does not exist", parent.display(digits)),
+                    });
+                }
+            }
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            errors.sort_by_key(|error| error.line);
+            Err(errors)
+        }
+    }
+
+    /// Creates or updates requirements to match those read from another
+    /// tool.
+    ///
+    /// Each requirement records its `source` and identifier as its origin.
+    /// Requirements already imported from the same source are matched by
+    /// identifier and updated in place, so changed content changes their
+    /// fingerprint and links from their children become suspect. New
+    /// requirements are created in their kind and namespace.
+    ///
+    /// Links between requirements from the same source, and their types, are
+    /// made to match the import; links to other requirements are left alone.
+    /// Link types that the configuration doesn't allow are dropped, leaving
+    /// the link untyped. Requirements imported before but absent now are
+    /// reported, not deleted.
+    ///
+    /// Changes are made in memory; call [`Self::flush`] to write them.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a kind or namespace is invalid or not allowed, a
+    /// title spans multiple lines, or a link breaks the configured link
+    /// rules.
+    pub fn import_requirements(
+        &mut self,
+        source: &str,
+        requirements: &[ImportedRequirement],
+    ) -> anyhow::Result<Import> {
+        let mut known = self.imported_from(source);
+        let existing: BTreeSet<Hrid> = known.values().cloned().collect();
+
+        let mut import = Import::default();
+        for requirement in requirements {
+            let entry =
+                self.import_requirement(source, requirement, known.get(&requirement.identifier))?;
+            known.insert(requirement.identifier.clone(), entry.hrid.clone());
+            import.entries.push(entry);
+        }
+
+        for (requirement, entry) in requirements.iter().zip(&mut import.entries) {
+            let changed = self.sync_imported_links(
+                source,
+                requirement,
+                &entry.hrid,
+                &known,
+                &mut import.unresolved,
+            )?;
+            if changed && entry.change == ImportChange::Unchanged {
+                entry.change = ImportChange::Updated;
+            }
+        }
+
+        let imported: BTreeSet<&Hrid> = import.entries.iter().map(|entry| &entry.hrid).collect();
+        import.missing = existing
+            .into_iter()
+            .filter(|hrid| !imported.contains(hrid))
+            .collect();
+        Ok(import)
+    }
+
+    /// The requirements imported from `source`, keyed by identifier.
+    fn imported_from(&self, source: &str) -> HashMap<String, Hrid> {
+        self.tree
+            .iter()
+            .filter_map(|view| {
+                let origin = view.origin?;
+                (origin.source == source).then(|| (origin.identifier.clone(), view.hrid.clone()))
+            })
+            .collect()
+    }
+
+    fn import_requirement(
+        &mut self,
+        source: &str,
+        requirement: &ImportedRequirement,
+        existing: Option<&Hrid>,
+    ) -> anyhow::Result<ImportEntry> {
+        let title = if requirement.title.trim().is_empty() {
+            requirement.identifier.clone()
+        } else {
+            requirement.title.clone()
+        };
+
+        let (hrid, change) = if let Some(hrid) = existing {
+            let changed = self.update_requirement(
+                hrid,
+                Some(title),
+                Some(requirement.body.clone()),
+                requirement.tags.clone(),
+            )?;
+            let change = if changed {
+                ImportChange::Updated
+            } else {
+                ImportChange::Unchanged
+            };
+            (hrid.clone(), change)
+        } else {
+            let content = format!("# {title}\n\n{}", requirement.body);
+            let created = self.add_requirement_with_namespace(
+                requirement.namespace.clone(),
+                &requirement.kind,
+                content,
+            )?;
+            let hrid = created.hrid().clone();
+            self.tree.set_requirement_origin(
+                created.uuid(),
+                Some(Origin {
+                    source: source.to_string(),
+                    identifier: requirement.identifier.clone(),
+                }),
+            );
+            if requirement.tags.is_some() {
+                self.update_requirement(&hrid, None, None, requirement.tags.clone())?;
+            }
+            (hrid, ImportChange::Created)
+        };
+
+        Ok(ImportEntry {
+            hrid,
+            identifier: requirement.identifier.clone(),
+            change,
+        })
+    }
+
+    /// Links an imported requirement to its imported parents, and unlinks it
+    /// from requirements of the same source that are no longer its parents.
+    ///
+    /// Returns whether any link changed.
+    fn sync_imported_links(
+        &mut self,
+        source: &str,
+        requirement: &ImportedRequirement,
+        hrid: &Hrid,
+        known: &HashMap<String, Hrid>,
+        unresolved: &mut Vec<(String, String)>,
+    ) -> anyhow::Result<bool> {
+        let mut parents = Vec::new();
+        for (identifier, link_type) in &requirement.parents {
+            if let Some(parent) = known.get(identifier) {
+                let link_type = link_type
+                    .as_deref()
+                    .map(str::to_lowercase)
+                    .filter(|link_type| self.config.is_link_type_allowed(link_type));
+                parents.push((parent.clone(), link_type));
+            } else {
+                unresolved.push((requirement.identifier.clone(), identifier.clone()));
+            }
+        }
+
+        let Some(view) = self.tree.find_by_hrid(hrid) else {
+            return Ok(false);
+        };
+        let uuid = *view.uuid;
+        let linked: BTreeMap<Hrid, (Uuid, Option<String>)> = view
+            .parents
+            .iter()
+            .filter(|(uuid, _)| {
+                self.tree
+                    .requirement(*uuid)
+                    .and_then(|parent| parent.origin)
+                    .is_some_and(|origin| origin.source == source)
+            })
+            .map(|(uuid, parent)| (parent.hrid.clone(), (*uuid, parent.link_type.clone())))
+            .collect();
+
+        let mut changed = false;
+        for (parent, link_type) in &parents {
+            match linked.get(parent) {
+                None => {
+                    self.link_requirement_with_type(hrid, parent, link_type.as_deref())?;
+                    changed = true;
+                }
+                // A changed type is set in place, so a link that is suspect
+                // stays suspect.
+                Some((parent_uuid, linked_type)) if linked_type != link_type => {
+                    self.tree
+                        .set_link_type(uuid, *parent_uuid, link_type.clone());
+                    self.mark_dirty(uuid);
+                    changed = true;
+                }
+                Some(_) => {}
+            }
+        }
+        let wanted: BTreeSet<&Hrid> = parents.iter().map(|(parent, _)| parent).collect();
+        for parent in linked.keys() {
+            if !wanted.contains(parent) {
+                self.unlink_requirement(hrid, parent)?;
+                changed = true;
+            }
+        }
+        Ok(changed)
+    }
+
+    /// Replaces the configuration of an empty directory and adds
+    /// requirements exactly as given, keeping their UUIDs, creation times,
+    /// and link fingerprints.
+    ///
+    /// Each requirement is written to its path, relative to the root, or to
+    /// its canonical path if it has none. Changes are made in memory; call
+    /// [`Self::flush`] to write them, along with the configuration.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the directory already has requirements, a
+    /// requirement's kind isn't allowed by `config`, a UUID, HRID, or path is
+    /// repeated, or a path is absolute, leaves the root, or isn't a markdown
+    /// file.
+    pub fn restore(
+        &mut self,
+        config: Config,
+        requirements: Vec<(Requirement, Option<PathBuf>)>,
+    ) -> anyhow::Result<()> {
+        if self.tree.iter().next().is_some() {
+            bail!("{} already has requirements", self.root.display());
+        }
+        for (requirement, path) in &requirements {
+            let hrid = requirement.hrid().display(config.digits());
+            if !config.is_kind_allowed(requirement.hrid().kind()) {
+                bail!(
+                    "{hrid}: kind '{}' is not allowed",
+                    requirement.hrid().kind()
+                );
+            }
+            if let Some(path) = path {
+                let relative = path
+                    .components()
+                    .all(|component| matches!(component, Component::Normal(_)));
+                if !relative || path.extension().is_none_or(|extension| extension != "md") {
+                    bail!(
+                        "{hrid}: '{}' is not a markdown file in the root",
+                        path.display()
+                    );
+                }
+            }
+        }
+
+        self.config = config;
+        self.config_dirty = true;
+        let mut written = HashSet::new();
+        for (requirement, path) in requirements {
+            let uuid = requirement.uuid();
+            let path = path.map_or_else(
+                || self.canonical_path_for(requirement.hrid()),
+                |path| self.root.join(path),
+            );
+            if !written.insert(path.clone()) {
+                bail!("more than one requirement is written to {}", path.display());
+            }
+            self.tree.insert(requirement)?;
+            self.paths.insert(uuid, path);
+            self.mark_dirty(uuid);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::directory::setup_temp_directory;
+
+    fn imported(identifier: &str, body: &str, parents: &[&str]) -> ImportedRequirement {
+        ImportedRequirement {
+            identifier: identifier.to_string(),
+            kind: "CUS".to_string(),
+            namespace: Vec::new(),
+            title: format!("Requirement {identifier}"),
+            body: body.to_string(),
+            tags: None,
+            parents: parents
+                .iter()
+                .map(|parent| ((*parent).to_string(), Some("Parent".to_string())))
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn reimport_updates_in_place_and_makes_links_suspect() {
+        let (_tmp, mut directory) = setup_temp_directory();
+        let first = [
+            imported("_a", "Original text.", &[]),
+            imported("_b", "Child text.", &["_a"]),
+            imported("_c", "Dropped later.", &[]),
+        ];
+        let import = directory.import_requirements("reqif", &first).unwrap();
+        assert_eq!(import.count(ImportChange::Created), 3);
+        let cus1 = import.entries[0].hrid.clone();
+        let cus2 = import.entries[1].hrid.clone();
+        assert_eq!(directory.ancestors_of(&cus2), std::slice::from_ref(&cus1));
+
+        let sys = directory
+            .add_requirement("SYS", "# Design".to_string())
+            .unwrap();
+        directory.link_requirement(sys.hrid(), &cus1).unwrap();
+
+        let second = [
+            imported("_a", "Revised text.", &[]),
+            imported("_b", "Child text.", &[]),
+            imported("_d", "New.", &["_missing"]),
+        ];
+        let import = directory.import_requirements("reqif", &second).unwrap();
+        let changes: Vec<_> = import
+            .entries
+            .iter()
+            .map(|entry| (entry.hrid.clone(), entry.change))
+            .collect();
+        assert_eq!(
+            changes,
+            [
+                (cus1.clone(), ImportChange::Updated),
+                (cus2.clone(), ImportChange::Updated),
+                (Hrid::try_from("CUS-004").unwrap(), ImportChange::Created),
+            ]
+        );
+        assert_eq!(import.missing, [Hrid::try_from("CUS-003").unwrap()]);
+        assert_eq!(
+            import.unresolved,
+            [("_d".to_string(), "_missing".to_string())]
+        );
+        assert!(directory.ancestors_of(&cus2).is_empty());
+
+        let view = directory.find_by_hrid(&cus1).unwrap();
+        assert_eq!(view.body, "Revised text.");
+        assert_eq!(view.origin.unwrap().identifier, "_a");
+        assert!(directory
+            .suspect_links()
+            .iter()
+            .any(|link| link.child_hrid == *sys.hrid() && link.parent_hrid == cus1));
+
+        let unchanged = directory.import_requirements("reqif", &second).unwrap();
+        assert_eq!(unchanged.count(ImportChange::Unchanged), 3);
+    }
+
+    #[test]
+    fn reimport_updates_changed_link_types() {
+        let (_tmp, mut directory) = setup_temp_directory();
+        let mut requirements = [
+            imported("_a", "Parent text.", &[]),
+            imported("_b", "Child text.", &["_a"]),
+        ];
+        let import = directory
+            .import_requirements("reqif", &requirements)
+            .unwrap();
+        let (parent, child) = (&import.entries[0].hrid, &import.entries[1].hrid);
+        let link_type = |directory: &Directory| {
+            directory.find_by_hrid(child).unwrap().parents[0]
+                .1
+                .link_type
+                .clone()
+        };
+        assert_eq!(link_type(&directory).as_deref(), Some("parent"));
+
+        // A suspect link stays suspect when only its type changes.
+        directory
+            .update_requirement(parent, None, Some("Revised.".to_string()), None)
+            .unwrap();
+        requirements[0].body = "Revised.".to_string();
+
+        requirements[1].parents = vec![("_a".to_string(), Some("refines".to_string()))];
+        let import = directory
+            .import_requirements("reqif", &requirements)
+            .unwrap();
+        assert_eq!(import.entries[1].change, ImportChange::Updated);
+        assert_eq!(link_type(&directory).as_deref(), Some("refines"));
+        assert_eq!(directory.suspect_links().len(), 1);
+
+        requirements[1].parents = vec![("_a".to_string(), None)];
+        let import = directory
+            .import_requirements("reqif", &requirements)
+            .unwrap();
+        assert_eq!(import.entries[1].change, ImportChange::Updated);
+        assert_eq!(link_type(&directory), None);
+
+        let import = directory
+            .import_requirements("reqif", &requirements)
+            .unwrap();
+        assert_eq!(import.count(ImportChange::Unchanged), 2);
+    }
+}
@@ -9,10 +9,12 @@ use std::{
 
 use rayon::iter::{IntoParallelRefIterator, ParallelIterator};
 use walkdir::WalkDir;
+use xxhash_rust::xxh3::xxh3_64;
 
 use super::Directory;
 use crate::{
     domain::{requirement::LoadError, Config, Tree},
+    storage::markdown::FRONTMATTER_VERSION,
     Requirement,
 };
 
@@ -119,20 +121,57 @@ impl Directory {
     /// Returns an error if unrecognised files (other than the ignored ones)
     /// are found when `allow_unrecognised` is false in the configuration.
     pub fn new_ignoring(root: PathBuf, ignored: &[PathBuf]) -> Result<Self, DirectoryLoadError> {
-        let config = load_config(&root)?;
+        Self::new_with_contents(root, ignored, &HashMap::new())
+    }
+
+    /// Opens a directory at the given path, reading the files in `contents`
+    /// from memory instead of from disk, and treating the ignored files as if
+    /// they did not exist.
+    ///
+    /// This lets editors check unsaved changes: `contents` holds the text of
+    /// open documents, keyed by path. Markdown files in `contents` that don't
+    /// exist on disk yet are loaded too, if they are inside the root.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if unrecognised files (other than the ignored ones)
+    /// are found when `allow_unrecognised` is false in the configuration.
+    pub fn new_with_contents(
+        root: PathBuf,
+        ignored: &[PathBuf],
+        contents: &HashMap<PathBuf, String>,
+    ) -> Result<Self, DirectoryLoadError> {
+        let (config, config_version) = load_config(&root)?;
         let ignored: HashSet<PathBuf> = ignored.iter().map(|path| lexical_absolute(path)).collect();
-        let md_paths: Vec<PathBuf> = collect_markdown_paths(&root)?
-            .into_iter()
-            .filter(|path| !ignored.contains(&lexical_absolute(path)))
+        let contents: HashMap<PathBuf, &str> = contents
+            .iter()
+            .map(|(path, text)| (lexical_absolute(path), text.as_str()))
             .collect();
+        let mut md_paths: Vec<PathBuf> = collect_markdown_paths(&root)?;
+        let on_disk: HashSet<PathBuf> =
+            md_paths.iter().map(|path| lexical_absolute(path)).collect();
+        let root_absolute = lexical_absolute(&root);
+        md_paths.extend(
+            contents
+                .keys()
+                .filter(|path| {
+                    path.starts_with(&root_absolute)
+                        && path.extension() == Some(OsStr::new("md"))
+                        && !on_disk.contains(*path)
+                })
+                .cloned(),
+        );
+        md_paths.retain(|path| !ignored.contains(&lexical_absolute(path)));
 
         let (requirements, unrecognised_paths): (Vec<_>, Vec<_>) = md_paths
             .par_iter()
-            .map(|path| try_load_requirement(path, &root, &config))
+            .map(|path| {
+                let text = contents.get(&lexical_absolute(path)).copied();
+                try_load_requirement(path, text, &config)
+            })
             .partition(Result::is_ok);
 
-        let requirements: Vec<(Requirement, PathBuf)> =
-            requirements.into_iter().map(Result::unwrap).collect();
+        let requirements: Vec<LoadedFile> = requirements.into_iter().map(Result::unwrap).collect();
         let unrecognised_paths: Vec<_> = unrecognised_paths
             .into_iter()
             .map(Result::unwrap_err)
@@ -146,8 +185,13 @@ impl Directory {
         if !config.allowed_kinds().is_empty() {
             let disallowed: Vec<(PathBuf, String)> = requirements
                 .iter()
-                .filter(|(req, _path)| !config.is_kind_allowed(req.hrid().kind()))
-                .map(|(req, path)| (path.clone(), req.hrid().kind().to_string()))
+                .filter(|file| !config.is_kind_allowed(file.requirement.hrid().kind()))
+                .map(|file| {
+                    (
+                        file.path.clone(),
+                        file.requirement.hrid().kind().to_string(),
+                    )
+                })
                 .collect();
 
             if !disallowed.is_empty() {
@@ -159,14 +203,29 @@ impl Directory {
         }
 
         let mut tree = Tree::with_capacity(requirements.len());
+        tree.set_fingerprint_scheme(config.fingerprint());
         let mut paths = HashMap::with_capacity(requirements.len());
-        for (req, path) in requirements {
-            let uuid = req.uuid();
-            tree.insert(req)
+        let mut outdated = HashMap::new();
+        let mut hashes = HashMap::new();
+        for LoadedFile {
+            requirement,
+            path,
+            version,
+            hash,
+        } in requirements
+        {
+            let uuid = requirement.uuid();
+            if version < FRONTMATTER_VERSION {
+                outdated.insert(uuid, version);
+            }
+            tree.insert(requirement)
                 .map_err(|error| DirectoryLoadError::Duplicate {
                     error,
                     path: path.clone(),
                 })?;
+            if let Some(hash) = hash {
+                hashes.insert(lexical_absolute(&path), hash);
+            }
             paths.insert(uuid, path);
         }
 
@@ -181,6 +240,10 @@ impl Directory {
             dirty: HashSet::new(),
             paths,
             deletions: HashSet::new(),
+            config_version,
+            config_dirty: false,
+            outdated,
+            hashes,
         })
     }
 }
@@ -191,12 +254,17 @@ impl Directory {
 /// default configuration. A config file that exists but cannot be read or
 /// parsed is an error: silently falling back to defaults would flip settings
 /// such as `subfolders_are_namespaces` and reinterpret the whole store.
-pub(super) fn load_config(root: &Path) -> Result<Config, DirectoryLoadError> {
+///
+/// Also returns the version the config file is stored in (`None` if there is
+/// no config file).
+pub(super) fn load_config(root: &Path) -> Result<(Config, Option<u32>), DirectoryLoadError> {
     let path = root.join(".req/config.toml");
     if path.exists() {
-        Config::load(&path).map_err(|message| DirectoryLoadError::InvalidConfig { path, message })
+        Config::load_versioned(&path)
+            .map(|(config, version)| (config, Some(version)))
+            .map_err(|message| DirectoryLoadError::InvalidConfig { path, message })
     } else {
-        Ok(Config::default())
+        Ok((Config::default(), None))
     }
 }
 
@@ -204,7 +272,7 @@ pub(super) fn load_config(root: &Path) -> Result<Config, DirectoryLoadError> {
 /// arrived at via different routes (e.g. `root/../SUMMARY.md` vs
 /// `SUMMARY.md`) compare equal. Purely lexical: symlinks are not resolved and
 /// the path need not exist.
-fn lexical_absolute(path: &Path) -> PathBuf {
+pub(super) fn lexical_absolute(path: &Path) -> PathBuf {
     let absolute = std::path::absolute(path).unwrap_or_else(|_| path.to_path_buf());
     let mut resolved = PathBuf::new();
     for component in absolute.components() {
@@ -242,15 +310,29 @@ fn collect_markdown_paths(root: &PathBuf) -> Result<Vec<PathBuf>, DirectoryLoadE
     Ok(paths)
 }
 
+/// A requirement loaded while opening a directory.
+#[derive(Debug)]
+struct LoadedFile {
+    requirement: Requirement,
+    path: PathBuf,
+    /// The frontmatter version it was stored in.
+    version: u32,
+    /// The hash of its file, if it was read from disk.
+    hash: Option<u64>,
+}
+
 fn try_load_requirement(
     path: &Path,
-    _root: &Path,
+    text: Option<&str>,
     config: &Config,
-) -> Result<(Requirement, PathBuf), (PathBuf, LoadError)> {
-    // Load the requirement from the file
-    // The HRID is now read from the frontmatter, not parsed from the path
-    match load_requirement_from_file(path, config) {
-        Ok(req) => Ok((req, path.to_path_buf())),
+) -> Result<LoadedFile, (PathBuf, LoadError)> {
+    match load_requirement(path, text, config) {
+        Ok((requirement, version, hash)) => Ok(LoadedFile {
+            requirement,
+            path: path.to_path_buf(),
+            version,
+            hash,
+        }),
         Err(e) => {
             tracing::debug!(
                 "Failed to load requirement from {}: {:?}",
@@ -262,21 +344,74 @@ fn try_load_requirement(
     }
 }
 
-fn load_requirement_from_file(path: &Path, _config: &Config) -> Result<Requirement, LoadError> {
+/// Loads a requirement from its file, or from its in-memory text if it has
+/// any, also returning the frontmatter version it was stored in and the hash
+/// of the file (if it was read from disk).
+pub(super) fn load_requirement(
+    path: &Path,
+    text: Option<&str>,
+    config: &Config,
+) -> Result<(Requirement, u32, Option<u64>), LoadError> {
+    // The HRID is now read from the frontmatter, not parsed from the path
+    text.map_or_else(
+        || {
+            load_requirement_from_file(path, config)
+                .map(|(requirement, version, hash)| (requirement, version, Some(hash)))
+        },
+        |text| {
+            load_requirement_from_text(text)
+                .map(|(requirement, version)| (requirement, version, None))
+        },
+    )
+}
+
+/// Loads a requirement, also returning the frontmatter version it was stored
+/// in and the hash of the file.
+fn load_requirement_from_file(
+    path: &Path,
+    _config: &Config,
+) -> Result<(Requirement, u32, u64), LoadError> {
     // Load directly from the file path we found during directory scanning
     // The HRID is read from the frontmatter within the file
-    use std::{fs::File, io::BufReader};
-
-    use crate::storage::markdown::MarkdownRequirement;
-
-    let file = File::open(path).map_err(|io_error| match io_error.kind() {
+    let bytes = std::fs::read(path).map_err(|io_error| match io_error.kind() {
         std::io::ErrorKind::NotFound => LoadError::NotFound,
         _ => LoadError::Io(io_error),
     })?;
 
-    let mut reader = BufReader::new(file);
-    let md_req = MarkdownRequirement::read(&mut reader)?;
-    Ok(md_req.try_into()?)
+    let (requirement, version) = load_requirement_from_bytes(&bytes)?;
+    Ok((requirement, version, content_hash(&bytes)))
+}
+
+/// Loads a requirement from the text of a file, also returning the
+/// frontmatter version it was stored in.
+fn load_requirement_from_text(text: &str) -> Result<(Requirement, u32), LoadError> {
+    load_requirement_from_bytes(text.as_bytes())
+}
+
+fn load_requirement_from_bytes(mut bytes: &[u8]) -> Result<(Requirement, u32), LoadError> {
+    use crate::storage::markdown::MarkdownRequirement;
+
+    let md_req = MarkdownRequirement::read(&mut bytes)?;
+    let version = md_req.version();
+    Ok((md_req.try_into()?, version))
+}
+
+/// The hash of a requirement file's content, to tell when it changed.
+pub(super) fn content_hash(bytes: &[u8]) -> u64 {
+    xxh3_64(bytes)
+}
+
+/// The hash of the file at `path`, or `None` if there is no file.
+///
+/// # Errors
+///
+/// Returns an error if the file exists but can't be read.
+pub(super) fn file_hash(path: &Path) -> std::io::Result<Option<u64>> {
+    match std::fs::read(path) {
+        Ok(bytes) => Ok(Some(content_hash(&bytes))),
+        Err(error) if error.kind() == std::io::ErrorKind::NotFound => Ok(None),
+        Err(error) => Err(error),
+    }
 }
 
 #[cfg(test)]
@@ -642,4 +777,47 @@ created: 2025-01-01T00:00:00Z
         let dir = Directory::new_ignoring(root.to_path_buf(), &ignored).unwrap();
         assert_eq!(dir.requirements().count(), 1);
     }
+
+    #[test]
+    fn new_with_contents_reads_open_documents_instead_of_disk() {
+        let tmp = TempDir::new().unwrap();
+        let root = tmp.path();
+        let file = |hrid: &str, uuid: &str, title: &str| {
+            format!(
+                "---\n_version: '1'\nuuid: {uuid}\ncreated: 2025-01-01T00:00:00Z\n---\n# {hrid} \
+                 {title}\n"
+            )
+        };
+        std::fs::write(
+            root.join("USR-001.md"),
+            file("USR-001", "12345678-1234-1234-1234-123456789014", "On disk"),
+        )
+        .unwrap();
+
+        // An unsaved edit, and a new file that hasn't been saved yet.
+        let contents = HashMap::from([
+            (
+                root.join("USR-001.md"),
+                file("USR-001", "12345678-1234-1234-1234-123456789014", "Edited"),
+            ),
+            (
+                root.join("USR-002.md"),
+                file("USR-002", "12345678-1234-1234-1234-123456789015", "New"),
+            ),
+        ]);
+        let dir = Directory::new_with_contents(root.to_path_buf(), &[], &contents).unwrap();
+
+        let title = |hrid: &str| {
+            dir.find_by_hrid(&Hrid::from_str(hrid).unwrap())
+                .unwrap()
+                .title
+                .to_string()
+        };
+        assert_eq!(title("USR-001"), "Edited");
+        assert_eq!(title("USR-002"), "New");
+        assert_eq!(
+            dir.path_for(&Hrid::from_str("USR-002").unwrap()),
+            Some(root.join("USR-002.md").as_path())
+        );
+    }
 }
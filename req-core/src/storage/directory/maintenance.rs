@@ -1,9 +1,12 @@
-//! Drift detection, suspect-link review, and repair.
+//! Drift detection, suspect-link review, format migration, and repair.
 
 use std::path::PathBuf;
 
 use super::Directory;
-use crate::domain::{requirement::LoadError, Hrid};
+use crate::{
+    domain::{requirement::LoadError, AttributeError, Hrid},
+    storage::markdown::{CONFIG_VERSION, FRONTMATTER_VERSION},
+};
 
 /// Result of accepting a suspect link.
 #[derive(Debug)]
@@ -14,6 +17,51 @@ pub enum AcceptResult {
     AlreadyUpToDate,
 }
 
+/// A custom attribute value that does not match the configured schema.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AttributeIssue {
+    /// The requirement with the offending attribute.
+    pub hrid: Hrid,
+    /// The attribute name.
+    pub attribute: String,
+    /// What is wrong with the attribute.
+    pub problem: AttributeProblem,
+}
+
+/// The ways a requirement's attribute can violate the schema.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AttributeProblem {
+    /// A required attribute is not set.
+    Missing,
+    /// The attribute is not declared for the requirement's kind.
+    Undeclared,
+    /// The value does not match the declared type.
+    Invalid(AttributeError),
+}
+
+impl std::fmt::Display for AttributeProblem {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Missing => f.write_str("required attribute is missing"),
+            Self::Undeclared => f.write_str("attribute is not declared for this kind"),
+            Self::Invalid(e) => write!(f, "{e}"),
+        }
+    }
+}
+
+/// A file stored in an older format version than this release writes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OutdatedFile {
+    /// The path of the file.
+    pub path: PathBuf,
+    /// The requirement stored in the file, or `None` for `.req/config.toml`.
+    pub hrid: Option<Hrid>,
+    /// The version the file is stored in.
+    pub version: u32,
+    /// The version the file will be rewritten in.
+    pub latest: u32,
+}
+
 /// Error type for accepting suspect links.
 #[derive(Debug)]
 pub enum AcceptSuspectLinkError {
@@ -166,6 +214,57 @@ impl Directory {
             .collect()
     }
 
+    /// Find the files stored in an older format version than this release
+    /// writes.
+    ///
+    /// The config file (if outdated) comes first, followed by requirements
+    /// sorted by path.
+    #[must_use]
+    pub fn outdated_files(&self) -> Vec<OutdatedFile> {
+        let config = self
+            .config_version
+            .filter(|&version| version < CONFIG_VERSION)
+            .map(|version| OutdatedFile {
+                path: self.root.join(".req/config.toml"),
+                hrid: None,
+                version,
+                latest: CONFIG_VERSION,
+            });
+
+        let mut requirements: Vec<_> = self
+            .outdated
+            .iter()
+            .filter_map(|(uuid, &version)| {
+                Some(OutdatedFile {
+                    path: self.paths.get(uuid)?.clone(),
+                    hrid: Some(self.tree.hrid(*uuid)?.clone()),
+                    version,
+                    latest: FRONTMATTER_VERSION,
+                })
+            })
+            .collect();
+        requirements.sort_by(|a, b| a.path.cmp(&b.path));
+
+        config.into_iter().chain(requirements).collect()
+    }
+
+    /// Upgrade every outdated file to the format version this release writes.
+    ///
+    /// The files are rewritten on the next [`Self::flush`].
+    ///
+    /// Returns the files that will be rewritten.
+    pub fn migrate(&mut self) -> Vec<OutdatedFile> {
+        let outdated = self.outdated_files();
+        self.config_dirty |= self
+            .config_version
+            .is_some_and(|version| version < CONFIG_VERSION);
+        let uuids: Vec<_> = self.outdated.keys().copied().collect();
+        for uuid in uuids {
+            self.mark_dirty(uuid);
+        }
+        outdated
+    }
+
     /// Find all suspect links in the requirement graph.
     ///
     /// A link is suspect when the fingerprint stored in a child requirement
@@ -175,6 +274,66 @@ impl Directory {
         self.tree.suspect_links()
     }
 
+    /// Find all links from approved children to draft parents.
+    #[must_use]
+    pub fn status_conflicts(&self) -> Vec<crate::domain::StatusConflict> {
+        self.tree.status_conflicts()
+    }
+
+    /// Find all requirements that break the link rules of their kind.
+    #[must_use]
+    pub fn link_rule_violations(&self) -> Vec<crate::domain::LinkRuleViolation> {
+        self.tree.link_rule_violations(&self.config)
+    }
+
+    /// Analyse how completely each kind of requirement is traced to the kinds
+    /// downstream of it.
+    #[must_use]
+    pub fn coverage(&self) -> crate::domain::CoverageReport {
+        self.tree.coverage(&self.config)
+    }
+
+    /// Check every requirement's custom attributes against the schema in the
+    /// configuration.
+    ///
+    /// Results are sorted by HRID and attribute name.
+    #[must_use]
+    pub fn attribute_issues(&self) -> Vec<AttributeIssue> {
+        let mut issues = Vec::new();
+        for view in self.tree.iter() {
+            let kind = view.hrid.kind();
+            let declared: std::collections::BTreeMap<_, _> =
+                self.config.attributes_for_kind(kind).collect();
+
+            for (name, attribute) in &declared {
+                let problem = match view.attributes.get(*name) {
+                    None if attribute.required => Some(AttributeProblem::Missing),
+                    None => None,
+                    Some(value) => attribute.check(value).err().map(AttributeProblem::Invalid),
+                };
+                if let Some(problem) = problem {
+                    issues.push(AttributeIssue {
+                        hrid: view.hrid.clone(),
+                        attribute: (*name).to_string(),
+                        problem,
+                    });
+                }
+            }
+
+            for name in view.attributes.keys() {
+                if !declared.contains_key(name.as_str()) {
+                    issues.push(AttributeIssue {
+                        hrid: view.hrid.clone(),
+                        attribute: name.clone(),
+                        problem: AttributeProblem::Undeclared,
+                    });
+                }
+            }
+        }
+        issues.sort_by(|a, b| (&a.hrid, &a.attribute).cmp(&(&b.hrid, &b.attribute)));
+        issues
+    }
+
     /// Detect all cycles in the requirement graph.
     ///
     /// Returns a list of cycles, where each cycle is represented as a path of
@@ -303,6 +462,7 @@ mod tests {
             Parent {
                 hrid: Hrid::try_from("WRONG-999").unwrap(),
                 fingerprint: parent.fingerprint(),
+                link_type: None,
             },
         );
         child.save(&dir.root, &dir.config).unwrap();
@@ -317,4 +477,113 @@ mod tests {
 
         assert_eq!(&parent_ref.hrid, parent.hrid());
     }
+
+    #[test]
+    fn migrate_rewrites_outdated_files() {
+        let (_tmp, dir) = setup_temp_directory();
+        let path = dir.root.join("USR-001.md");
+        std::fs::write(
+            &path,
+            "---\n_version: '1'\nuuid: 12b3f5c5-b1a8-4aa8-a882-20ff1c2aab53\ncreated: \
+             2025-07-14T07:15:00Z\n---\n# USR-001 Title\n",
+        )
+        .unwrap();
+
+        let mut dir = Directory::new(dir.root).unwrap();
+        let outdated = dir.outdated_files();
+        assert_eq!(
+            outdated,
+            [OutdatedFile {
+                path: path.clone(),
+                hrid: Some(Hrid::try_from("USR-001").unwrap()),
+                version: 1,
+                latest: FRONTMATTER_VERSION,
+            }]
+        );
+
+        assert_eq!(dir.migrate(), outdated);
+        dir.flush().unwrap();
+        assert!(dir.outdated_files().is_empty());
+
+        let content = std::fs::read_to_string(&path).unwrap();
+        assert!(content.starts_with("---\n_version: '2'\n"));
+        assert!(Directory::new(dir.root.clone())
+            .unwrap()
+            .outdated_files()
+            .is_empty());
+    }
+
+    #[test]
+    fn attribute_issues_report_schema_violations() {
+        let (_tmp, mut dir) = setup_temp_directory();
+        let mut missing = dir.add_requirement("USR", String::new()).unwrap();
+        dir.flush().unwrap();
+
+        let root = dir.root.clone();
+        std::fs::create_dir_all(root.join(".req")).unwrap();
+        std::fs::write(
+            root.join(".req/config.toml"),
+            "_version = \"1\"\n\n[attributes.priority]\ntype = \"enum\"\nvalues = [\"low\", \
+             \"high\"]\nrequired = true\n",
+        )
+        .unwrap();
+        let config = crate::domain::Config::load(&root.join(".req/config.toml")).unwrap();
+        missing.set_attribute(
+            "owner".to_string(),
+            Some(crate::domain::AttributeValue::Text("alice".to_string())),
+        );
+        missing.save(&root, &config).unwrap();
+
+        let dir = Directory::new(root).unwrap();
+        let issues: Vec<_> = dir
+            .attribute_issues()
+            .into_iter()
+            .map(|issue| (issue.attribute, issue.problem))
+            .collect();
+        assert_eq!(
+            issues,
+            [
+                ("owner".to_string(), AttributeProblem::Undeclared),
+                ("priority".to_string(), AttributeProblem::Missing),
+            ]
+        );
+    }
+
+    #[test]
+    fn fingerprint_scheme_changes_keep_existing_links_valid() {
+        let (_tmp, mut dir) = setup_temp_directory();
+        let parent = dir
+            .add_requirement("USR", "# Parent\n\nThe system shall work.".to_string())
+            .unwrap();
+        let child = dir.add_requirement("SYS", String::new()).unwrap();
+        dir.link_requirement(child.hrid(), parent.hrid()).unwrap();
+        dir.flush().unwrap();
+
+        let root = dir.root.clone();
+        std::fs::create_dir_all(root.join(".req")).unwrap();
+        std::fs::write(
+            root.join(".req/config.toml"),
+            "_version = \"1\"\n\n[fingerprint]\ntitle = true\nnormalize = \"whitespace\"\n",
+        )
+        .unwrap();
+        let mut dir = Directory::new(root).unwrap();
+        assert!(dir.suspect_links().is_empty());
+
+        // The stored fingerprint predates normalisation, so a reflow is a change.
+        let reflow = Some("The system\nshall work.".to_string());
+        dir.update_requirement(parent.hrid(), None, reflow, None)
+            .unwrap();
+        assert_eq!(dir.suspect_links().len(), 1);
+        dir.accept_all_suspect_links();
+        assert!(dir.suspect_links().is_empty());
+
+        let reflow = Some("The system shall\nwork.".to_string());
+        dir.update_requirement(parent.hrid(), None, reflow, None)
+            .unwrap();
+        assert!(dir.suspect_links().is_empty());
+
+        dir.update_requirement(parent.hrid(), Some("Renamed".to_string()), None, None)
+            .unwrap();
+        assert_eq!(dir.suspect_links().len(), 1);
+    }
 }
@@ -0,0 +1,459 @@
+//! Reloading requirements whose files changed on disk.
+//!
+//! A long-running process (such as the MCP server) keeps a [`Directory`] open
+//! while people edit files and pull changes underneath it. Reloading just the
+//! files that changed keeps it current without reopening the whole directory.
+
+use std::{
+    collections::{BTreeSet, HashMap},
+    ffi::OsStr,
+    path::{Path, PathBuf},
+};
+
+use uuid::Uuid;
+use walkdir::WalkDir;
+
+use super::{
+    load::{lexical_absolute, load_requirement},
+    Directory,
+};
+use crate::{
+    domain::{requirement::LoadError, Hrid},
+    storage::markdown::FRONTMATTER_VERSION,
+    Requirement,
+};
+
+/// The changes made by [`Directory::reload`].
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Reload {
+    /// Requirements that were loaded for the first time, including
+    /// requirements whose HRID changed (by their new HRID).
+    pub added: Vec<Hrid>,
+    /// Requirements whose content changed.
+    pub updated: Vec<Hrid>,
+    /// Requirements that were unloaded because their files were deleted,
+    /// including requirements whose HRID changed (by their old HRID).
+    pub removed: Vec<Hrid>,
+    /// Requirements whose unsaved changes were discarded, because their files
+    /// changed on disk or by [`Directory::discard_changes`].
+    pub conflicts: Vec<Hrid>,
+    /// Files that couldn't be reloaded, and why. Whatever was loaded from
+    /// them before is kept.
+    pub invalid: Vec<(PathBuf, String)>,
+}
+
+impl Directory {
+    /// Reloads the requirements in the given files from disk, after they
+    /// were created, changed, or deleted by something else.
+    ///
+    /// A changed directory reloads every file in it, and every file that
+    /// was loaded from it. Paths outside the root, inside `.req`, or that
+    /// aren't Markdown are ignored.
+    ///
+    /// Files on disk win over unsaved changes: a requirement that changed
+    /// both in memory and on disk is reloaded, and reported as a conflict.
+    /// Files that aren't valid requirements (or whose UUID or HRID clashes
+    /// with another requirement) are reported, and otherwise left alone.
+    ///
+    /// A change to `.req/config.toml` reopens the whole directory instead,
+    /// since the configuration affects how every file is read. If it can't
+    /// be reopened, nothing is reloaded and the error is reported against
+    /// the config file.
+    pub fn reload(&mut self, changed: &[PathBuf]) -> Reload {
+        self.reload_with_contents(changed, &HashMap::new())
+    }
+
+    /// Reloads the requirements in the given files, reading the files in
+    /// `contents` from memory instead of from disk.
+    ///
+    /// This lets editors follow unsaved changes as they are typed: `contents`
+    /// holds the text of open documents, keyed by path, as for
+    /// [`Directory::new_with_contents`]. Otherwise this is the same as
+    /// [`Directory::reload`].
+    pub fn reload_with_contents(
+        &mut self,
+        changed: &[PathBuf],
+        contents: &HashMap<PathBuf, String>,
+    ) -> Reload {
+        let mut reload = Reload::default();
+        let config = lexical_absolute(&self.root.join(".req/config.toml"));
+        if changed
+            .iter()
+            .map(|path| lexical_absolute(path))
+            .any(|path| path == config || Some(path.as_path()) == config.parent())
+        {
+            self.reopen(&mut reload, config);
+            return reload;
+        }
+
+        let mut loaded: HashMap<PathBuf, Uuid> = self
+            .paths
+            .iter()
+            .map(|(uuid, path)| (lexical_absolute(path), *uuid))
+            .collect();
+
+        let contents: HashMap<PathBuf, &str> = contents
+            .iter()
+            .map(|(path, text)| (lexical_absolute(path), text.as_str()))
+            .collect();
+        let mut missing = Vec::new();
+        for path in self.changed_files(changed, &loaded) {
+            let text = contents.get(&path).copied();
+            match load_requirement(&path, text, &self.config) {
+                Ok((requirement, version, hash)) => {
+                    self.reload_file(&mut reload, &mut loaded, path, requirement, version, hash);
+                }
+                Err(LoadError::NotFound) => missing.push(path),
+                Err(error) => reload
+                    .invalid
+                    .push((path, format!("Not a valid requirement: {error}"))),
+            }
+        }
+
+        // Deleted files are handled last, so that a file moved to a new path
+        // is reloaded from there rather than unloaded.
+        for path in missing {
+            if let Some(&uuid) = loaded.get(&path) {
+                self.unload(&mut reload, &mut loaded, uuid);
+            }
+        }
+
+        sort(&mut reload);
+        reload
+    }
+
+    /// Discards all unsaved changes, reloading the requirements they touched
+    /// from disk.
+    ///
+    /// Requirements added since the last flush are unloaded, and requirements
+    /// deleted since are loaded again.
+    pub fn discard_changes(&mut self) -> Reload {
+        let mut changed: Vec<PathBuf> = self
+            .dirty
+            .iter()
+            .filter_map(|uuid| self.paths.get(uuid))
+            .cloned()
+            .collect();
+        changed.extend(self.deletions.drain());
+        self.reload(&changed)
+    }
+
+    /// Replaces the whole directory with a freshly loaded copy.
+    fn reopen(&mut self, reload: &mut Reload, config: PathBuf) {
+        let reopened = match Self::new(self.root.clone()) {
+            Ok(reopened) => reopened,
+            Err(error) => {
+                reload.invalid.push((config, error.to_string()));
+                return;
+            }
+        };
+
+        for old in self.tree.iter() {
+            let uuid = *old.uuid;
+            if self.dirty.contains(&uuid) {
+                reload.conflicts.push(old.hrid.clone());
+            }
+            match reopened.tree.hrid(uuid) {
+                None => reload.removed.push(old.hrid.clone()),
+                Some(hrid) if hrid != old.hrid => {
+                    reload.removed.push(old.hrid.clone());
+                    reload.added.push(hrid.clone());
+                }
+                Some(hrid) => {
+                    if self.tree.get_requirement(uuid) != reopened.tree.get_requirement(uuid) {
+                        reload.updated.push(hrid.clone());
+                    }
+                }
+            }
+        }
+        reload.added.extend(
+            reopened
+                .tree
+                .iter()
+                .filter(|new| self.tree.hrid(*new.uuid).is_none())
+                .map(|new| new.hrid.clone()),
+        );
+        sort(reload);
+        *self = reopened;
+    }
+
+    /// The requirement files that may have changed: each changed Markdown
+    /// file, and every file in (or previously loaded from) a changed
+    /// directory.
+    fn changed_files(
+        &self,
+        changed: &[PathBuf],
+        loaded: &HashMap<PathBuf, Uuid>,
+    ) -> BTreeSet<PathBuf> {
+        let root = lexical_absolute(&self.root);
+        let mut files = BTreeSet::new();
+        for path in changed {
+            let path = lexical_absolute(path);
+            if is_markdown(&path) {
+                files.insert(path);
+                continue;
+            }
+            files.extend(
+                loaded
+                    .keys()
+                    .filter(|file| file.starts_with(&path))
+                    .cloned(),
+            );
+            if path.is_dir() {
+                files.extend(
+                    WalkDir::new(&path)
+                        .into_iter()
+                        .filter_map(Result::ok)
+                        .map(|entry| lexical_absolute(entry.path()))
+                        .filter(|file| is_markdown(file)),
+                );
+            }
+        }
+        files.retain(|file| {
+            file.strip_prefix(&root)
+                .is_ok_and(|relative| !relative.components().any(|c| c.as_os_str() == ".req"))
+        });
+        files
+    }
+
+    fn reload_file(
+        &mut self,
+        reload: &mut Reload,
+        loaded: &mut HashMap<PathBuf, Uuid>,
+        path: PathBuf,
+        requirement: Requirement,
+        version: u32,
+        hash: Option<u64>,
+    ) {
+        let uuid = requirement.uuid();
+        let hrid = requirement.hrid().clone();
+        if !self.config.is_kind_allowed(hrid.kind()) {
+            let message = format!(
+                "Kind {} is not allowed (allowed kinds: {})",
+                hrid.kind(),
+                self.config.allowed_kinds().join(", ")
+            );
+            reload.invalid.push((path, message));
+            return;
+        }
+
+        let previous_path = self.paths.get(&uuid).map(|path| lexical_absolute(path));
+        if let Some(other) = previous_path
+            .as_ref()
+            .filter(|previous| **previous != path && previous.exists())
+        {
+            let message = format!(
+                "Duplicate requirement UUID: {uuid} is also in {}",
+                other.display()
+            );
+            reload.invalid.push((path, message));
+            return;
+        }
+        if previous_path.as_ref() == Some(&path)
+            && self.tree.get_requirement(uuid).as_ref() == Some(&requirement)
+        {
+            // The text may still have changed (e.g. its formatting).
+            self.record_hash(path, hash);
+            return;
+        }
+
+        // The file used to hold a different requirement, which is gone.
+        if let Some(&other) = loaded.get(&path).filter(|&&other| other != uuid) {
+            self.unload(reload, loaded, other);
+        }
+
+        let old_hrid = self.tree.hrid(uuid).cloned();
+        if let Err(error) = self.tree.replace(requirement) {
+            reload.invalid.push((path, error.to_string()));
+            return;
+        }
+        if self.dirty.remove(&uuid) {
+            reload.conflicts.push(hrid.clone());
+        }
+        match old_hrid {
+            None => reload.added.push(hrid),
+            Some(old_hrid) if old_hrid != hrid => {
+                reload.removed.push(old_hrid);
+                reload.added.push(hrid);
+            }
+            Some(_) => reload.updated.push(hrid),
+        }
+
+        if let Some(previous_path) = previous_path {
+            loaded.remove(&previous_path);
+        }
+        loaded.insert(path.clone(), uuid);
+        self.record_hash(path.clone(), hash);
+        self.paths.insert(uuid, path);
+        if version < FRONTMATTER_VERSION {
+            self.outdated.insert(uuid, version);
+        } else {
+            self.outdated.remove(&uuid);
+        }
+    }
+
+    /// Records the hash of a file read from disk. A file read from memory
+    /// may differ from what is on disk, so it has no hash.
+    fn record_hash(&mut self, path: PathBuf, hash: Option<u64>) {
+        match hash {
+            Some(hash) => self.hashes.insert(path, hash),
+            None => self.hashes.remove(&path),
+        };
+    }
+
+    fn unload(&mut self, reload: &mut Reload, loaded: &mut HashMap<PathBuf, Uuid>, uuid: Uuid) {
+        if let Some(path) = self.paths.remove(&uuid) {
+            let path = lexical_absolute(&path);
+            self.hashes.remove(&path);
+            loaded.remove(&path);
+        }
+        self.outdated.remove(&uuid);
+        let conflict = self.dirty.remove(&uuid);
+        if let Some(hrid) = self.tree.unload(uuid) {
+            if conflict {
+                reload.conflicts.push(hrid.clone());
+            }
+            reload.removed.push(hrid);
+        }
+    }
+}
+
+fn sort(reload: &mut Reload) {
+    reload.added.sort();
+    reload.updated.sort();
+    reload.removed.sort();
+    reload.conflicts.sort();
+}
+
+fn is_markdown(path: &Path) -> bool {
+    path.extension() == Some(OsStr::new("md"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{super::setup_temp_directory, *};
+
+    #[test]
+    fn reload_follows_changes_on_disk() {
+        let (_tmp, mut dir) = setup_temp_directory();
+        let parent = dir.add_requirement("USR", "# Parent".to_string()).unwrap();
+        let child = dir.add_requirement("SYS", "# Child".to_string()).unwrap();
+        dir.link_requirement(child.hrid(), parent.hrid()).unwrap();
+        dir.flush().unwrap();
+        let parent_path = dir.path_for(parent.hrid()).unwrap().to_path_buf();
+
+        // Files that haven't changed are left alone.
+        assert_eq!(
+            dir.reload(std::slice::from_ref(&parent_path)),
+            Reload::default()
+        );
+
+        // An edit on disk wins over unsaved changes, and keeps the link from
+        // the child.
+        dir.update_requirement(parent.hrid(), None, Some("Unsaved.".to_string()), None)
+            .unwrap();
+        let text = std::fs::read_to_string(&parent_path).unwrap() + "\nEdited.\n";
+        std::fs::write(&parent_path, text).unwrap();
+        let reload = dir.reload(std::slice::from_ref(&parent_path));
+        assert_eq!(reload.updated, [parent.hrid().clone()]);
+        assert_eq!(reload.conflicts, [parent.hrid().clone()]);
+        assert_eq!(dir.find_by_hrid(parent.hrid()).unwrap().body, "Edited.");
+        assert_eq!(dir.children_of(parent.hrid()), [child.hrid().clone()]);
+
+        // A deleted parent is unloaded, and its child refers to a parent that
+        // doesn't exist.
+        std::fs::remove_file(&parent_path).unwrap();
+        let reload = dir.reload(&[parent_path.clone(), dir.root().join("notes.txt")]);
+        assert_eq!(reload.removed, [parent.hrid().clone()]);
+        assert!(dir.find_by_hrid(parent.hrid()).is_none());
+        assert_eq!(dir.find_by_hrid(child.hrid()).unwrap().parents.len(), 1);
+
+        // An invalid file is reported and not loaded.
+        std::fs::write(&parent_path, "not a requirement").unwrap();
+        let reload = dir.reload(std::slice::from_ref(&parent_path));
+        assert_eq!(reload.invalid.len(), 1);
+        assert!(dir.find_by_hrid(parent.hrid()).is_none());
+
+        // Restoring the parent restores the link from the child.
+        parent
+            .save_to_path(&parent_path, dir.config().digits())
+            .unwrap();
+        let reload = dir.reload(&[dir.root().to_path_buf()]);
+        assert_eq!(reload.added, [parent.hrid().clone()]);
+        assert_eq!(dir.children_of(parent.hrid()), [child.hrid().clone()]);
+
+        // A broken config can't be applied, so nothing changes.
+        let config = dir.root().join(".req/config.toml");
+        std::fs::create_dir_all(config.parent().unwrap()).unwrap();
+        std::fs::write(&config, "not = [valid").unwrap();
+        let reload = dir.reload(std::slice::from_ref(&config));
+        assert_eq!(reload.invalid.len(), 1);
+        assert!(dir.find_by_hrid(parent.hrid()).is_some());
+
+        // A valid config reopens the directory, which only reports the
+        // requirements that changed; a config the requirements break is
+        // reported too.
+        std::fs::write(
+            &config,
+            "_version = \"1\"\nallowed_kinds = [\"USR\", \"SYS\"]\n",
+        )
+        .unwrap();
+        assert_eq!(dir.reload(std::slice::from_ref(&config)), Reload::default());
+        std::fs::write(&config, "_version = \"1\"\nallowed_kinds = [\"USR\"]\n").unwrap();
+        let reload = dir.reload(&[config]);
+        assert_eq!(reload.invalid.len(), 1);
+    }
+
+    #[test]
+    fn reload_with_contents_reads_open_documents_instead_of_disk() {
+        let (_tmp, mut dir) = setup_temp_directory();
+        let requirement = dir.add_requirement("USR", "# Saved".to_string()).unwrap();
+        dir.flush().unwrap();
+        let path = dir.path_for(requirement.hrid()).unwrap().to_path_buf();
+        let unsaved = dir.root().join("USR-002.md");
+
+        let text = std::fs::read_to_string(&path)
+            .unwrap()
+            .replace("Saved", "Unsaved");
+        let new = "---\n_version: '1'\nuuid: 5c9c0d4e-9a2f-4c1e-8f5e-0d1c2b3a4f56\n\
+                   created: 2025-01-01T00:00:00Z\n---\n# USR-002 New\n";
+        let contents = HashMap::from([(path.clone(), text), (unsaved.clone(), new.to_string())]);
+        let reload = dir.reload_with_contents(&[path.clone(), unsaved.clone()], &contents);
+        assert_eq!(reload.added, [Hrid::try_from("USR-002").unwrap()]);
+        assert_eq!(reload.updated, [requirement.hrid().clone()]);
+        assert_eq!(
+            dir.find_by_hrid(requirement.hrid()).unwrap().title,
+            "Unsaved"
+        );
+
+        // Closing the documents goes back to what is on disk.
+        let reload = dir.reload(&[path, unsaved]);
+        assert_eq!(reload.removed, [Hrid::try_from("USR-002").unwrap()]);
+        assert_eq!(dir.find_by_hrid(requirement.hrid()).unwrap().title, "Saved");
+    }
+
+    #[test]
+    fn discard_changes_restores_what_is_on_disk() {
+        let (_tmp, mut dir) = setup_temp_directory();
+        let kept = dir.add_requirement("USR", "# Kept".to_string()).unwrap();
+        let deleted = dir.add_requirement("USR", "# Deleted".to_string()).unwrap();
+        dir.flush().unwrap();
+
+        dir.update_requirement(kept.hrid(), None, Some("Unsaved.".to_string()), None)
+            .unwrap();
+        dir.delete_requirement(deleted.hrid()).unwrap();
+        let added = dir.add_requirement("SYS", "# Added".to_string()).unwrap();
+
+        let reload = dir.discard_changes();
+        assert_eq!(reload.added, [deleted.hrid().clone()]);
+        assert_eq!(reload.removed, [added.hrid().clone()]);
+        assert_eq!(
+            reload.conflicts,
+            [added.hrid().clone(), kept.hrid().clone()]
+        );
+        assert_eq!(dir.find_by_hrid(kept.hrid()).unwrap().body, "");
+        assert!(dir.find_by_hrid(deleted.hrid()).is_some());
+        assert!(dir.find_by_hrid(added.hrid()).is_none());
+        assert!(dir.flush().unwrap().is_empty());
+    }
+}
@@ -0,0 +1,464 @@
+//! Keeping test requirements in step with annotated tests and their
+//! results.
+
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+
+use super::Directory;
+use crate::{
+    domain::{
+        requirement::{TestMetadata, TestResult},
+        Hrid, TestOutcome, Verification,
+    },
+    storage::{test_report::TestRun, test_scan::DiscoveredTest},
+};
+
+/// What happened to a test requirement during a sync.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TestChange {
+    /// A requirement was created for a newly annotated test.
+    Created,
+    /// The requirement's title, description, or links were updated.
+    Updated,
+    /// The requirement already matched its test.
+    Unchanged,
+    /// The test no longer exists (or is no longer annotated), so its
+    /// requirement was deleted.
+    Retired,
+}
+
+/// A test requirement touched by a sync.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TestSyncEntry {
+    /// The test requirement.
+    pub hrid: Hrid,
+    /// Where the test is defined.
+    pub location: String,
+    /// What happened to the requirement.
+    pub change: TestChange,
+}
+
+/// The outcome of [`Directory::sync_tests`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TestSync {
+    /// Every test requirement, sorted by HRID.
+    pub entries: Vec<TestSyncEntry>,
+    /// Annotations naming requirements that don't exist, as (test location,
+    /// HRID). These links are not made.
+    pub unresolved: Vec<(String, Hrid)>,
+    /// Requirements of removed tests that could not be retired because other
+    /// requirements link to them.
+    pub not_retired: Vec<Hrid>,
+}
+
+impl TestSync {
+    /// Counts the entries with a given change.
+    #[must_use]
+    pub fn count(&self, change: TestChange) -> usize {
+        self.entries
+            .iter()
+            .filter(|entry| entry.change == change)
+            .count()
+    }
+}
+
+impl Directory {
+    /// Creates, updates, and retires test requirements to match a set of
+    /// annotated tests.
+    ///
+    /// Test requirements are matched to tests by location. Each test gets a
+    /// requirement of the configured test kind, titled and described by its
+    /// doc comment and linked to the requirements it verifies; links to
+    /// requirements it no longer verifies are removed. Requirements whose
+    /// test has gone are deleted, unless other requirements link to them.
+    ///
+    /// Changes are made in memory; call [`Self::flush`] to write them.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the test kind is not allowed, or a link breaks
+    /// the configured link rules.
+    pub fn sync_tests(&mut self, tests: &[DiscoveredTest]) -> anyhow::Result<TestSync> {
+        let kind = self.config.tests().kind.clone();
+        let link_type = self.config.tests().link_type.clone();
+        let existing: HashMap<String, Hrid> = self
+            .tree
+            .iter()
+            .filter_map(|view| Some((view.test?.location.clone(), view.hrid.clone())))
+            .collect();
+
+        let mut sync = TestSync::default();
+        for test in tests {
+            let verifies: BTreeSet<Hrid> = test
+                .verifies
+                .iter()
+                .filter(|hrid| {
+                    let found = self.tree.find_by_hrid(hrid).is_some();
+                    if !found {
+                        sync.unresolved
+                            .push((test.location.clone(), (*hrid).clone()));
+                    }
+                    found
+                })
+                .cloned()
+                .collect();
+
+            let (hrid, mut change) = if let Some(hrid) = existing.get(&test.location) {
+                let changed = self.update_requirement(
+                    hrid,
+                    Some(test.title.clone()),
+                    Some(test.description.clone()),
+                    None,
+                )?;
+                let change = if changed {
+                    TestChange::Updated
+                } else {
+                    TestChange::Unchanged
+                };
+                (hrid.clone(), change)
+            } else {
+                let content = format!("# {}\n\n{}", test.title, test.description);
+                let requirement = self.add_requirement(&kind, content)?;
+                let uuid = requirement.uuid();
+                self.tree.set_requirement_test(
+                    uuid,
+                    Some(TestMetadata {
+                        location: test.location.clone(),
+                        result: None,
+                    }),
+                );
+                (requirement.hrid().clone(), TestChange::Created)
+            };
+
+            let linked: BTreeSet<Hrid> = self
+                .tree
+                .find_by_hrid(&hrid)
+                .map(|view| {
+                    view.parents
+                        .iter()
+                        .map(|(_, parent)| parent.hrid.clone())
+                        .collect()
+                })
+                .unwrap_or_default();
+            for parent in verifies.difference(&linked) {
+                self.link_requirement_with_type(&hrid, parent, link_type.as_deref())?;
+                change = change.max_with(TestChange::Updated);
+            }
+            for parent in linked.difference(&verifies) {
+                self.unlink_requirement(&hrid, parent)?;
+                change = change.max_with(TestChange::Updated);
+            }
+
+            sync.entries.push(TestSyncEntry {
+                hrid,
+                location: test.location.clone(),
+                change,
+            });
+        }
+
+        let found: BTreeSet<&str> = tests.iter().map(|test| test.location.as_str()).collect();
+        for (location, hrid) in existing {
+            if found.contains(location.as_str()) {
+                continue;
+            }
+            if self.delete_requirement(&hrid).is_ok() {
+                sync.entries.push(TestSyncEntry {
+                    hrid,
+                    location,
+                    change: TestChange::Retired,
+                });
+            } else {
+                sync.not_retired.push(hrid);
+            }
+        }
+
+        sync.entries.sort_by(|a, b| a.hrid.cmp(&b.hrid));
+        sync.not_retired.sort();
+        Ok(sync)
+    }
+}
+
+/// The outcome of [`Directory::import_test_results`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TestImport {
+    /// The outcome recorded against each matched test requirement, sorted by
+    /// HRID.
+    pub recorded: Vec<(Hrid, TestOutcome)>,
+    /// Test requirements with no result in the report, sorted by HRID. Their
+    /// previous results are kept.
+    pub not_run: Vec<Hrid>,
+    /// Names of results that match no test requirement.
+    pub unmatched: Vec<String>,
+    /// Names of results that match more than one test requirement. These
+    /// results are not recorded.
+    pub ambiguous: Vec<String>,
+}
+
+impl TestImport {
+    /// Counts the recorded results with a given outcome.
+    #[must_use]
+    pub fn count(&self, outcome: TestOutcome) -> usize {
+        self.recorded
+            .iter()
+            .filter(|(_, recorded)| *recorded == outcome)
+            .count()
+    }
+}
+
+impl Directory {
+    /// Records the results of a test run against the test requirements.
+    ///
+    /// Results are matched to test requirements by test name: the last
+    /// segment of a result's module path that names a test function, with
+    /// the test's file name breaking ties. If several results match one
+    /// requirement (as with parameterised tests), the worst outcome and the
+    /// latest timestamp are recorded. Results without a timestamp are given
+    /// `now`.
+    ///
+    /// Changes are made in memory; call [`Self::flush`] to write them.
+    pub fn import_test_results(&mut self, runs: &[TestRun], now: DateTime<Utc>) -> TestImport {
+        let tests: Vec<(Uuid, Hrid, String)> = self
+            .tree
+            .iter()
+            .filter_map(|view| Some((*view.uuid, view.hrid.clone(), view.test?.location.clone())))
+            .collect();
+
+        let mut import = TestImport::default();
+        let mut results: BTreeMap<usize, TestResult> = BTreeMap::new();
+        for run in runs {
+            let segments: Vec<&str> = run.name.split("::").collect();
+            let mut matches: Vec<usize> = segments
+                .iter()
+                .rev()
+                .map(|segment| {
+                    (0..tests.len())
+                        .filter(|&index| test_function(&tests[index].2) == *segment)
+                        .collect::<Vec<_>>()
+                })
+                .find(|matches| !matches.is_empty())
+                .unwrap_or_default();
+            if matches.len() > 1 {
+                let narrowed: Vec<usize> = matches
+                    .iter()
+                    .copied()
+                    .filter(|&index| {
+                        test_module(&tests[index].2)
+                            .is_some_and(|module| segments.contains(&module))
+                    })
+                    .collect();
+                if !narrowed.is_empty() {
+                    matches = narrowed;
+                }
+            }
+
+            let [index] = matches[..] else {
+                if matches.is_empty() {
+                    import.unmatched.push(run.name.clone());
+                } else {
+                    import.ambiguous.push(run.name.clone());
+                }
+                continue;
+            };
+            let timestamp = run.timestamp.unwrap_or(now);
+            results
+                .entry(index)
+                .and_modify(|result| {
+                    result.outcome = result.outcome.max(run.outcome);
+                    result.timestamp = result.timestamp.max(timestamp);
+                })
+                .or_insert(TestResult {
+                    outcome: run.outcome,
+                    timestamp,
+                });
+        }
+
+        for (index, (uuid, hrid, location)) in tests.into_iter().enumerate() {
+            let Some(result) = results.remove(&index) else {
+                import.not_run.push(hrid);
+                continue;
+            };
+            import.recorded.push((hrid, result.outcome));
+            let test = TestMetadata {
+                location,
+                result: Some(result),
+            };
+            if self.tree.set_requirement_test(uuid, Some(test)) == Some(true) {
+                self.mark_dirty(uuid);
+            }
+        }
+
+        import.recorded.sort();
+        import.not_run.sort();
+        import
+    }
+
+    /// Roll up the verification of every requirement from the recorded
+    /// results of the tests beneath it (see [`crate::domain::Verification`]).
+    ///
+    /// Unverified requirements (with no tests beneath them) are left out.
+    #[must_use]
+    pub fn verification(&self) -> HashMap<Uuid, Verification> {
+        self.tree.verification()
+    }
+}
+
+/// The name of the function in a test location (`path/to/file.rs::name`).
+fn test_function(location: &str) -> &str {
+    location
+        .rsplit_once("::")
+        .map_or(location, |(_, function)| function)
+}
+
+/// The name of the module a test location's file defines: its file stem, or
+/// its directory for `mod.rs`, `lib.rs`, and `main.rs`.
+fn test_module(location: &str) -> Option<&str> {
+    let file = location.rsplit_once("::")?.0;
+    let mut components = file.rsplit('/');
+    let stem = components.next()?.strip_suffix(".rs")?;
+    if matches!(stem, "mod" | "lib" | "main") {
+        components.next()
+    } else {
+        Some(stem)
+    }
+}
+
+impl TestChange {
+    /// Upgrades an unchanged requirement to `change`; created requirements
+    /// stay created.
+    fn max_with(self, change: Self) -> Self {
+        if self == Self::Unchanged {
+            change
+        } else {
+            self
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tempfile::tempdir;
+
+    use super::*;
+
+    fn test(location: &str, title: &str, verifies: &[&Hrid]) -> DiscoveredTest {
+        DiscoveredTest {
+            location: location.to_string(),
+            title: title.to_string(),
+            description: String::new(),
+            verifies: verifies.iter().map(|hrid| (*hrid).clone()).collect(),
+        }
+    }
+
+    #[test]
+    fn sync_creates_updates_and_retires_test_requirements() {
+        let tmp = tempdir().unwrap();
+        let mut directory = Directory::new(tmp.path().to_path_buf()).unwrap();
+        let sys1 = directory
+            .add_requirement("SYS", "# One".to_string())
+            .unwrap();
+        let sys2 = directory
+            .add_requirement("SYS", "# Two".to_string())
+            .unwrap();
+        let missing = Hrid::try_from("SYS-009").unwrap();
+
+        let first = directory
+            .sync_tests(&[
+                test("src/a.rs::one", "One", &[sys1.hrid(), &missing]),
+                test("src/a.rs::two", "Two", &[sys2.hrid()]),
+            ])
+            .unwrap();
+        assert_eq!(first.count(TestChange::Created), 2);
+        assert_eq!(first.unresolved, [("src/a.rs::one".to_string(), missing)]);
+        let one = first.entries[0].hrid.clone();
+        assert_eq!(one.display(3).to_string(), "TEST-001");
+        assert_eq!(directory.ancestors_of(&one), [sys1.hrid().clone()]);
+        directory.flush().unwrap();
+
+        // Reloading finds the requirements by location.
+        let mut directory = Directory::new(tmp.path().to_path_buf()).unwrap();
+        let second = directory
+            .sync_tests(&[test("src/a.rs::one", "One", &[sys2.hrid()])])
+            .unwrap();
+        let changes: Vec<_> = second
+            .entries
+            .iter()
+            .map(|entry| (entry.hrid.display(3).to_string(), entry.change))
+            .collect();
+        assert_eq!(
+            changes,
+            [
+                ("TEST-001".to_string(), TestChange::Updated),
+                ("TEST-002".to_string(), TestChange::Retired),
+            ]
+        );
+        assert_eq!(directory.ancestors_of(&one), [sys2.hrid().clone()]);
+
+        let third = directory
+            .sync_tests(&[test("src/a.rs::one", "One", &[sys2.hrid()])])
+            .unwrap();
+        assert_eq!(third.count(TestChange::Unchanged), 1);
+        assert_eq!(third.entries.len(), 1);
+    }
+
+    #[test]
+    fn import_records_results_against_test_requirements() {
+        let tmp = tempdir().unwrap();
+        let mut directory = Directory::new(tmp.path().to_path_buf()).unwrap();
+        let sys = directory
+            .add_requirement("SYS", "# System".to_string())
+            .unwrap();
+        directory
+            .sync_tests(&[
+                test("src/auth.rs::login", "Login", &[sys.hrid()]),
+                test("src/session.rs::login", "Session login", &[sys.hrid()]),
+                test("tests/api.rs::refresh", "Refresh", &[sys.hrid()]),
+                test("tests/api.rs::logout", "Logout", &[sys.hrid()]),
+            ])
+            .unwrap();
+
+        let run = |name: &str, outcome| TestRun {
+            name: name.to_string(),
+            outcome,
+            timestamp: None,
+        };
+        let now = Utc::now();
+        let import = directory.import_test_results(
+            &[
+                run("auth::tests::login", TestOutcome::Passed),
+                run("session::tests::login", TestOutcome::Failed),
+                run("refresh::case_1", TestOutcome::Passed),
+                run("refresh::case_2", TestOutcome::Skipped),
+                run("tests::login", TestOutcome::Passed),
+                run("tests::unrelated", TestOutcome::Passed),
+            ],
+            now,
+        );
+
+        let recorded: Vec<_> = import
+            .recorded
+            .iter()
+            .map(|(hrid, outcome)| (hrid.display(3).to_string(), *outcome))
+            .collect();
+        assert_eq!(
+            recorded,
+            [
+                ("TEST-001".to_string(), TestOutcome::Passed),
+                ("TEST-002".to_string(), TestOutcome::Failed),
+                ("TEST-003".to_string(), TestOutcome::Skipped),
+            ]
+        );
+        assert_eq!(import.not_run, [Hrid::try_from("TEST-004").unwrap()]);
+        assert_eq!(import.ambiguous, ["tests::login"]);
+        assert_eq!(import.unmatched, ["tests::unrelated"]);
+
+        let view = directory.find_by_hrid(&import.recorded[0].0).unwrap();
+        let result = view.test.unwrap().result.as_ref().unwrap();
+        assert_eq!(result.timestamp, now);
+        assert_eq!(
+            directory.verification().get(&sys.uuid()),
+            Some(&Verification::Failed)
+        );
+    }
+}
@@ -0,0 +1,460 @@
+//! Reading the requirements in a Doorstop project, to migrate them to
+//! Requiem.
+//!
+//! A Doorstop project is a tree of documents, each a directory with a
+//! `.doorstop.yml` naming its prefix and parent document, holding one YAML
+//! file per item. Each document's prefix becomes a kind, and each item a
+//! requirement identified by its UID (its file name):
+//!
+//! - `header` becomes the title, and `text` the body
+//! - `links` become parent links; Doorstop's link fingerprints are dropped,
+//!   since links are fingerprinted afresh when they are made
+//! - `level` orders the items of a document, and documents are ordered
+//!   parents first, so HRIDs follow the outline of the project
+//! - inactive, non-normative, and derived items are tagged `inactive`,
+//!   `non-normative`, and `derived`
+//!
+//! Anything else, such as `ref`, `references`, and custom attributes, is
+//! reported as unmapped rather than imported.
+
+use std::{
+    cmp::Ordering,
+    collections::{BTreeSet, HashMap},
+    io,
+    path::{Path, PathBuf},
+};
+
+use serde::Deserialize;
+use serde_yaml::{Mapping, Value};
+
+use crate::storage::directory::ImportedRequirement;
+
+/// The source recorded in the origin of requirements imported from
+/// Doorstop.
+pub const SOURCE: &str = "doorstop";
+
+/// The file marking a directory as a Doorstop document.
+const CONFIG_FILE: &str = ".doorstop.yml";
+/// The file marking a Doorstop document to be skipped.
+const SKIP_FILE: &str = ".doorstop.skip";
+
+/// Item fields that are imported, or that have no meaning once imported.
+const MAPPED_FIELDS: [&str; 8] = [
+    "active",
+    "derived",
+    "normative",
+    "header",
+    "level",
+    "links",
+    "text",
+    "reviewed",
+];
+
+/// The requirements read from a Doorstop project.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DoorstopProject {
+    /// The prefixes of the documents read, parents first.
+    pub documents: Vec<String>,
+    /// The items, document by document in level order.
+    pub requirements: Vec<ImportedRequirement>,
+    /// What could not be imported, as (document prefix or item UID,
+    /// description).
+    pub unmapped: Vec<(String, String)>,
+}
+
+/// Error type for reading Doorstop projects.
+#[derive(Debug, thiserror::Error)]
+pub enum DoorstopError {
+    /// A file could not be read.
+    #[error("failed to read {}: {source}", path.display())]
+    Io {
+        /// The file that could not be read.
+        path: PathBuf,
+        /// The underlying error.
+        source: io::Error,
+    },
+
+    /// The project directory could not be traversed.
+    #[error(transparent)]
+    Walk(#[from] walkdir::Error),
+
+    /// A document's `.doorstop.yml` is not valid.
+    #[error("invalid Doorstop document {}: {source}", path.display())]
+    Config {
+        /// The invalid `.doorstop.yml`.
+        path: PathBuf,
+        /// Why it is invalid.
+        source: serde_yaml::Error,
+    },
+
+    /// No `.doorstop.yml` was found beneath the project directory.
+    #[error("no Doorstop documents (.doorstop.yml) found in {}", .0.display())]
+    NoDocuments(PathBuf),
+}
+
+#[derive(Debug, Deserialize)]
+struct DocumentConfig {
+    settings: Settings,
+}
+
+#[derive(Debug, Deserialize)]
+struct Settings {
+    prefix: String,
+    #[serde(default)]
+    parent: Option<String>,
+    #[serde(default)]
+    itemformat: Option<String>,
+}
+
+struct Document {
+    path: PathBuf,
+    settings: Settings,
+}
+
+/// Reads every document and item in the Doorstop project at `root`, to be
+/// imported in `namespace`.
+///
+/// # Errors
+///
+/// Returns an error if the project can't be traversed, a file can't be
+/// read, a `.doorstop.yml` is invalid, or there are no documents. Items that
+/// can't be read are reported as unmapped instead.
+pub fn read_project(root: &Path, namespace: &[String]) -> Result<DoorstopProject, DoorstopError> {
+    let documents = find_documents(root)?;
+    if documents.is_empty() {
+        return Err(DoorstopError::NoDocuments(root.to_path_buf()));
+    }
+
+    let mut project = DoorstopProject::default();
+    for document in documents {
+        let prefix = document.settings.prefix.clone();
+        project.documents.push(prefix.clone());
+        if document.path.join(SKIP_FILE).exists() {
+            project
+                .unmapped
+                .push((prefix, "document is marked to be skipped".to_string()));
+            continue;
+        }
+        if let Some(format) = document
+            .settings
+            .itemformat
+            .as_deref()
+            .filter(|format| *format != "yaml")
+        {
+            project
+                .unmapped
+                .push((prefix, format!("{format} items are not supported")));
+            continue;
+        }
+        let kind: String = prefix
+            .chars()
+            .filter(char::is_ascii_alphabetic)
+            .map(|c| c.to_ascii_uppercase())
+            .collect();
+        if kind.is_empty() {
+            project
+                .unmapped
+                .push((prefix, "prefix has no letters to use as a kind".to_string()));
+            continue;
+        }
+        if kind != prefix {
+            project
+                .unmapped
+                .push((prefix.clone(), format!("prefix is imported as kind {kind}")));
+        }
+
+        let mut items = read_items(&document.path, &kind, namespace, &mut project.unmapped)?;
+        items.sort_by(|(a_level, a), (b_level, b)| {
+            compare_levels(a_level, b_level).then_with(|| a.identifier.cmp(&b.identifier))
+        });
+        project
+            .requirements
+            .extend(items.into_iter().map(|(_, requirement)| requirement));
+    }
+    Ok(project)
+}
+
+/// Finds the documents beneath `root`, parents first.
+fn find_documents(root: &Path) -> Result<Vec<Document>, DoorstopError> {
+    let mut documents = Vec::new();
+    for entry in walkdir::WalkDir::new(root).sort_by_file_name() {
+        let entry = entry?;
+        if entry.file_name() != CONFIG_FILE {
+            continue;
+        }
+        let path = entry.path();
+        let text = std::fs::read_to_string(path).map_err(|source| DoorstopError::Io {
+            path: path.to_path_buf(),
+            source,
+        })?;
+        let config: DocumentConfig =
+            serde_yaml::from_str(&text).map_err(|source| DoorstopError::Config {
+                path: path.to_path_buf(),
+                source,
+            })?;
+        documents.push(Document {
+            path: path.parent().unwrap_or(root).to_path_buf(),
+            settings: config.settings,
+        });
+    }
+
+    let parents: HashMap<String, Option<String>> = documents
+        .iter()
+        .map(|document| {
+            (
+                document.settings.prefix.clone(),
+                document.settings.parent.clone(),
+            )
+        })
+        .collect();
+    let depth = |prefix: &str| {
+        let mut depth = 0;
+        let mut current = parents.get(prefix).cloned().flatten();
+        while let Some(parent) = current {
+            depth += 1;
+            if depth > parents.len() {
+                break;
+            }
+            current = parents.get(&parent).cloned().flatten();
+        }
+        depth
+    };
+    documents.sort_by_cached_key(|document| {
+        (
+            depth(&document.settings.prefix),
+            document.settings.prefix.clone(),
+        )
+    });
+    Ok(documents)
+}
+
+/// Reads the items of the document in `dir`, with their levels.
+///
+/// Items are read from the document's directory and its subdirectories,
+/// except those that are documents themselves.
+fn read_items(
+    dir: &Path,
+    kind: &str,
+    namespace: &[String],
+    unmapped: &mut Vec<(String, String)>,
+) -> Result<Vec<(Vec<u32>, ImportedRequirement)>, DoorstopError> {
+    let mut items = Vec::new();
+    let walker = walkdir::WalkDir::new(dir)
+        .sort_by_file_name()
+        .into_iter()
+        .filter_entry(|entry| {
+            entry.path() == dir
+                || !(entry.file_type().is_dir() && entry.path().join(CONFIG_FILE).exists())
+        });
+    for entry in walker {
+        let entry = entry?;
+        let path = entry.path();
+        let is_item = entry.file_type().is_file()
+            && path.extension().is_some_and(|extension| extension == "yml")
+            && !entry.file_name().to_string_lossy().starts_with('.');
+        if !is_item {
+            continue;
+        }
+        let uid = path
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        let text = std::fs::read_to_string(path).map_err(|source| DoorstopError::Io {
+            path: path.to_path_buf(),
+            source,
+        })?;
+        match serde_yaml::from_str::<Mapping>(&text) {
+            Ok(fields) => items.push(read_item(uid, &fields, kind, namespace, unmapped)),
+            Err(error) => unmapped.push((uid, format!("item could not be read: {error}"))),
+        }
+    }
+    Ok(items)
+}
+
+fn read_item(
+    uid: String,
+    fields: &Mapping,
+    kind: &str,
+    namespace: &[String],
+    unmapped: &mut Vec<(String, String)>,
+) -> (Vec<u32>, ImportedRequirement) {
+    let field = |name: &str| fields.get(name).filter(|value| !value.is_null());
+    let flag = |name, default| field(name).and_then(Value::as_bool).unwrap_or(default);
+    let text = |name| {
+        field(name)
+            .and_then(Value::as_str)
+            .unwrap_or_default()
+            .trim()
+            .to_string()
+    };
+
+    let mut tags = BTreeSet::new();
+    if !flag("active", true) {
+        tags.insert("inactive".to_string());
+    }
+    if !flag("normative", true) {
+        tags.insert("non-normative".to_string());
+    }
+    if flag("derived", false) {
+        tags.insert("derived".to_string());
+    }
+
+    let parents = field("links")
+        .and_then(Value::as_sequence)
+        .into_iter()
+        .flatten()
+        .filter_map(|link| match link {
+            Value::String(parent) => Some(parent.clone()),
+            Value::Mapping(link) => link.keys().next()?.as_str().map(str::to_string),
+            _ => None,
+        })
+        .map(|parent| (parent, None))
+        .collect();
+
+    let level = field("level")
+        .map(|level| match level {
+            Value::Number(number) => number.to_string(),
+            Value::String(level) => level.clone(),
+            _ => String::new(),
+        })
+        .unwrap_or_default()
+        .split('.')
+        .filter_map(|part| part.trim().parse().ok())
+        .collect();
+
+    for (name, value) in fields {
+        let Some(name) = name.as_str() else {
+            continue;
+        };
+        if MAPPED_FIELDS.contains(&name) || is_empty(value) {
+            continue;
+        }
+        unmapped.push((uid.clone(), format!("attribute '{name}' is not imported")));
+    }
+
+    let requirement = ImportedRequirement {
+        title: text("header"),
+        body: text("text"),
+        identifier: uid,
+        kind: kind.to_string(),
+        namespace: namespace.to_vec(),
+        tags: Some(tags),
+        parents,
+    };
+    (level, requirement)
+}
+
+fn is_empty(value: &Value) -> bool {
+    match value {
+        Value::Null => true,
+        Value::String(text) => text.trim().is_empty(),
+        Value::Sequence(values) => values.is_empty(),
+        Value::Mapping(values) => values.is_empty(),
+        _ => false,
+    }
+}
+
+/// Compares Doorstop levels such as `1.2.10` part by part; items without a
+/// level come last.
+fn compare_levels(a: &[u32], b: &[u32]) -> Ordering {
+    match (a.is_empty(), b.is_empty()) {
+        (true, false) => Ordering::Greater,
+        (false, true) => Ordering::Less,
+        _ => a.cmp(b),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use tempfile::tempdir;
+
+    use super::*;
+
+    #[test]
+    fn reads_documents_parents_first_in_level_order() {
+        let tmp = tempdir().unwrap();
+        let root = tmp.path();
+        let write = |path: &str, text: &str| {
+            let path = root.join(path);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, text).unwrap();
+        };
+        write(
+            "reqs/.doorstop.yml",
+            "settings:\n  digits: 3\n  prefix: REQ\n  sep: ''\n",
+        );
+        write(
+            "reqs/REQ002.yml",
+            "active: true\nlevel: 1.1\nlinks: []\nnormative: true\nref: ''\ntext: |\n  Second.\n",
+        );
+        write(
+            "reqs/REQ001.yml",
+            "active: true\nheader: Braking\nlevel: '1.2'\nnormative: false\ntext: First.\n",
+        );
+        write(
+            "reqs/tests/.doorstop.yml",
+            "settings:\n  prefix: LLT_A\n  parent: REQ\n",
+        );
+        write(
+            "reqs/tests/LLT_A001.yml",
+            "active: false\nderived: true\nlinks:\n- REQ001: 2b5d1e\n- REQ002\nref: \
+             test_brakes\nreviewed: abc\ntext: Test.\n",
+        );
+
+        let project = read_project(root, &[]).unwrap();
+        assert_eq!(project.documents, ["REQ", "LLT_A"]);
+        let summary: Vec<_> = project
+            .requirements
+            .iter()
+            .map(|requirement| {
+                (
+                    requirement.identifier.as_str(),
+                    requirement.kind.as_str(),
+                    requirement.title.as_str(),
+                    requirement.body.as_str(),
+                    requirement.tags.clone().unwrap_or_default(),
+                )
+            })
+            .collect();
+        let tags = |tags: &[&str]| tags.iter().map(ToString::to_string).collect();
+        assert_eq!(
+            summary,
+            [
+                ("REQ002", "REQ", "", "Second.", tags(&[])),
+                (
+                    "REQ001",
+                    "REQ",
+                    "Braking",
+                    "First.",
+                    tags(&["non-normative"])
+                ),
+                (
+                    "LLT_A001",
+                    "LLTA",
+                    "",
+                    "Test.",
+                    tags(&["derived", "inactive"])
+                ),
+            ]
+        );
+        assert_eq!(
+            project.requirements[2].parents,
+            [("REQ001".to_string(), None), ("REQ002".to_string(), None)]
+        );
+        assert_eq!(
+            project.unmapped,
+            [
+                (
+                    "LLT_A".to_string(),
+                    "prefix is imported as kind LLTA".to_string()
+                ),
+                (
+                    "LLT_A001".to_string(),
+                    "attribute 'ref' is not imported".to_string()
+                ),
+            ]
+        );
+    }
+}
@@ -0,0 +1,290 @@
+//! Reading requirements from git history.
+//!
+//! Files are read from the object database of the local repository with the
+//! `git` command, so nothing in the working tree is touched.
+
+use std::{
+    io::{self, BufRead, BufReader, Read, Write},
+    path::{Component, Path},
+    process::{Command, Stdio},
+};
+
+use uuid::Uuid;
+
+use crate::{
+    domain::{requirement::ContentRef, FingerprintScheme},
+    storage::markdown::MarkdownRequirement,
+    Requirement,
+};
+
+/// Error type for reading requirements from git history.
+#[derive(Debug, thiserror::Error)]
+pub enum GitError {
+    /// The `git` command could not be run.
+    #[error("failed to run git: {0}")]
+    Spawn(#[from] io::Error),
+
+    /// A `git` command failed (e.g. the revision doesn't exist, or the
+    /// directory is not in a git repository).
+    #[error("git {command} failed: {stderr}")]
+    Failed {
+        /// The git subcommand that failed.
+        command: String,
+        /// What git printed to stderr.
+        stderr: String,
+    },
+
+    /// The revision looks like a command-line option.
+    #[error("invalid revision '{0}'")]
+    InvalidRevision(String),
+
+    /// `git` produced output that could not be understood.
+    #[error("unexpected output from git: {0}")]
+    UnexpectedOutput(String),
+}
+
+/// Reads the requirements under `root` as they were at a git revision.
+///
+/// Markdown files that are not valid requirements at that revision (such as
+/// READMEs) are skipped, as are files under `.req`.
+///
+/// # Errors
+///
+/// Returns an error if `root` is not in a git repository, the revision
+/// doesn't exist, or `git` cannot be run.
+pub fn requirements_at_revision(root: &Path, revision: &str) -> Result<Vec<Requirement>, GitError> {
+    if revision.starts_with('-') {
+        return Err(GitError::InvalidRevision(revision.to_string()));
+    }
+    let prefix = String::from_utf8_lossy(&git(root, &["rev-parse", "--show-prefix"])?)
+        .trim_end()
+        .to_string();
+    let listing = git(
+        root,
+        &[
+            "ls-tree",
+            "-r",
+            "-z",
+            "--full-name",
+            "--name-only",
+            revision,
+            "--",
+            ".",
+        ],
+    )?;
+
+    let objects: Vec<String> = listing
+        .split(|&byte| byte == 0)
+        .filter_map(|path| std::str::from_utf8(path).ok())
+        .filter(|path| is_requirement_path(path.strip_prefix(&prefix).unwrap_or(path)))
+        .map(|path| format!("{revision}:{path}"))
+        .collect();
+
+    Ok(read_blobs(root, &objects)?
+        .into_iter()
+        .filter_map(|(object, contents)| parse(&object, &contents))
+        .collect())
+}
+
+/// Finds the most recent committed version of a requirement whose content
+/// has the given fingerprint.
+///
+/// The history of the requirement's file is followed across renames, and
+/// the fingerprint is recalculated with the scheme that produced it. Returns
+/// the commit and the requirement as it was in that commit, or `None` if no
+/// committed version matches (e.g. the content was never committed).
+///
+/// # Errors
+///
+/// Returns an error if `root` is not in a git repository or `git` cannot be
+/// run.
+pub fn find_fingerprint(
+    root: &Path,
+    path: &Path,
+    uuid: Uuid,
+    fingerprint: &str,
+) -> Result<Option<(String, Requirement)>, GitError> {
+    let Some(scheme) = FingerprintScheme::of(fingerprint) else {
+        return Ok(None);
+    };
+    let path = path.to_string_lossy();
+    let log = git(
+        root,
+        &[
+            "log",
+            "--follow",
+            "-z",
+            "--format=%H",
+            "--name-only",
+            "--",
+            &path,
+        ],
+    )?;
+
+    // "<commit>\0\n<path>\0" for each commit that touched the file, newest
+    // first.
+    let log = String::from_utf8_lossy(&log);
+    let fields: Vec<&str> = log.split('\0').collect();
+    let objects: Vec<String> = fields
+        .chunks_exact(2)
+        .map(|entry| format!("{}:{}", entry[0], entry[1].trim_start_matches('\n')))
+        .collect();
+
+    Ok(read_blobs(root, &objects)?
+        .into_iter()
+        .find_map(|(object, contents)| {
+            let requirement = parse(&object, &contents)?;
+            let matches = requirement.uuid() == uuid
+                && scheme.fingerprint(ContentRef {
+                    title: requirement.title(),
+                    body: requirement.body(),
+                    tags: requirement.tags(),
+                }) == fingerprint;
+            let (commit, _) = object.split_once(':')?;
+            matches.then(|| (commit.to_string(), requirement))
+        }))
+}
+
+/// Parses a requirement read from git, returning `None` if it isn't valid.
+fn parse(object: &str, contents: &[u8]) -> Option<Requirement> {
+    let requirement = MarkdownRequirement::read(&mut &contents[..])
+        .ok()
+        .and_then(|requirement| Requirement::try_from(requirement).ok());
+    if requirement.is_none() {
+        tracing::debug!("Skipping {object}: not a valid requirement");
+    }
+    requirement
+}
+
+/// Checks whether a path relative to the requirements root may hold a
+/// requirement.
+fn is_requirement_path(path: &str) -> bool {
+    let path = Path::new(path);
+    path.extension().is_some_and(|extension| extension == "md")
+        && !path
+            .components()
+            .any(|component| component == Component::Normal(".req".as_ref()))
+}
+
+/// Runs a git command in `dir`, returning its stdout.
+fn git(dir: &Path, args: &[&str]) -> Result<Vec<u8>, GitError> {
+    let output = Command::new("git").arg("-C").arg(dir).args(args).output()?;
+    if !output.status.success() {
+        return Err(GitError::Failed {
+            command: args[0].to_string(),
+            stderr: String::from_utf8_lossy(&output.stderr).trim().to_string(),
+        });
+    }
+    Ok(output.stdout)
+}
+
+/// Reads the contents of files, given as `<revision>:<full path>`, using a
+/// single `git cat-file --batch` process.
+///
+/// Files that don't exist are skipped.
+fn read_blobs(dir: &Path, objects: &[String]) -> Result<Vec<(String, Vec<u8>)>, GitError> {
+    let mut child = Command::new("git")
+        .arg("-C")
+        .arg(dir)
+        .args(["cat-file", "--batch"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()?;
+
+    // Write the requests from another thread so that a full stdout pipe can't
+    // deadlock the two processes.
+    let mut stdin = child.stdin.take().expect("stdin is piped");
+    let mut requests = String::new();
+    for object in objects {
+        requests.push_str(object);
+        requests.push('\n');
+    }
+    let writer = std::thread::spawn(move || stdin.write_all(requests.as_bytes()));
+
+    let mut stdout = BufReader::new(child.stdout.take().expect("stdout is piped"));
+    let mut blobs = Vec::with_capacity(objects.len());
+    for object in objects {
+        let mut header = String::new();
+        stdout.read_line(&mut header)?;
+        // "<oid> <type> <size>", or "<object> missing"
+        let size = match header.split_whitespace().collect::<Vec<_>>()[..] {
+            [_, "blob", size] => size
+                .parse::<usize>()
+                .map_err(|_| GitError::UnexpectedOutput(header.clone()))?,
+            [.., "missing"] => continue,
+            _ => return Err(GitError::UnexpectedOutput(header)),
+        };
+        let mut contents = vec![0; size + 1];
+        stdout.read_exact(&mut contents)?;
+        contents.pop(); // trailing newline
+        blobs.push((object.clone(), contents));
+    }
+
+    writer
+        .join()
+        .map_err(|_| GitError::UnexpectedOutput("cat-file writer panicked".to_string()))??;
+    let output = child.wait_with_output()?;
+    if !output.status.success() {
+        return Err(GitError::Failed {
+            command: "cat-file".to_string(),
+            stderr: String::from_utf8_lossy(&output.stderr).trim().to_string(),
+        });
+    }
+    Ok(blobs)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Directory;
+
+    fn run_git(dir: &Path, args: &[&str]) {
+        let status = Command::new("git")
+            .arg("-C")
+            .arg(dir)
+            .args(["-c", "user.name=Test", "-c", "user.email=test@example.com"])
+            .args(args)
+            .stdout(Stdio::null())
+            .status()
+            .unwrap();
+        assert!(status.success(), "git {args:?} failed");
+    }
+
+    #[test]
+    fn reads_requirements_as_committed() {
+        let tmp = tempfile::tempdir().unwrap();
+        let repo = tmp.path();
+        let root = repo.join("requirements");
+        run_git(repo, &["init", "-q"]);
+
+        let mut dir = Directory::new(root.clone()).unwrap();
+        let requirement = dir
+            .add_requirement("USR", "# Committed\n\nOld body".to_string())
+            .unwrap();
+        dir.flush().unwrap();
+        std::fs::write(root.join("README.md"), "Not a requirement\n").unwrap();
+        run_git(repo, &["add", "."]);
+        run_git(repo, &["commit", "-q", "-m", "initial"]);
+
+        dir.update_requirement(requirement.hrid(), None, Some("New body".to_string()), None)
+            .unwrap();
+        dir.add_requirement("USR", "# Uncommitted".to_string())
+            .unwrap();
+        dir.flush().unwrap();
+
+        let committed = requirements_at_revision(&root, "HEAD").unwrap();
+        assert_eq!(committed.len(), 1);
+        assert_eq!(committed[0].uuid(), requirement.uuid());
+        assert_eq!(committed[0].body(), "Old body");
+
+        assert!(matches!(
+            requirements_at_revision(&root, "no-such-branch"),
+            Err(GitError::Failed { .. })
+        ));
+        assert!(matches!(
+            requirements_at_revision(&root, "--output=x"),
+            Err(GitError::InvalidRevision(_))
+        ));
+    }
+}
@@ -0,0 +1,584 @@
+//! Exporting requirements as a static HTML site.
+//!
+//! The site has a page for each requirement, with its rendered body and its
+//! links to parents and children, a page for each kind listing its
+//! requirements, and a graph of every link. Suspect links are flagged
+//! wherever they appear. Requirements are searched in the browser from an
+//! index written alongside the pages as a script, so the site needs no web
+//! server and works when opened straight from disk.
+//!
+//! [`export`] builds the site in memory, leaving the caller to write it out.
+
+use std::{
+    collections::{BTreeMap, HashMap, HashSet},
+    fmt::Write as _,
+    path::PathBuf,
+};
+
+use serde::Serialize;
+use uuid::Uuid;
+
+use crate::{
+    domain::{requirement::Parent, Hrid, RequirementView, Verification},
+    Directory,
+};
+
+mod graph;
+mod markdown;
+
+pub use markdown::escape;
+
+const STYLE: &str = include_str!("html/style.css");
+const SEARCH: &str = include_str!("html/search.js");
+
+/// The directory requirement pages are written to.
+const REQUIREMENTS: &str = "requirements";
+/// The directory kind pages are written to.
+const KINDS: &str = "kinds";
+
+/// Builds the site for a directory of requirements.
+///
+/// Returns the contents of each file, keyed by its path relative to the
+/// site's root. Requirement pages are written to `requirements/`, kind pages
+/// to `kinds/`, and the index, graph, stylesheet, and search scripts to the
+/// root.
+#[must_use]
+pub fn export(directory: &Directory) -> BTreeMap<PathBuf, String> {
+    let site = Site::new(directory);
+
+    let mut files = BTreeMap::new();
+    files.insert(PathBuf::from("style.css"), STYLE.to_string());
+    files.insert(PathBuf::from("search.js"), SEARCH.to_string());
+    files.insert(PathBuf::from("search-index.js"), site.search_index());
+    files.insert(PathBuf::from("index.html"), site.index());
+    files.insert(PathBuf::from("graph.html"), site.graph());
+    for (kind, requirements) in &site.kinds {
+        files.insert(
+            PathBuf::from(KINDS).join(format!("{kind}.html")),
+            site.kind(kind, requirements),
+        );
+    }
+    for requirement in &site.requirements {
+        files.insert(
+            PathBuf::from(REQUIREMENTS).join(format!("{}.html", site.hrid(requirement.hrid))),
+            site.requirement(requirement),
+        );
+    }
+    files
+}
+
+/// A requirement in the search index.
+#[derive(Serialize)]
+struct Entry<'a> {
+    hrid: String,
+    title: &'a str,
+    tags: Vec<&'a str>,
+    body: &'a str,
+    url: String,
+}
+
+/// The requirements and links a site is built from.
+struct Site<'a> {
+    directory: &'a Directory,
+    /// Every requirement, in HRID order.
+    requirements: Vec<RequirementView<'a>>,
+    /// The indices of each kind's requirements.
+    kinds: BTreeMap<&'a str, Vec<usize>>,
+    /// Suspect links, as child and parent UUIDs.
+    suspect: HashSet<(Uuid, Uuid)>,
+    verification: HashMap<Uuid, Verification>,
+}
+
+impl<'a> Site<'a> {
+    fn new(directory: &'a Directory) -> Self {
+        let mut requirements: Vec<_> = directory.requirements().collect();
+        requirements.sort_by(|a, b| a.hrid.cmp(b.hrid));
+        let mut kinds: BTreeMap<&str, Vec<usize>> = BTreeMap::new();
+        for (index, requirement) in requirements.iter().enumerate() {
+            kinds
+                .entry(requirement.hrid.kind())
+                .or_default()
+                .push(index);
+        }
+        let suspect = directory
+            .suspect_links()
+            .into_iter()
+            .map(|link| (link.child_uuid, link.parent_uuid))
+            .collect();
+        Self {
+            directory,
+            requirements,
+            kinds,
+            suspect,
+            verification: directory.verification(),
+        }
+    }
+
+    fn hrid(&self, hrid: &Hrid) -> String {
+        hrid.display(self.directory.config().digits()).to_string()
+    }
+
+    /// Wraps the content of a page in the site's layout. `root` is the path
+    /// from the page to the site's root.
+    fn page(&self, title: &str, root: &str, main: &str, scripts: &str) -> String {
+        let mut html = String::new();
+        let _ = write!(
+            html,
+            "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n<meta \
+             name=\"viewport\" content=\"width=device-width, initial-scale=1\">\n<title>{}</title>\n\
+             <link rel=\"stylesheet\" href=\"{root}style.css\">\n</head>\n<body>\n<header>\n<a \
+             class=\"home\" href=\"{root}index.html\">Requirements</a>\n<nav>",
+            markdown::escape(title)
+        );
+        for kind in self.kinds.keys() {
+            let kind = markdown::escape(kind);
+            let _ = write!(html, "<a href=\"{root}{KINDS}/{kind}.html\">{kind}</a>");
+        }
+        let _ = write!(
+            html,
+            "<a href=\"{root}graph.html\">Graph</a></nav>\n<form action=\"{root}index.html\"><input \
+             id=\"search\" name=\"q\" type=\"search\" placeholder=\"Search requirements\" \
+             aria-label=\"Search requirements\"></form>\n</header>\n{main}{scripts}</body>\n</html>\n"
+        );
+        html
+    }
+
+    fn search_index(&self) -> String {
+        let entries: Vec<Entry> = self
+            .requirements
+            .iter()
+            .map(|requirement| {
+                let hrid = self.hrid(requirement.hrid);
+                Entry {
+                    url: format!("{REQUIREMENTS}/{hrid}.html"),
+                    hrid,
+                    title: requirement.title,
+                    tags: requirement.tags.iter().map(String::as_str).collect(),
+                    body: requirement.body,
+                }
+            })
+            .collect();
+        let entries = serde_json::to_string(&entries).unwrap_or_else(|_| "[]".to_string());
+        format!("window.REQUIEM_INDEX = {entries};\n")
+    }
+
+    fn index(&self) -> String {
+        let mut main = String::from("<main>\n<h1>Requirements</h1>\n");
+        let _ = writeln!(
+            main,
+            "<p>{} requirements in {} kinds, with {} suspect links. See the <a \
+             href=\"graph.html\">graph</a> of every link.</p>",
+            self.requirements.len(),
+            self.kinds.len(),
+            self.suspect.len()
+        );
+        main.push_str("<ol id=\"results\"></ol>\n");
+        main.push_str(
+            "<table>\n<thead>\n<tr><th>Kind</th><th>Description</th><th>Requirements</th><th>Suspect \
+             links</th></tr>\n</thead>\n<tbody>\n",
+        );
+        for (kind, requirements) in &self.kinds {
+            let description = self
+                .directory
+                .config()
+                .metadata_for_kind(kind)
+                .and_then(|metadata| metadata.description.as_deref())
+                .unwrap_or_default();
+            let suspect: usize = requirements
+                .iter()
+                .map(|&index| self.suspect_parents(&self.requirements[index]))
+                .sum();
+            let _ = writeln!(
+                main,
+                "<tr><td><a href=\"{KINDS}/{0}.html\">{0}</a></td><td>{1}</td><td>{2}</td><td>{3}</td></tr>",
+                markdown::escape(kind),
+                markdown::escape(description),
+                requirements.len(),
+                suspect
+            );
+        }
+        main.push_str("</tbody>\n</table>\n</main>\n");
+        self.page(
+            "Requirements",
+            "",
+            &main,
+            "<script src=\"search-index.js\"></script>\n<script src=\"search.js\"></script>\n",
+        )
+    }
+
+    fn graph(&self) -> String {
+        let kinds: HashMap<&str, usize> = self
+            .kinds
+            .keys()
+            .zip(0..)
+            .map(|(&kind, index)| (kind, index))
+            .collect();
+        let indices: HashMap<Uuid, usize> = self
+            .requirements
+            .iter()
+            .enumerate()
+            .map(|(index, requirement)| (*requirement.uuid, index))
+            .collect();
+        let nodes: Vec<graph::Node> = self
+            .requirements
+            .iter()
+            .map(|requirement| {
+                let hrid = self.hrid(requirement.hrid);
+                graph::Node {
+                    href: format!("{REQUIREMENTS}/{hrid}.html"),
+                    hrid,
+                    title: requirement.title.to_string(),
+                    kind: kinds[requirement.hrid.kind()],
+                }
+            })
+            .collect();
+        let edges: Vec<graph::Edge> = self
+            .requirements
+            .iter()
+            .enumerate()
+            .flat_map(|(child, requirement)| {
+                let indices = &indices;
+                requirement.parents.iter().filter_map(move |(uuid, _)| {
+                    Some(graph::Edge {
+                        child,
+                        parent: *indices.get(uuid)?,
+                        suspect: self.suspect.contains(&(*requirement.uuid, *uuid)),
+                    })
+                })
+            })
+            .collect();
+
+        let main = format!(
+            "<main class=\"wide\">\n<h1>Graph</h1>\n<p>Each requirement is drawn below its \
+             parents. Suspect links are dashed and highlighted.</p>\n<div \
+             class=\"graph-container\">\n{}</div>\n</main>\n",
+            graph::render(&nodes, &edges)
+        );
+        self.page("Graph", "", &main, "")
+    }
+
+    fn kind(&self, kind: &str, requirements: &[usize]) -> String {
+        let mut main = format!("<main>\n<h1>{}</h1>\n", markdown::escape(kind));
+        if let Some(description) = self
+            .directory
+            .config()
+            .metadata_for_kind(kind)
+            .and_then(|metadata| metadata.description.as_deref())
+        {
+            let _ = writeln!(main, "<p>{}</p>", markdown::escape(description));
+        }
+        main.push_str(
+            "<table>\n<thead>\n<tr><th>Requirement</th><th>Title</th><th>Status</th><th>Parents</\
+             th><th>Children</th></tr>\n</thead>\n<tbody>\n",
+        );
+        for &index in requirements {
+            let requirement = &self.requirements[index];
+            let badges = if self.suspect_parents(requirement) > 0 {
+                " <span class=\"badge suspect\">suspect</span>"
+            } else {
+                ""
+            };
+            let _ = writeln!(
+                main,
+                "<tr><td>{}</td><td>{}{badges}</td><td>{}</td><td>{}</td><td>{}</td></tr>",
+                self.link(requirement, "../"),
+                markdown::escape(requirement.title),
+                markdown::escape(requirement.status.unwrap_or_default()),
+                requirement.parents.len(),
+                requirement.children.len()
+            );
+        }
+        main.push_str("</tbody>\n</table>\n</main>\n");
+        self.page(kind, "../", &main, "")
+    }
+
+    fn requirement(&self, requirement: &RequirementView) -> String {
+        let hrid = self.hrid(requirement.hrid);
+        let kind = markdown::escape(requirement.hrid.kind());
+        let mut main = format!(
+            "<main>\n<p class=\"breadcrumb\"><a href=\"../index.html\">Requirements</a> › <a \
+             href=\"../{KINDS}/{kind}.html\">{kind}</a></p>\n<h1><span class=\"hrid\">{}</span> \
+             {}</h1>\n",
+            markdown::escape(&hrid),
+            markdown::escape(requirement.title)
+        );
+
+        let mut badges = String::new();
+        if let Some(status) = requirement.status {
+            let _ = write!(
+                badges,
+                "<span class=\"badge status\" title=\"Status\">{}</span>",
+                markdown::escape(status)
+            );
+        }
+        if let Some(verification) = self.verification.get(requirement.uuid) {
+            let _ = write!(
+                badges,
+                "<span class=\"badge {0}\" title=\"Verification\">{0}</span>",
+                verification.as_str()
+            );
+        }
+        if self.suspect_parents(requirement) > 0 {
+            badges.push_str(
+                "<span class=\"badge suspect\" title=\"A parent has changed since it was \
+                 linked\">suspect</span>",
+            );
+        }
+        for tag in requirement.tags {
+            let _ = write!(
+                badges,
+                "<span class=\"badge\">{}</span>",
+                markdown::escape(tag)
+            );
+        }
+        if !badges.is_empty() {
+            let _ = writeln!(main, "<p class=\"badges\">{badges}</p>");
+        }
+
+        if !requirement.attributes.is_empty() {
+            main.push_str("<dl class=\"details\">\n");
+            for (name, value) in requirement.attributes {
+                let _ = writeln!(
+                    main,
+                    "<dt>{}</dt><dd>{}</dd>",
+                    markdown::escape(name),
+                    markdown::escape(&value.to_string())
+                );
+            }
+            main.push_str("</dl>\n");
+        }
+        if !requirement.body.trim().is_empty() {
+            let _ = write!(
+                main,
+                "<div class=\"body\">\n{}</div>\n",
+                markdown::render(requirement.body)
+            );
+        }
+
+        main.push_str("<h2>Parents</h2>\n");
+        let mut parents: Vec<&(Uuid, Parent)> = requirement.parents.iter().collect();
+        parents.sort_by(|a, b| a.1.hrid.cmp(&b.1.hrid));
+        self.links(
+            &mut main,
+            parents.into_iter().map(|(uuid, parent)| {
+                (
+                    *uuid,
+                    &parent.hrid,
+                    parent.link_type.as_deref(),
+                    self.suspect.contains(&(*requirement.uuid, *uuid)),
+                )
+            }),
+        );
+
+        main.push_str("<h2>Children</h2>\n");
+        let mut children: Vec<RequirementView> = requirement
+            .children
+            .iter()
+            .filter_map(|uuid| self.directory.find_by_uuid(*uuid))
+            .collect();
+        children.sort_by(|a, b| a.hrid.cmp(b.hrid));
+        self.links(
+            &mut main,
+            children.iter().map(|child| {
+                let link_type = child
+                    .parents
+                    .iter()
+                    .find(|(uuid, _)| uuid == requirement.uuid)
+                    .and_then(|(_, parent)| parent.link_type.as_deref());
+                (
+                    *child.uuid,
+                    child.hrid,
+                    link_type,
+                    self.suspect.contains(&(*child.uuid, *requirement.uuid)),
+                )
+            }),
+        );
+
+        main.push_str(&self.details(requirement));
+        main.push_str("</main>\n");
+        self.page(&format!("{hrid} {}", requirement.title), "../", &main, "")
+    }
+
+    /// Lists linked requirements, each as its UUID, HRID, link type, and
+    /// whether the link is suspect.
+    fn links<'b>(
+        &self,
+        html: &mut String,
+        links: impl Iterator<Item = (Uuid, &'b Hrid, Option<&'b str>, bool)>,
+    ) {
+        let mut links = links.peekable();
+        if links.peek().is_none() {
+            html.push_str("<p>None.</p>\n");
+            return;
+        }
+        html.push_str("<ul class=\"links\">\n");
+        for (uuid, hrid, link_type, suspect) in links {
+            html.push_str("<li>");
+            match self.directory.find_by_uuid(uuid) {
+                Some(linked) => {
+                    let _ = write!(
+                        html,
+                        "{} {}",
+                        self.link(&linked, "../"),
+                        markdown::escape(linked.title)
+                    );
+                }
+                None => {
+                    let _ = write!(
+                        html,
+                        "<span class=\"hrid\">{}</span> <span class=\"badge missing\">missing</span>",
+                        markdown::escape(&self.hrid(hrid))
+                    );
+                }
+            }
+            if let Some(link_type) = link_type {
+                let _ = write!(
+                    html,
+                    " <span class=\"badge link-type\">{}</span>",
+                    markdown::escape(link_type)
+                );
+            }
+            if suspect {
+                html.push_str(
+                    " <span class=\"badge suspect\" title=\"The parent has changed since it was \
+                     linked\">suspect</span>",
+                );
+            }
+            html.push_str("</li>\n");
+        }
+        html.push_str("</ul>\n");
+    }
+
+    fn details(&self, requirement: &RequirementView) -> String {
+        let mut details = vec![
+            ("UUID", requirement.uuid.to_string()),
+            (
+                "Created",
+                requirement.created.format("%Y-%m-%d %H:%M UTC").to_string(),
+            ),
+        ];
+        if let Some(path) = self
+            .directory
+            .path_for(requirement.hrid)
+            .map(|path| path.strip_prefix(self.directory.root()).unwrap_or(path))
+        {
+            details.push(("File", path.display().to_string()));
+        }
+        if let Some(test) = requirement.test {
+            details.push(("Test", test.location.clone()));
+            if let Some(result) = &test.result {
+                details.push((
+                    "Last result",
+                    format!(
+                        "{} ({})",
+                        result.outcome,
+                        result.timestamp.format("%Y-%m-%d %H:%M UTC")
+                    ),
+                ));
+            }
+        }
+        if let Some(origin) = requirement.origin {
+            details.push((
+                "Imported from",
+                format!("{} {}", origin.source, origin.identifier),
+            ));
+        }
+
+        let mut html = String::from("<h2>Details</h2>\n<dl class=\"details\">\n");
+        for (name, value) in details {
+            let _ = writeln!(html, "<dt>{name}</dt><dd>{}</dd>", markdown::escape(&value));
+        }
+        html.push_str("</dl>\n");
+        html
+    }
+
+    /// Links to a requirement's page from a page `root` below the site's
+    /// root.
+    fn link(&self, requirement: &RequirementView, root: &str) -> String {
+        let hrid = markdown::escape(&self.hrid(requirement.hrid));
+        format!(
+            "<a class=\"hrid\" href=\"{root}{REQUIREMENTS}/{hrid}.html\" title=\"{}\">{hrid}</a>",
+            markdown::escape(requirement.title)
+        )
+    }
+
+    /// The number of a requirement's parent links that are suspect.
+    fn suspect_parents(&self, requirement: &RequirementView) -> usize {
+        requirement
+            .parents
+            .iter()
+            .filter(|(parent, _)| self.suspect.contains(&(*requirement.uuid, *parent)))
+            .count()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn builds_a_page_for_every_requirement_and_kind() {
+        let root = tempfile::tempdir().unwrap();
+        let mut directory = Directory::new(root.path().to_path_buf()).unwrap();
+        let user = directory
+            .add_requirement("USR", "# Log in\n\nUsers shall log in.".to_string())
+            .unwrap();
+        let system = directory
+            .add_requirement("SYS", "# Sessions".to_string())
+            .unwrap();
+        directory
+            .link_requirement(system.hrid(), user.hrid())
+            .unwrap();
+        directory
+            .update_requirement(
+                user.hrid(),
+                None,
+                Some("Users shall **log in**.\n\n<script>alert(1)</script>".to_string()),
+                None,
+            )
+            .unwrap();
+
+        let files = export(&directory);
+        let paths: Vec<_> = files.keys().map(|path| path.to_str().unwrap()).collect();
+        assert_eq!(
+            paths,
+            [
+                "graph.html",
+                "index.html",
+                "kinds/SYS.html",
+                "kinds/USR.html",
+                "requirements/SYS-001.html",
+                "requirements/USR-001.html",
+                "search-index.js",
+                "search.js",
+                "style.css",
+            ]
+        );
+
+        let page = |path: &str| &files[&PathBuf::from(path)];
+        let user = page("requirements/USR-001.html");
+        assert!(user.contains("<p>Users shall <strong>log in</strong>.</p>"));
+        assert!(user.contains("&lt;script&gt;alert(1)&lt;/script&gt;"));
+        assert!(user.contains("href=\"../requirements/SYS-001.html\""));
+
+        // The child's link to its changed parent is suspect, which flags the
+        // child and the link on both pages.
+        assert_eq!(user.matches("badge suspect").count(), 1);
+        let system = page("requirements/SYS-001.html");
+        assert!(system.contains("href=\"../requirements/USR-001.html\""));
+        assert_eq!(system.matches("badge suspect").count(), 2);
+        assert!(page("kinds/SYS.html").contains("badge suspect"));
+        assert!(page("graph.html").contains("<path class=\"edge suspect\""));
+
+        let index = page("search-index.js");
+        let entries: serde_json::Value = serde_json::from_str(
+            index
+                .strip_prefix("window.REQUIEM_INDEX = ")
+                .and_then(|index| index.strip_suffix(";\n"))
+                .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(entries[1]["hrid"], "USR-001");
+        assert_eq!(entries[1]["url"], "requirements/USR-001.html");
+    }
+}
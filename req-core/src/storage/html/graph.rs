@@ -0,0 +1,248 @@
+//! Drawing the requirement graph as SVG.
+//!
+//! Requirements are laid out in layers, parents above children: a
+//! requirement without parents is in the top layer, and every other
+//! requirement is one layer below its lowest parent. Each layer is ordered by
+//! the average position of its requirements' parents, which keeps most links
+//! short and uncrossed. Links found to be in a cycle are drawn, but don't
+//! affect the layering.
+
+use std::fmt::Write as _;
+
+use super::markdown::escape;
+
+const NODE_WIDTH: usize = 168;
+const NODE_HEIGHT: usize = 44;
+const COLUMN_GAP: usize = 24;
+const LAYER_GAP: usize = 72;
+const MARGIN: usize = 16;
+/// The number of characters of a title shown in a node.
+const TITLE_LENGTH: usize = 24;
+/// The number of colours nodes are drawn in, one per kind.
+const PALETTE: usize = 6;
+
+/// A requirement in the graph.
+pub struct Node {
+    /// The requirement's HRID, as displayed.
+    pub hrid: String,
+    /// The requirement's title.
+    pub title: String,
+    /// The index of the requirement's kind, which picks its colour.
+    pub kind: usize,
+    /// The link to the requirement's page.
+    pub href: String,
+}
+
+/// A link from a child to a parent, by their indices in the nodes.
+pub struct Edge {
+    /// The index of the child.
+    pub child: usize,
+    /// The index of the parent.
+    pub parent: usize,
+    /// Whether the link is suspect.
+    pub suspect: bool,
+}
+
+/// Draws the graph.
+pub fn render(nodes: &[Node], edges: &[Edge]) -> String {
+    let mut parents = vec![Vec::new(); nodes.len()];
+    for edge in edges {
+        parents[edge.child].push(edge.parent);
+    }
+
+    let mut layers: Vec<Vec<usize>> = Vec::new();
+    let mut depths = vec![None; nodes.len()];
+    for node in 0..nodes.len() {
+        let depth = depth(node, &parents, &mut depths, &mut vec![false; nodes.len()]);
+        if layers.len() <= depth {
+            layers.resize_with(depth + 1, Vec::new);
+        }
+        layers[depth].push(node);
+    }
+
+    order(&mut layers, &parents);
+
+    let columns = layers.iter().map(Vec::len).max().unwrap_or(0);
+    let width = 2 * MARGIN + columns * NODE_WIDTH + columns.saturating_sub(1) * COLUMN_GAP;
+    let height =
+        2 * MARGIN + layers.len() * NODE_HEIGHT + layers.len().saturating_sub(1) * LAYER_GAP;
+
+    // The top-left corner of each node, with each layer centred.
+    let mut corners = vec![(0, 0); nodes.len()];
+    for (depth, layer) in layers.iter().enumerate() {
+        let offset = (columns - layer.len()) * (NODE_WIDTH + COLUMN_GAP) / 2;
+        for (column, &node) in layer.iter().enumerate() {
+            corners[node] = (
+                MARGIN + offset + column * (NODE_WIDTH + COLUMN_GAP),
+                MARGIN + depth * (NODE_HEIGHT + LAYER_GAP),
+            );
+        }
+    }
+
+    let mut svg = String::new();
+    let _ = writeln!(
+        svg,
+        "<svg class=\"graph\" xmlns=\"http://www.w3.org/2000/svg\" width=\"{width}\" \
+         height=\"{height}\" viewBox=\"0 0 {width} {height}\">"
+    );
+    svg.push_str(
+        "<defs><marker id=\"arrow\" viewBox=\"0 0 10 10\" refX=\"10\" refY=\"5\" \
+         markerWidth=\"8\" markerHeight=\"8\" orient=\"auto-start-reverse\"><path d=\"M 0 0 L \
+         10 5 L 0 10 z\"/></marker></defs>\n",
+    );
+
+    // Suspect links are drawn last, so they aren't hidden by others.
+    let mut ordered: Vec<&Edge> = edges.iter().collect();
+    ordered.sort_by_key(|edge| edge.suspect);
+    for edge in ordered {
+        let (child_x, child_y) = corners[edge.child];
+        let (parent_x, parent_y) = corners[edge.parent];
+        let (x1, y1) = (child_x + NODE_WIDTH / 2, child_y);
+        let (x2, y2) = (parent_x + NODE_WIDTH / 2, parent_y + NODE_HEIGHT);
+        let class = if edge.suspect { "edge suspect" } else { "edge" };
+        let _ = writeln!(
+            svg,
+            "<path class=\"{class}\" d=\"M {x1} {y1} C {x1} {c1} {x2} {c2} {x2} {y2}\" \
+             marker-end=\"url(#arrow)\"><title>{} → {}{}</title></path>",
+            escape(&nodes[edge.child].hrid),
+            escape(&nodes[edge.parent].hrid),
+            if edge.suspect { " (suspect)" } else { "" },
+            c1 = y1.saturating_sub(LAYER_GAP / 2),
+            c2 = y2 + LAYER_GAP / 2,
+        );
+    }
+
+    for (node, &(x, y)) in nodes.iter().zip(&corners) {
+        let mut title: String = node.title.chars().take(TITLE_LENGTH).collect();
+        if node.title.chars().count() > TITLE_LENGTH {
+            title.pop();
+            title.push('…');
+        }
+        let _ = writeln!(
+            svg,
+            "<a href=\"{href}\"><g class=\"node kind-{kind}\"><title>{hrid} {full}</title><rect \
+             x=\"{x}\" y=\"{y}\" width=\"{NODE_WIDTH}\" height=\"{NODE_HEIGHT}\" rx=\"6\"/><text \
+             class=\"hrid\" x=\"{tx}\" y=\"{hy}\">{hrid}</text><text x=\"{tx}\" \
+             y=\"{ty}\">{title}</text></g></a>",
+            href = escape(&node.href),
+            kind = node.kind % PALETTE,
+            hrid = escape(&node.hrid),
+            full = escape(&node.title),
+            title = escape(&title),
+            tx = x + NODE_WIDTH / 2,
+            hy = y + 18,
+            ty = y + 35,
+        );
+    }
+    svg.push_str("</svg>\n");
+    svg
+}
+
+/// Orders each layer by where its nodes' parents are in the layers above.
+fn order(layers: &mut [Vec<usize>], parents: &[Vec<usize>]) {
+    let mut position = vec![0.0; parents.len()];
+    for layer in layers {
+        let barycentre = |node: usize| {
+            let placed: Vec<f64> = parents[node]
+                .iter()
+                .map(|&parent| position[parent])
+                .filter(|&x: &f64| x > 0.0)
+                .collect();
+            if placed.is_empty() {
+                f64::INFINITY
+            } else {
+                placed.iter().sum::<f64>() / f64::from(u32::try_from(placed.len()).unwrap_or(1))
+            }
+        };
+        let mut keyed: Vec<(f64, usize)> =
+            layer.iter().map(|&node| (barycentre(node), node)).collect();
+        keyed.sort_by(|a, b| a.0.total_cmp(&b.0).then(a.1.cmp(&b.1)));
+        *layer = keyed.into_iter().map(|(_, node)| node).collect();
+        let count = f64::from(u32::try_from(layer.len()).unwrap_or(u32::MAX));
+        for (index, &node) in (1..).zip(layer.iter()) {
+            position[node] = f64::from(index) / (count + 1.0);
+        }
+    }
+}
+
+/// The layer of a node: one below its lowest parent, ignoring parents on a
+/// cycle through it.
+fn depth(
+    node: usize,
+    parents: &[Vec<usize>],
+    depths: &mut [Option<usize>],
+    visiting: &mut [bool],
+) -> usize {
+    if let Some(depth) = depths[node] {
+        return depth;
+    }
+    visiting[node] = true;
+    let mut deepest = 0;
+    for &parent in &parents[node] {
+        if !visiting[parent] {
+            deepest = deepest.max(depth(parent, parents, depths, visiting) + 1);
+        }
+    }
+    visiting[node] = false;
+    depths[node] = Some(deepest);
+    deepest
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{render, Edge, Node};
+
+    fn node(hrid: &str) -> Node {
+        Node {
+            hrid: hrid.to_string(),
+            title: format!("The {hrid} requirement, which has a long title"),
+            kind: 0,
+            href: format!("requirements/{hrid}.html"),
+        }
+    }
+
+    #[test]
+    fn lays_out_parents_above_children() {
+        let nodes = [
+            node("SYS-001"),
+            node("USR-001"),
+            node("USR-002"),
+            node("SYS-002"),
+        ];
+        let edges = [
+            Edge {
+                child: 0,
+                parent: 2,
+                suspect: true,
+            },
+            Edge {
+                child: 3,
+                parent: 1,
+                suspect: false,
+            },
+            // A cycle doesn't stop the graph being drawn.
+            Edge {
+                child: 2,
+                parent: 0,
+                suspect: false,
+            },
+        ];
+        let svg = render(&nodes, &edges);
+
+        let corner = |hrid: &str, axis: &str| {
+            let node = &svg[svg.find(&format!("\"requirements/{hrid}.html\"")).unwrap()..];
+            let value = &node[node.find(&format!(" {axis}=\"")).unwrap() + 4..];
+            value[..value.find('"').unwrap()].parse::<usize>().unwrap()
+        };
+        assert_eq!(corner("USR-001", "y"), corner("USR-002", "y"));
+        assert!(corner("SYS-001", "y") > corner("USR-002", "y"));
+        assert!(corner("SYS-002", "y") > corner("USR-001", "y"));
+
+        // Each layer is ordered under its parents.
+        assert!(corner("USR-001", "x") < corner("USR-002", "x"));
+        assert!(corner("SYS-002", "x") < corner("SYS-001", "x"));
+
+        assert!(svg.contains("<path class=\"edge suspect\""));
+        assert!(svg.contains(">The SYS-001 requirement…</text>"));
+    }
+}
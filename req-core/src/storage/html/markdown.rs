@@ -0,0 +1,169 @@
+//! Rendering requirement bodies from markdown to HTML.
+//!
+//! Markdown is rendered as `CommonMark`, with GitHub-style tables and
+//! strikethrough. Raw HTML is escaped rather than passed through, and links
+//! and images are only kept for relative URLs and known-safe schemes.
+
+use pulldown_cmark::{html, CowStr, Event, Options, Parser, Tag};
+
+/// Renders markdown as HTML.
+pub fn render(markdown: &str) -> String {
+    let options = Options::ENABLE_TABLES | Options::ENABLE_STRIKETHROUGH;
+    let events = Parser::new_ext(markdown, options).map(|event| match event {
+        Event::Html(raw) | Event::InlineHtml(raw) => Event::Text(raw),
+        Event::Start(Tag::Link {
+            link_type,
+            dest_url,
+            title,
+            id,
+        }) => Event::Start(Tag::Link {
+            link_type,
+            dest_url: safe_url(dest_url),
+            title,
+            id,
+        }),
+        Event::Start(Tag::Image {
+            link_type,
+            dest_url,
+            title,
+            id,
+        }) => Event::Start(Tag::Image {
+            link_type,
+            dest_url: safe_url(dest_url),
+            title,
+            id,
+        }),
+        event => event,
+    });
+
+    let mut rendered = String::new();
+    html::push_html(&mut rendered, events);
+    rendered
+}
+
+/// Escapes text for use in HTML content and attribute values.
+#[must_use]
+pub fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Keeps only URLs that are relative or use a scheme known to be safe to
+/// follow; anything else (such as `javascript:`) becomes `#`.
+fn safe_url(url: CowStr<'_>) -> CowStr<'_> {
+    if is_safe_url(&url) {
+        url
+    } else {
+        CowStr::Borrowed("#")
+    }
+}
+
+/// Whether a URL is relative, or uses a scheme known to be safe to follow.
+///
+/// The scheme must be spelled out exactly: browsers skip control characters
+/// and whitespace in it, so a URL such as `\u{1}javascript:` is not relative.
+fn is_safe_url(url: &str) -> bool {
+    match url.find([':', '/', '?', '#']) {
+        Some(end) if url[end..].starts_with(':') => matches!(
+            url[..end].to_ascii_lowercase().as_str(),
+            "http" | "https" | "mailto"
+        ),
+        _ => true,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::render;
+
+    #[test]
+    fn renders_the_markdown_requirements_are_written_in() {
+        let markdown = "\
+Users shall **log in** with an _email_ and `password`.
+Next line <b>raw</b>.
+
+## Acceptance criteria
+
+1. Valid credentials are accepted
+2. Invalid ones are rejected:
+   - with a message
+   - and a [link](https://example.com) or [not](javascript:alert(1))
+
+> Note: ~~not~~ *yet*
+
+| Field | Required |
+| :---- | -------: |
+| email | yes |
+
+```rust
+fn main() {}
+```
+
+---
+snake_case_name and ![diagram](images/flow.png)
+
+<script>alert(1)</script>";
+
+        let expected = "\
+<p>Users shall <strong>log in</strong> with an <em>email</em> and <code>password</code>.
+Next line &lt;b&gt;raw&lt;/b&gt;.</p>
+<h2>Acceptance criteria</h2>
+<ol>
+<li>Valid credentials are accepted</li>
+<li>Invalid ones are rejected:
+<ul>
+<li>with a message</li>
+<li>and a <a href=\"https://example.com\">link</a> or <a href=\"#\">not</a></li>
+</ul>
+</li>
+</ol>
+<blockquote>
+<p>Note: <del>not</del> <em>yet</em></p>
+</blockquote>
+<table><thead><tr><th style=\"text-align: left\">Field</th><th style=\"text-align: right\">Required</th></tr></thead><tbody>
+<tr><td style=\"text-align: left\">email</td><td style=\"text-align: right\">yes</td></tr>
+</tbody></table>
+<pre><code class=\"language-rust\">fn main() {}
+</code></pre>
+<hr />
+<p>snake_case_name and <img src=\"images/flow.png\" alt=\"diagram\" /></p>
+&lt;script&gt;alert(1)&lt;/script&gt;";
+        assert_eq!(render(markdown), expected);
+    }
+
+    #[test]
+    fn only_safe_urls_are_linked() {
+        for url in [
+            "javascript:alert(1)",
+            "JavaScript:alert(1)",
+            "\u{1}javascript:alert(1)",
+            " javascript:alert(1)",
+            "java\tscript:alert(1)",
+            "data:text/html,x",
+            "file:///etc/passwd",
+        ] {
+            assert!(!super::is_safe_url(url), "{url:?}");
+        }
+        for url in [
+            "https://example.com",
+            "HTTP://example.com",
+            "mailto:someone@example.com",
+            "images/flow.png",
+            "../USR-001.html#top",
+            "/docs/a:b",
+            "?q=a:b",
+        ] {
+            assert!(super::is_safe_url(url), "{url:?}");
+        }
+    }
+}
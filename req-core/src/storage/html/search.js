@@ -0,0 +1,82 @@
+// Searches the requirements listed in search-index.js as the query is typed.
+//
+// Every word of the query must appear in a requirement's HRID, title, tags,
+// or body. Matches in the HRID rank highest, then the title, then the tags.
+(function () {
+  const input = document.getElementById("search");
+  const results = document.getElementById("results");
+  const index = window.REQUIEM_INDEX || [];
+  if (!input || !results) {
+    return;
+  }
+
+  function score(entry, terms) {
+    let total = 0;
+    for (const term of terms) {
+      if (entry.hrid.toLowerCase().includes(term)) {
+        total += 8;
+      } else if (entry.title.toLowerCase().includes(term)) {
+        total += 4;
+      } else if (entry.tags.some((tag) => tag.toLowerCase().includes(term))) {
+        total += 2;
+      } else if (entry.body.toLowerCase().includes(term)) {
+        total += 1;
+      } else {
+        return 0;
+      }
+    }
+    return total;
+  }
+
+  function snippet(body, terms) {
+    const lower = body.toLowerCase();
+    const at = Math.max(0, ...terms.map((term) => lower.indexOf(term)));
+    const start = Math.max(0, at - 60);
+    const text = body.slice(start, start + 160).replace(/\s+/g, " ").trim();
+    return (start > 0 ? "… " : "") + text + (start + 160 < body.length ? " …" : "");
+  }
+
+  function search() {
+    const terms = input.value.toLowerCase().split(/\s+/).filter(Boolean);
+    results.replaceChildren();
+    if (terms.length === 0) {
+      return;
+    }
+
+    const matches = index
+      .map((entry) => [score(entry, terms), entry])
+      .filter(([score]) => score > 0)
+      .sort((a, b) => b[0] - a[0] || a[1].hrid.localeCompare(b[1].hrid))
+      .slice(0, 50);
+
+    for (const [, entry] of matches) {
+      const item = document.createElement("li");
+      const link = document.createElement("a");
+      link.href = entry.url;
+      const hrid = document.createElement("span");
+      hrid.className = "hrid";
+      hrid.textContent = entry.hrid;
+      link.append(hrid, " " + entry.title);
+      item.append(link);
+      if (entry.body) {
+        const text = document.createElement("span");
+        text.className = "snippet";
+        text.textContent = snippet(entry.body, terms);
+        item.append(text);
+      }
+      results.append(item);
+    }
+    if (matches.length === 0) {
+      const item = document.createElement("li");
+      item.textContent = "No matching requirements.";
+      results.append(item);
+    }
+  }
+
+  const query = new URLSearchParams(window.location.search).get("q");
+  if (query) {
+    input.value = query;
+  }
+  input.addEventListener("input", search);
+  search();
+})();
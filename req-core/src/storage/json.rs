@@ -0,0 +1,454 @@
+//! Reading and writing a whole repository as a single JSON document.
+//!
+//! The document holds the configuration, the templates in `.req/templates`,
+//! and every requirement with all of its frontmatter: UUID, creation time,
+//! tags, status, attributes, test and origin metadata, and parent links with
+//! their fingerprints. Requirements are written in HRID order, each with its
+//! path relative to the root, so a repository restored from the document
+//! with [`Directory::restore`] and [`write_templates`] flushes to the same
+//! files.
+//!
+//! The format is described by the JSON Schema in [`SCHEMA`].
+//!
+//! [`Directory::restore`]: crate::Directory::restore
+
+use std::{
+    collections::{BTreeMap, BTreeSet, HashMap},
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::{
+    domain::{
+        requirement::{Content, Metadata, Origin, Parent, TestMetadata},
+        AttributeValue, Config, Hrid, HridError,
+    },
+    Directory, Requirement,
+};
+
+/// The JSON Schema of the document written by [`export`].
+pub const SCHEMA: &str = include_str!("../../schema/repository.schema.json");
+
+/// A repository read from a JSON document.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Repository {
+    /// The repository's configuration.
+    pub config: Config,
+    /// The contents of each template, keyed by the prefix it applies to
+    /// (such as `USR` or `AUTH-USR`).
+    pub templates: BTreeMap<String, String>,
+    /// Every requirement, with the path of its file relative to the root if
+    /// the document gives one.
+    pub requirements: Vec<(Requirement, Option<PathBuf>)>,
+}
+
+/// Error type for reading JSON documents.
+#[derive(Debug, thiserror::Error)]
+pub enum ParseError {
+    /// The document isn't valid JSON, or doesn't match the schema.
+    #[error("invalid document: {0}")]
+    Json(#[from] serde_json::Error),
+
+    /// A requirement or parent has an invalid HRID.
+    #[error("invalid HRID '{hrid}': {source}")]
+    Hrid {
+        /// The HRID as written.
+        hrid: String,
+        /// Why it is invalid.
+        source: HridError,
+    },
+
+    /// A template's prefix isn't a plain file name.
+    #[error("invalid template name '{0}'")]
+    Template(String),
+}
+
+/// The versions of the document.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(tag = "_version")]
+enum Document {
+    #[serde(rename = "1")]
+    V1 {
+        config: Config,
+        #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+        templates: BTreeMap<String, String>,
+        requirements: Vec<Record>,
+    },
+}
+
+/// A requirement as written to the document.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Record {
+    uuid: Uuid,
+    hrid: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    path: Option<String>,
+    created: DateTime<Utc>,
+    title: String,
+    #[serde(default)]
+    body: String,
+    #[serde(default)]
+    tags: BTreeSet<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    status: Option<String>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    attributes: BTreeMap<String, AttributeValue>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    test: Option<TestMetadata>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    origin: Option<Origin>,
+    #[serde(default)]
+    parents: Vec<Link>,
+}
+
+/// A parent link as written to the document.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Link {
+    uuid: Uuid,
+    hrid: String,
+    fingerprint: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    r#type: Option<String>,
+}
+
+/// Writes the configuration, templates, and every requirement in
+/// `directory` as a JSON document.
+///
+/// # Errors
+///
+/// Returns an error if the templates can't be read.
+///
+/// # Panics
+///
+/// Never panics: the document has only string keys, so it always
+/// serializes.
+pub fn export(directory: &Directory) -> io::Result<String> {
+    let digits = directory.config().digits();
+    let mut requirements: Vec<Requirement> = directory
+        .requirements()
+        .map(|view| view.to_requirement())
+        .collect();
+    requirements.sort_by(|a, b| a.hrid().cmp(b.hrid()));
+
+    let requirements = requirements
+        .into_iter()
+        .map(|requirement| {
+            let path = directory
+                .path_for(requirement.hrid())
+                .and_then(|path| path.strip_prefix(directory.root()).ok())
+                .map(relative_path);
+            record(requirement, path, digits)
+        })
+        .collect();
+    let document = Document::V1 {
+        config: directory.config().clone(),
+        templates: read_templates(directory.root())?,
+        requirements,
+    };
+
+    let mut json =
+        serde_json::to_string_pretty(&document).expect("a repository always serializes to JSON");
+    json.push('\n');
+    Ok(json)
+}
+
+/// Reads a repository from a JSON document written by [`export`].
+///
+/// # Errors
+///
+/// Returns an error if the document isn't valid JSON, doesn't match the
+/// schema, or has an invalid HRID.
+pub fn parse(document: &str) -> Result<Repository, ParseError> {
+    let Document::V1 {
+        config,
+        templates,
+        requirements,
+    } = serde_json::from_str(document)?;
+    if let Some(prefix) = templates.keys().find(|prefix| !is_file_stem(prefix)) {
+        return Err(ParseError::Template(prefix.clone()));
+    }
+
+    let requirements = requirements
+        .into_iter()
+        .map(requirement)
+        .collect::<Result<_, _>>()?;
+    Ok(Repository {
+        config,
+        templates,
+        requirements,
+    })
+}
+
+/// Writes each template to `.req/templates` under `root`.
+///
+/// # Errors
+///
+/// Returns an error if a template can't be written.
+pub fn write_templates(root: &Path, templates: &BTreeMap<String, String>) -> io::Result<()> {
+    let directory = templates_directory(root);
+    if !templates.is_empty() {
+        fs::create_dir_all(&directory)?;
+    }
+    for (prefix, template) in templates {
+        fs::write(directory.join(format!("{prefix}.md")), template)?;
+    }
+    Ok(())
+}
+
+fn templates_directory(root: &Path) -> PathBuf {
+    root.join(".req").join("templates")
+}
+
+/// Reads the templates in `.req/templates`, keyed by file stem.
+fn read_templates(root: &Path) -> io::Result<BTreeMap<String, String>> {
+    let entries = match fs::read_dir(templates_directory(root)) {
+        Ok(entries) => entries,
+        Err(error) if error.kind() == io::ErrorKind::NotFound => return Ok(BTreeMap::new()),
+        Err(error) => return Err(error),
+    };
+
+    let mut templates = BTreeMap::new();
+    for entry in entries {
+        let path = entry?.path();
+        if path.extension().is_none_or(|extension| extension != "md") {
+            continue;
+        }
+        if let Some(prefix) = path.file_stem().and_then(|stem| stem.to_str()) {
+            templates.insert(prefix.to_string(), fs::read_to_string(&path)?);
+        }
+    }
+    Ok(templates)
+}
+
+/// Whether `prefix` can name a template file without leaving the templates
+/// directory.
+fn is_file_stem(prefix: &str) -> bool {
+    !prefix.is_empty()
+        && prefix
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+}
+
+/// Joins the components of a relative path with `/`, whatever the platform.
+fn relative_path(path: &Path) -> String {
+    path.components()
+        .map(|component| component.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+fn record(requirement: Requirement, path: Option<String>, digits: usize) -> Record {
+    let Requirement {
+        content: Content { title, body, tags },
+        metadata:
+            Metadata {
+                uuid,
+                hrid,
+                created,
+                status,
+                attributes,
+                test,
+                origin,
+                parents,
+            },
+    } = requirement;
+
+    let mut parents: Vec<(Uuid, Parent)> = parents.into_iter().collect();
+    parents.sort_by(|a, b| a.1.hrid.cmp(&b.1.hrid).then(a.0.cmp(&b.0)));
+
+    Record {
+        uuid,
+        hrid: hrid.display(digits).to_string(),
+        path,
+        created,
+        title,
+        body,
+        tags,
+        status,
+        attributes,
+        test,
+        origin,
+        parents: parents
+            .into_iter()
+            .map(|(uuid, parent)| Link {
+                uuid,
+                hrid: parent.hrid.display(digits).to_string(),
+                fingerprint: parent.fingerprint,
+                r#type: parent.link_type,
+            })
+            .collect(),
+    }
+}
+
+fn requirement(record: Record) -> Result<(Requirement, Option<PathBuf>), ParseError> {
+    let Record {
+        uuid,
+        hrid,
+        path,
+        created,
+        title,
+        body,
+        tags,
+        status,
+        attributes,
+        test,
+        origin,
+        parents,
+    } = record;
+
+    let parents = parents
+        .into_iter()
+        .map(|link| {
+            let parent = Parent {
+                hrid: parse_hrid(&link.hrid)?,
+                fingerprint: link.fingerprint,
+                link_type: link.r#type,
+            };
+            Ok((link.uuid, parent))
+        })
+        .collect::<Result<HashMap<_, _>, ParseError>>()?;
+
+    let requirement = Requirement {
+        content: Content { title, body, tags },
+        metadata: Metadata {
+            uuid,
+            hrid: parse_hrid(&hrid)?,
+            created,
+            status,
+            attributes,
+            test,
+            origin,
+            parents,
+        },
+    };
+    Ok((requirement, path.map(PathBuf::from)))
+}
+
+fn parse_hrid(hrid: &str) -> Result<Hrid, ParseError> {
+    Hrid::try_from(hrid).map_err(|source| ParseError::Hrid {
+        hrid: hrid.to_string(),
+        source,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Every file under `root`, keyed by its path relative to `root`.
+    fn files(root: &Path) -> BTreeMap<PathBuf, String> {
+        walkdir::WalkDir::new(root)
+            .into_iter()
+            .map(Result::unwrap)
+            .filter(|entry| entry.file_type().is_file())
+            .map(|entry| {
+                let path = entry.path().strip_prefix(root).unwrap().to_path_buf();
+                (path, fs::read_to_string(entry.path()).unwrap())
+            })
+            .collect()
+    }
+
+    #[test]
+    fn restoring_an_export_rebuilds_identical_files() {
+        // Hand-written configuration is rewritten in Requiem's own layout, so
+        // start from a configuration Requiem wrote.
+        let original = tempfile::tempdir().unwrap();
+        let config = original.path().join(".req/config.toml");
+        fs::create_dir_all(original.path().join(".req")).unwrap();
+        fs::write(
+            &config,
+            "_version = \"1\"\nallowed_kinds = [\"USR\", \"SYS\"]\ndigits = 4\n\n\
+             [attributes.risk]\ntype = \"integer\"\n",
+        )
+        .unwrap();
+        Config::load(&config).unwrap().save(&config).unwrap();
+        fs::create_dir_all(original.path().join(".req/templates")).unwrap();
+        fs::write(
+            original.path().join(".req/templates/USR.md"),
+            "## Rationale\n",
+        )
+        .unwrap();
+
+        let mut directory = Directory::new(original.path().to_path_buf()).unwrap();
+        let first = directory
+            .add_requirement("USR", "# Log in\n\nUsers shall log in.".to_string())
+            .unwrap();
+        let second = directory
+            .add_requirement("USR", "# Log out".to_string())
+            .unwrap();
+        let system = directory
+            .add_requirement("SYS", "# Sessions".to_string())
+            .unwrap();
+        directory
+            .link_requirement(system.hrid(), second.hrid())
+            .unwrap();
+        directory
+            .link_requirement(system.hrid(), first.hrid())
+            .unwrap();
+        directory
+            .update_requirement(second.hrid(), None, Some("Changed.".to_string()), None)
+            .unwrap();
+        directory
+            .set_attribute(first.hrid(), "risk", Some("3"))
+            .unwrap();
+        directory.flush().unwrap();
+        fs::create_dir_all(original.path().join("nested")).unwrap();
+        fs::rename(
+            original.path().join("SYS-0001.md"),
+            original.path().join("nested/SYS-0001.md"),
+        )
+        .unwrap();
+
+        let directory = Directory::new(original.path().to_path_buf()).unwrap();
+        let document = export(&directory).unwrap();
+        let repository = parse(&document).unwrap();
+        assert_eq!(
+            repository.requirements[0].1,
+            Some(PathBuf::from("nested/SYS-0001.md"))
+        );
+
+        let restored = tempfile::tempdir().unwrap();
+        let mut directory = Directory::new(restored.path().to_path_buf()).unwrap();
+        directory
+            .restore(repository.config, repository.requirements)
+            .unwrap();
+        directory.flush().unwrap();
+        write_templates(restored.path(), &repository.templates).unwrap();
+
+        assert_eq!(files(original.path()), files(restored.path()));
+        let directory = Directory::new(restored.path().to_path_buf()).unwrap();
+        assert_eq!(export(&directory).unwrap(), document);
+        assert_eq!(directory.suspect_links().len(), 1);
+
+        // The schema describes every field that was written.
+        let schema: serde_json::Value = serde_json::from_str(SCHEMA).unwrap();
+        let document: serde_json::Value = serde_json::from_str(&document).unwrap();
+        let described = |properties: &serde_json::Value, value: &serde_json::Value| {
+            value
+                .as_object()
+                .unwrap()
+                .keys()
+                .all(|key| properties.get(key).is_some())
+        };
+        assert!(described(&schema["properties"], &document));
+        for requirement in document["requirements"].as_array().unwrap() {
+            assert!(described(
+                &schema["$defs"]["requirement"]["properties"],
+                requirement
+            ));
+            for link in requirement["parents"].as_array().unwrap() {
+                assert!(described(&schema["$defs"]["link"]["properties"], link));
+            }
+        }
+        assert!(described(
+            &schema["$defs"]["config"]["properties"],
+            &document["config"]
+        ));
+    }
+}
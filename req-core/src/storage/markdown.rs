@@ -3,7 +3,8 @@
 //! A requirement file is a markdown document with a YAML frontmatter block.
 //! The frontmatter carries identity and link metadata (`frontmatter`
 //! submodule), while the first markdown heading carries the HRID and title
-//! (`content` submodule).
+//! (`content` submodule). Files written in older frontmatter versions are
+//! upgraded as they are read (`migration` submodule).
 
 use std::{
     fs::File,
@@ -21,11 +22,14 @@ use crate::{
 
 mod content;
 mod frontmatter;
+mod migration;
 
 use content::parse_content;
 pub(crate) use content::trim_empty_lines;
 use frontmatter::FrontMatter;
 pub use frontmatter::{hrid_as_string, hrid_from_string, Parent};
+pub(crate) use migration::migrate_config;
+pub use migration::{MigrationError, CONFIG_VERSION, FRONTMATTER_VERSION};
 
 /// A requirement serialized in markdown format with YAML frontmatter.
 #[derive(Debug, Clone)]
@@ -34,10 +38,23 @@ pub struct MarkdownRequirement {
     hrid: Hrid,
     title: String,
     body: String,
+    /// The frontmatter version the requirement was stored in.
+    version: u32,
 }
 
 impl MarkdownRequirement {
-    fn write<W: Write>(&self, writer: &mut W, digits: usize) -> io::Result<()> {
+    /// Returns the frontmatter version the requirement was stored in.
+    ///
+    /// This is [`FRONTMATTER_VERSION`] unless the requirement was read from a
+    /// file written in an older version. Requirements are always written in
+    /// [`FRONTMATTER_VERSION`].
+    #[must_use]
+    pub const fn version(&self) -> u32 {
+        self.version
+    }
+
+    /// Renders the requirement as the text of its file.
+    pub(crate) fn render(&self, digits: usize) -> io::Result<String> {
         let frontmatter = serde_yaml::to_string(&self.frontmatter)
             .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
 
@@ -45,13 +62,11 @@ impl MarkdownRequirement {
         let heading = format!("# {} {}", self.hrid.display(digits), self.title);
 
         // Combine frontmatter, heading, and body
-        let result = if self.body.is_empty() {
+        Ok(if self.body.is_empty() {
             format!("---\n{frontmatter}---\n{heading}\n")
         } else {
             format!("---\n{frontmatter}---\n{heading}\n\n{}\n", self.body)
-        };
-
-        writer.write_all(result.as_bytes())
+        })
     }
 
     pub(crate) fn read<R: BufRead>(reader: &mut R) -> Result<Self, LoadError> {
@@ -85,7 +100,9 @@ impl MarkdownRequirement {
         // The rest of the lines are Markdown content
         let content = lines.collect::<Result<Vec<_>, _>>()?.join("\n");
 
-        let front: FrontMatter = serde_yaml::from_str(&frontmatter)?;
+        let mut raw: serde_yaml::Mapping = serde_yaml::from_str(&frontmatter)?;
+        let version = migration::migrate_frontmatter(&mut raw)?;
+        let front: FrontMatter = serde_yaml::from_value(raw.into())?;
 
         // Extract HRID, title, and body from content
         let (hrid, title, body) = parse_content(&content)?;
@@ -95,6 +112,7 @@ impl MarkdownRequirement {
             hrid,
             title,
             body,
+            version,
         })
     }
 
@@ -131,36 +149,7 @@ impl MarkdownRequirement {
     ///
     /// Returns an error if the file cannot be created or written to.
     pub fn save_to_path(&self, file_path: &Path, digits: usize) -> io::Result<()> {
-        let dir = file_path
-            .parent()
-            .filter(|p| !p.as_os_str().is_empty())
-            .unwrap_or_else(|| Path::new("."));
-        std::fs::create_dir_all(dir)?;
-
-        // Write to a temporary file in the same directory, then rename it over
-        // the destination. The rename is atomic on both Unix and Windows, so a
-        // crash mid-write can never leave a truncated requirement behind. We
-        // deliberately skip fsync: the threat model is crash-truncation, not
-        // power loss, and these are plain-text files typically tracked in git.
-        let mut tmp = tempfile::NamedTempFile::new_in(dir)?;
-        self.write(&mut tmp, digits)?;
-
-        // The temp file is created owner-only on Unix and persist replaces
-        // the destination inode, so carry over the destination's existing
-        // permissions (or the conventional 0o644 for new files) to avoid
-        // silently making shared-readable requirement files private.
-        #[cfg(unix)]
-        {
-            use std::os::unix::fs::PermissionsExt;
-            let permissions = std::fs::metadata(file_path).map_or_else(
-                |_| std::fs::Permissions::from_mode(0o644),
-                |metadata| metadata.permissions(),
-            );
-            tmp.as_file().set_permissions(permissions)?;
-        }
-
-        tmp.persist(file_path).map_err(|e| e.error)?;
-        Ok(())
+        write_file(file_path, &self.render(digits)?)
     }
 
     /// Reads a requirement using the given configuration.
@@ -186,7 +175,16 @@ impl MarkdownRequirement {
             config.digits(),
         );
 
-        let file = File::open(&file_path).map_err(|io_error| match io_error.kind() {
+        Self::load_from_path(&file_path)
+    }
+
+    /// Reads a requirement from a specific file path.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be read or parsed.
+    pub fn load_from_path(file_path: &Path) -> Result<Self, LoadError> {
+        let file = File::open(file_path).map_err(|io_error| match io_error.kind() {
             io::ErrorKind::NotFound => LoadError::NotFound,
             _ => LoadError::Io(io_error),
         })?;
@@ -196,6 +194,42 @@ impl MarkdownRequirement {
     }
 }
 
+/// Writes the text of a requirement file to `file_path`, atomically.
+///
+/// Parent directories are created automatically if they don't exist.
+pub(crate) fn write_file(file_path: &Path, text: &str) -> io::Result<()> {
+    let dir = file_path
+        .parent()
+        .filter(|p| !p.as_os_str().is_empty())
+        .unwrap_or_else(|| Path::new("."));
+    std::fs::create_dir_all(dir)?;
+
+    // Write to a temporary file in the same directory, then rename it over
+    // the destination. The rename is atomic on both Unix and Windows, so a
+    // crash mid-write can never leave a truncated requirement behind. We
+    // deliberately skip fsync: the threat model is crash-truncation, not
+    // power loss, and these are plain-text files typically tracked in git.
+    let mut tmp = tempfile::NamedTempFile::new_in(dir)?;
+    tmp.write_all(text.as_bytes())?;
+
+    // The temp file is created owner-only on Unix and persist replaces
+    // the destination inode, so carry over the destination's existing
+    // permissions (or the conventional 0o644 for new files) to avoid
+    // silently making shared-readable requirement files private.
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let permissions = std::fs::metadata(file_path).map_or_else(
+            |_| std::fs::Permissions::from_mode(0o644),
+            |metadata| metadata.permissions(),
+        );
+        tmp.as_file().set_permissions(permissions)?;
+    }
+
+    tmp.persist(file_path).map_err(|e| e.error)?;
+    Ok(())
+}
+
 /// Errors that can occur when loading a requirement from markdown.
 #[derive(Debug, thiserror::Error)]
 pub enum LoadError {
@@ -214,6 +248,10 @@ pub enum LoadError {
     /// The HRID could not be parsed.
     #[error("invalid HRID in title: {0}")]
     Hrid(#[from] HridError),
+
+    /// The frontmatter version could not be upgraded.
+    #[error("unsupported frontmatter: {0}")]
+    Migration(#[from] MigrationError),
 }
 
 impl From<Requirement> for MarkdownRequirement {
@@ -225,22 +263,45 @@ impl From<Requirement> for MarkdownRequirement {
                     uuid,
                     hrid,
                     created,
+                    status,
+                    attributes,
+                    test,
+                    origin,
                     parents,
                 },
         } = req;
 
+        let mut parents: Vec<Parent> = parents
+            .into_iter()
+            .map(
+                |(
+                    uuid,
+                    DomainParent {
+                        hrid,
+                        fingerprint,
+                        link_type,
+                    },
+                )| Parent {
+                    uuid,
+                    fingerprint,
+                    hrid,
+                    link_type,
+                },
+            )
+            .collect();
+        // Parents are held in a hash map, so sort them to write the same
+        // file for the same requirement every time.
+        parents.sort_by(|a, b| a.hrid.cmp(&b.hrid).then(a.uuid.cmp(&b.uuid)));
+
         let frontmatter = FrontMatter {
             uuid,
             created,
+            status,
+            attributes,
+            test,
+            origin,
             tags,
-            parents: parents
-                .into_iter()
-                .map(|(uuid, DomainParent { hrid, fingerprint })| Parent {
-                    uuid,
-                    fingerprint,
-                    hrid,
-                })
-                .collect(),
+            parents,
         };
 
         Self {
@@ -248,6 +309,7 @@ impl From<Requirement> for MarkdownRequirement {
             hrid,
             title,
             body,
+            version: FRONTMATTER_VERSION,
         }
     }
 }
@@ -262,11 +324,16 @@ impl TryFrom<MarkdownRequirement> for Requirement {
                 FrontMatter {
                     uuid,
                     created,
+                    status,
+                    attributes,
+                    test,
+                    origin,
                     tags,
                     parents,
                 },
             title,
             body,
+            version: _,
         } = req;
 
         let parent_map = parents
@@ -276,12 +343,14 @@ impl TryFrom<MarkdownRequirement> for Requirement {
                     uuid,
                     fingerprint,
                     hrid: parent_hrid,
+                    link_type,
                 } = parent;
                 Ok((
                     uuid,
                     DomainParent {
                         hrid: parent_hrid,
                         fingerprint,
+                        link_type,
                     },
                 ))
             })
@@ -293,6 +362,10 @@ impl TryFrom<MarkdownRequirement> for Requirement {
                 uuid,
                 hrid,
                 created,
+                status,
+                attributes,
+                test,
+                origin,
                 parents: parent_map,
             },
         })
@@ -301,14 +374,18 @@ impl TryFrom<MarkdownRequirement> for Requirement {
 
 #[cfg(test)]
 mod tests {
-    use std::{collections::BTreeSet, io::Cursor, num::NonZeroUsize};
+    use std::{
+        collections::{BTreeMap, BTreeSet},
+        io::Cursor,
+        num::NonZeroUsize,
+    };
 
     use chrono::{TimeZone, Utc};
     use tempfile::TempDir;
     use uuid::Uuid;
 
     use super::{Parent, *};
-    use crate::domain::hrid::KindString;
+    use crate::domain::{hrid::KindString, AttributeValue};
 
     fn req_hrid() -> Hrid {
         Hrid::new(
@@ -325,10 +402,15 @@ mod tests {
             uuid: Uuid::parse_str("550e8400-e29b-41d4-a716-446655440000").unwrap(),
             fingerprint: "fingerprint1".to_string(),
             hrid: "REQ-PARENT-001".parse().unwrap(),
+            link_type: None,
         }];
         FrontMatter {
             uuid,
             created,
+            status: None,
+            attributes: BTreeMap::new(),
+            test: None,
+            origin: None,
             tags,
             parents,
         }
@@ -337,7 +419,7 @@ mod tests {
     #[test]
     fn markdown_round_trip() {
         let input = r"---
-_version: '1'
+_version: '2'
 uuid: 12b3f5c5-b1a8-4aa8-a882-20ff1c2aab53
 created: 2025-07-14T07:15:00Z
 tags:
@@ -358,13 +440,106 @@ This is a paragraph.
 
         assert_eq!(requirement.hrid, req_hrid());
 
-        let mut bytes: Vec<u8> = vec![];
-        requirement.write(&mut bytes, 3).unwrap();
-
-        let actual = String::from_utf8(bytes).unwrap();
+        let actual = requirement.render(3).unwrap();
         assert_eq!(input, &actual);
     }
 
+    #[test]
+    fn markdown_round_trip_with_link_type() {
+        let input = r"---
+_version: '2'
+uuid: 12b3f5c5-b1a8-4aa8-a882-20ff1c2aab53
+created: 2025-07-14T07:15:00Z
+parents:
+- uuid: 550e8400-e29b-41d4-a716-446655440000
+  fingerprint: fingerprint1
+  hrid: SYS-001
+  type: verifies
+---
+# TST-001 The Title
+";
+
+        let mut reader = Cursor::new(input);
+        let requirement = MarkdownRequirement::read(&mut reader).unwrap();
+        assert_eq!(
+            requirement.frontmatter.parents[0].link_type.as_deref(),
+            Some("verifies")
+        );
+
+        let text = requirement.render(3).unwrap();
+        assert_eq!(input, text);
+    }
+
+    #[test]
+    fn markdown_upgrades_older_versions() {
+        let input = r"---
+_version: '1'
+uuid: 12b3f5c5-b1a8-4aa8-a882-20ff1c2aab53
+created: 2025-07-14T07:15:00Z
+tags:
+- tag1
+---
+# REQ-001 The Title
+";
+
+        let mut reader = Cursor::new(input);
+        let requirement = MarkdownRequirement::read(&mut reader).unwrap();
+        assert_eq!(requirement.version(), 1);
+
+        let text = requirement.render(3).unwrap();
+        assert_eq!(input.replace("_version: '1'", "_version: '2'"), text);
+    }
+
+    #[test]
+    fn markdown_round_trip_with_status() {
+        let input = r"---
+_version: '2'
+uuid: 12b3f5c5-b1a8-4aa8-a882-20ff1c2aab53
+created: 2025-07-14T07:15:00Z
+status: approved
+tags:
+- tag1
+---
+# REQ-001 The Title
+";
+
+        let mut reader = Cursor::new(input);
+        let requirement = MarkdownRequirement::read(&mut reader).unwrap();
+        assert_eq!(requirement.frontmatter.status.as_deref(), Some("approved"));
+
+        let text = requirement.render(3).unwrap();
+        assert_eq!(input, text);
+    }
+
+    #[test]
+    fn markdown_round_trip_with_attributes() {
+        let input = r"---
+_version: '2'
+uuid: 12b3f5c5-b1a8-4aa8-a882-20ff1c2aab53
+created: 2025-07-14T07:15:00Z
+attributes:
+  owner: alice
+  review-date: 2025-09-01
+  risk: 3
+---
+# REQ-001 The Title
+";
+
+        let mut reader = Cursor::new(input);
+        let requirement = MarkdownRequirement::read(&mut reader).unwrap();
+        assert_eq!(
+            requirement.frontmatter.attributes.get("risk"),
+            Some(&AttributeValue::Integer(3))
+        );
+        assert_eq!(
+            requirement.frontmatter.attributes.get("review-date"),
+            Some(&AttributeValue::Text("2025-09-01".to_string()))
+        );
+
+        let text = requirement.render(3).unwrap();
+        assert_eq!(input, text);
+    }
+
     #[test]
     fn markdown_minimal_content() {
         let hrid = req_hrid();
@@ -481,13 +656,10 @@ created: not-a-date
             hrid: req_hrid(),
             title: "Test content".to_string(),
             body: String::new(),
+            version: FRONTMATTER_VERSION,
         };
 
-        let mut buffer = Vec::new();
-        let result = requirement.write(&mut buffer, 3);
-
-        assert!(result.is_ok());
-        let output = String::from_utf8(buffer).unwrap();
+        let output = requirement.render(3).unwrap();
         assert!(output.contains("---"));
         assert!(output.contains("# REQ-001 Test content"));
         // The frontmatter should not have an hrid field at the top level
@@ -522,6 +694,7 @@ created: not-a-date
             hrid: hrid.clone(),
             title: title.clone(),
             body: body.clone(),
+            version: FRONTMATTER_VERSION,
         };
 
         // Test save
@@ -548,6 +721,7 @@ created: not-a-date
             hrid: req_hrid(),
             title: "First".to_string(),
             body: "first body".to_string(),
+            version: FRONTMATTER_VERSION,
         };
         requirement.save_to_path(&path, 3).unwrap();
 
@@ -577,6 +751,7 @@ created: not-a-date
             hrid: req_hrid(),
             title: "Title".to_string(),
             body: String::new(),
+            version: FRONTMATTER_VERSION,
         };
 
         // New files get the conventional world-readable mode, not the
@@ -605,6 +780,7 @@ created: not-a-date
             hrid: req_hrid(),
             title: "First".to_string(),
             body: "original body".to_string(),
+            version: FRONTMATTER_VERSION,
         };
         requirement.save_to_path(&path, 3).unwrap();
 
@@ -727,6 +903,7 @@ Just plain text without a heading
             hrid: hrid.clone(),
             title,
             body: body.clone(),
+            version: FRONTMATTER_VERSION,
         };
 
         // Save and reload
@@ -1,12 +1,15 @@
 //! YAML frontmatter (de)serialization for requirement files.
 
-use std::collections::BTreeSet;
+use std::collections::{BTreeMap, BTreeSet};
 
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
-use crate::domain::Hrid;
+use crate::domain::{
+    requirement::{Origin, TestMetadata},
+    AttributeValue, Hrid,
+};
 
 /// The YAML frontmatter block of a serialized requirement.
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
@@ -15,6 +18,10 @@ use crate::domain::Hrid;
 pub(super) struct FrontMatter {
     pub(super) uuid: Uuid,
     pub(super) created: DateTime<Utc>,
+    pub(super) status: Option<String>,
+    pub(super) attributes: BTreeMap<String, AttributeValue>,
+    pub(super) test: Option<TestMetadata>,
+    pub(super) origin: Option<Origin>,
     pub(super) tags: BTreeSet<String>,
     pub(super) parents: Vec<Parent>,
 }
@@ -29,6 +36,8 @@ pub struct Parent {
         deserialize_with = "hrid_from_string"
     )]
     pub(super) hrid: Hrid,
+    #[serde(rename = "type", default, skip_serializing_if = "Option::is_none")]
+    pub(super) link_type: Option<String>,
 }
 
 /// Serialize an HRID as a string.
@@ -57,13 +66,28 @@ where
     Hrid::try_from(s.as_str()).map_err(serde::de::Error::custom)
 }
 
+/// The serialized versions of the frontmatter.
+///
+/// Only the latest version is (de)serialized directly; older versions are
+/// upgraded by the `migration` module before deserialization.
 #[derive(Debug, Serialize, Deserialize)]
 #[serde(tag = "_version")]
 enum FrontMatterVersion {
-    #[serde(rename = "1")]
-    V1 {
+    /// Adds the lifecycle `status` and custom `attributes` fields, the `test`
+    /// field of generated test requirements (with the result of their last
+    /// run), and the `origin` field of imported requirements.
+    #[serde(rename = "2")]
+    V2 {
         uuid: Uuid,
         created: DateTime<Utc>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        status: Option<String>,
+        #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+        attributes: BTreeMap<String, AttributeValue>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        test: Option<TestMetadata>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        origin: Option<Origin>,
         #[serde(default, skip_serializing_if = "BTreeSet::is_empty")]
         tags: BTreeSet<String>,
         #[serde(default, skip_serializing_if = "Vec::is_empty")]
@@ -74,14 +98,22 @@ enum FrontMatterVersion {
 impl From<FrontMatterVersion> for FrontMatter {
     fn from(version: FrontMatterVersion) -> Self {
         match version {
-            FrontMatterVersion::V1 {
+            FrontMatterVersion::V2 {
                 uuid,
                 created,
+                status,
+                attributes,
+                test,
+                origin,
                 tags,
                 parents,
             } => Self {
                 uuid,
                 created,
+                status,
+                attributes,
+                test,
+                origin,
                 tags,
                 parents,
             },
@@ -94,12 +126,20 @@ impl From<FrontMatter> for FrontMatterVersion {
         let FrontMatter {
             uuid,
             created,
+            status,
+            attributes,
+            test,
+            origin,
             tags,
             parents,
         } = front_matter;
-        Self::V1 {
+        Self::V2 {
             uuid,
             created,
+            status,
+            attributes,
+            test,
+            origin,
             tags,
             parents,
         }
@@ -113,7 +153,7 @@ mod tests {
     use chrono::TimeZone;
 
     use super::*;
-    use crate::domain::hrid::KindString;
+    use crate::domain::{hrid::KindString, TestOutcome, TestResult};
 
     fn req_hrid() -> Hrid {
         Hrid::new(
@@ -131,11 +171,16 @@ mod tests {
             uuid: Uuid::parse_str("550e8400-e29b-41d4-a716-446655440000").unwrap(),
             fingerprint: "fp1".to_string(),
             hrid: req_hrid(),
+            link_type: Some("verifies".to_string()),
         }];
 
         let frontmatter = FrontMatter {
             uuid,
             created,
+            status: None,
+            attributes: BTreeMap::new(),
+            test: None,
+            origin: None,
             tags,
             parents,
         };
@@ -143,6 +188,27 @@ mod tests {
         let back_to_frontmatter: FrontMatter = version.into();
 
         assert_eq!(frontmatter, back_to_frontmatter);
+
+        let with_metadata = FrontMatter {
+            status: Some("approved".to_string()),
+            attributes: BTreeMap::from([("risk".to_string(), AttributeValue::Integer(3))]),
+            test: Some(TestMetadata {
+                location: "src/lib.rs::parses".to_string(),
+                result: Some(TestResult {
+                    outcome: TestOutcome::Failed,
+                    timestamp: Utc.with_ymd_and_hms(2025, 7, 14, 7, 15, 0).unwrap(),
+                }),
+            }),
+            origin: Some(Origin {
+                source: "reqif".to_string(),
+                identifier: "_customer-req-1".to_string(),
+            }),
+            ..frontmatter
+        };
+        let version: FrontMatterVersion = with_metadata.clone().into();
+        let back_to_frontmatter: FrontMatter = version.into();
+
+        assert_eq!(with_metadata, back_to_frontmatter);
     }
 
     #[test]
@@ -155,6 +221,7 @@ mod tests {
             uuid,
             fingerprint: fingerprint.clone(),
             hrid: hrid.clone(),
+            link_type: None,
         };
 
         assert_eq!(parent.uuid, uuid);
@@ -0,0 +1,200 @@
+//! Upgrading stored documents between format versions.
+//!
+//! Requirement frontmatter and `.req/config.toml` both carry a `_version`
+//! tag. Only the latest version of each is understood by the typed
+//! (de)serializers; older documents are lifted to it one version at a time by
+//! a chain of [`Step`]s applied to the raw document before deserialization.
+//!
+//! Adding a format version therefore means adding one step (from the
+//! previous latest version) and bumping the matching `*_VERSION` constant.
+//!
+//! A version is added for each release that changes a format, not for each
+//! change: new optional fields join the latest version for as long as it is
+//! unreleased, since no release has written documents without them.
+
+/// The frontmatter version written by this release.
+pub const FRONTMATTER_VERSION: u32 = 2;
+
+/// The `config.toml` version written by this release.
+pub const CONFIG_VERSION: u32 = 1;
+
+/// Error type for documents that cannot be migrated.
+#[derive(Debug, Clone, thiserror::Error, PartialEq, Eq)]
+pub enum MigrationError {
+    /// The document has no `_version` field.
+    #[error("missing '_version' field")]
+    MissingVersion,
+
+    /// The `_version` field is not a quoted version number.
+    #[error("invalid '_version' field: {0}")]
+    InvalidVersion(String),
+
+    /// The document was written by a newer release.
+    #[error("version {found} is newer than the latest supported version {latest}")]
+    Unsupported {
+        /// The version found in the document.
+        found: u32,
+        /// The latest version this release understands.
+        latest: u32,
+    },
+}
+
+/// An upgrade of a document from one format version to the next.
+struct Step<D> {
+    /// The version the step upgrades from (it produces `from + 1`).
+    from: u32,
+    /// Rewrites the fields of a version `from` document.
+    apply: fn(&mut D),
+}
+
+/// Frontmatter upgrade steps, ordered by version.
+const FRONTMATTER_STEPS: &[Step<serde_yaml::Mapping>] = &[
+    // Version 2 adds the optional `status`, `attributes`, `test` (with its
+    // `result`), and `origin` fields.
+    Step {
+        from: 1,
+        apply: |_| {},
+    },
+];
+
+/// Config upgrade steps, ordered by version.
+const CONFIG_STEPS: &[Step<toml::Table>] = &[];
+
+/// A raw document with a `_version` tag.
+trait Document {
+    /// The raw `_version` value, rendered for error messages.
+    fn raw_version(&self) -> Option<String>;
+
+    /// The `_version` value, if it is a quoted version number.
+    fn version(&self) -> Option<u32>;
+
+    /// Replaces the `_version` value.
+    fn set_version(&mut self, version: u32);
+}
+
+impl Document for serde_yaml::Mapping {
+    fn raw_version(&self) -> Option<String> {
+        self.get("_version").map(|value| match value {
+            serde_yaml::Value::String(s) => s.clone(),
+            other => serde_yaml::to_string(other)
+                .unwrap_or_default()
+                .trim()
+                .to_string(),
+        })
+    }
+
+    fn version(&self) -> Option<u32> {
+        self.get("_version")?.as_str()?.parse().ok()
+    }
+
+    fn set_version(&mut self, version: u32) {
+        self.insert("_version".into(), version.to_string().into());
+    }
+}
+
+impl Document for toml::Table {
+    fn raw_version(&self) -> Option<String> {
+        self.get("_version").map(|value| match value {
+            toml::Value::String(s) => s.clone(),
+            other => other.to_string(),
+        })
+    }
+
+    fn version(&self) -> Option<u32> {
+        self.get("_version")?.as_str()?.parse().ok()
+    }
+
+    fn set_version(&mut self, version: u32) {
+        self.insert("_version".into(), version.to_string().into());
+    }
+}
+
+/// Upgrades a document to `latest`, returning the version it was stored in.
+fn migrate<D: Document>(
+    document: &mut D,
+    steps: &[Step<D>],
+    latest: u32,
+) -> Result<u32, MigrationError> {
+    let found = document.version().ok_or_else(|| {
+        document.raw_version().map_or(
+            MigrationError::MissingVersion,
+            MigrationError::InvalidVersion,
+        )
+    })?;
+    if found > latest {
+        return Err(MigrationError::Unsupported { found, latest });
+    }
+
+    for step in steps.iter().skip_while(|step| step.from < found) {
+        (step.apply)(document);
+        document.set_version(step.from + 1);
+    }
+    Ok(found)
+}
+
+/// Upgrades raw frontmatter to [`FRONTMATTER_VERSION`], returning the version
+/// it was stored in.
+pub(super) fn migrate_frontmatter(
+    frontmatter: &mut serde_yaml::Mapping,
+) -> Result<u32, MigrationError> {
+    migrate(frontmatter, FRONTMATTER_STEPS, FRONTMATTER_VERSION)
+}
+
+/// Upgrades a raw config table to [`CONFIG_VERSION`], returning the version it
+/// was stored in.
+pub fn migrate_config(config: &mut toml::Table) -> Result<u32, MigrationError> {
+    migrate(config, CONFIG_STEPS, CONFIG_VERSION)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn frontmatter(yaml: &str) -> serde_yaml::Mapping {
+        serde_yaml::from_str(yaml).unwrap()
+    }
+
+    #[test]
+    fn frontmatter_is_upgraded_one_version_at_a_time() {
+        let mut document = frontmatter("_version: '1'\nuuid: abc\n");
+        assert_eq!(migrate_frontmatter(&mut document), Ok(1));
+        assert_eq!(document.version(), Some(FRONTMATTER_VERSION));
+        assert_eq!(document.get("uuid"), Some(&"abc".into()));
+
+        let mut current = frontmatter("_version: '2'\n");
+        assert_eq!(migrate_frontmatter(&mut current), Ok(2));
+        assert_eq!(current, frontmatter("_version: '2'\n"));
+    }
+
+    #[test]
+    fn unknown_versions_are_rejected() {
+        assert_eq!(
+            migrate_frontmatter(&mut frontmatter("uuid: abc\n")),
+            Err(MigrationError::MissingVersion)
+        );
+        assert_eq!(
+            migrate_frontmatter(&mut frontmatter("_version: one\n")),
+            Err(MigrationError::InvalidVersion("one".to_string()))
+        );
+        assert_eq!(
+            migrate_frontmatter(&mut frontmatter("_version: 1\n")),
+            Err(MigrationError::InvalidVersion("1".to_string()))
+        );
+        assert_eq!(
+            migrate_frontmatter(&mut frontmatter("_version: '3'\n")),
+            Err(MigrationError::Unsupported {
+                found: 3,
+                latest: FRONTMATTER_VERSION,
+            })
+        );
+
+        let mut config: toml::Table = toml::from_str("_version = \"2\"\n").unwrap();
+        assert_eq!(
+            migrate_config(&mut config),
+            Err(MigrationError::Unsupported {
+                found: 2,
+                latest: CONFIG_VERSION,
+            })
+        );
+    }
+}
@@ -0,0 +1,34 @@
+//! Reading and writing ReqIF 1.2, the Requirements Interchange Format used
+//! by tools such as DOORS and Polarion.
+//!
+//! - `export`: writing requirements as a ReqIF document
+//! - `import`: reading the requirements in a ReqIF document, so they can be
+//!   imported with [`Directory::import_requirements`]
+//!
+//! Requirements are written with the standard `ReqIF.ForeignID` (HRID),
+//! `ReqIF.Name` (title), and `ReqIF.Text` (body) attributes, which are also
+//! the first choice when reading.
+//!
+//! [`Directory::import_requirements`]: crate::Directory::import_requirements
+
+mod export;
+mod import;
+
+pub use export::export;
+pub use import::{parse, Document, ParseError, SOURCE};
+
+/// The XML namespace of ReqIF documents.
+pub const NAMESPACE: &str = "http://www.omg.org/spec/ReqIF/20110401/reqif.xsd";
+
+/// The name of the attribute holding a requirement's HRID.
+pub const HRID_ATTRIBUTE: &str = "ReqIF.ForeignID";
+/// The name of the attribute holding a requirement's title.
+pub const TITLE_ATTRIBUTE: &str = "ReqIF.Name";
+/// The name of the attribute holding a requirement's body.
+pub const BODY_ATTRIBUTE: &str = "ReqIF.Text";
+/// The name of the attribute holding a requirement's tags.
+pub const TAGS_ATTRIBUTE: &str = "Tags";
+/// The name of the attribute holding a requirement's lifecycle status.
+pub const STATUS_ATTRIBUTE: &str = "Status";
+/// The name of the relation type of parent links without a link type.
+pub const PARENT_RELATION: &str = "Parent";
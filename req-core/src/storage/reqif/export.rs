@@ -0,0 +1,471 @@
+//! Writing requirements as a ReqIF document.
+//!
+//! Each kind becomes a `SPEC-OBJECT-TYPE`, and a `SPECIFICATION` listing its
+//! requirements in HRID order. Requirements become `SPEC-OBJECT`s identified
+//! by their UUIDs, with string attributes for the HRID, title, body (as
+//! markdown), tags (comma-separated), status, and each custom attribute.
+//! Parent links become `SPEC-RELATION`s from child to parent, with a
+//! `SPEC-RELATION-TYPE` for each link type.
+//!
+//! ReqIF identifiers must be XML names, which can't start with a digit, so
+//! UUIDs are written with a leading `_`.
+
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt::Write,
+};
+
+use chrono::{DateTime, SecondsFormat, Utc};
+use uuid::Uuid;
+
+use super::{
+    BODY_ATTRIBUTE, HRID_ATTRIBUTE, NAMESPACE, PARENT_RELATION, STATUS_ATTRIBUTE, TAGS_ATTRIBUTE,
+    TITLE_ATTRIBUTE,
+};
+use crate::{Directory, RequirementView};
+
+const STRING_TYPE: &str = "_requiem-string";
+const SPECIFICATION_TYPE: &str = "_requiem-specification";
+
+/// Writes every requirement in a directory as a ReqIF document.
+///
+/// `exported_at` is recorded as the document's creation time, and as the
+/// last change of everything that isn't a requirement (requirements use
+/// their creation time).
+#[must_use]
+pub fn export(directory: &Directory, exported_at: DateTime<Utc>) -> String {
+    let digits = directory.config().digits();
+    let mut requirements: Vec<RequirementView<'_>> = directory.requirements().collect();
+    requirements.sort_by(|a, b| a.hrid.cmp(b.hrid));
+
+    let mut kinds: BTreeMap<&str, Vec<&RequirementView<'_>>> = BTreeMap::new();
+    for requirement in &requirements {
+        kinds
+            .entry(requirement.hrid.kind())
+            .or_default()
+            .push(requirement);
+    }
+    let link_types: BTreeSet<Option<&str>> = requirements
+        .iter()
+        .flat_map(|requirement| &requirement.parents)
+        .map(|(_, parent)| parent.link_type.as_deref())
+        .collect();
+
+    let now = timestamp(exported_at);
+    let mut xml = Xml::default();
+    xml.out
+        .push_str("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    xml.open("REQ-IF", &[("xmlns", NAMESPACE)]);
+
+    write_header(&mut xml, &now);
+
+    xml.open("CORE-CONTENT", &[]);
+    xml.open("REQ-IF-CONTENT", &[]);
+
+    xml.open("DATATYPES", &[]);
+    xml.empty(
+        "DATATYPE-DEFINITION-STRING",
+        &[
+            ("IDENTIFIER", STRING_TYPE),
+            ("LAST-CHANGE", &now),
+            ("LONG-NAME", "String"),
+            ("MAX-LENGTH", "1000000"),
+        ],
+    );
+    xml.close("DATATYPES");
+
+    write_spec_types(&mut xml, &kinds, &link_types, &now);
+
+    xml.open("SPEC-OBJECTS", &[]);
+    for requirement in &requirements {
+        write_object(&mut xml, requirement, digits);
+    }
+    xml.close("SPEC-OBJECTS");
+
+    xml.open("SPEC-RELATIONS", &[]);
+    for requirement in &requirements {
+        for (parent, link) in &requirement.parents {
+            write_relation(&mut xml, requirement, *parent, link.link_type.as_deref());
+        }
+    }
+    xml.close("SPEC-RELATIONS");
+
+    xml.open("SPECIFICATIONS", &[]);
+    for (kind, requirements) in &kinds {
+        write_specification(&mut xml, kind, requirements, &now);
+    }
+    xml.close("SPECIFICATIONS");
+
+    xml.close("REQ-IF-CONTENT");
+    xml.close("CORE-CONTENT");
+    xml.close("REQ-IF");
+    xml.out
+}
+
+fn write_header(xml: &mut Xml, now: &str) {
+    xml.open("THE-HEADER", &[]);
+    xml.open("REQ-IF-HEADER", &[("IDENTIFIER", "_requiem-header")]);
+    xml.text("CREATION-TIME", now);
+    xml.text("REQ-IF-TOOL-ID", "requiem");
+    xml.text("REQ-IF-VERSION", "1.0");
+    xml.text("SOURCE-TOOL-ID", "requiem");
+    xml.text("TITLE", "Requirements");
+    xml.close("REQ-IF-HEADER");
+    xml.close("THE-HEADER");
+}
+
+fn write_spec_types(
+    xml: &mut Xml,
+    kinds: &BTreeMap<&str, Vec<&RequirementView<'_>>>,
+    link_types: &BTreeSet<Option<&str>>,
+    now: &str,
+) {
+    xml.open("SPEC-TYPES", &[]);
+    for (kind, requirements) in kinds {
+        write_object_type(xml, kind, requirements, now);
+    }
+    for link_type in link_types {
+        xml.empty(
+            "SPEC-RELATION-TYPE",
+            &[
+                ("IDENTIFIER", &relation_type_id(*link_type)),
+                ("LAST-CHANGE", now),
+                ("LONG-NAME", link_type.unwrap_or(PARENT_RELATION)),
+            ],
+        );
+    }
+    xml.empty(
+        "SPECIFICATION-TYPE",
+        &[
+            ("IDENTIFIER", SPECIFICATION_TYPE),
+            ("LAST-CHANGE", now),
+            ("LONG-NAME", "Requirements"),
+        ],
+    );
+    xml.close("SPEC-TYPES");
+}
+
+/// The attributes of a kind's requirements: the standard ones, then each
+/// custom attribute set on any of them, as (long name, identifier suffix).
+fn attributes_of(requirements: &[&RequirementView<'_>]) -> Vec<(String, String)> {
+    let standard = [
+        (HRID_ATTRIBUTE, "hrid"),
+        (TITLE_ATTRIBUTE, "title"),
+        (BODY_ATTRIBUTE, "body"),
+        (TAGS_ATTRIBUTE, "tags"),
+        (STATUS_ATTRIBUTE, "status"),
+    ]
+    .map(|(name, suffix)| (name.to_string(), suffix.to_string()));
+    let custom: BTreeSet<&String> = requirements
+        .iter()
+        .flat_map(|requirement| requirement.attributes.keys())
+        .collect();
+    standard
+        .into_iter()
+        .chain(
+            custom
+                .into_iter()
+                .map(|name| (name.clone(), format!("attribute-{}", xml_name(name)))),
+        )
+        .collect()
+}
+
+fn write_object_type(xml: &mut Xml, kind: &str, requirements: &[&RequirementView<'_>], now: &str) {
+    xml.open(
+        "SPEC-OBJECT-TYPE",
+        &[
+            ("IDENTIFIER", &format!("_requiem-type-{kind}")),
+            ("LAST-CHANGE", now),
+            ("LONG-NAME", kind),
+        ],
+    );
+    xml.open("SPEC-ATTRIBUTES", &[]);
+    for (name, suffix) in attributes_of(requirements) {
+        xml.open(
+            "ATTRIBUTE-DEFINITION-STRING",
+            &[
+                ("IDENTIFIER", &format!("_requiem-type-{kind}-{suffix}")),
+                ("LAST-CHANGE", now),
+                ("LONG-NAME", &name),
+            ],
+        );
+        xml.reference("TYPE", "DATATYPE-DEFINITION-STRING-REF", STRING_TYPE);
+        xml.close("ATTRIBUTE-DEFINITION-STRING");
+    }
+    xml.close("SPEC-ATTRIBUTES");
+    xml.close("SPEC-OBJECT-TYPE");
+}
+
+fn write_object(xml: &mut Xml, requirement: &RequirementView<'_>, digits: usize) {
+    let kind = requirement.hrid.kind();
+    xml.open(
+        "SPEC-OBJECT",
+        &[
+            ("IDENTIFIER", &object_id(requirement)),
+            ("LAST-CHANGE", &timestamp(*requirement.created)),
+            ("LONG-NAME", requirement.title),
+        ],
+    );
+
+    let tags = requirement
+        .tags
+        .iter()
+        .map(String::as_str)
+        .collect::<Vec<_>>()
+        .join(", ");
+    let mut values = vec![
+        (
+            "hrid".to_string(),
+            requirement.hrid.display(digits).to_string(),
+        ),
+        ("title".to_string(), requirement.title.to_string()),
+        ("body".to_string(), requirement.body.to_string()),
+        ("tags".to_string(), tags),
+        (
+            "status".to_string(),
+            requirement.status.unwrap_or_default().to_string(),
+        ),
+    ];
+    values.extend(
+        requirement
+            .attributes
+            .iter()
+            .map(|(name, value)| (format!("attribute-{}", xml_name(name)), value.to_string())),
+    );
+
+    xml.open("VALUES", &[]);
+    for (suffix, value) in values {
+        if value.is_empty() {
+            continue;
+        }
+        xml.open("ATTRIBUTE-VALUE-STRING", &[("THE-VALUE", &value)]);
+        xml.reference(
+            "DEFINITION",
+            "ATTRIBUTE-DEFINITION-STRING-REF",
+            &format!("_requiem-type-{kind}-{suffix}"),
+        );
+        xml.close("ATTRIBUTE-VALUE-STRING");
+    }
+    xml.close("VALUES");
+
+    xml.reference(
+        "TYPE",
+        "SPEC-OBJECT-TYPE-REF",
+        &format!("_requiem-type-{kind}"),
+    );
+    xml.close("SPEC-OBJECT");
+}
+
+fn write_relation(
+    xml: &mut Xml,
+    requirement: &RequirementView<'_>,
+    parent: Uuid,
+    link_type: Option<&str>,
+) {
+    xml.open(
+        "SPEC-RELATION",
+        &[
+            ("IDENTIFIER", &format!("_{}-{parent}", requirement.uuid)),
+            ("LAST-CHANGE", &timestamp(*requirement.created)),
+        ],
+    );
+    xml.reference("SOURCE", "SPEC-OBJECT-REF", &object_id(requirement));
+    xml.reference("TARGET", "SPEC-OBJECT-REF", &format!("_{parent}"));
+    xml.reference(
+        "TYPE",
+        "SPEC-RELATION-TYPE-REF",
+        &relation_type_id(link_type),
+    );
+    xml.close("SPEC-RELATION");
+}
+
+fn write_specification(
+    xml: &mut Xml,
+    kind: &str,
+    requirements: &[&RequirementView<'_>],
+    now: &str,
+) {
+    xml.open(
+        "SPECIFICATION",
+        &[
+            ("IDENTIFIER", &format!("_requiem-specification-{kind}")),
+            ("LAST-CHANGE", now),
+            ("LONG-NAME", kind),
+        ],
+    );
+    xml.open("CHILDREN", &[]);
+    for requirement in requirements {
+        xml.open(
+            "SPEC-HIERARCHY",
+            &[
+                (
+                    "IDENTIFIER",
+                    &format!("_requiem-hierarchy-{}", requirement.uuid),
+                ),
+                ("LAST-CHANGE", now),
+            ],
+        );
+        xml.reference("OBJECT", "SPEC-OBJECT-REF", &object_id(requirement));
+        xml.close("SPEC-HIERARCHY");
+    }
+    xml.close("CHILDREN");
+    xml.reference("TYPE", "SPECIFICATION-TYPE-REF", SPECIFICATION_TYPE);
+    xml.close("SPECIFICATION");
+}
+
+fn object_id(requirement: &RequirementView<'_>) -> String {
+    format!("_{}", requirement.uuid)
+}
+
+fn relation_type_id(link_type: Option<&str>) -> String {
+    link_type.map_or_else(
+        || "_requiem-relation".to_string(),
+        |link_type| format!("_requiem-relation-{}", xml_name(link_type)),
+    )
+}
+
+fn timestamp(time: DateTime<Utc>) -> String {
+    time.to_rfc3339_opts(SecondsFormat::Secs, true)
+}
+
+/// Replaces the characters of a name that can't appear in an XML identifier.
+fn xml_name(name: &str) -> String {
+    name.chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.') {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
+
+/// A minimal indenting XML writer.
+#[derive(Default)]
+struct Xml {
+    out: String,
+    depth: usize,
+}
+
+impl Xml {
+    fn start(&mut self, tag: &str, attributes: &[(&str, &str)]) {
+        let _ = write!(self.out, "{}<{tag}", "  ".repeat(self.depth));
+        for (name, value) in attributes {
+            let _ = write!(self.out, " {name}=\"{}\"", escape(value, true));
+        }
+    }
+
+    fn open(&mut self, tag: &str, attributes: &[(&str, &str)]) {
+        self.start(tag, attributes);
+        self.out.push_str(">\n");
+        self.depth += 1;
+    }
+
+    fn empty(&mut self, tag: &str, attributes: &[(&str, &str)]) {
+        self.start(tag, attributes);
+        self.out.push_str("/>\n");
+    }
+
+    fn close(&mut self, tag: &str) {
+        self.depth -= 1;
+        let _ = writeln!(self.out, "{}</{tag}>", "  ".repeat(self.depth));
+    }
+
+    fn text(&mut self, tag: &str, text: &str) {
+        let _ = writeln!(
+            self.out,
+            "{}<{tag}>{}</{tag}>",
+            "  ".repeat(self.depth),
+            escape(text, false)
+        );
+    }
+
+    /// Writes a reference to an identifiable element, wrapped in `tag`.
+    fn reference(&mut self, tag: &str, kind: &str, identifier: &str) {
+        self.open(tag, &[]);
+        self.text(kind, identifier);
+        self.close(tag);
+    }
+}
+
+/// Escapes text for XML. In attribute values, whitespace other than spaces
+/// is escaped too, so that it survives attribute value normalisation.
+fn escape(text: &str, attribute: bool) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' if attribute => escaped.push_str("&quot;"),
+            '\n' if attribute => escaped.push_str("&#10;"),
+            '\r' if attribute => escaped.push_str("&#13;"),
+            '\t' if attribute => escaped.push_str("&#9;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use tempfile::tempdir;
+
+    use super::*;
+
+    #[test]
+    fn exports_kinds_objects_and_relations() {
+        let tmp = tempdir().unwrap();
+        let mut directory = Directory::new(tmp.path().to_path_buf()).unwrap();
+        let usr = directory
+            .add_requirement(
+                "USR",
+                "# Log in\n\nUsers <shall> log in.\n\nQuickly.".to_string(),
+            )
+            .unwrap();
+        let sys = directory
+            .add_requirement("SYS", "# Auth service".to_string())
+            .unwrap();
+        directory
+            .link_requirement_with_type(sys.hrid(), usr.hrid(), Some("satisfies"))
+            .unwrap();
+
+        let reqif = export(&directory, Utc::now());
+        let document = roxmltree::Document::parse(&reqif).expect("export should be valid XML");
+        let count = |tag| {
+            document
+                .descendants()
+                .filter(|node| node.has_tag_name((NAMESPACE, tag)))
+                .count()
+        };
+        assert_eq!(count("SPEC-OBJECT-TYPE"), 2);
+        assert_eq!(count("SPECIFICATION"), 2);
+        assert_eq!(count("SPEC-OBJECT"), 2);
+        assert_eq!(count("SPEC-RELATION-TYPE"), 1);
+
+        let relation = document
+            .descendants()
+            .find(|node| node.has_tag_name((NAMESPACE, "SPEC-RELATION")))
+            .unwrap();
+        let refs: Vec<_> = relation
+            .descendants()
+            .filter(|node| node.has_tag_name((NAMESPACE, "SPEC-OBJECT-REF")))
+            .filter_map(|node| node.text())
+            .collect();
+        assert_eq!(
+            refs,
+            [format!("_{}", sys.uuid()), format!("_{}", usr.uuid())]
+        );
+
+        let body = document
+            .descendants()
+            .filter(|node| node.has_tag_name((NAMESPACE, "ATTRIBUTE-VALUE-STRING")))
+            .find(|node| {
+                node.descendants()
+                    .any(|definition| definition.text() == Some("_requiem-type-USR-body"))
+            })
+            .and_then(|node| node.attribute("THE-VALUE"));
+        assert_eq!(body, Some("Users <shall> log in.\n\nQuickly."));
+    }
+}
@@ -0,0 +1,391 @@
+//! Reading the requirements in a ReqIF document.
+//!
+//! Each `SPEC-OBJECT` becomes a requirement, identified by its ReqIF
+//! `IDENTIFIER`. Attributes are recognised by the long name of their
+//! definition, whatever their datatype:
+//!
+//! - the title is the first of `ReqIF.Name`, `ReqIF.ChapterName`, or `Object
+//!   Heading` (as exported by DOORS), falling back to the object's long name
+//!   and then `ReqIF.ForeignID`
+//! - the body is the first of `ReqIF.Text`, `Object Text`, or
+//!   `ReqIF.Description`
+//! - tags are read from a comma-separated `Tags` attribute
+//!
+//! XHTML values are reduced to text, with each paragraph and list item on
+//! its own line. Each `SPEC-RELATION` links its source to its target as a
+//! parent, typed by the long name of its relation type (relations of the
+//! `Parent` type are untyped). Requirements are listed in the order of the
+//! document's specifications, followed by any objects they leave out.
+//!
+//! Requirements are imported from a source named after the document's
+//! `REQ-IF-HEADER` identifier, so that documents from different suppliers
+//! keep their identifiers apart, while a later delivery of the same document
+//! updates what was imported from it.
+//!
+//! Elements are matched by local name, so documents that prefix the ReqIF
+//! namespace are read too.
+
+use std::collections::{BTreeSet, HashMap, HashSet};
+
+use roxmltree::Node;
+
+use super::{BODY_ATTRIBUTE, HRID_ATTRIBUTE, PARENT_RELATION, TAGS_ATTRIBUTE, TITLE_ATTRIBUTE};
+use crate::storage::directory::ImportedRequirement;
+
+/// The source recorded in the origin of requirements imported from ReqIF,
+/// followed by `:` and the document's header identifier if it has one.
+pub const SOURCE: &str = "reqif";
+
+const TITLE_ATTRIBUTES: [&str; 3] = [TITLE_ATTRIBUTE, "ReqIF.ChapterName", "Object Heading"];
+const BODY_ATTRIBUTES: [&str; 3] = [BODY_ATTRIBUTE, "Object Text", "ReqIF.Description"];
+
+/// Error type for reading ReqIF documents.
+#[derive(Debug, thiserror::Error)]
+pub enum ParseError {
+    /// The document isn't well-formed XML.
+    #[error("invalid ReqIF XML: {0}")]
+    Xml(#[from] roxmltree::Error),
+
+    /// The document is XML, but not ReqIF.
+    #[error("not a ReqIF document: expected a REQ-IF root element")]
+    NotReqif,
+}
+
+/// The requirements read from a ReqIF document.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Document {
+    /// The source to import the requirements from, such as
+    /// `reqif:_customer-header`.
+    pub source: String,
+    /// The requirements, in document order.
+    pub requirements: Vec<ImportedRequirement>,
+}
+
+/// Reads the requirements in a ReqIF document, to be imported as `kind` in
+/// `namespace`.
+///
+/// # Errors
+///
+/// Returns an error if the document is malformed XML, or isn't ReqIF.
+pub fn parse(document: &str, kind: &str, namespace: &[String]) -> Result<Document, ParseError> {
+    let document = roxmltree::Document::parse(document)?;
+    let root = document.root_element();
+    if root.tag_name().name() != "REQ-IF" {
+        return Err(ParseError::NotReqif);
+    }
+    let source = child(root, "THE-HEADER")
+        .and_then(|header| child(header, "REQ-IF-HEADER"))
+        .and_then(|header| header.attribute("IDENTIFIER"))
+        .map_or_else(
+            || SOURCE.to_string(),
+            |identifier| format!("{SOURCE}:{identifier}"),
+        );
+
+    let names = long_names(root);
+    let mut parents = relations(root, &names);
+    let objects: HashMap<&str, Node<'_, '_>> = elements(root, "SPEC-OBJECT")
+        .filter_map(|object| Some((object.attribute("IDENTIFIER")?, object)))
+        .collect();
+
+    let mut seen = HashSet::new();
+    let requirements = elements(root, "SPEC-HIERARCHY")
+        .filter_map(|hierarchy| {
+            child(hierarchy, "OBJECT")
+                .and_then(|object| child(object, "SPEC-OBJECT-REF"))
+                .and_then(|reference| reference.text())
+        })
+        .chain(elements(root, "SPEC-OBJECT").filter_map(|object| object.attribute("IDENTIFIER")))
+        .filter(|identifier| seen.insert(*identifier))
+        .filter_map(|identifier| {
+            let object = objects.get(identifier)?;
+            let values = values(*object, &names);
+            let first = |attributes: &[&str]| {
+                attributes
+                    .iter()
+                    .find_map(|name| values.get(*name).filter(|value| !value.trim().is_empty()))
+                    .cloned()
+            };
+            let title = first(&TITLE_ATTRIBUTES)
+                .or_else(|| object.attribute("LONG-NAME").map(str::to_string))
+                .or_else(|| first(&[HRID_ATTRIBUTE]))
+                .unwrap_or_default();
+            Some(ImportedRequirement {
+                identifier: identifier.to_string(),
+                kind: kind.to_string(),
+                namespace: namespace.to_vec(),
+                title: title.split_whitespace().collect::<Vec<_>>().join(" "),
+                body: first(&BODY_ATTRIBUTES).unwrap_or_default(),
+                tags: values.get(TAGS_ATTRIBUTE).map(|tags| {
+                    tags.split(',')
+                        .map(str::trim)
+                        .filter(|tag| !tag.is_empty())
+                        .map(str::to_string)
+                        .collect::<BTreeSet<_>>()
+                }),
+                parents: parents.remove(identifier).unwrap_or_default(),
+            })
+        })
+        .collect();
+    Ok(Document {
+        source,
+        requirements,
+    })
+}
+
+/// The long names of the document's attribute definitions, enum values, and
+/// relation types, keyed by identifier.
+fn long_names<'a>(root: Node<'a, '_>) -> HashMap<&'a str, &'a str> {
+    root.descendants()
+        .filter(|node| {
+            let name = node.tag_name().name();
+            name.starts_with("ATTRIBUTE-DEFINITION-")
+                || name == "ENUM-VALUE"
+                || name == "SPEC-RELATION-TYPE"
+        })
+        .filter_map(|node| Some((node.attribute("IDENTIFIER")?, node.attribute("LONG-NAME")?)))
+        .collect()
+}
+
+/// The parents of each object, as (identifier, link type), keyed by the
+/// child's identifier.
+fn relations<'a>(
+    root: Node<'a, '_>,
+    names: &HashMap<&str, &str>,
+) -> HashMap<&'a str, Vec<(String, Option<String>)>> {
+    let mut parents: HashMap<&str, Vec<_>> = HashMap::new();
+    for relation in elements(root, "SPEC-RELATION") {
+        let reference = |end, kind| {
+            child(relation, end)
+                .and_then(|end| child(end, kind))
+                .and_then(|reference| reference.text())
+        };
+        let (Some(source), Some(target)) = (
+            reference("SOURCE", "SPEC-OBJECT-REF"),
+            reference("TARGET", "SPEC-OBJECT-REF"),
+        ) else {
+            continue;
+        };
+        let link_type = reference("TYPE", "SPEC-RELATION-TYPE-REF")
+            .and_then(|relation_type| names.get(relation_type))
+            .filter(|name| **name != PARENT_RELATION)
+            .map(|name| (*name).to_string());
+        parents
+            .entry(source)
+            .or_default()
+            .push((target.to_string(), link_type));
+    }
+    parents
+}
+
+/// The values of an object's attributes, keyed by long name.
+fn values(object: Node<'_, '_>, names: &HashMap<&str, &str>) -> HashMap<String, String> {
+    let Some(values) = child(object, "VALUES") else {
+        return HashMap::new();
+    };
+    values
+        .children()
+        .filter(Node::is_element)
+        .filter_map(|value| {
+            let definition = child(value, "DEFINITION")?
+                .children()
+                .find(Node::is_element)?
+                .text()?;
+            let name = names.get(definition)?;
+            let text = match value.tag_name().name() {
+                "ATTRIBUTE-VALUE-XHTML" => xhtml_text(child(value, "THE-VALUE")?),
+                "ATTRIBUTE-VALUE-ENUMERATION" => child(value, "VALUES")?
+                    .children()
+                    .filter_map(|reference| names.get(reference.text()?).copied())
+                    .collect::<Vec<_>>()
+                    .join(", "),
+                _ => value.attribute("THE-VALUE")?.to_string(),
+            };
+            Some(((*name).to_string(), text))
+        })
+        .collect()
+}
+
+/// Reduces XHTML to text, with each block element on its own line and each
+/// list item prefixed with `- `.
+fn xhtml_text(node: Node<'_, '_>) -> String {
+    let mut text = String::new();
+    collect_text(node, &mut text);
+
+    let mut out = String::new();
+    let mut previous: Option<&str> = None;
+    for line in text.lines().map(str::trim).filter(|line| !line.is_empty()) {
+        if let Some(previous) = previous {
+            let list = previous.starts_with("- ") && line.starts_with("- ");
+            out.push_str(if list { "\n" } else { "\n\n" });
+        }
+        out.push_str(line);
+        previous = Some(line);
+    }
+    out
+}
+
+fn collect_text(node: Node<'_, '_>, text: &mut String) {
+    for child in node.children() {
+        if child.is_text() {
+            for c in child.text().unwrap_or_default().chars() {
+                if !c.is_whitespace() {
+                    text.push(c);
+                } else if !text.ends_with([' ', '\n']) {
+                    text.push(' ');
+                }
+            }
+        } else if child.is_element() {
+            let name = child.tag_name().name();
+            let block = matches!(
+                name,
+                "p" | "div" | "br" | "li" | "tr" | "h1" | "h2" | "h3" | "h4" | "h5" | "h6"
+            );
+            if block {
+                text.push('\n');
+            }
+            if name == "li" {
+                text.push_str("- ");
+            }
+            collect_text(child, text);
+            if block {
+                text.push('\n');
+            }
+        }
+    }
+}
+
+fn elements<'a, 'input>(
+    root: Node<'a, 'input>,
+    name: &'static str,
+) -> impl Iterator<Item = Node<'a, 'input>> {
+    root.descendants()
+        .filter(move |node| node.tag_name().name() == name)
+}
+
+fn child<'a, 'input>(node: Node<'a, 'input>, name: &str) -> Option<Node<'a, 'input>> {
+    node.children()
+        .find(|child| child.tag_name().name() == name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DOCUMENT: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<REQ-IF xmlns="http://www.omg.org/spec/ReqIF/20110401/reqif.xsd"
+        xmlns:xhtml="http://www.w3.org/1999/xhtml">
+  <THE-HEADER>
+    <REQ-IF-HEADER IDENTIFIER="_header"/>
+  </THE-HEADER>
+  <CORE-CONTENT>
+    <REQ-IF-CONTENT>
+      <SPEC-TYPES>
+        <SPEC-OBJECT-TYPE IDENTIFIER="type" LONG-NAME="Requirement">
+          <SPEC-ATTRIBUTES>
+            <ATTRIBUTE-DEFINITION-STRING IDENTIFIER="id" LONG-NAME="ReqIF.ForeignID"/>
+            <ATTRIBUTE-DEFINITION-XHTML IDENTIFIER="text" LONG-NAME="ReqIF.Text"/>
+            <ATTRIBUTE-DEFINITION-ENUMERATION IDENTIFIER="tags" LONG-NAME="Tags"/>
+          </SPEC-ATTRIBUTES>
+        </SPEC-OBJECT-TYPE>
+        <SPEC-RELATION-TYPE IDENTIFIER="refines" LONG-NAME="refines"/>
+        <SPEC-RELATION-TYPE IDENTIFIER="parent" LONG-NAME="Parent"/>
+      </SPEC-TYPES>
+      <DATATYPES>
+        <DATATYPE-DEFINITION-ENUMERATION IDENTIFIER="areas">
+          <SPECIFIED-VALUES>
+            <ENUM-VALUE IDENTIFIER="safety" LONG-NAME="safety"/>
+            <ENUM-VALUE IDENTIFIER="ui" LONG-NAME="ui"/>
+          </SPECIFIED-VALUES>
+        </DATATYPE-DEFINITION-ENUMERATION>
+      </DATATYPES>
+      <SPEC-OBJECTS>
+        <SPEC-OBJECT IDENTIFIER="_child" LONG-NAME="Brake   warning">
+          <VALUES>
+            <ATTRIBUTE-VALUE-XHTML>
+              <DEFINITION><ATTRIBUTE-DEFINITION-XHTML-REF>text</ATTRIBUTE-DEFINITION-XHTML-REF></DEFINITION>
+              <THE-VALUE>
+                <xhtml:div>
+                  <xhtml:p>The system <xhtml:b>shall</xhtml:b> warn
+                    the driver.</xhtml:p>
+                  <xhtml:ul><xhtml:li>Visually</xhtml:li><xhtml:li>Audibly</xhtml:li></xhtml:ul>
+                </xhtml:div>
+              </THE-VALUE>
+            </ATTRIBUTE-VALUE-XHTML>
+            <ATTRIBUTE-VALUE-ENUMERATION>
+              <DEFINITION><ATTRIBUTE-DEFINITION-ENUMERATION-REF>tags</ATTRIBUTE-DEFINITION-ENUMERATION-REF></DEFINITION>
+              <VALUES><ENUM-VALUE-REF>safety</ENUM-VALUE-REF><ENUM-VALUE-REF>ui</ENUM-VALUE-REF></VALUES>
+            </ATTRIBUTE-VALUE-ENUMERATION>
+          </VALUES>
+          <TYPE><SPEC-OBJECT-TYPE-REF>type</SPEC-OBJECT-TYPE-REF></TYPE>
+        </SPEC-OBJECT>
+        <SPEC-OBJECT IDENTIFIER="_parent">
+          <VALUES>
+            <ATTRIBUTE-VALUE-STRING THE-VALUE="C-1">
+              <DEFINITION><ATTRIBUTE-DEFINITION-STRING-REF>id</ATTRIBUTE-DEFINITION-STRING-REF></DEFINITION>
+            </ATTRIBUTE-VALUE-STRING>
+          </VALUES>
+          <TYPE><SPEC-OBJECT-TYPE-REF>type</SPEC-OBJECT-TYPE-REF></TYPE>
+        </SPEC-OBJECT>
+      </SPEC-OBJECTS>
+      <SPEC-RELATIONS>
+        <SPEC-RELATION IDENTIFIER="r1">
+          <SOURCE><SPEC-OBJECT-REF>_child</SPEC-OBJECT-REF></SOURCE>
+          <TARGET><SPEC-OBJECT-REF>_parent</SPEC-OBJECT-REF></TARGET>
+          <TYPE><SPEC-RELATION-TYPE-REF>refines</SPEC-RELATION-TYPE-REF></TYPE>
+        </SPEC-RELATION>
+        <SPEC-RELATION IDENTIFIER="r2">
+          <SOURCE><SPEC-OBJECT-REF>_child</SPEC-OBJECT-REF></SOURCE>
+          <TARGET><SPEC-OBJECT-REF>_other</SPEC-OBJECT-REF></TARGET>
+          <TYPE><SPEC-RELATION-TYPE-REF>parent</SPEC-RELATION-TYPE-REF></TYPE>
+        </SPEC-RELATION>
+      </SPEC-RELATIONS>
+      <SPECIFICATIONS>
+        <SPECIFICATION IDENTIFIER="spec">
+          <CHILDREN>
+            <SPEC-HIERARCHY IDENTIFIER="h1">
+              <OBJECT><SPEC-OBJECT-REF>_parent</SPEC-OBJECT-REF></OBJECT>
+            </SPEC-HIERARCHY>
+          </CHILDREN>
+        </SPECIFICATION>
+      </SPECIFICATIONS>
+    </REQ-IF-CONTENT>
+  </CORE-CONTENT>
+</REQ-IF>"#;
+
+    #[test]
+    fn parses_objects_values_and_relations() {
+        let document = parse(DOCUMENT, "CUS", &["ACME".to_string()]).unwrap();
+        assert_eq!(document.source, "reqif:_header");
+        assert_eq!(
+            document.requirements,
+            [
+                ImportedRequirement {
+                    identifier: "_parent".to_string(),
+                    kind: "CUS".to_string(),
+                    namespace: vec!["ACME".to_string()],
+                    title: "C-1".to_string(),
+                    body: String::new(),
+                    tags: None,
+                    parents: Vec::new(),
+                },
+                ImportedRequirement {
+                    identifier: "_child".to_string(),
+                    kind: "CUS".to_string(),
+                    namespace: vec!["ACME".to_string()],
+                    title: "Brake warning".to_string(),
+                    body: "The system shall warn the driver.\n\n- Visually\n- Audibly".to_string(),
+                    tags: Some(BTreeSet::from(["safety".to_string(), "ui".to_string()])),
+                    parents: vec![
+                        ("_parent".to_string(), Some("refines".to_string())),
+                        ("_other".to_string(), None),
+                    ],
+                },
+            ]
+        );
+
+        assert!(matches!(
+            parse("<testsuites/>", "CUS", &[]),
+            Err(ParseError::NotReqif)
+        ));
+    }
+}
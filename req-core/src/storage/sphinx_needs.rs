@@ -0,0 +1,150 @@
+//! Writing requirements as a sphinx-needs `needs.json` file, which Sphinx
+//! projects read with the `needimport` directive.
+//!
+//! Each requirement becomes a need whose ID is its HRID and whose type is
+//! its kind in lowercase (the name of the sphinx-needs directive for the
+//! kind), with its title, body as content, tags, status, and its parents'
+//! HRIDs as `links`. The file holds a single, unnamed version.
+
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, SecondsFormat, Utc};
+use serde::Serialize;
+
+use crate::Directory;
+
+/// The whole file.
+#[derive(Serialize)]
+struct NeedsFile<'a> {
+    created: &'a str,
+    current_version: &'a str,
+    project: &'a str,
+    versions: BTreeMap<&'a str, Version<'a>>,
+}
+
+/// The needs of one version of the project.
+#[derive(Serialize)]
+struct Version<'a> {
+    created: &'a str,
+    needs: BTreeMap<String, Need<'a>>,
+    needs_amount: usize,
+}
+
+#[derive(Serialize)]
+struct Need<'a> {
+    id: String,
+    #[serde(rename = "type")]
+    kind: String,
+    title: &'a str,
+    content: &'a str,
+    tags: Vec<&'a str>,
+    status: Option<&'a str>,
+    links: Vec<String>,
+}
+
+/// Writes every requirement in a directory as a `needs.json` file for the
+/// named project.
+///
+/// `exported_at` is recorded as the time the file and its version were
+/// created.
+#[must_use]
+pub fn export(directory: &Directory, project: &str, exported_at: DateTime<Utc>) -> String {
+    let digits = directory.config().digits();
+    let created = exported_at.to_rfc3339_opts(SecondsFormat::Secs, true);
+
+    let needs: BTreeMap<String, Need> = directory
+        .requirements()
+        .map(|requirement| {
+            let id = requirement.hrid.display(digits).to_string();
+            let mut links: Vec<String> = requirement
+                .parents
+                .iter()
+                .filter_map(|(uuid, _)| directory.find_by_uuid(*uuid))
+                .map(|parent| parent.hrid.display(digits).to_string())
+                .collect();
+            links.sort();
+            let need = Need {
+                id: id.clone(),
+                kind: requirement.hrid.kind().to_lowercase(),
+                title: requirement.title,
+                content: requirement.body,
+                tags: requirement.tags.iter().map(String::as_str).collect(),
+                status: requirement.status,
+                links,
+            };
+            (id, need)
+        })
+        .collect();
+
+    let file = NeedsFile {
+        created: &created,
+        current_version: "",
+        project,
+        versions: BTreeMap::from([(
+            "",
+            Version {
+                created: &created,
+                needs_amount: needs.len(),
+                needs,
+            },
+        )]),
+    };
+    let mut json = serde_json::to_string_pretty(&file).unwrap_or_default();
+    json.push('\n');
+    json
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+
+    use super::*;
+
+    #[test]
+    fn writes_a_need_per_requirement_linked_to_its_parents() {
+        let root = tempfile::tempdir().unwrap();
+        let mut directory = Directory::new(root.path().to_path_buf()).unwrap();
+        let user = directory
+            .add_requirement("USR", "# Log in\n\nUsers shall log in.".to_string())
+            .unwrap();
+        let system = directory
+            .add_requirement_with_namespace(
+                vec!["AUTH".to_string()],
+                "SYS",
+                "# Sessions".to_string(),
+            )
+            .unwrap();
+        directory
+            .link_requirement(system.hrid(), user.hrid())
+            .unwrap();
+        directory
+            .update_requirement(system.hrid(), None, None, Some(["auth".to_string()].into()))
+            .unwrap();
+
+        let exported_at = Utc.with_ymd_and_hms(2025, 1, 2, 3, 4, 5).unwrap();
+        let file: serde_json::Value =
+            serde_json::from_str(&export(&directory, "demo", exported_at)).unwrap();
+        assert_eq!(file["current_version"], "");
+        assert_eq!(file["project"], "demo");
+        let version = &file["versions"][""];
+        assert_eq!(version["created"], "2025-01-02T03:04:05Z");
+        assert_eq!(version["needs_amount"], 2);
+        assert_eq!(
+            version["needs"]["AUTH-SYS-001"],
+            serde_json::json!({
+                "id": "AUTH-SYS-001",
+                "type": "sys",
+                "title": "Sessions",
+                "content": "",
+                "tags": ["auth"],
+                "status": null,
+                "links": ["USR-001"],
+            })
+        );
+        assert_eq!(
+            version["needs"]["USR-001"]["content"],
+            "Users shall log in."
+        );
+        assert_eq!(version["needs"]["USR-001"]["links"], serde_json::json!([]));
+    }
+}
@@ -0,0 +1,212 @@
+//! Reading test results from test reports.
+//!
+//! Two formats are understood, and told apart by their content:
+//!
+//! - JUnit XML, as written by `cargo nextest` and most CI tooling. A test case
+//!   failed if it has a `<failure>` or `<error>`, and was skipped if it has a
+//!   `<skipped>`. The report's `timestamp`, if any, is used as the time of
+//!   each run.
+//! - The JSON lines written by `cargo test -- -Z unstable-options --format
+//!   json`. Lines that aren't test events (such as cargo's own output) are
+//!   ignored.
+//!
+//! Test names are reported as module paths, such as `auth::tests::login`.
+//! JUnit test cases are named by joining their `classname` and `name`.
+
+use chrono::{DateTime, NaiveDateTime, Utc};
+
+use crate::domain::TestOutcome;
+
+/// The result of one test in a report.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TestRun {
+    /// The name of the test, as a `::`-separated module path.
+    pub name: String,
+    /// Whether the test passed.
+    pub outcome: TestOutcome,
+    /// When the test was run, if the report says.
+    pub timestamp: Option<DateTime<Utc>>,
+}
+
+/// Error type for reading test reports.
+#[derive(Debug, thiserror::Error)]
+pub enum ReportError {
+    /// The report looks like XML, but isn't well formed.
+    #[error("invalid JUnit XML: {0}")]
+    Xml(#[from] roxmltree::Error),
+
+    /// The report is neither JUnit XML nor libtest JSON.
+    #[error("unrecognised test report: expected JUnit XML or libtest JSON")]
+    Unrecognised,
+}
+
+/// Reads the test results in a JUnit XML or libtest JSON report.
+///
+/// # Errors
+///
+/// Returns an error if the report is malformed XML, or isn't in either
+/// format.
+pub fn parse_test_report(report: &str) -> Result<Vec<TestRun>, ReportError> {
+    let report = report.trim_start_matches('\u{feff}').trim_start();
+    if report.starts_with('<') {
+        parse_junit(report)
+    } else {
+        parse_libtest(report)
+    }
+}
+
+fn parse_junit(report: &str) -> Result<Vec<TestRun>, ReportError> {
+    let document = roxmltree::Document::parse(report)?;
+    let root = document.root_element();
+    if !matches!(root.tag_name().name(), "testsuites" | "testsuite") {
+        return Err(ReportError::Unrecognised);
+    }
+
+    let runs = root
+        .descendants()
+        .filter(|node| node.has_tag_name("testcase"))
+        .filter_map(|case| {
+            let name = case.attribute("name")?;
+            let name = match case.attribute("classname") {
+                Some(class) if !class.is_empty() => format!("{class}::{name}"),
+                _ => name.to_string(),
+            };
+            let outcome = case
+                .children()
+                .filter(roxmltree::Node::is_element)
+                .find_map(|child| match child.tag_name().name() {
+                    "failure" | "error" => Some(TestOutcome::Failed),
+                    "skipped" => Some(TestOutcome::Skipped),
+                    _ => None,
+                })
+                .unwrap_or(TestOutcome::Passed);
+            let timestamp = case
+                .ancestors()
+                .find_map(|node| node.attribute("timestamp"))
+                .and_then(parse_timestamp);
+            Some(TestRun {
+                name,
+                outcome,
+                timestamp,
+            })
+        })
+        .collect();
+    Ok(runs)
+}
+
+/// Parses a JUnit timestamp, which is usually ISO 8601 without a timezone
+/// (taken to be UTC).
+fn parse_timestamp(timestamp: &str) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(timestamp)
+        .map(|timestamp| timestamp.with_timezone(&Utc))
+        .or_else(|_| {
+            NaiveDateTime::parse_from_str(timestamp, "%Y-%m-%dT%H:%M:%S%.f")
+                .map(|timestamp| timestamp.and_utc())
+        })
+        .ok()
+}
+
+fn parse_libtest(report: &str) -> Result<Vec<TestRun>, ReportError> {
+    let mut recognised = false;
+    let mut runs = Vec::new();
+    for line in report.lines() {
+        let Ok(event) = serde_json::from_str::<serde_json::Value>(line) else {
+            continue;
+        };
+        let field = |name| event.get(name).and_then(serde_json::Value::as_str);
+        let Some(kind) = field("type") else {
+            continue;
+        };
+        recognised = true;
+        if kind != "test" {
+            continue;
+        }
+        let outcome = match field("event") {
+            Some("ok") => TestOutcome::Passed,
+            Some("failed") => TestOutcome::Failed,
+            Some("ignored") => TestOutcome::Skipped,
+            _ => continue,
+        };
+        if let Some(name) = field("name") {
+            runs.push(TestRun {
+                name: name.to_string(),
+                outcome,
+                timestamp: None,
+            });
+        }
+    }
+
+    if recognised {
+        Ok(runs)
+    } else {
+        Err(ReportError::Unrecognised)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+
+    use super::*;
+
+    fn summary(runs: &[TestRun]) -> Vec<(&str, TestOutcome)> {
+        runs.iter()
+            .map(|run| (run.name.as_str(), run.outcome))
+            .collect()
+    }
+
+    #[test]
+    fn parses_junit_and_libtest_reports() {
+        let junit = r#"<?xml version="1.0" encoding="UTF-8"?>
+<testsuites name="nextest-run">
+    <testsuite name="auth" tests="3" timestamp="2025-07-14T07:15:00.250">
+        <testcase name="tests::login" classname="auth"/>
+        <testcase name="tests::logout" classname="auth">
+            <failure message="assertion failed">thread panicked</failure>
+        </testcase>
+        <testcase name="tests::refresh" classname="auth">
+            <skipped/>
+        </testcase>
+    </testsuite>
+</testsuites>"#;
+        let runs = parse_test_report(junit).unwrap();
+        assert_eq!(
+            summary(&runs),
+            [
+                ("auth::tests::login", TestOutcome::Passed),
+                ("auth::tests::logout", TestOutcome::Failed),
+                ("auth::tests::refresh", TestOutcome::Skipped),
+            ]
+        );
+        assert_eq!(
+            runs[0].timestamp,
+            Utc.with_ymd_and_hms(2025, 7, 14, 7, 15, 0)
+                .single()
+                .map(|timestamp| timestamp + chrono::Duration::milliseconds(250))
+        );
+
+        let libtest = r#"   Compiling auth v0.1.0
+{ "type": "suite", "event": "started", "test_count": 3 }
+{ "type": "test", "event": "started", "name": "tests::login" }
+{ "type": "test", "name": "tests::login", "event": "ok" }
+{ "type": "test", "name": "tests::logout", "event": "failed", "stdout": "panicked" }
+{ "type": "test", "name": "tests::refresh", "event": "ignored" }
+{ "type": "suite", "event": "failed", "passed": 1, "failed": 1, "ignored": 1 }
+"#;
+        let runs = parse_test_report(libtest).unwrap();
+        assert_eq!(
+            summary(&runs),
+            [
+                ("tests::login", TestOutcome::Passed),
+                ("tests::logout", TestOutcome::Failed),
+                ("tests::refresh", TestOutcome::Skipped),
+            ]
+        );
+        assert!(runs.iter().all(|run| run.timestamp.is_none()));
+
+        assert!(matches!(
+            parse_test_report("test result: ok"),
+            Err(ReportError::Unrecognised)
+        ));
+    }
+}
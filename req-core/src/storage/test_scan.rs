@@ -0,0 +1,304 @@
+//! Discovering annotated tests in Rust source files.
+//!
+//! A test is any function with a `#[test]` attribute (or a path ending in
+//! `::test`, such as `#[tokio::test]`, or `#[test_case(..)]`) that names the
+//! requirements it verifies, with either a comment marker:
+//!
+//! ```text
+//! // verifies: SYS-001, SYS-002
+//! /// @verifies SYS-001
+//! ```
+//!
+//! or a `#[requirement("SYS-001")]` attribute. Unannotated tests are ignored.
+//! Sources are scanned line by line rather than parsed, so annotations must
+//! sit between a function and the item before it.
+
+use std::{
+    collections::BTreeSet,
+    io,
+    path::{Path, PathBuf},
+};
+
+use crate::domain::Hrid;
+
+/// A test found in source code, with the requirements it verifies.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DiscoveredTest {
+    /// Where the test is defined, as `path/to/file.rs::test_name`.
+    pub location: String,
+    /// The first line of the test's doc comment, or its name.
+    pub title: String,
+    /// The rest of the test's doc comment.
+    pub description: String,
+    /// The requirements the test verifies, in the order they are named.
+    pub verifies: Vec<Hrid>,
+}
+
+/// Error type for scanning source files for tests.
+#[derive(Debug, thiserror::Error)]
+pub enum ScanError {
+    /// A source pattern is not a valid glob.
+    #[error("invalid source pattern '{pattern}': {source}")]
+    Pattern {
+        /// The rejected pattern.
+        pattern: String,
+        /// Why the pattern was rejected.
+        source: glob::PatternError,
+    },
+
+    /// A source file could not be read.
+    #[error("failed to read {}: {source}", path.display())]
+    Io {
+        /// The file that could not be read.
+        path: PathBuf,
+        /// The underlying error.
+        source: io::Error,
+    },
+}
+
+/// Finds the annotated tests in the source files matching some glob
+/// patterns.
+///
+/// Patterns and the paths in test locations are relative to `root`. Tests
+/// are returned sorted by location; if two tests share a location, only the
+/// first is kept.
+///
+/// # Errors
+///
+/// Returns an error if a pattern is invalid or a matching file cannot be
+/// read.
+pub fn scan_tests(root: &Path, patterns: &[String]) -> Result<Vec<DiscoveredTest>, ScanError> {
+    let prefix = glob::Pattern::escape(&root.to_string_lossy());
+    let mut files = BTreeSet::new();
+    for pattern in patterns {
+        let full = format!("{prefix}/{pattern}");
+        let paths = glob::glob(&full).map_err(|source| ScanError::Pattern {
+            pattern: pattern.clone(),
+            source,
+        })?;
+        files.extend(paths.filter_map(Result::ok).filter(|path| path.is_file()));
+    }
+
+    let mut tests = Vec::new();
+    let mut locations = BTreeSet::new();
+    for path in files {
+        let source = std::fs::read_to_string(&path).map_err(|source| ScanError::Io {
+            path: path.clone(),
+            source,
+        })?;
+        let file = path
+            .strip_prefix(root)
+            .unwrap_or(&path)
+            .components()
+            .map(|component| component.as_os_str().to_string_lossy())
+            .collect::<Vec<_>>()
+            .join("/");
+        for test in scan_source(&file, &source) {
+            if locations.insert(test.location.clone()) {
+                tests.push(test);
+            } else {
+                tracing::warn!(
+                    "Skipping {}: another test has the same location",
+                    test.location
+                );
+            }
+        }
+    }
+    tests.sort_by(|a, b| a.location.cmp(&b.location));
+    Ok(tests)
+}
+
+/// The comments and attributes collected since the last item.
+#[derive(Default)]
+struct Annotations {
+    docs: Vec<String>,
+    verifies: Vec<Hrid>,
+    is_test: bool,
+}
+
+/// Finds the annotated tests in the contents of one source file.
+fn scan_source(file: &str, source: &str) -> Vec<DiscoveredTest> {
+    let mut tests = Vec::new();
+    let mut annotations = Annotations::default();
+
+    for line in source.lines().map(str::trim) {
+        if line.is_empty() {
+            continue;
+        }
+        if let Some(doc) = line.strip_prefix("///") {
+            match marker(doc) {
+                Some(hrids) => annotations.verifies.extend(parse_hrids(file, hrids)),
+                None => annotations
+                    .docs
+                    .push(doc.strip_prefix(' ').unwrap_or(doc).to_string()),
+            }
+            continue;
+        }
+        if let Some(comment) = line.strip_prefix("//") {
+            if let Some(hrids) = marker(comment) {
+                annotations.verifies.extend(parse_hrids(file, hrids));
+            }
+            continue;
+        }
+        if let Some(attribute) = line.strip_prefix("#[") {
+            let path = attribute
+                .split(['(', ']'])
+                .next()
+                .unwrap_or_default()
+                .trim();
+            if path == "test" || path.ends_with("::test") || path == "test_case" {
+                annotations.is_test = true;
+            } else if path == "requirement" || path.ends_with("::requirement") {
+                let arguments = attribute.split('"').skip(1).step_by(2);
+                annotations
+                    .verifies
+                    .extend(arguments.flat_map(|hrids| parse_hrids(file, hrids)));
+            }
+            continue;
+        }
+
+        let annotations = std::mem::take(&mut annotations);
+        if let Some(name) = function_name(line) {
+            if annotations.is_test && !annotations.verifies.is_empty() {
+                tests.push(discovered(file, name, annotations));
+            }
+        }
+    }
+
+    tests
+}
+
+fn discovered(file: &str, name: &str, annotations: Annotations) -> DiscoveredTest {
+    let Annotations {
+        docs, mut verifies, ..
+    } = annotations;
+    let mut seen = BTreeSet::new();
+    verifies.retain(|hrid| seen.insert(hrid.clone()));
+
+    let mut docs = docs.into_iter();
+    let title = docs
+        .next()
+        .filter(|line| !line.trim().is_empty())
+        .unwrap_or_else(|| name.to_string());
+    let description = docs.collect::<Vec<_>>().join("\n").trim().to_string();
+
+    DiscoveredTest {
+        location: format!("{file}::{name}"),
+        title: title.trim().to_string(),
+        description,
+        verifies,
+    }
+}
+
+/// Returns the requirement list of a `verifies:` or `@verifies` comment.
+fn marker(comment: &str) -> Option<&str> {
+    let comment = comment.trim();
+    ["verifies:", "@verifies"].into_iter().find_map(|marker| {
+        comment
+            .get(..marker.len())
+            .filter(|start| start.eq_ignore_ascii_case(marker))
+            .map(|_| &comment[marker.len()..])
+    })
+}
+
+/// Parses a list of HRIDs separated by commas or whitespace, skipping (and
+/// logging) any that are invalid.
+fn parse_hrids<'a>(file: &'a str, list: &'a str) -> impl Iterator<Item = Hrid> + 'a {
+    list.split([',', ' ', '\t'])
+        .map(|token| token.trim_matches(['"', '`']))
+        .filter(|token| !token.is_empty())
+        .filter_map(move |token| match Hrid::try_from(token) {
+            Ok(hrid) => Some(hrid),
+            Err(e) => {
+                tracing::warn!("Ignoring '{token}' in {file}: {e}");
+                None
+            }
+        })
+}
+
+/// Returns the name of the function declared on a line, if any.
+fn function_name(line: &str) -> Option<&str> {
+    let mut words = line.split_whitespace();
+    let qualifiers = words.by_ref().take_while(|&word| word != "fn");
+    if !qualifiers
+        .into_iter()
+        .all(|word| word.starts_with("pub") || ["async", "const", "unsafe"].contains(&word))
+    {
+        return None;
+    }
+    let name = words.next()?;
+    let end = name.find(['(', '<']).unwrap_or(name.len());
+    Some(&name[..end]).filter(|name| !name.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_annotated_tests() {
+        let source = r#"
+fn helper() {}
+
+#[cfg(test)]
+mod tests {
+    /// Rejects expired tokens
+    ///
+    /// Tokens past their expiry are refused.
+    /// @verifies SYS-001
+    #[test]
+    fn rejects_expired_tokens() {}
+
+    // verifies: SYS-002, usr-9
+    #[tokio::test]
+    async fn refreshes_tokens() {}
+
+    #[test]
+    #[requirement("SYS-001", "SYS-003")]
+    fn logs_out() {}
+
+    #[test]
+    fn unannotated() {}
+
+    // verifies: SYS-004
+    fn not_a_test() {}
+}
+"#;
+        let tests = scan_source("src/auth.rs", source);
+        let summary: Vec<_> = tests
+            .iter()
+            .map(|test| {
+                let verifies: Vec<_> = test
+                    .verifies
+                    .iter()
+                    .map(|h| h.display(3).to_string())
+                    .collect();
+                (test.location.as_str(), test.title.as_str(), verifies)
+            })
+            .collect();
+        assert_eq!(
+            summary,
+            [
+                (
+                    "src/auth.rs::rejects_expired_tokens",
+                    "Rejects expired tokens",
+                    vec!["SYS-001".to_string()]
+                ),
+                (
+                    "src/auth.rs::refreshes_tokens",
+                    "refreshes_tokens",
+                    vec!["SYS-002".to_string()]
+                ),
+                (
+                    "src/auth.rs::logs_out",
+                    "logs_out",
+                    vec!["SYS-001".to_string(), "SYS-003".to_string()]
+                ),
+            ]
+        );
+        assert_eq!(
+            tests[0].description,
+            "Tokens past their expiry are refused."
+        );
+    }
+}
@@ -0,0 +1,320 @@
+//! The checks of `req validate`, as diagnostics on the files they concern.
+//!
+//! Problems with a link are reported on the parent's entry in the child's
+//! frontmatter; problems with a whole requirement on its title heading. Each
+//! diagnostic's code is the `req validate --check` type that finds it.
+
+use std::{
+    collections::{BTreeMap, HashSet},
+    path::{Path, PathBuf},
+};
+
+use lsp_types::{Diagnostic, DiagnosticSeverity, NumberOrString, Range};
+use requiem_core::{Directory, Hrid};
+
+use crate::text;
+
+/// Diagnostics, keyed by the path of the file they belong to.
+pub type Diagnostics = BTreeMap<PathBuf, Vec<Diagnostic>>;
+
+/// Runs every check on `directory`, reading file text with `read`.
+pub fn check(directory: &Directory, read: impl Fn(&Path) -> Option<String>) -> Diagnostics {
+    let mut checker = Checker {
+        directory,
+        digits: directory.config().digits(),
+        read,
+        texts: BTreeMap::new(),
+        diagnostics: Diagnostics::new(),
+    };
+    checker.links();
+    checker.cycles();
+    checker.paths();
+    checker.status();
+    checker.attributes();
+    checker.rules();
+    checker.diagnostics
+}
+
+/// A diagnostic for a whole file, on its first line.
+pub fn file_error(message: String) -> Diagnostic {
+    diagnostic(
+        Range::default(),
+        DiagnosticSeverity::ERROR,
+        "structure",
+        message,
+    )
+}
+
+struct Checker<'a, F> {
+    directory: &'a Directory,
+    digits: usize,
+    read: F,
+    texts: BTreeMap<PathBuf, String>,
+    diagnostics: Diagnostics,
+}
+
+/// Where a diagnostic about a requirement goes.
+enum Anchor<'a> {
+    /// The requirement's title heading.
+    Heading,
+    /// The entry for the parent with this UUID in the frontmatter.
+    Parent(String),
+    /// A key in the frontmatter.
+    Key(&'a str),
+}
+
+impl<F: Fn(&Path) -> Option<String>> Checker<'_, F> {
+    fn display(&self, hrid: &Hrid) -> String {
+        hrid.display(self.digits).to_string()
+    }
+
+    fn push(
+        &mut self,
+        hrid: &Hrid,
+        anchor: &Anchor,
+        severity: DiagnosticSeverity,
+        code: &str,
+        message: String,
+    ) {
+        let Some(path) = self.directory.path_for(hrid).map(Path::to_path_buf) else {
+            return;
+        };
+        if !self.texts.contains_key(&path) {
+            let text = (self.read)(&path).unwrap_or_default();
+            self.texts.insert(path.clone(), text);
+        }
+        let text = &self.texts[&path];
+        let line = match anchor {
+            Anchor::Heading => None,
+            Anchor::Parent(uuid) => text::parent_line(text, uuid),
+            Anchor::Key(key) => text::key_line(text, key),
+        }
+        .unwrap_or_else(|| text::heading_line(text));
+        let range = text::line_range(text, line);
+        self.diagnostics
+            .entry(path)
+            .or_default()
+            .push(diagnostic(range, severity, code, message));
+    }
+
+    /// Broken references, stale parent HRIDs, and suspect links.
+    fn links(&mut self) {
+        let suspect: HashSet<_> = self
+            .directory
+            .suspect_links()
+            .into_iter()
+            .map(|link| (link.child_uuid, link.parent_uuid))
+            .collect();
+        let directory = self.directory;
+        for requirement in directory.requirements() {
+            let child = self.display(requirement.hrid);
+            for (uuid, parent) in &requirement.parents {
+                let anchor = Anchor::Parent(uuid.to_string());
+                let stored = self.display(&parent.hrid);
+                let Some(found) = directory.find_by_uuid(*uuid) else {
+                    self.push(
+                        requirement.hrid,
+                        &anchor,
+                        DiagnosticSeverity::ERROR,
+                        "links",
+                        format!("Parent {stored} ({uuid}) does not exist"),
+                    );
+                    continue;
+                };
+                let current = self.display(found.hrid);
+                if current != stored {
+                    self.push(
+                        requirement.hrid,
+                        &anchor,
+                        DiagnosticSeverity::WARNING,
+                        "links",
+                        format!(
+                            "Parent HRID {stored} is out of date: the parent is now {current} \
+                             (fix with `req validate --fix`)"
+                        ),
+                    );
+                }
+                if suspect.contains(&(*requirement.uuid, *uuid)) {
+                    self.push(
+                        requirement.hrid,
+                        &anchor,
+                        DiagnosticSeverity::WARNING,
+                        "suspect",
+                        format!(
+                            "Suspect link: {current} has changed since this link was last \
+                             reviewed (accept with `req review --accept {child} {current}`)"
+                        ),
+                    );
+                }
+            }
+        }
+    }
+
+    /// Circular dependencies, reported on every link in the cycle.
+    fn cycles(&mut self) {
+        for cycle in self.directory.detect_cycles() {
+            let shown: Vec<String> = cycle.iter().map(|hrid| self.display(hrid)).collect();
+            let message = format!("Circular dependency: {}", shown.join(" → "));
+            for link in cycle.windows(2) {
+                let Some(parent) = self.directory.find_by_hrid(&link[1]) else {
+                    continue;
+                };
+                let anchor = Anchor::Parent(parent.uuid.to_string());
+                self.push(
+                    &link[0],
+                    &anchor,
+                    DiagnosticSeverity::ERROR,
+                    "links",
+                    message.clone(),
+                );
+            }
+        }
+    }
+
+    /// Files that are not at their canonical locations.
+    fn paths(&mut self) {
+        let root = self.directory.root().to_path_buf();
+        for (hrid, _, expected) in self.directory.check_path_drift() {
+            let expected = expected.strip_prefix(&root).unwrap_or(&expected);
+            self.push(
+                &hrid,
+                &Anchor::Heading,
+                DiagnosticSeverity::WARNING,
+                "paths",
+                format!(
+                    "{} belongs at {} (move it with `req sync`)",
+                    self.display(&hrid),
+                    expected.display()
+                ),
+            );
+        }
+    }
+
+    /// Approved requirements that depend on draft parents.
+    fn status(&mut self) {
+        for conflict in self.directory.status_conflicts() {
+            let parent = self.display(&conflict.parent_hrid);
+            self.push(
+                &conflict.child_hrid,
+                &Anchor::Parent(conflict.parent_uuid.to_string()),
+                DiagnosticSeverity::WARNING,
+                "status",
+                format!("Approved requirement depends on draft parent {parent}"),
+            );
+        }
+    }
+
+    /// Custom attributes that break the schema in `config.toml`.
+    fn attributes(&mut self) {
+        for issue in self.directory.attribute_issues() {
+            self.push(
+                &issue.hrid,
+                &Anchor::Key(&issue.attribute),
+                DiagnosticSeverity::WARNING,
+                "attributes",
+                format!("Attribute '{}': {}", issue.attribute, issue.problem),
+            );
+        }
+    }
+
+    /// Links that break the link rules of their kind.
+    fn rules(&mut self) {
+        for violation in self.directory.link_rule_violations() {
+            let anchor = violation
+                .parent
+                .as_ref()
+                .and_then(|parent| self.directory.find_by_hrid(parent))
+                .map_or(Anchor::Heading, |parent| {
+                    Anchor::Parent(parent.uuid.to_string())
+                });
+            self.push(
+                &violation.hrid,
+                &anchor,
+                DiagnosticSeverity::WARNING,
+                "rules",
+                violation.error.to_string(),
+            );
+        }
+    }
+}
+
+fn diagnostic(
+    range: Range,
+    severity: DiagnosticSeverity,
+    code: &str,
+    message: String,
+) -> Diagnostic {
+    Diagnostic {
+        range,
+        severity: Some(severity),
+        code: Some(NumberOrString::String(code.to_string())),
+        source: Some("req".to_string()),
+        message,
+        ..Diagnostic::default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reports_link_problems_on_the_parent_entry() {
+        let root = tempfile::tempdir().unwrap();
+        let mut directory = Directory::new(root.path().to_path_buf()).unwrap();
+        let user = directory
+            .add_requirement("USR", "# Log in".to_string())
+            .unwrap();
+        let system = directory
+            .add_requirement("SYS", "# Sessions".to_string())
+            .unwrap();
+        directory
+            .link_requirement(system.hrid(), user.hrid())
+            .unwrap();
+        directory
+            .update_requirement(user.hrid(), None, Some("Changed.".to_string()), None)
+            .unwrap();
+        directory.flush().unwrap();
+
+        // Links that would form a cycle can only be made by hand.
+        let user_path = directory.path_for(user.hrid()).unwrap();
+        let text = std::fs::read_to_string(user_path).unwrap().replacen(
+            "\n---\n",
+            &format!(
+                "\nparents:\n- uuid: {}\n  fingerprint: x\n  hrid: SYS-001\n---\n",
+                system.uuid()
+            ),
+            1,
+        );
+        std::fs::write(user_path, text).unwrap();
+        let directory = Directory::new(root.path().to_path_buf()).unwrap();
+
+        let diagnostics = check(&directory, |path| std::fs::read_to_string(path).ok());
+        let path = directory.path_for(system.hrid()).unwrap();
+        let text = std::fs::read_to_string(path).unwrap();
+        let system = &diagnostics[path];
+        let codes: Vec<_> = system
+            .iter()
+            .map(|diagnostic| diagnostic.code.clone().unwrap())
+            .collect();
+        assert_eq!(
+            codes,
+            [
+                NumberOrString::String("suspect".to_string()),
+                NumberOrString::String("links".to_string()),
+            ]
+        );
+        let line = text
+            .lines()
+            .nth(system[0].range.start.line as usize)
+            .unwrap();
+        assert_eq!(line.trim(), "hrid: USR-001");
+        assert!(system[0]
+            .message
+            .contains("req review --accept SYS-001 USR-001"));
+        assert_eq!(
+            system[1].message,
+            "Circular dependency: SYS-001 → USR-001 → SYS-001"
+        );
+    }
+}
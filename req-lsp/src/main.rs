@@ -0,0 +1,235 @@
+//! Language server for requirements management.
+//!
+//! Offers go-to-definition and hover previews for HRIDs, HRID completion, and
+//! the diagnostics of `req validate`, published as documents are edited.
+
+mod diagnostics;
+mod server;
+mod text;
+
+use std::{path::PathBuf, time::Duration};
+
+use anyhow::Context;
+use clap::Parser;
+use lsp_server::{Connection, ErrorCode, Message, Notification, Request, Response};
+use lsp_types::{
+    notification::{
+        DidChangeTextDocument, DidCloseTextDocument, DidOpenTextDocument, DidSaveTextDocument,
+        Notification as _, PublishDiagnostics,
+    },
+    request::{Completion, GotoDefinition, HoverRequest, Request as _},
+    CompletionOptions, CompletionParams, DidChangeTextDocumentParams, DidCloseTextDocumentParams,
+    DidOpenTextDocumentParams, DidSaveTextDocumentParams, GotoDefinitionParams,
+    GotoDefinitionResponse, HoverParams, HoverProviderCapability, InitializeParams, OneOf,
+    SaveOptions, ServerCapabilities, TextDocumentPositionParams, TextDocumentSyncCapability,
+    TextDocumentSyncKind, TextDocumentSyncOptions, TextDocumentSyncSaveOptions, Url,
+};
+use server::Server;
+
+/// How long edits must pause before they are reloaded.
+const DEBOUNCE: Duration = Duration::from_millis(200);
+
+#[derive(Debug, Parser)]
+#[command(version, about)]
+struct Cli {
+    /// The requirements root (defaults to the root of the editor's workspace)
+    #[arg(short, long)]
+    root: Option<PathBuf>,
+}
+
+fn main() -> anyhow::Result<()> {
+    // Log to stderr, since stdout carries the protocol.
+    tracing_subscriber::fmt()
+        .with_writer(std::io::stderr)
+        .with_env_filter(
+            tracing_subscriber::EnvFilter::try_from_default_env()
+                .unwrap_or_else(|_| tracing_subscriber::EnvFilter::new("info")),
+        )
+        .init();
+
+    let cli = Cli::parse();
+    let (connection, io_threads) = Connection::stdio();
+
+    let params = connection.initialize(serde_json::to_value(capabilities())?)?;
+    let params: InitializeParams = serde_json::from_value(params)?;
+    let root = match cli.root.or_else(|| workspace_root(&params)) {
+        Some(root) => root,
+        None => std::env::current_dir()?,
+    };
+    let root = root
+        .canonicalize()
+        .with_context(|| format!("failed to canonicalize {}", root.display()))?;
+
+    tracing::info!("Loading requirements from {}", root.display());
+    let mut server = Server::new(root);
+    run(&connection, &mut server)?;
+    drop(connection);
+    io_threads.join()?;
+    Ok(())
+}
+
+fn capabilities() -> ServerCapabilities {
+    ServerCapabilities {
+        text_document_sync: Some(TextDocumentSyncCapability::Options(
+            TextDocumentSyncOptions {
+                open_close: Some(true),
+                change: Some(TextDocumentSyncKind::FULL),
+                save: Some(TextDocumentSyncSaveOptions::SaveOptions(SaveOptions {
+                    include_text: Some(false),
+                })),
+                ..TextDocumentSyncOptions::default()
+            },
+        )),
+        definition_provider: Some(OneOf::Left(true)),
+        hover_provider: Some(HoverProviderCapability::Simple(true)),
+        completion_provider: Some(CompletionOptions::default()),
+        ..ServerCapabilities::default()
+    }
+}
+
+/// The first workspace folder, or the (deprecated) root URI.
+fn workspace_root(params: &InitializeParams) -> Option<PathBuf> {
+    #[allow(deprecated)]
+    let root_uri = params.root_uri.as_ref();
+    params
+        .workspace_folders
+        .as_ref()
+        .and_then(|folders| folders.first())
+        .map(|folder| &folder.uri)
+        .or(root_uri)
+        .and_then(|uri| uri.to_file_path().ok())
+}
+
+/// Handles messages until the client shuts the server down.
+///
+/// Edits are reloaded once no message has arrived for [`DEBOUNCE`], so that
+/// typing doesn't reload the requirements on every keystroke.
+fn run(connection: &Connection, server: &mut Server) -> anyhow::Result<()> {
+    publish(connection, server)?;
+    loop {
+        let message = if server.has_pending_changes() {
+            match connection.receiver.recv_timeout(DEBOUNCE) {
+                Ok(message) => message,
+                Err(error) if error.is_timeout() => {
+                    server.refresh();
+                    publish(connection, server)?;
+                    continue;
+                }
+                Err(_) => return Ok(()),
+            }
+        } else {
+            match connection.receiver.recv() {
+                Ok(message) => message,
+                Err(_) => return Ok(()),
+            }
+        };
+        match message {
+            Message::Request(request) => {
+                if connection.handle_shutdown(&request)? {
+                    return Ok(());
+                }
+                // Requests are answered from the latest text.
+                if server.refresh() {
+                    publish(connection, server)?;
+                }
+                let id = request.id.clone();
+                let response = match respond(server, request) {
+                    Ok(Some(result)) => Response::new_ok(id, result),
+                    Ok(None) => Response::new_err(
+                        id,
+                        ErrorCode::MethodNotFound as i32,
+                        "unsupported request".to_string(),
+                    ),
+                    Err(error) => {
+                        Response::new_err(id, ErrorCode::InvalidParams as i32, error.to_string())
+                    }
+                };
+                connection.sender.send(Message::Response(response))?;
+            }
+            Message::Notification(notification) => match notify(server, notification) {
+                Ok(true) => publish(connection, server)?,
+                Ok(false) => {}
+                Err(error) => tracing::warn!("Failed to handle notification: {error}"),
+            },
+            Message::Response(_) => {}
+        }
+    }
+}
+
+/// The result of a request, or `None` if the request isn't supported.
+fn respond(server: &Server, request: Request) -> anyhow::Result<Option<serde_json::Value>> {
+    let result = match request.method.as_str() {
+        GotoDefinition::METHOD => {
+            let params: GotoDefinitionParams = serde_json::from_value(request.params)?;
+            let (path, position) = position(&params.text_document_position_params)?;
+            serde_json::to_value(
+                server
+                    .definition(&path, position)
+                    .map(GotoDefinitionResponse::Scalar),
+            )?
+        }
+        HoverRequest::METHOD => {
+            let params: HoverParams = serde_json::from_value(request.params)?;
+            let (path, position) = position(&params.text_document_position_params)?;
+            serde_json::to_value(server.hover(&path, position))?
+        }
+        Completion::METHOD => {
+            let params: CompletionParams = serde_json::from_value(request.params)?;
+            let (path, position) = position(&params.text_document_position)?;
+            serde_json::to_value(server.completion(&path, position))?
+        }
+        _ => return Ok(None),
+    };
+    Ok(Some(result))
+}
+
+/// Applies a notification, returning whether the diagnostics need
+/// republishing now. Edits are left for [`run`] to reload.
+fn notify(server: &mut Server, notification: Notification) -> anyhow::Result<bool> {
+    match notification.method.as_str() {
+        DidOpenTextDocument::METHOD => {
+            let params: DidOpenTextDocumentParams = serde_json::from_value(notification.params)?;
+            server.update(path(&params.text_document.uri)?, params.text_document.text);
+        }
+        DidChangeTextDocument::METHOD => {
+            let params: DidChangeTextDocumentParams = serde_json::from_value(notification.params)?;
+            // Documents are synced in full, so the last change holds the
+            // whole text.
+            if let Some(change) = params.content_changes.into_iter().last() {
+                server.update(path(&params.text_document.uri)?, change.text);
+            }
+        }
+        DidSaveTextDocument::METHOD => {
+            let _: DidSaveTextDocumentParams = serde_json::from_value(notification.params)?;
+            server.reload();
+            return Ok(true);
+        }
+        DidCloseTextDocument::METHOD => {
+            let params: DidCloseTextDocumentParams = serde_json::from_value(notification.params)?;
+            server.close(&path(&params.text_document.uri)?);
+        }
+        _ => {}
+    }
+    Ok(false)
+}
+
+fn publish(connection: &Connection, server: &mut Server) -> anyhow::Result<()> {
+    for params in server.diagnostics() {
+        connection
+            .sender
+            .send(Message::Notification(Notification::new(
+                PublishDiagnostics::METHOD.to_string(),
+                params,
+            )))?;
+    }
+    Ok(())
+}
+
+fn path(uri: &Url) -> anyhow::Result<PathBuf> {
+    uri.to_file_path()
+        .map_err(|()| anyhow::anyhow!("not a file URI: {uri}"))
+}
+
+fn position(params: &TextDocumentPositionParams) -> anyhow::Result<(PathBuf, lsp_types::Position)> {
+    Ok((path(&params.text_document.uri)?, params.position))
+}
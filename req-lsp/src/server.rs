@@ -0,0 +1,348 @@
+//! The language server's state, and its answers to requests.
+//!
+//! Open documents are read from their unsaved text, so that navigation and
+//! diagnostics follow what the user is typing. Edited documents are reloaded
+//! once the edits pause (or before a request is answered), and only they are
+//! re-parsed. Saving rereads every requirement, picking up changes made
+//! outside the editor.
+
+use std::{
+    collections::{BTreeMap, BTreeSet, HashMap},
+    fs,
+    path::{Path, PathBuf},
+};
+
+use lsp_types::{
+    CompletionItem, CompletionItemKind, CompletionTextEdit, Documentation, Hover, HoverContents,
+    Location, MarkupContent, MarkupKind, Position, PublishDiagnosticsParams, Range, TextEdit, Url,
+};
+use requiem_core::{storage::directory::DirectoryLoadError, Directory, Hrid, RequirementView};
+
+use crate::{
+    diagnostics::{self, Diagnostics},
+    text,
+};
+
+pub struct Server {
+    root: PathBuf,
+    /// The text of open documents.
+    documents: HashMap<PathBuf, String>,
+    /// Documents opened, edited, or closed since they were last reloaded.
+    pending: BTreeSet<PathBuf>,
+    /// The requirements, as of the last successful load.
+    directory: Option<Directory>,
+    /// Files that couldn't be loaded, and why.
+    load_errors: BTreeMap<PathBuf, String>,
+    /// Files with diagnostics at the last publication, which must be cleared
+    /// if they no longer have any.
+    published: BTreeSet<PathBuf>,
+}
+
+impl Server {
+    pub fn new(root: PathBuf) -> Self {
+        let mut server = Self {
+            root,
+            documents: HashMap::new(),
+            pending: BTreeSet::new(),
+            directory: None,
+            load_errors: BTreeMap::new(),
+            published: BTreeSet::new(),
+        };
+        server.reload();
+        server
+    }
+
+    /// Records the text of a document that was opened or edited, to be
+    /// reloaded by [`Server::refresh`].
+    pub fn update(&mut self, path: PathBuf, text: String) {
+        self.documents.insert(path.clone(), text);
+        self.pending.insert(path);
+    }
+
+    /// Forgets the text of a closed document, going back to what is on disk
+    /// at the next [`Server::refresh`].
+    pub fn close(&mut self, path: &Path) {
+        self.documents.remove(path);
+        self.pending.insert(path.to_path_buf());
+    }
+
+    /// Whether documents changed since they were last reloaded.
+    pub fn has_pending_changes(&self) -> bool {
+        !self.pending.is_empty()
+    }
+
+    /// Reloads the documents that changed since they were last reloaded,
+    /// returning whether there were any.
+    pub fn refresh(&mut self) -> bool {
+        if self.pending.is_empty() {
+            return false;
+        }
+        let changed: Vec<PathBuf> = std::mem::take(&mut self.pending).into_iter().collect();
+        let Some(directory) = &mut self.directory else {
+            self.reload();
+            return true;
+        };
+        let reload = directory.reload_with_contents(&changed, &self.documents);
+        for path in &changed {
+            self.load_errors.remove(path);
+        }
+        self.load_errors.extend(reload.invalid);
+        true
+    }
+
+    /// Rereads every requirement.
+    ///
+    /// Files that aren't valid requirements are left out (and reported as
+    /// diagnostics), so one broken file doesn't stop the rest from loading.
+    /// If the requirements can't be loaded at all, the last good copy is
+    /// kept.
+    pub fn reload(&mut self) {
+        self.pending.clear();
+        self.load_errors.clear();
+        let mut ignored = Vec::new();
+        loop {
+            let failed: Vec<(PathBuf, String)> =
+                match Directory::new_with_contents(self.root.clone(), &ignored, &self.documents) {
+                    Ok(directory) => {
+                        self.directory = Some(directory);
+                        return;
+                    }
+                    Err(DirectoryLoadError::UnrecognisedFiles(files)) => files
+                        .into_iter()
+                        .map(|(path, error)| (path, format!("Not a valid requirement: {error}")))
+                        .collect(),
+                    Err(DirectoryLoadError::Duplicate { error, path }) => {
+                        vec![(path, error.to_string())]
+                    }
+                    Err(DirectoryLoadError::DisallowedKinds {
+                        files,
+                        allowed_kinds,
+                    }) => files
+                        .into_iter()
+                        .map(|(path, kind)| {
+                            let message = format!(
+                                "Kind {kind} is not allowed (allowed kinds: {})",
+                                allowed_kinds.join(", ")
+                            );
+                            (path, message)
+                        })
+                        .collect(),
+                    Err(error) => {
+                        tracing::error!("Failed to load requirements: {error}");
+                        return;
+                    }
+                };
+            if failed.iter().all(|(path, _)| ignored.contains(path)) {
+                return;
+            }
+            ignored.extend(failed.iter().map(|(path, _)| path.clone()));
+            self.load_errors.extend(failed);
+        }
+    }
+
+    /// The diagnostics to publish for every file that has any, or had any at
+    /// the last publication.
+    pub fn diagnostics(&mut self) -> Vec<PublishDiagnosticsParams> {
+        let mut diagnostics = self
+            .directory
+            .as_ref()
+            .map_or_else(Diagnostics::new, |directory| {
+                diagnostics::check(directory, |path| self.text(path))
+            });
+        for (path, message) in &self.load_errors {
+            diagnostics
+                .entry(path.clone())
+                .or_default()
+                .push(diagnostics::file_error(message.clone()));
+        }
+
+        // Files that no longer have diagnostics are published with none, to
+        // clear them.
+        let previous =
+            std::mem::replace(&mut self.published, diagnostics.keys().cloned().collect());
+        for path in previous {
+            diagnostics.entry(path).or_default();
+        }
+        diagnostics
+            .into_iter()
+            .filter_map(|(path, diagnostics)| {
+                Some(PublishDiagnosticsParams::new(
+                    Url::from_file_path(path).ok()?,
+                    diagnostics,
+                    None,
+                ))
+            })
+            .collect()
+    }
+
+    /// The title heading of the requirement mentioned at `position`.
+    pub fn definition(&self, path: &Path, position: Position) -> Option<Location> {
+        let (requirement, _) = self.requirement_at(path, position)?;
+        let target = self.directory.as_ref()?.path_for(requirement.hrid)?;
+        let text = self.text(target).unwrap_or_default();
+        Some(Location::new(
+            Url::from_file_path(target).ok()?,
+            text::line_range(&text, text::heading_line(&text)),
+        ))
+    }
+
+    /// A preview of the requirement mentioned at `position`.
+    pub fn hover(&self, path: &Path, position: Position) -> Option<Hover> {
+        let (requirement, range) = self.requirement_at(path, position)?;
+        let mut preview = format!(
+            "**{}** {}",
+            requirement.hrid.display(self.digits()),
+            requirement.title
+        );
+        if !requirement.body.is_empty() {
+            preview.push_str("\n\n");
+            preview.push_str(requirement.body);
+        }
+        Some(Hover {
+            contents: HoverContents::Markup(markdown(preview)),
+            range: Some(range),
+        })
+    }
+
+    /// Every requirement's HRID, to replace the word being typed at
+    /// `position`.
+    pub fn completion(&self, path: &Path, position: Position) -> Vec<CompletionItem> {
+        let Some(directory) = &self.directory else {
+            return Vec::new();
+        };
+        let (_, range) = text::prefix_at(&self.text(path).unwrap_or_default(), position);
+        let mut items: Vec<CompletionItem> = directory
+            .requirements()
+            .map(|requirement| {
+                let hrid = requirement.hrid.display(self.digits()).to_string();
+                CompletionItem {
+                    label: hrid.clone(),
+                    kind: Some(CompletionItemKind::REFERENCE),
+                    detail: Some(requirement.title.to_string()),
+                    documentation: (!requirement.body.is_empty())
+                        .then(|| Documentation::MarkupContent(markdown(requirement.body.into()))),
+                    text_edit: Some(CompletionTextEdit::Edit(TextEdit::new(range, hrid))),
+                    ..CompletionItem::default()
+                }
+            })
+            .collect();
+        items.sort_by(|a, b| a.label.cmp(&b.label));
+        items
+    }
+
+    fn digits(&self) -> usize {
+        self.directory
+            .as_ref()
+            .map_or(3, |directory| directory.config().digits())
+    }
+
+    /// The text of a file: its unsaved text if it is open, otherwise what is
+    /// on disk.
+    fn text(&self, path: &Path) -> Option<String> {
+        self.documents
+            .get(path)
+            .cloned()
+            .or_else(|| fs::read_to_string(path).ok())
+    }
+
+    /// The requirement whose HRID is at `position`, and the HRID's range.
+    fn requirement_at(
+        &self,
+        path: &Path,
+        position: Position,
+    ) -> Option<(RequirementView<'_>, Range)> {
+        let text = self.text(path)?;
+        let (word, range) = text::word_at(&text, position)?;
+        let hrid = Hrid::try_from(word).ok()?;
+        let requirement = self.directory.as_ref()?.find_by_hrid(&hrid)?;
+        Some((requirement, range))
+    }
+}
+
+const fn markdown(value: String) -> MarkupContent {
+    MarkupContent {
+        kind: MarkupKind::Markdown,
+        value,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn navigates_and_diagnoses_unsaved_text() {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path().canonicalize().unwrap();
+        let mut directory = Directory::new(root.clone()).unwrap();
+        let user = directory
+            .add_requirement("USR", "# Log in\n\nUsers shall log in.".to_string())
+            .unwrap();
+        let system = directory
+            .add_requirement("SYS", "# Sessions".to_string())
+            .unwrap();
+        directory
+            .link_requirement(system.hrid(), user.hrid())
+            .unwrap();
+        directory.flush().unwrap();
+        let user_path = directory.path_for(user.hrid()).unwrap().to_path_buf();
+        let system_path = directory.path_for(system.hrid()).unwrap().to_path_buf();
+
+        let mut server = Server::new(root);
+        assert!(server.diagnostics().is_empty());
+
+        // Mention the user requirement in the (unsaved) system requirement.
+        let text = fs::read_to_string(&system_path).unwrap() + "\nRefines USR-001.\n";
+        let line = u32::try_from(text.lines().count() - 1).unwrap();
+        server.update(system_path.clone(), text);
+        assert!(server.has_pending_changes());
+        assert!(server.refresh());
+        assert!(!server.refresh());
+
+        let location = server
+            .definition(&system_path, Position::new(line, 10))
+            .unwrap();
+        assert_eq!(location.uri, Url::from_file_path(&user_path).unwrap());
+        let hover = server.hover(&system_path, Position::new(line, 10)).unwrap();
+        let HoverContents::Markup(preview) = hover.contents else {
+            panic!("expected markdown");
+        };
+        assert_eq!(preview.value, "**USR-001** Log in\n\nUsers shall log in.");
+        let labels: Vec<_> = server
+            .completion(&system_path, Position::new(line, 11))
+            .into_iter()
+            .map(|item| item.label)
+            .collect();
+        assert_eq!(labels, ["SYS-001", "USR-001"]);
+
+        // Editing the parent makes the link suspect, and breaking the child's
+        // frontmatter reports it as invalid.
+        let edited = fs::read_to_string(&user_path).unwrap() + "\nAnd log out.\n";
+        server.update(user_path.clone(), edited);
+        server.refresh();
+        let published = server.diagnostics();
+        assert_eq!(published.len(), 1);
+        assert_eq!(published[0].uri, Url::from_file_path(&system_path).unwrap());
+        assert!(published[0].diagnostics[0]
+            .message
+            .starts_with("Suspect link"));
+
+        server.update(system_path.clone(), "---\nnot: [valid\n".to_string());
+        server.refresh();
+        let published = server.diagnostics();
+        assert_eq!(published.len(), 1);
+        assert!(published[0]
+            .diagnostics
+            .iter()
+            .any(|diagnostic| diagnostic.message.starts_with("Not a valid requirement")));
+
+        // Closing both documents goes back to the files on disk, clearing
+        // the diagnostics.
+        server.close(&user_path);
+        server.close(&system_path);
+        server.refresh();
+        let published = server.diagnostics();
+        assert_eq!(published.len(), 1);
+        assert!(published[0].diagnostics.is_empty());
+    }
+}
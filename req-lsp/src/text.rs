@@ -0,0 +1,175 @@
+//! Finding things in the text of requirement files, and converting between
+//! byte offsets and LSP positions (which count UTF-16 code units).
+
+use lsp_types::{Position, Range};
+
+/// The word at `position`, if it could be an HRID, and its range.
+pub fn word_at(text: &str, position: Position) -> Option<(&str, Range)> {
+    let line = text.lines().nth(position.line as usize)?;
+    let offset = to_offset(line, position.character);
+    let start = word_start(line, offset);
+    let end = line[offset..]
+        .find(|c| !is_word_char(c))
+        .map_or(line.len(), |index| offset + index);
+    (start < end).then(|| (&line[start..end], span(line, position.line, start, end)))
+}
+
+/// The part of the word at `position` before the cursor, and its range.
+pub fn prefix_at(text: &str, position: Position) -> (String, Range) {
+    let line = text.lines().nth(position.line as usize).unwrap_or_default();
+    let offset = to_offset(line, position.character);
+    let start = word_start(line, offset);
+    (
+        line[start..offset].to_string(),
+        span(line, position.line, start, offset),
+    )
+}
+
+/// The range of a line, without its indentation.
+pub fn line_range(text: &str, line: usize) -> Range {
+    let content = text.lines().nth(line).unwrap_or_default();
+    let start = content.len() - content.trim_start().len();
+    span(
+        content,
+        u32::try_from(line).unwrap_or(u32::MAX),
+        start,
+        content.len(),
+    )
+}
+
+/// The line of the requirement's title heading.
+pub fn heading_line(text: &str) -> usize {
+    let frontmatter = frontmatter_lines(text);
+    text.lines()
+        .enumerate()
+        .skip(frontmatter)
+        .find(|(_, line)| line.starts_with("# "))
+        .map_or(frontmatter, |(index, _)| index)
+}
+
+/// The line of the parent reference with the given UUID in the frontmatter:
+/// the line naming the parent's HRID if there is one, otherwise the line
+/// with its UUID.
+pub fn parent_line(text: &str, uuid: &str) -> Option<usize> {
+    let lines: Vec<&str> = text.lines().take(frontmatter_lines(text)).collect();
+    let start = lines.iter().position(|line| line.contains(uuid))?;
+    let hrid = lines[start..]
+        .iter()
+        .enumerate()
+        .skip(1)
+        .take_while(|(_, line)| line.starts_with("  ") && !line.trim_start().starts_with("- "))
+        .find(|(_, line)| line.trim_start().starts_with("hrid:"))
+        .map(|(index, _)| start + index);
+    Some(hrid.unwrap_or(start))
+}
+
+/// The line of a top-level key in the frontmatter, or of a key nested one
+/// level below it.
+pub fn key_line(text: &str, key: &str) -> Option<usize> {
+    let prefix = format!("{key}:");
+    text.lines()
+        .take(frontmatter_lines(text))
+        .position(|line| line.trim_start().starts_with(&prefix))
+}
+
+/// The number of lines taken by the frontmatter, including its delimiters
+/// (zero if there is none).
+fn frontmatter_lines(text: &str) -> usize {
+    let mut lines = text.lines();
+    if lines.next() != Some("---") {
+        return 0;
+    }
+    lines
+        .position(|line| line == "---")
+        .map_or(0, |index| index + 2)
+}
+
+/// The offset of the start of the word that ends at `offset`.
+fn word_start(line: &str, offset: usize) -> usize {
+    line[..offset]
+        .char_indices()
+        .rev()
+        .find(|(_, c)| !is_word_char(*c))
+        .map_or(0, |(index, c)| index + c.len_utf8())
+}
+
+const fn is_word_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || c == '-' || c == '_'
+}
+
+fn span(line: &str, number: u32, start: usize, end: usize) -> Range {
+    Range::new(
+        Position::new(number, to_character(line, start)),
+        Position::new(number, to_character(line, end)),
+    )
+}
+
+/// The byte offset in `line` of a UTF-16 column.
+fn to_offset(line: &str, character: u32) -> usize {
+    let mut units = 0;
+    for (offset, c) in line.char_indices() {
+        if units >= character as usize {
+            return offset;
+        }
+        units += c.len_utf16();
+    }
+    line.len()
+}
+
+/// The UTF-16 column of a byte offset in `line`.
+fn to_character(line: &str, offset: usize) -> u32 {
+    let units: usize = line[..offset].chars().map(char::len_utf16).sum();
+    u32::try_from(units).unwrap_or(u32::MAX)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FILE: &str = "\
+---
+_version: '1'
+uuid: 00000000-0000-0000-0000-000000000002
+parents:
+- uuid: 00000000-0000-0000-0000-000000000001
+  fingerprint: abc
+  hrid: USR-001
+---
+# SYS-001 Sessions
+
+Refines «USR-001».
+";
+
+    #[test]
+    fn finds_words_in_utf16_columns() {
+        let (word, range) = word_at(FILE, Position::new(10, 11)).unwrap();
+        assert_eq!(word, "USR-001");
+        assert_eq!(
+            range,
+            Range::new(Position::new(10, 9), Position::new(10, 16))
+        );
+        assert!(word_at(FILE, Position::new(9, 0)).is_none());
+
+        let (prefix, range) = prefix_at(FILE, Position::new(10, 12));
+        assert_eq!(prefix, "USR");
+        assert_eq!(range.start, Position::new(10, 9));
+    }
+
+    #[test]
+    fn finds_frontmatter_lines() {
+        assert_eq!(
+            parent_line(FILE, "00000000-0000-0000-0000-000000000001"),
+            Some(6)
+        );
+        assert_eq!(
+            parent_line(FILE, "00000000-0000-0000-0000-000000000009"),
+            None
+        );
+        assert_eq!(key_line(FILE, "parents"), Some(3));
+        assert_eq!(heading_line(FILE), 8);
+        assert_eq!(
+            line_range(FILE, 6),
+            Range::new(Position::new(6, 2), Position::new(6, 15))
+        );
+    }
+}
@@ -1,11 +1,14 @@
 //! MCP server for requirements management.
 //!
 //! This server provides tools for discovering and navigating requirements
-//! using the Model Context Protocol (MCP).
+//! using the Model Context Protocol (MCP), and reloads them as their files
+//! change on disk.
 
+mod resources;
 mod server;
 mod state;
 mod tools;
+mod watch;
 
 use std::path::PathBuf;
 
@@ -48,10 +51,20 @@ async fn main() -> Result<()> {
     let count = state.directory.read().await.requirements().count();
     tracing::info!("Loaded {} requirements", count);
 
-    let server = ReqMcpServer::new(state);
+    // The watcher stops when dropped, so it is kept until the server stops.
+    let _watcher = match watch::watch(&state, &canonical_root) {
+        Ok(watcher) => Some(watcher),
+        Err(error) => {
+            tracing::warn!("Not watching requirements for changes: {error}");
+            None
+        }
+    };
+
+    let server = ReqMcpServer::new(state.clone());
 
     tracing::info!("Starting MCP server over stdio");
     let service = server.serve(stdio()).await?;
+    tokio::spawn(resources::forward(service.peer().clone(), state));
     let quit_reason = service.waiting().await?;
     tracing::info!("Server stopped: {:?}", quit_reason);
 
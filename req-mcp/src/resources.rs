@@ -0,0 +1,255 @@
+//! Requirements as MCP resources, at `requirement://<HRID>` URIs.
+//!
+//! Clients that subscribe to a requirement are notified when it is reloaded
+//! from disk, and are told when requirements are added or removed. Clients
+//! on protocol versions before `2026-07-28` subscribe with
+//! `resources/subscribe` and are notified directly; later clients subscribe
+//! with `subscriptions/listen`. The former are also sent a warning when
+//! unsaved changes are discarded because their files changed on disk.
+
+use std::{
+    collections::HashSet,
+    sync::atomic::{AtomicBool, Ordering},
+};
+
+use requiem_core::{storage::directory::Reload, Hrid};
+use rmcp::{
+    model::{
+        ListResourcesResult, ProtocolVersion, ReadResourceResult, Resource, ResourceContents,
+        ResourceUpdatedNotificationParam,
+    },
+    service::SubscriptionContext,
+    ErrorData as McpError, Peer, RoleServer,
+};
+use serde_json::json;
+use tokio::sync::broadcast::error::RecvError;
+
+use crate::{server::ReqMcpServer, state::ServerState};
+
+/// The scheme of requirement resource URIs.
+const SCHEME: &str = "requirement://";
+
+const MIME_TYPE: &str = "text/markdown";
+
+/// The requirements that changed in one reload, by resource URI.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Changes {
+    /// Requirements that were added, changed, or removed.
+    pub updated: Vec<String>,
+    /// Whether requirements were added or removed.
+    pub list_changed: bool,
+    /// Requirements whose unsaved changes were discarded because their files
+    /// changed on disk.
+    pub conflicts: Vec<String>,
+}
+
+impl Changes {
+    pub fn new(reload: &Reload, digits: usize) -> Self {
+        Self {
+            updated: reload
+                .added
+                .iter()
+                .chain(&reload.updated)
+                .chain(&reload.removed)
+                .map(|hrid| uri(hrid, digits))
+                .collect(),
+            list_changed: !reload.added.is_empty() || !reload.removed.is_empty(),
+            conflicts: reload
+                .conflicts
+                .iter()
+                .map(|hrid| uri(hrid, digits))
+                .collect(),
+        }
+    }
+
+    pub const fn is_empty(&self) -> bool {
+        self.updated.is_empty() && !self.list_changed
+    }
+}
+
+fn uri(hrid: &Hrid, digits: usize) -> String {
+    format!("{SCHEME}{}", hrid.display(digits))
+}
+
+fn parse_uri(uri: &str) -> Result<Hrid, McpError> {
+    let hrid = uri.strip_prefix(SCHEME).ok_or_else(|| {
+        McpError::invalid_params(
+            "not a requirement URI",
+            Some(json!({ "uri": uri, "expected": format!("{SCHEME}<HRID>") })),
+        )
+    })?;
+    ReqMcpServer::parse_hrid(hrid)
+}
+
+pub async fn list(server: &ReqMcpServer) -> ListResourcesResult {
+    let directory = server.state.directory.read().await;
+    let digits = directory.config().digits();
+    let mut resources: Vec<Resource> = directory
+        .requirements()
+        .map(|requirement| {
+            let hrid = ReqMcpServer::format_hrid(requirement.hrid, digits);
+            Resource::new(uri(requirement.hrid, digits), hrid)
+                .with_title(requirement.title)
+                .with_mime_type(MIME_TYPE)
+        })
+        .collect();
+    drop(directory);
+
+    resources.sort_by(|a, b| a.name.cmp(&b.name));
+    ListResourcesResult::with_all_items(resources)
+}
+
+pub async fn read(server: &ReqMcpServer, uri: &str) -> Result<ReadResourceResult, McpError> {
+    let hrid = parse_uri(uri)?;
+    let directory = server.state.directory.read().await;
+    let Some(requirement) = directory.find_by_hrid(&hrid) else {
+        return Err(McpError::resource_not_found(
+            "requirement not found",
+            Some(json!({ "uri": uri })),
+        ));
+    };
+    let mut text = format!(
+        "# {} {}\n",
+        ReqMcpServer::format_hrid(&hrid, directory.config().digits()),
+        requirement.title
+    );
+    if !requirement.body.is_empty() {
+        text.push('\n');
+        text.push_str(requirement.body);
+        text.push('\n');
+    }
+    drop(directory);
+
+    Ok(ReadResourceResult::new(vec![ResourceContents::text(
+        text, uri,
+    )
+    .with_mime_type(MIME_TYPE)]))
+}
+
+pub fn subscribe(server: &ReqMcpServer, uri: String) -> Result<(), McpError> {
+    parse_uri(&uri)?;
+    subscriptions(&server.state).insert(uri);
+    Ok(())
+}
+
+pub fn unsubscribe(server: &ReqMcpServer, uri: &str) {
+    subscriptions(&server.state).remove(uri);
+}
+
+fn subscriptions(state: &ServerState) -> std::sync::MutexGuard<'_, HashSet<String>> {
+    state
+        .subscriptions
+        .lock()
+        .unwrap_or_else(std::sync::PoisonError::into_inner)
+}
+
+/// Sends the changes accepted by a `subscriptions/listen` request until it is
+/// cancelled.
+pub async fn listen(server: &ReqMcpServer, context: SubscriptionContext) {
+    let mut receiver = server.state.changes.subscribe();
+    let accepted = context.accepted().clone();
+    let subscribed: HashSet<&String> = accepted.resource_subscriptions.iter().flatten().collect();
+    loop {
+        let changed = tokio::select! {
+            () = context.cancelled() => return,
+            changed = receiver.recv() => match changed {
+                Ok(changed) => changed,
+                Err(RecvError::Lagged(_)) => continue,
+                Err(RecvError::Closed) => return,
+            },
+        };
+        for uri in changed
+            .updated
+            .iter()
+            .filter(|uri| subscribed.contains(uri))
+        {
+            if context.sink().notify_resource_updated(uri).await.is_err() {
+                return;
+            }
+        }
+        if changed.list_changed
+            && accepted.resources_list_changed == Some(true)
+            && context.sink().notify_resource_list_changed().await.is_err()
+        {
+            return;
+        }
+    }
+}
+
+/// Sends changes to subscribed resources to a client on a protocol version
+/// before `2026-07-28`, until the connection closes.
+pub async fn forward(peer: Peer<RoleServer>, state: ServerState) {
+    let legacy = peer
+        .peer_info()
+        .is_some_and(|info| info.protocol_version < ProtocolVersion::V_2026_07_28);
+    if !legacy {
+        return;
+    }
+
+    let mut receiver = state.changes.subscribe();
+    loop {
+        let changed = match receiver.recv().await {
+            Ok(changed) => changed,
+            Err(RecvError::Lagged(_)) => continue,
+            Err(RecvError::Closed) => return,
+        };
+        let subscribed: Vec<String> = {
+            let subscriptions = subscriptions(&state);
+            changed
+                .updated
+                .into_iter()
+                .filter(|uri| subscriptions.contains(uri))
+                .collect()
+        };
+        for uri in subscribed {
+            let notification = ResourceUpdatedNotificationParam::new(uri);
+            if let Err(error) = peer.notify_resource_updated(notification).await {
+                tracing::warn!("Failed to notify client of a changed requirement: {error}");
+            }
+        }
+        if changed.list_changed
+            && let Err(error) = peer.notify_resource_list_changed().await
+        {
+            tracing::warn!("Failed to notify client of changed requirements: {error}");
+        }
+        if !changed.conflicts.is_empty() && state.log_warnings.load(Ordering::Relaxed) {
+            warn_of_conflicts(&peer, changed.conflicts).await;
+        }
+    }
+}
+
+/// Sets whether warnings are sent to a client on a protocol version before
+/// `2026-07-28`, from the level it asked for with `logging/setLevel`.
+#[expect(
+    deprecated,
+    reason = "logging is only offered to clients before protocol version 2026-07-28"
+)]
+pub fn set_level(log_warnings: &AtomicBool, level: rmcp::model::LoggingLevel) {
+    use rmcp::model::LoggingLevel;
+
+    let warnings = matches!(
+        level,
+        LoggingLevel::Debug | LoggingLevel::Info | LoggingLevel::Notice | LoggingLevel::Warning
+    );
+    log_warnings.store(warnings, Ordering::Relaxed);
+}
+
+/// Warns a client on a protocol version before `2026-07-28` that its unsaved
+/// changes to requirements were discarded.
+#[expect(
+    deprecated,
+    reason = "logging is only offered to clients before protocol version 2026-07-28"
+)]
+async fn warn_of_conflicts(peer: &Peer<RoleServer>, conflicts: Vec<String>) {
+    use rmcp::model::{LoggingLevel, LoggingMessageNotificationParam};
+
+    let data = json!({
+        "message": "Discarded unsaved changes to requirements whose files changed on disk",
+        "conflicts": conflicts,
+    });
+    let notification =
+        LoggingMessageNotificationParam::new(LoggingLevel::Warning, data).with_logger("req-mcp");
+    if let Err(error) = peer.notify_logging_message(notification).await {
+        tracing::warn!("Failed to warn client of discarded changes: {error}");
+    }
+}
@@ -1,9 +1,15 @@
 //! Shared server state for the MCP server.
 
-use std::{path::Path, sync::Arc};
+use std::{
+    collections::HashSet,
+    path::Path,
+    sync::{atomic::AtomicBool, Arc, Mutex},
+};
 
 use requiem_core::Directory;
-use tokio::sync::RwLock;
+use tokio::sync::{broadcast, RwLock};
+
+use crate::resources::Changes;
 
 /// Shared state for the MCP server.
 ///
@@ -11,8 +17,15 @@ use tokio::sync::RwLock;
 /// `Arc<RwLock>` for thread-safe access across async tasks.
 #[derive(Clone)]
 pub struct ServerState {
-    /// The requirements directory loaded on startup.
+    /// The requirements directory, loaded on startup and reloaded as its
+    /// files change on disk.
     pub directory: Arc<RwLock<Directory>>,
+    /// Requirements reloaded from disk, as they change.
+    pub changes: broadcast::Sender<Changes>,
+    /// Resource URIs the client subscribed to with `resources/subscribe`.
+    pub subscriptions: Arc<Mutex<HashSet<String>>>,
+    /// Whether the client's `logging/setLevel` lets warnings through.
+    pub log_warnings: Arc<AtomicBool>,
 }
 
 impl ServerState {
@@ -23,8 +36,12 @@ impl ServerState {
     /// Returns an error if the directory cannot be loaded.
     pub fn new(root: impl AsRef<Path>) -> anyhow::Result<Self> {
         let directory = Directory::new(root.as_ref().to_path_buf())?;
+        let (changes, _) = broadcast::channel(64);
         Ok(Self {
             directory: Arc::new(RwLock::new(directory)),
+            changes,
+            subscriptions: Arc::default(),
+            log_warnings: Arc::new(AtomicBool::new(true)),
         })
     }
 }
@@ -1,5 +1,6 @@
 //! MCP tool definitions and the tool router, grouped into submodules by
-//! concern (discovery, editing, lineage, search).
+//! concern (discovery, editing, lineage, search), and the server handler that
+//! also serves requirements as resources.
 
 mod discovery;
 mod editing;
@@ -8,11 +9,16 @@ mod search;
 
 use rmcp::{
     handler::server::wrapper::Parameters,
-    model::{CallToolResult, ServerCapabilities, ServerInfo},
-    tool, tool_handler, tool_router, ErrorData as McpError, ServerHandler,
+    model::{
+        CallToolResult, ListResourcesResult, PaginatedRequestParams, ReadResourceRequestParams,
+        ReadResourceResponse, ServerCapabilities, ServerInfo, SubscribeRequestParams,
+        SubscriptionFilter, UnsubscribeRequestParams,
+    },
+    service::{RequestContext, SubscriptionContext},
+    tool, tool_handler, tool_router, ErrorData as McpError, RoleServer, ServerHandler,
 };
 
-use crate::server::ReqMcpServer;
+use crate::{resources, server::ReqMcpServer};
 
 #[tool_router]
 impl ReqMcpServer {
@@ -78,8 +84,9 @@ impl ReqMcpServer {
     }
 
     #[tool(
-        description = "List suspect parent-child links where stored fingerprints drifted; start \
-                       here before marking reviewed",
+        description = "List suspect parent-child links where stored fingerprints drifted; set \
+                       includeDiffs for a diff of what changed in each parent when git history \
+                       has it; start here before marking reviewed",
         annotations(
             title = "List Pending Reviews",
             read_only_hint = true,
@@ -195,8 +202,9 @@ impl ReqMcpServer {
     }
 
     #[tool(
-        description = "Link an existing requirement to a parent (child satisfies parent); \
-                       relinking an existing pair refreshes the stored fingerprint",
+        description = "Link an existing requirement to a parent (child satisfies parent), \
+                       optionally with a linkType such as \"verifies\"; relinking an existing \
+                       pair refreshes the stored fingerprint",
         annotations(
             title = "Link Requirement",
             read_only_hint = false,
@@ -274,12 +282,22 @@ impl ReqMcpServer {
 #[allow(clippy::unused_async_trait_impl)]
 #[tool_handler]
 impl ServerHandler for ReqMcpServer {
+    #[expect(
+        deprecated,
+        reason = "logging is only offered to clients before protocol version 2026-07-28"
+    )]
     fn get_info(&self) -> ServerInfo {
         // `ServerInfo` is `#[non_exhaustive]`, so it can only be built from its
         // `Default` and then customised in place.
         #[allow(clippy::field_reassign_with_default)]
         let mut info = ServerInfo::default();
-        info.capabilities = ServerCapabilities::builder().enable_tools().build();
+        info.capabilities = ServerCapabilities::builder()
+            .enable_resources()
+            .enable_resources_subscribe()
+            .enable_resources_list_changed()
+            .enable_tools()
+            .enable_logging()
+            .build();
         info.instructions = Some(
             "Requirements graph MCP server (requires REQ_ROOT pointing at your requirements \
              repo). Start with list_requirement_kinds, then list_requirements(kind) to get HRIDs. \
@@ -290,9 +308,71 @@ impl ServerHandler for ReqMcpServer {
              with link_requirement and unlink_requirement, and remove requirements with \
              delete_requirement (mode: refuse/orphan/cascade; dryRun to preview). For link drift, \
              call review to list suspect child→parent links (fingerprint mismatches), then \
-             review_requirement to accept if the child still satisfies the parent."
+             review_requirement to accept if the child still satisfies the parent. Requirements \
+             are also resources at requirement://<HRID>; they are reloaded when their files \
+             change on disk, and subscribers are notified."
                 .to_owned(),
         );
         info
     }
+
+    #[expect(
+        deprecated,
+        reason = "logging is only offered to clients before protocol version 2026-07-28"
+    )]
+    async fn set_level(
+        &self,
+        request: rmcp::model::SetLevelRequestParams,
+        _context: RequestContext<RoleServer>,
+    ) -> Result<(), McpError> {
+        resources::set_level(&self.state.log_warnings, request.level);
+        Ok(())
+    }
+
+    async fn list_resources(
+        &self,
+        _request: Option<PaginatedRequestParams>,
+        _context: RequestContext<RoleServer>,
+    ) -> Result<ListResourcesResult, McpError> {
+        Ok(resources::list(self).await)
+    }
+
+    async fn read_resource(
+        &self,
+        request: ReadResourceRequestParams,
+        _context: RequestContext<RoleServer>,
+    ) -> Result<ReadResourceResponse, McpError> {
+        resources::read(self, &request.uri).await.map(Into::into)
+    }
+
+    fn accepted_subscription_filter(
+        &self,
+        requested: &SubscriptionFilter,
+    ) -> Option<SubscriptionFilter> {
+        // Only resource notifications are advertised, so the SDK drops the
+        // rest.
+        Some(requested.clone())
+    }
+
+    async fn listen(&self, context: SubscriptionContext) -> Result<(), McpError> {
+        resources::listen(self, context).await;
+        Ok(())
+    }
+
+    async fn subscribe(
+        &self,
+        request: SubscribeRequestParams,
+        _context: RequestContext<RoleServer>,
+    ) -> Result<(), McpError> {
+        resources::subscribe(self, request.uri)
+    }
+
+    async fn unsubscribe(
+        &self,
+        request: UnsubscribeRequestParams,
+        _context: RequestContext<RoleServer>,
+    ) -> Result<(), McpError> {
+        resources::unsubscribe(self, &request.uri);
+        Ok(())
+    }
 }
@@ -1,8 +1,9 @@
 //! Discovery tools: list requirement kinds, list requirements by kind, and
 //! fetch the details of a single requirement.
 
-use std::collections::BTreeSet;
+use std::collections::{BTreeMap, BTreeSet};
 
+use requiem_core::domain::AttributeValue;
 use rmcp::{handler::server::wrapper::Parameters, model::CallToolResult, ErrorData as McpError};
 use serde::{Deserialize, Serialize};
 use serde_json::json;
@@ -75,12 +76,34 @@ pub struct RequirementDetails {
     pub body: String,
     /// Tags on the requirement.
     pub tags: Vec<String>,
+    /// Lifecycle status (e.g. "draft", "approved"), if one has been set.
+    #[serde(default)]
+    pub status: Option<String>,
+    /// Custom attribute values keyed by name.
+    #[serde(default)]
+    pub attributes: BTreeMap<String, serde_json::Value>,
     /// Direct parent HRIDs.
     pub parents: Vec<String>,
     /// Direct child HRIDs.
     pub children: Vec<String>,
 }
 
+/// Convert custom attribute values to JSON, keeping integers as numbers.
+pub(super) fn attribute_values(
+    attributes: &BTreeMap<String, AttributeValue>,
+) -> BTreeMap<String, serde_json::Value> {
+    attributes
+        .iter()
+        .map(|(name, value)| {
+            let value = match value {
+                AttributeValue::Integer(number) => json!(number),
+                AttributeValue::Text(text) => json!(text),
+            };
+            (name.clone(), value)
+        })
+        .collect()
+}
+
 pub(super) async fn list_requirement_kinds(
     server: &ReqMcpServer,
 ) -> Result<CallToolResult, McpError> {
@@ -191,6 +214,8 @@ pub(super) async fn get_requirement(
     let view_title = view.title.to_string();
     let view_body = view.body.to_string();
     let view_tags: Vec<String> = view.tags.iter().cloned().collect();
+    let view_status = view.status.map(str::to_string);
+    let view_attributes = attribute_values(view.attributes);
     let parents: Vec<String> = view
         .parents
         .iter()
@@ -210,6 +235,8 @@ pub(super) async fn get_requirement(
         title: view_title,
         body: view_body,
         tags: view_tags,
+        status: view_status,
+        attributes: view_attributes,
         parents,
         children,
     };
@@ -1,14 +1,17 @@
 //! Editing tools: create, update, link, delete, and review requirements.
 
-use std::{collections::BTreeSet, fs};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fs,
+};
 
-use requiem_core::{Directory, Hrid, LinkRequirementError};
+use requiem_core::{storage::directory::FlushError, Directory, Hrid, LinkRequirementError};
 use rmcp::{handler::server::wrapper::Parameters, model::CallToolResult, ErrorData as McpError};
 use serde::{Deserialize, Serialize};
 use serde_json::json;
 
-use super::discovery::RequirementDetails;
-use crate::server::ReqMcpServer;
+use super::discovery::{attribute_values, RequirementDetails};
+use crate::{server::ReqMcpServer, watch};
 
 #[derive(Debug, Clone, Deserialize, Serialize, schemars::JsonSchema)]
 #[serde(rename_all = "camelCase")]
@@ -65,6 +68,10 @@ pub struct UpdateRequirementParams {
     /// Replacement set of tags (omit to leave unchanged).
     #[serde(default)]
     pub tags: Option<Vec<String>>,
+    /// Custom attributes to set, keyed by name; a null value removes the
+    /// attribute. Attributes not listed are left unchanged.
+    #[serde(default)]
+    pub attributes: Option<BTreeMap<String, serde_json::Value>>,
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize, schemars::JsonSchema)]
@@ -83,6 +90,10 @@ pub struct LinkRequirementParams {
     pub child: String,
     /// Parent HRID to link the child to.
     pub parent: String,
+    /// Optional relationship type, e.g. "satisfies" or "verifies". Omit to
+    /// keep the existing type when relinking.
+    #[serde(default)]
+    pub link_type: Option<String>,
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize, schemars::JsonSchema)]
@@ -95,6 +106,9 @@ pub struct LinkRequirementResponse {
     /// Whether the link already existed. Relinking an existing pair refreshes
     /// the stored parent fingerprint (equivalent to accepting a suspect link).
     pub already_linked: bool,
+    /// Relationship type recorded on the link, if any.
+    #[serde(default)]
+    pub link_type: Option<String>,
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize, schemars::JsonSchema)]
@@ -208,19 +222,88 @@ fn requirement_details(
         title: view.title.to_string(),
         body: view.body.to_string(),
         tags: view.tags.iter().cloned().collect(),
+        status: view.status.map(str::to_string),
+        attributes: attribute_values(view.attributes),
         parents,
         children,
     })
 }
 
+/// Read a raw attribute value from JSON; `null` means "remove".
+fn attribute_input(name: &str, value: serde_json::Value) -> Result<Option<String>, McpError> {
+    match value {
+        serde_json::Value::Null => Ok(None),
+        serde_json::Value::String(text) => Ok(Some(text)),
+        serde_json::Value::Number(number) => Ok(Some(number.to_string())),
+        other => Err(McpError::invalid_params(
+            "attribute values must be strings, numbers, or null",
+            Some(json!({ "attribute": name, "value": other })),
+        )),
+    }
+}
+
+/// Check a raw attribute value against the schema for the requirement's kind.
+fn check_attribute(
+    config: &requiem_core::Config,
+    hrid: &Hrid,
+    name: &str,
+    raw: &str,
+) -> Result<(), McpError> {
+    let Some(attribute) = config.attribute_for_kind(hrid.kind(), name) else {
+        let declared: Vec<_> = config
+            .attributes_for_kind(hrid.kind())
+            .map(|(name, _)| name)
+            .collect();
+        return Err(McpError::invalid_params(
+            "attribute is not declared for this kind",
+            Some(json!({
+                "attribute": name,
+                "kind": hrid.kind(),
+                "declared_attributes": declared,
+            })),
+        ));
+    };
+    attribute.parse(raw).map(|_| ()).map_err(|error| {
+        McpError::invalid_params(
+            "invalid attribute value",
+            Some(json!({
+                "attribute": name,
+                "type": attribute.value_type.to_string(),
+                "reason": error.to_string(),
+            })),
+        )
+    })
+}
+
 /// Persist pending changes, mapping failures to an MCP error.
-fn flush(directory: &mut Directory) -> Result<(), McpError> {
-    directory.flush().map(|_| ()).map_err(|error| {
-        McpError::internal_error(
+///
+/// If a requirement's file changed on disk since it was read, nothing is
+/// written: the pending changes are discarded in favour of what is on disk,
+/// and the caller is told which requirements conflicted.
+fn flush(server: &ReqMcpServer, directory: &mut Directory) -> Result<(), McpError> {
+    match directory.flush() {
+        Ok(_) => Ok(()),
+        Err(FlushError::Conflict(conflicts)) => {
+            let digits = directory.config().digits();
+            let reload = directory.discard_changes();
+            watch::publish(&server.state, &reload, digits);
+            let conflicts: Vec<String> = conflicts
+                .iter()
+                .map(|(hrid, _)| ReqMcpServer::format_hrid(hrid, digits))
+                .collect();
+            Err(McpError::invalid_request(
+                "requirements changed on disk; the change was not saved",
+                Some(json!({
+                    "conflicts": conflicts,
+                    "reason": "the requirements were reloaded from disk; retry the change",
+                })),
+            ))
+        }
+        Err(error) => Err(McpError::internal_error(
             "failed to persist changes",
             Some(json!({ "reason": error.to_string() })),
-        )
-    })
+        )),
+    }
 }
 
 pub(super) async fn create_requirement_kind(
@@ -418,10 +501,23 @@ pub(super) async fn create_requirement(
                     "cannot create link: would form a cycle",
                     Some(json!({ "reason": message })),
                 ),
+                LinkRequirementError::BreaksLinkRule(rule) => McpError::invalid_params(
+                    "link is not allowed by the kind's link rules",
+                    Some(json!({ "reason": rule.to_string() })),
+                ),
+                LinkRequirementError::DisallowedLinkType {
+                    link_type,
+                    allowed_link_types,
+                } => McpError::invalid_params(
+                    "link type is not allowed by configuration",
+                    Some(
+                        json!({ "link_type": link_type, "allowed_link_types": allowed_link_types }),
+                    ),
+                ),
             })?;
     }
 
-    flush(&mut directory)?;
+    flush(server, &mut directory)?;
 
     let hrid = requirement.hrid().clone();
     let response = requirement_details(&directory, &hrid, digits)?;
@@ -441,10 +537,14 @@ pub(super) async fn update_requirement(
 ) -> Result<CallToolResult, McpError> {
     let params = params.0;
 
-    if params.title.is_none() && params.body.is_none() && params.tags.is_none() {
+    if params.title.is_none()
+        && params.body.is_none()
+        && params.tags.is_none()
+        && params.attributes.is_none()
+    {
         return Err(McpError::invalid_params(
-            "at least one of `title`, `body`, or `tags` is required",
-            Some(json!({ "fields": ["title", "body", "tags"] })),
+            "at least one of `title`, `body`, `tags`, or `attributes` is required",
+            Some(json!({ "fields": ["title", "body", "tags", "attributes"] })),
         ));
     }
 
@@ -477,7 +577,22 @@ pub(super) async fn update_requirement(
         ));
     }
 
-    let changed = directory
+    // Check every attribute against the schema before changing anything, so
+    // a rejected value cannot leave a partial update behind.
+    let attributes = params
+        .attributes
+        .unwrap_or_default()
+        .into_iter()
+        .map(|(name, value)| {
+            let raw = attribute_input(&name, value)?;
+            if let Some(raw) = &raw {
+                check_attribute(directory.config(), &hrid, &name, raw)?;
+            }
+            Ok((name, raw))
+        })
+        .collect::<Result<Vec<_>, McpError>>()?;
+
+    let mut changed = directory
         .update_requirement(&hrid, params.title, params.body, tags)
         .map_err(|error| {
             McpError::internal_error(
@@ -486,8 +601,23 @@ pub(super) async fn update_requirement(
             )
         })?;
 
+    for (name, raw) in attributes {
+        let previous = directory
+            .set_attribute(&hrid, &name, raw.as_deref())
+            .map_err(|error| {
+                McpError::internal_error(
+                    "failed to update attribute",
+                    Some(json!({ "attribute": name, "reason": error.to_string() })),
+                )
+            })?;
+        let current = directory
+            .find_by_hrid(&hrid)
+            .and_then(|view| view.attributes.get(&name).cloned());
+        changed |= previous != current;
+    }
+
     if changed {
-        flush(&mut directory)?;
+        flush(server, &mut directory)?;
     }
 
     let response = UpdateRequirementResponse {
@@ -525,8 +655,8 @@ pub(super) async fn link_requirement(
 
     let already_linked = directory.children_of(&parent).contains(&child);
 
-    directory
-        .link_requirement(&child, &parent)
+    let view = directory
+        .link_requirement_with_type(&child, &parent, params.link_type.as_deref())
         .map_err(|error| match error {
             LinkRequirementError::ChildNotFound(_) => McpError::resource_not_found(
                 "child requirement not found",
@@ -540,14 +670,31 @@ pub(super) async fn link_requirement(
                 "cannot create link: would form a cycle",
                 Some(json!({ "reason": message })),
             ),
+            LinkRequirementError::BreaksLinkRule(rule) => McpError::invalid_params(
+                "link is not allowed by the kind's link rules",
+                Some(json!({ "reason": rule.to_string() })),
+            ),
+            LinkRequirementError::DisallowedLinkType {
+                link_type,
+                allowed_link_types,
+            } => McpError::invalid_params(
+                "link type is not allowed by configuration",
+                Some(json!({ "link_type": link_type, "allowed_link_types": allowed_link_types })),
+            ),
         })?;
+    let link_type = view
+        .parents
+        .iter()
+        .find(|(_, info)| info.hrid == parent)
+        .and_then(|(_, info)| info.link_type.clone());
 
-    flush(&mut directory)?;
+    flush(server, &mut directory)?;
 
     let response = LinkRequirementResponse {
         child: ReqMcpServer::format_hrid(&child, digits),
         parent: ReqMcpServer::format_hrid(&parent, digits),
         already_linked,
+        link_type,
     };
 
     drop(directory);
@@ -604,7 +751,7 @@ pub(super) async fn unlink_requirement(
             )
         })?;
 
-    flush(&mut directory)?;
+    flush(server, &mut directory)?;
 
     let response = UnlinkRequirementResponse {
         child: ReqMcpServer::format_hrid(&child, digits),
@@ -701,7 +848,7 @@ pub(super) async fn delete_requirement(
             )
         })?;
 
-        flush(&mut directory)?;
+        flush(server, &mut directory)?;
     }
 
     let response = DeleteRequirementResponse {
@@ -767,7 +914,7 @@ pub(super) async fn review_requirement(
             ),
         })?;
 
-    flush(&mut directory)?;
+    flush(server, &mut directory)?;
 
     let status = match result {
         requiem_core::storage::directory::AcceptResult::Updated => "updated",
@@ -842,6 +989,7 @@ mod tests {
                 title: Some("New title".to_string()),
                 body: Some("New body".to_string()),
                 tags: Some(vec!["safety".to_string()]),
+                attributes: None,
             }),
         )
         .await
@@ -865,6 +1013,7 @@ mod tests {
                 title: Some("New title".to_string()),
                 body: Some("New body".to_string()),
                 tags: Some(vec!["safety".to_string()]),
+                attributes: None,
             }),
         )
         .await
@@ -872,6 +1021,42 @@ mod tests {
         assert_eq!(structured(&result)["changed"], Value::Bool(false));
     }
 
+    #[tokio::test]
+    async fn update_requirement_keeps_edits_made_on_disk() {
+        let (tmp, server) = server_with_root();
+        let hrid = create(&server, "REQ", "Title", vec![]).await;
+        let path = tmp.path().join(format!("{hrid}.md"));
+        let text = std::fs::read_to_string(&path)
+            .unwrap()
+            .replace("Body", "Edited on disk");
+        std::fs::write(&path, &text).unwrap();
+        let mut published = server.state.changes.subscribe();
+
+        let error = update_requirement(
+            &server,
+            Parameters(UpdateRequirementParams {
+                hrid: hrid.clone(),
+                title: None,
+                body: Some("Edited by the tool".to_string()),
+                tags: None,
+                attributes: None,
+            }),
+        )
+        .await
+        .expect_err("the update should conflict with the edit on disk");
+
+        assert_eq!(error.data.unwrap()["conflicts"][0], hrid.as_str());
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), text);
+        let directory = server.state.directory.read().await;
+        let view = directory
+            .find_by_hrid(&Hrid::try_from(hrid.as_str()).unwrap())
+            .unwrap();
+        assert_eq!(view.body, "Edited on disk");
+        drop(directory);
+        let changes = published.recv().await.unwrap();
+        assert_eq!(changes.conflicts, [format!("requirement://{hrid}")]);
+    }
+
     #[tokio::test]
     async fn update_requirement_normalizes_content() {
         let (_tmp, server) = server_with_root();
@@ -885,6 +1070,7 @@ mod tests {
                 title: Some("Line one\nLine two".to_string()),
                 body: None,
                 tags: None,
+                attributes: None,
             }),
         )
         .await;
@@ -898,6 +1084,7 @@ mod tests {
                 title: Some("  Padded title  ".to_string()),
                 body: Some("\n\nPadded body\n\n".to_string()),
                 tags: Some(vec!["  ".to_string(), "kept".to_string()]),
+                attributes: None,
             }),
         )
         .await
@@ -920,12 +1107,59 @@ mod tests {
                 title: None,
                 body: None,
                 tags: None,
+                attributes: None,
             }),
         )
         .await;
         assert!(result.is_err());
     }
 
+    #[tokio::test]
+    async fn update_requirement_sets_and_removes_attributes() {
+        let tmp = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(tmp.path().join(".req")).unwrap();
+        std::fs::write(
+            tmp.path().join(".req/config.toml"),
+            "_version = \"1\"\n\n[attributes.risk]\ntype = \"integer\"\n\n[attributes.owner]\ntype \
+             = \"string\"\n",
+        )
+        .unwrap();
+        let server = ReqMcpServer::new(ServerState::new(tmp.path()).unwrap());
+        let hrid = create(&server, "REQ", "Title", vec![]).await;
+
+        let update = |attributes: Value| UpdateRequirementParams {
+            hrid: hrid.clone(),
+            title: Some("Changed".to_string()),
+            body: None,
+            tags: None,
+            attributes: Some(serde_json::from_value(attributes).unwrap()),
+        };
+
+        // A rejected value leaves the whole update unapplied.
+        let error = update_requirement(&server, Parameters(update(json!({ "risk": "high" }))))
+            .await
+            .unwrap_err();
+        assert_eq!(error.data.unwrap()["attribute"], "risk");
+
+        let result = update_requirement(
+            &server,
+            Parameters(update(json!({ "risk": 3, "owner": "alice" }))),
+        )
+        .await
+        .expect("update should succeed");
+        let data = structured(&result);
+        assert_eq!(data["requirement"]["title"], "Changed");
+        assert_eq!(data["requirement"]["attributes"]["risk"], 3);
+        assert_eq!(data["requirement"]["attributes"]["owner"], "alice");
+
+        let result = update_requirement(&server, Parameters(update(json!({ "owner": null }))))
+            .await
+            .expect("removal should succeed");
+        let data = structured(&result);
+        assert_eq!(data["changed"], Value::Bool(true));
+        assert_eq!(data["requirement"]["attributes"], json!({ "risk": 3 }));
+    }
+
     #[tokio::test]
     async fn link_and_unlink_round_trip() {
         let (_tmp, server) = server_with_root();
@@ -937,6 +1171,7 @@ mod tests {
             Parameters(LinkRequirementParams {
                 child: child.clone(),
                 parent: parent.clone(),
+                link_type: None,
             }),
         )
         .await
@@ -949,6 +1184,7 @@ mod tests {
             Parameters(LinkRequirementParams {
                 child: child.clone(),
                 parent: parent.clone(),
+                link_type: None,
             }),
         )
         .await
@@ -961,6 +1197,7 @@ mod tests {
             Parameters(LinkRequirementParams {
                 child: parent.clone(),
                 parent: child.clone(),
+                link_type: None,
             }),
         )
         .await;
@@ -985,6 +1222,74 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[tokio::test]
+    async fn link_records_normalized_link_type() {
+        let (_tmp, server) = server_with_root();
+        let parent = create(&server, "SYS", "Parent", vec![]).await;
+        let child = create(&server, "TST", "Child", vec![]).await;
+
+        let result = link_requirement(
+            &server,
+            Parameters(LinkRequirementParams {
+                child: child.clone(),
+                parent: parent.clone(),
+                link_type: Some(" Verifies ".to_string()),
+            }),
+        )
+        .await
+        .expect("typed link should succeed");
+        assert_eq!(
+            structured(&result)["linkType"],
+            Value::String("verifies".to_string())
+        );
+
+        // Relinking without a type keeps the recorded type.
+        let result = link_requirement(
+            &server,
+            Parameters(LinkRequirementParams {
+                child,
+                parent,
+                link_type: None,
+            }),
+        )
+        .await
+        .expect("relink should succeed");
+        assert_eq!(
+            structured(&result)["linkType"],
+            Value::String("verifies".to_string())
+        );
+    }
+
+    #[tokio::test]
+    async fn link_enforces_kind_link_rules() {
+        let tmp = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(tmp.path().join(".req")).unwrap();
+        std::fs::write(
+            tmp.path().join(".req/config.toml"),
+            "_version = \"1\"\n\
+             allowed_kinds = [\"USR\", \"SYS\", { kind = \"TST\", parent_kinds = [\"SYS\"] }]\n",
+        )
+        .unwrap();
+        let server = ReqMcpServer::new(ServerState::new(tmp.path()).unwrap());
+        let user = create(&server, "USR", "User", vec![]).await;
+        let system = create(&server, "SYS", "System", vec![]).await;
+        let test = create(&server, "TST", "Test", vec![]).await;
+
+        let link = |parent: &str| LinkRequirementParams {
+            child: test.clone(),
+            parent: parent.to_string(),
+            link_type: None,
+        };
+
+        let error = link_requirement(&server, Parameters(link(&user)))
+            .await
+            .expect_err("TST may not link to USR");
+        assert_eq!(error.code, rmcp::model::ErrorCode::INVALID_PARAMS);
+        link_requirement(&server, Parameters(link(&system)))
+            .await
+            .expect("TST may link to SYS");
+    }
+
     #[tokio::test]
     async fn delete_refuses_requirement_with_children_by_default() {
         let (_tmp, server) = server_with_root();
@@ -1,7 +1,9 @@
 //! Search and suspect-link review tools.
 
+use requiem_core::{storage::directory::ParentHistory, SuspectLink};
 use rmcp::{handler::server::wrapper::Parameters, model::CallToolResult, ErrorData as McpError};
 use serde::{Deserialize, Serialize};
+use serde_json::json;
 
 use crate::server::ReqMcpServer;
 
@@ -11,6 +13,13 @@ pub struct ReviewParams {
     /// Optional kind filter for review queries.
     #[serde(default)]
     pub kind: Option<String>,
+    /// Optional link type filter, e.g. "verifies".
+    #[serde(default)]
+    pub link_type: Option<String>,
+    /// Include a diff of what changed in each parent since the link was last
+    /// reviewed, recovered from git history (slower).
+    #[serde(default)]
+    pub include_diffs: bool,
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize, schemars::JsonSchema)]
@@ -24,6 +33,18 @@ pub struct SuspectLinkView {
     pub stored_fingerprint: String,
     /// Current fingerprint of the parent (empty if parent missing).
     pub current_fingerprint: String,
+    /// Relationship type of the link, if any.
+    #[serde(default)]
+    pub link_type: Option<String>,
+    /// Unified diff of the parent's title, body, and tags since the link was
+    /// last reviewed, if requested and the reviewed version can be found in
+    /// git history.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub parent_diff: Option<String>,
+    /// Why git history couldn't be searched for the diff (e.g. the
+    /// requirements are not in a git repository).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub parent_diff_error: Option<String>,
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize, schemars::JsonSchema)]
@@ -32,6 +53,9 @@ pub struct ReviewResponse {
     /// Optional kind filter applied (matches either child or parent).
     #[serde(default)]
     pub kind: Option<String>,
+    /// Optional link type filter applied.
+    #[serde(default)]
+    pub link_type: Option<String>,
     /// Suspect links needing review.
     pub suspect_links: Vec<SuspectLinkView>,
 }
@@ -43,12 +67,11 @@ pub(super) async fn review(
 ) -> Result<CallToolResult, McpError> {
     let params = params.0;
     let kind_filter = params.kind.as_ref().map(|k| k.trim().to_uppercase());
+    let link_type_filter = params.link_type.as_ref().map(|t| t.trim().to_lowercase());
 
-    let response = {
+    let (digits, links, histories) = {
         let directory = server.state.directory.read().await;
-        let digits = directory.config().digits();
-
-        let suspect_links = directory
+        let links: Vec<SuspectLink> = directory
             .suspect_links()
             .into_iter()
             .filter(|link| {
@@ -56,20 +79,64 @@ pub(super) async fn review(
                     link.child_hrid.kind() == kind || link.parent_hrid.kind() == kind
                 })
             })
-            .map(|link| SuspectLinkView {
-                child: ReqMcpServer::format_hrid(&link.child_hrid, digits),
-                parent: ReqMcpServer::format_hrid(&link.parent_hrid, digits),
-                stored_fingerprint: link.stored_fingerprint,
-                current_fingerprint: link.current_fingerprint,
+            .filter(|link| {
+                link_type_filter
+                    .as_ref()
+                    .is_none_or(|wanted| link.link_type.as_ref() == Some(wanted))
             })
             .collect();
+        let histories: Vec<Option<ParentHistory>> = if params.include_diffs {
+            links
+                .iter()
+                .map(|link| directory.parent_history(link))
+                .collect()
+        } else {
+            Vec::new()
+        };
+        (directory.config().digits(), links, histories)
+    };
 
-        drop(directory);
+    // Searching git history runs a subprocess per link, so it happens on a
+    // blocking thread, without holding the directory lock.
+    let diffs: Vec<ParentDiff> = if histories.is_empty() {
+        Vec::new()
+    } else {
+        tokio::task::spawn_blocking(move || {
+            histories
+                .iter()
+                .map(|history| parent_diff(history.as_ref()))
+                .collect()
+        })
+        .await
+        .map_err(|error| {
+            McpError::internal_error(
+                "failed to recover parent diffs",
+                Some(json!({ "reason": error.to_string() })),
+            )
+        })?
+    };
 
-        ReviewResponse {
-            kind: kind_filter,
-            suspect_links,
-        }
+    let suspect_links = links
+        .into_iter()
+        .zip(
+            diffs
+                .into_iter()
+                .chain(std::iter::repeat_with(ParentDiff::default)),
+        )
+        .map(|(link, diff)| SuspectLinkView {
+            child: ReqMcpServer::format_hrid(&link.child_hrid, digits),
+            parent: ReqMcpServer::format_hrid(&link.parent_hrid, digits),
+            stored_fingerprint: link.stored_fingerprint,
+            current_fingerprint: link.current_fingerprint,
+            link_type: link.link_type,
+            parent_diff: diff.diff,
+            parent_diff_error: diff.error,
+        })
+        .collect();
+    let response = ReviewResponse {
+        kind: kind_filter,
+        link_type: link_type_filter,
+        suspect_links,
     };
 
     let summary = format!(
@@ -81,3 +148,76 @@ pub(super) async fn review(
         ReqMcpServer::serialize(response, "review response")?,
     ))
 }
+
+/// The diff of a suspect link's parent since it was reviewed, or why git
+/// history couldn't be searched for it.
+#[derive(Debug, Default)]
+struct ParentDiff {
+    diff: Option<String>,
+    error: Option<String>,
+}
+
+fn parent_diff(history: Option<&ParentHistory>) -> ParentDiff {
+    match history.map(ParentHistory::changes) {
+        None | Some(Ok(None)) => ParentDiff::default(),
+        Some(Ok(Some(changes))) => ParentDiff {
+            diff: Some(changes.diff),
+            error: None,
+        },
+        Some(Err(error)) => ParentDiff {
+            diff: None,
+            error: Some(error.to_string()),
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use requiem_core::Directory;
+
+    use super::*;
+    use crate::state::ServerState;
+
+    #[tokio::test]
+    async fn review_only_searches_git_history_when_asked() {
+        let tmp = tempfile::tempdir().unwrap();
+        let mut directory = Directory::new(tmp.path().to_path_buf()).unwrap();
+        let parent = directory
+            .add_requirement("USR", "# Parent".to_string())
+            .unwrap();
+        let child = directory
+            .add_requirement("SYS", "# Child".to_string())
+            .unwrap();
+        directory
+            .link_requirement(child.hrid(), parent.hrid())
+            .unwrap();
+        directory
+            .update_requirement(parent.hrid(), None, Some("Changed.".to_string()), None)
+            .unwrap();
+        directory.flush().unwrap();
+        let server = ReqMcpServer::new(ServerState::new(tmp.path()).unwrap());
+
+        let review = |include_diffs| {
+            let server = server.clone();
+            async move {
+                let params = ReviewParams {
+                    kind: None,
+                    link_type: None,
+                    include_diffs,
+                };
+                let result = super::review(&server, Parameters(params)).await.unwrap();
+                result.structured_content.unwrap()["suspectLinks"][0].clone()
+            }
+        };
+
+        let link = review(false).await;
+        assert_eq!(link["parent"], "USR-001");
+        assert!(link.get("parentDiff").is_none());
+        assert!(link.get("parentDiffError").is_none());
+
+        // The temporary directory isn't a git repository.
+        let link = review(true).await;
+        assert!(link.get("parentDiff").is_none());
+        assert!(link["parentDiffError"].is_string());
+    }
+}
@@ -0,0 +1,162 @@
+//! Reloading requirements when their files change on disk.
+//!
+//! The requirements root is watched for changes, which are debounced so that
+//! a `git pull` or an editor's save is reloaded as one batch. Only the
+//! changed files are reloaded; changes made on disk win over unsaved changes
+//! in memory, which are reported to the client as conflicts.
+
+use std::{
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use notify_debouncer_full::{
+    new_debouncer,
+    notify::{RecommendedWatcher, RecursiveMode},
+    DebounceEventResult, Debouncer, RecommendedCache,
+};
+use requiem_core::storage::directory::Reload;
+use tokio::sync::mpsc;
+
+use crate::{resources::Changes, state::ServerState};
+
+/// How long to wait for changes to settle before reloading.
+const DEBOUNCE: Duration = Duration::from_millis(200);
+
+/// Watches `root`, reloading changed requirements into `state` for as long
+/// as the returned watcher is kept.
+///
+/// # Errors
+///
+/// Returns an error if `root` can't be watched.
+pub fn watch(
+    state: &ServerState,
+    root: &Path,
+) -> notify_debouncer_full::notify::Result<Debouncer<RecommendedWatcher, RecommendedCache>> {
+    let (sender, mut receiver) = mpsc::unbounded_channel();
+    let mut debouncer = new_debouncer(DEBOUNCE, None, move |result: DebounceEventResult| {
+        let _ = sender.send(result);
+    })?;
+    debouncer.watch(root, RecursiveMode::Recursive)?;
+
+    let state = state.clone();
+    tokio::spawn(async move {
+        while let Some(result) = receiver.recv().await {
+            match result {
+                Ok(events) => {
+                    // Reading files (including reloading them) is reported
+                    // too, but changes nothing.
+                    let paths: Vec<PathBuf> = events
+                        .into_iter()
+                        .filter(|event| !event.kind.is_access())
+                        .flat_map(|event| event.event.paths)
+                        .collect();
+                    if !paths.is_empty() {
+                        reload(&state, &paths).await;
+                    }
+                }
+                Err(errors) => {
+                    for error in errors {
+                        tracing::warn!("Failed to watch requirements: {error}");
+                    }
+                }
+            }
+        }
+    });
+    Ok(debouncer)
+}
+
+/// Reloads the requirements in the changed files, and publishes what changed.
+pub async fn reload(state: &ServerState, paths: &[PathBuf]) -> Changes {
+    // Reloading walks directories and reads files, so it runs on a blocking
+    // thread. The lock is held throughout, so nothing sees a partial reload.
+    let mut directory = state.directory.clone().write_owned().await;
+    let paths = paths.to_vec();
+    let reloaded = tokio::task::spawn_blocking(move || {
+        let reload = directory.reload(&paths);
+        (reload, directory.config().digits())
+    })
+    .await;
+
+    match reloaded {
+        Ok((reload, digits)) => publish(state, &reload, digits),
+        Err(error) => {
+            tracing::error!("Failed to reload requirements: {error}");
+            Changes::default()
+        }
+    }
+}
+
+/// Logs the changes made by a reload, and publishes them to clients.
+pub fn publish(state: &ServerState, reload: &Reload, digits: usize) -> Changes {
+    for hrid in &reload.conflicts {
+        tracing::warn!(
+            "Discarded unsaved changes to {}: its file changed on disk",
+            hrid.display(digits)
+        );
+    }
+    for (path, error) in &reload.invalid {
+        tracing::warn!("Failed to reload {}: {error}", path.display());
+    }
+
+    let changes = Changes::new(reload, digits);
+    if !changes.is_empty() {
+        tracing::info!(
+            "Reloaded requirements from disk ({} added, {} updated, {} removed)",
+            reload.added.len(),
+            reload.updated.len(),
+            reload.removed.len()
+        );
+        // Nobody may be listening, which is fine.
+        let _ = state.changes.send(changes.clone());
+    }
+    changes
+}
+
+#[cfg(test)]
+mod tests {
+    use requiem_core::Directory;
+
+    use super::*;
+    use crate::{resources, server::ReqMcpServer};
+
+    #[tokio::test]
+    async fn reload_updates_resources_and_publishes_changes() {
+        let tmp = tempfile::tempdir().unwrap();
+        let mut directory = Directory::new(tmp.path().to_path_buf()).unwrap();
+        let requirement = directory
+            .add_requirement("USR", "# Log in".to_string())
+            .unwrap();
+        directory.flush().unwrap();
+        let path = directory
+            .path_for(requirement.hrid())
+            .unwrap()
+            .to_path_buf();
+
+        let state = ServerState::new(tmp.path()).unwrap();
+        let server = ReqMcpServer::new(state.clone());
+        let mut published = state.changes.subscribe();
+        let uri = "requirement://USR-001";
+        resources::subscribe(&server, uri.to_string()).unwrap();
+
+        // Unchanged files publish nothing.
+        assert!(reload(&state, std::slice::from_ref(&path)).await.is_empty());
+
+        let text = std::fs::read_to_string(&path).unwrap() + "\nUsers shall log in.\n";
+        std::fs::write(&path, text).unwrap();
+        let changes = reload(&state, std::slice::from_ref(&path)).await;
+        assert_eq!(changes.updated, [uri]);
+        assert!(!changes.list_changed);
+        assert_eq!(published.recv().await.unwrap(), changes);
+
+        let read = resources::read(&server, uri).await.unwrap();
+        let json = serde_json::to_value(&read.contents[0]).unwrap();
+        assert_eq!(json["text"], "# USR-001 Log in\n\nUsers shall log in.\n");
+
+        std::fs::remove_file(&path).unwrap();
+        let changes = reload(&state, std::slice::from_ref(&path)).await;
+        assert!(changes.list_changed);
+        assert!(resources::read(&server, uri).await.is_err());
+        assert!(resources::list(&server).await.resources.is_empty());
+    }
+}
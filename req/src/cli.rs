@@ -6,24 +6,32 @@ use std::{
     path::PathBuf,
 };
 
+mod baseline;
 mod config;
+mod coverage;
 mod create;
 mod delete;
 mod diagnose;
+mod diff;
 mod export;
+mod impact;
+mod import;
 mod init;
 mod kind;
 mod link;
 mod list;
+mod migrate;
 mod r#move;
 mod rename;
 mod review;
+mod set;
 mod show;
 mod status;
 mod sync;
 mod terminal;
 mod unlink;
 mod validate;
+mod verification;
 
 use clap::ArgAction;
 use list::List;
@@ -37,6 +45,18 @@ fn parse_hrid(s: &str) -> Result<Hrid, String> {
     Hrid::parse_lenient(s).map_err(|e| e.to_string())
 }
 
+/// Parse a `NAME=VALUE` custom attribute assignment from a CLI argument.
+fn parse_attribute(s: &str) -> Result<(String, String), String> {
+    let (name, value) = s
+        .split_once('=')
+        .ok_or_else(|| format!("expected NAME=VALUE, got '{s}'"))?;
+    let name = name.trim();
+    if name.is_empty() {
+        return Err(format!("missing attribute name in '{s}'"));
+    }
+    Ok((name.to_string(), value.trim().to_string()))
+}
+
 #[derive(Debug, clap::Parser)]
 #[command(version, about)]
 pub struct Cli {
@@ -115,6 +135,9 @@ pub enum Command {
     /// since the link was created or last reviewed.
     Review(review::Command),
 
+    /// Set or remove custom attributes on a requirement
+    Set(set::Command),
+
     /// Validate repository health
     Validate(Validate),
 
@@ -139,9 +162,33 @@ pub enum Command {
     /// Diagnose path-related issues
     Diagnose(diagnose::Command),
 
+    /// Upgrade requirement files and config to the current format version
+    Migrate(migrate::Command),
+
+    /// Report how completely requirements are traced downstream
+    Coverage(coverage::Command),
+
+    /// Show which requirements a change would make suspect
+    Impact(impact::Command),
+
+    /// Show requirement changes since a git revision
+    Diff(diff::Command),
+
+    /// Freeze the requirement set as a named baseline and compare baselines
+    #[command(subcommand)]
+    Baseline(baseline::Command),
+
     /// Export documentation artifacts generated from the requirements graph
     #[command(subcommand)]
     Export(export::Command),
+
+    /// Create and update requirements from documents written by other tools
+    #[command(subcommand)]
+    Import(import::Command),
+
+    /// Generate test requirements from annotated tests
+    #[command(subcommand)]
+    Tests(verification::Command),
 }
 
 impl Default for Command {
@@ -161,6 +208,7 @@ impl Command {
             Self::Unlink(command) => command.run(root)?,
             Self::Sync(command) => command.run(root)?,
             Self::Review(command) => command.run(root)?,
+            Self::Set(command) => command.run(root)?,
             Self::Validate(command) => command.run(root)?,
             Self::Show(command) => command.run(root)?,
             Self::List(command) => command.run(root)?,
@@ -169,7 +217,14 @@ impl Command {
             Self::Rename(command) => command.run(root)?,
             Self::Move(command) => command.run(&root)?,
             Self::Diagnose(command) => command.run(&root)?,
+            Self::Migrate(command) => command.run(root)?,
+            Self::Coverage(command) => command.run(root)?,
+            Self::Impact(command) => command.run(root)?,
+            Self::Diff(command) => command.run(root)?,
+            Self::Baseline(command) => command.run(root)?,
             Self::Export(command) => command.run(root)?,
+            Self::Import(command) => command.run(root)?,
+            Self::Tests(command) => command.run(root)?,
         }
         Ok(())
     }
@@ -204,4 +259,14 @@ mod tests {
 
         assert!(parse_hrid("invalid").is_err());
     }
+
+    #[test]
+    fn parse_attribute_splits_on_first_equals() {
+        assert_eq!(
+            parse_attribute(" owner = a=b "),
+            Ok(("owner".to_string(), "a=b".to_string()))
+        );
+        assert!(parse_attribute("owner").is_err());
+        assert!(parse_attribute("=value").is_err());
+    }
 }
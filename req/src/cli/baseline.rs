@@ -0,0 +1,216 @@
+//! The `req baseline` command: freeze the requirement set under a name and
+//! report what changed since.
+
+use std::path::PathBuf;
+
+use requiem_core::{
+    domain::{baseline::Relinked, BaselineDiff},
+    Directory,
+};
+
+use crate::cli::terminal::Colorize;
+
+#[derive(Debug, clap::Parser)]
+pub enum Command {
+    /// Snapshot every requirement into .req/baselines/<NAME>.json
+    ///
+    /// The snapshot records each requirement's UUID, HRID, title,
+    /// fingerprint, and parent links.
+    Create(Create),
+
+    /// Report what changed between two baselines
+    ///
+    /// Compares against the current requirements if only one baseline is
+    /// given.
+    Diff(Diff),
+}
+
+impl Command {
+    pub fn run(self, root: PathBuf) -> anyhow::Result<()> {
+        match self {
+            Self::Create(command) => command.run(root),
+            Self::Diff(command) => command.run(root),
+        }
+    }
+}
+
+#[derive(Debug, clap::Parser)]
+pub struct Create {
+    /// The name of the baseline (e.g. v2.0)
+    name: String,
+
+    /// Replace an existing baseline with the same name
+    #[arg(long)]
+    force: bool,
+}
+
+impl Create {
+    pub fn run(self, root: PathBuf) -> anyhow::Result<()> {
+        let directory = Directory::new(root)?;
+        let baseline = directory.create_baseline(&self.name, self.force)?;
+        let path = directory.baseline_path(&self.name)?;
+
+        println!(
+            "{}",
+            format!(
+                "✅ Created baseline '{}' with {} requirements",
+                baseline.name,
+                baseline.requirements.len()
+            )
+            .success()
+        );
+        println!(
+            "{}",
+            path.strip_prefix(directory.root())
+                .unwrap_or(&path)
+                .display()
+                .to_string()
+                .dim()
+        );
+        Ok(())
+    }
+}
+
+#[derive(Debug, clap::Parser)]
+pub struct Diff {
+    /// The earlier baseline
+    from: String,
+
+    /// The later baseline (defaults to the current requirements)
+    to: Option<String>,
+
+    /// Output format
+    #[arg(long, value_enum, default_value = "table")]
+    output: OutputFormat,
+}
+
+#[derive(Debug, Clone, Copy, Default, clap::ValueEnum)]
+enum OutputFormat {
+    #[default]
+    Table,
+    Json,
+}
+
+impl Diff {
+    pub fn run(self, root: PathBuf) -> anyhow::Result<()> {
+        let directory = Directory::new(root)?;
+        let from = directory.load_baseline(&self.from)?;
+        let to = match &self.to {
+            Some(name) => directory.load_baseline(name)?,
+            None => directory.baseline("current".to_string()),
+        };
+        let diff = from.diff(&to);
+
+        match self.output {
+            OutputFormat::Table => {
+                let to = self.to.as_ref().map_or_else(
+                    || "the current requirements".to_string(),
+                    |name| format!("'{name}'"),
+                );
+                println!("Changes from '{}' to {to}:", self.from);
+                print_diff(&diff);
+            }
+            OutputFormat::Json => println!("{}", serde_json::to_string_pretty(&diff)?),
+        }
+        Ok(())
+    }
+}
+
+pub(super) fn print_diff(diff: &BaselineDiff) {
+    if diff.is_empty() {
+        println!("{}", "✅ No changes".success());
+        return;
+    }
+
+    print_section("Added", diff.added.len());
+    for requirement in &diff.added {
+        println!("  + {}  {}", requirement.hrid, requirement.title.dim());
+    }
+    print_section("Removed", diff.removed.len());
+    for requirement in &diff.removed {
+        println!("  - {}  {}", requirement.hrid, requirement.title.dim());
+    }
+    print_section("Modified", diff.modified.len());
+    for requirement in &diff.modified {
+        println!("  ~ {}  {}", requirement.hrid, requirement.title.dim());
+    }
+    print_section("Renamed", diff.renamed.len());
+    for renamed in &diff.renamed {
+        println!("  {} → {}", renamed.from, renamed.to);
+    }
+    print_section("Re-linked", diff.relinked.len());
+    for relinked in &diff.relinked {
+        println!("  {}: {}", relinked.hrid, format_links(relinked));
+    }
+}
+
+/// Lists added (`+`) and removed (`-`) links, with their link types.
+pub(super) fn format_links(relinked: &Relinked) -> String {
+    relinked
+        .added
+        .iter()
+        .map(|link| ('+', link))
+        .chain(relinked.removed.iter().map(|link| ('-', link)))
+        .map(|(sign, link)| {
+            let link_type = link
+                .link_type
+                .as_ref()
+                .map(|link_type| format!(" ({link_type})"));
+            format!("{sign}{}{}", link.hrid, link_type.unwrap_or_default())
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+fn print_section(title: &str, count: usize) {
+    if count > 0 {
+        println!("\n{}", format!("{title} ({count}):").info());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use requiem_core::Directory;
+    use tempfile::tempdir;
+
+    use super::{Create, Diff, OutputFormat};
+
+    #[test]
+    fn baseline_create_refuses_to_overwrite_without_force() {
+        let tmp = tempdir().unwrap();
+        let root = tmp.path().to_path_buf();
+        let mut directory = Directory::new(root.clone()).unwrap();
+        directory
+            .add_requirement("USR", "# First".to_string())
+            .unwrap();
+        directory.flush().unwrap();
+
+        let create = |force| Create {
+            name: "v1.0".to_string(),
+            force,
+        };
+        create(false).run(root.clone()).unwrap();
+        create(false)
+            .run(root.clone())
+            .expect_err("existing baseline should not be replaced");
+        create(true).run(root.clone()).unwrap();
+
+        let baseline = directory.load_baseline("v1.0").unwrap();
+        assert_eq!(baseline.requirements.len(), 1);
+
+        Diff {
+            from: "v1.0".to_string(),
+            to: None,
+            output: OutputFormat::Json,
+        }
+        .run(root.clone())
+        .unwrap();
+        Diff {
+            from: "v1.0".to_string(),
+            to: Some("missing".to_string()),
+            output: OutputFormat::Table,
+        }
+        .run(root)
+        .expect_err("missing baseline should be reported");
+    }
+}
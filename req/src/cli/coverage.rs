@@ -0,0 +1,389 @@
+//! The `req coverage` command: how completely requirements are traced to the
+//! kinds downstream of them.
+
+use std::{collections::BTreeMap, fmt::Write, path::PathBuf};
+
+use requiem_core::{
+    domain::{CoverageReport, KindCoverage},
+    Directory, Hrid,
+};
+use tracing::instrument;
+
+use crate::cli::terminal::Colorize;
+
+#[derive(Debug, clap::Parser)]
+pub struct Command {
+    /// Minimum coverage percentage, for every kind (e.g. 90) or for one kind
+    /// (e.g. USR=100); repeatable
+    ///
+    /// Overrides the `min_coverage` thresholds in config.toml. Exits with
+    /// code 2 if any kind falls below its threshold.
+    #[arg(long, value_name = "[KIND=]PERCENT", value_parser = parse_threshold)]
+    min: Vec<Threshold>,
+
+    /// Output format
+    #[arg(long, value_enum, default_value = "text")]
+    output: OutputFormat,
+}
+
+#[derive(Debug, Clone, Copy, Default, clap::ValueEnum)]
+enum OutputFormat {
+    #[default]
+    Text,
+    Markdown,
+    Json,
+    Csv,
+}
+
+#[derive(Debug, Clone)]
+struct Threshold {
+    kind: Option<String>,
+    min: u8,
+}
+
+fn parse_threshold(value: &str) -> Result<Threshold, String> {
+    let (kind, percent) = match value.split_once('=') {
+        Some((kind, percent)) => (Some(kind.trim().to_uppercase()), percent),
+        None => (None, value),
+    };
+    let min = percent
+        .trim()
+        .trim_end_matches('%')
+        .parse::<u8>()
+        .ok()
+        .filter(|min| *min <= 100)
+        .ok_or_else(|| format!("invalid percentage '{percent}': expected 0 to 100"))?;
+    Ok(Threshold { kind, min })
+}
+
+/// A kind's coverage, with the threshold that applies to it.
+struct Row<'a> {
+    coverage: &'a KindCoverage,
+    min: Option<u8>,
+}
+
+impl Row<'_> {
+    fn passed(&self) -> bool {
+        self.min.is_none_or(|min| self.coverage.meets(min))
+    }
+}
+
+impl Command {
+    #[instrument(level = "debug", skip(self))]
+    pub fn run(self, root: PathBuf) -> anyhow::Result<()> {
+        let directory = Directory::new(root)?;
+        let report = directory.coverage();
+        let rows = self.rows(&directory, &report);
+
+        match self.output {
+            OutputFormat::Text => print_text(&directory, &report, &rows),
+            OutputFormat::Markdown => print!("{}", render_markdown(&directory, &report, &rows)),
+            OutputFormat::Json => {
+                let json = coverage_json(&directory, &report, &rows);
+                println!("{}", serde_json::to_string_pretty(&json)?);
+            }
+            OutputFormat::Csv => print!("{}", render_csv(&rows)),
+        }
+
+        if !rows.iter().all(Row::passed) {
+            std::process::exit(2);
+        }
+        Ok(())
+    }
+
+    /// Pairs each kind with its threshold: a kind-specific `--min`, then a
+    /// general `--min`, then `min_coverage` from the configuration.
+    fn rows<'a>(&self, directory: &Directory, report: &'a CoverageReport) -> Vec<Row<'a>> {
+        let general = self.min.iter().rev().find(|t| t.kind.is_none());
+        report
+            .kinds
+            .iter()
+            .map(|coverage| {
+                let specific = self
+                    .min
+                    .iter()
+                    .rev()
+                    .find(|t| t.kind.as_deref() == Some(coverage.kind.as_str()));
+                let min = specific.or(general).map(|t| t.min).or_else(|| {
+                    directory
+                        .config()
+                        .metadata_for_kind(&coverage.kind)
+                        .and_then(|metadata| metadata.min_coverage)
+                });
+                Row { coverage, min }
+            })
+            .collect()
+    }
+}
+
+fn title(directory: &Directory, hrid: &Hrid) -> String {
+    directory
+        .find_by_hrid(hrid)
+        .map(|view| view.title.to_string())
+        .unwrap_or_default()
+}
+
+fn format_percentage(percentage: Option<f64>) -> String {
+    percentage.map_or_else(|| "n/a".to_string(), |p| format!("{p:.1}%"))
+}
+
+fn print_text(directory: &Directory, report: &CoverageReport, rows: &[Row<'_>]) {
+    let digits = directory.config().digits();
+    if rows.is_empty() {
+        println!("No requirements found.");
+        return;
+    }
+
+    println!("Coverage Report");
+    for row in rows {
+        let coverage = row.coverage;
+        println!();
+        if coverage.is_leaf() {
+            println!(
+                "{} {}",
+                format!("{} ({} requirement(s))", coverage.kind, coverage.total).info(),
+                "leaf kind, not measured".dim()
+            );
+        } else {
+            println!(
+                "{} {}",
+                format!("{} ({} requirement(s))", coverage.kind, coverage.total).info(),
+                format!("downstream: {}", coverage.downstream_kinds.join(", ")).dim()
+            );
+            let threshold = row.min.map_or_else(String::new, |min| {
+                if row.passed() {
+                    format!("  (minimum {min}%)").success()
+                } else {
+                    format!("  (below minimum {min}%)").warning()
+                }
+            });
+            println!(
+                "  Covered:  {} ({}){threshold}",
+                coverage.covered(),
+                format_percentage(coverage.percentage())
+            );
+        }
+        print_gaps("Uncovered", &coverage.uncovered, directory, digits);
+        print_gaps("Orphans", &coverage.orphans, directory, digits);
+    }
+
+    println!();
+    println!("Overall coverage: {}", format_percentage(report.overall()));
+    for row in rows.iter().filter(|row| !row.passed()) {
+        println!(
+            "{}",
+            format!(
+                "❌ {} coverage is {}, below the minimum of {}%",
+                row.coverage.kind,
+                format_percentage(row.coverage.percentage()),
+                row.min.unwrap_or_default()
+            )
+            .warning()
+        );
+    }
+}
+
+fn print_gaps(label: &str, hrids: &[Hrid], directory: &Directory, digits: usize) {
+    if hrids.is_empty() {
+        return;
+    }
+    println!("  {label}: {}", hrids.len());
+    for hrid in hrids {
+        println!(
+            "    - {}  {}",
+            hrid.display(digits),
+            title(directory, hrid).dim()
+        );
+    }
+}
+
+fn render_markdown(directory: &Directory, report: &CoverageReport, rows: &[Row<'_>]) -> String {
+    let digits = directory.config().digits();
+    let mut markdown = String::from("# Coverage Report\n\n");
+    markdown
+        .push_str("| Kind | Requirements | Covered | Coverage | Minimum | Downstream kinds |\n");
+    markdown.push_str("|---|---|---|---|---|---|\n");
+    for row in rows {
+        let coverage = row.coverage;
+        let min = row.min.map_or_else(String::new, |min| {
+            format!("{min}% {}", if row.passed() { "✅" } else { "❌" })
+        });
+        let covered = if coverage.is_leaf() {
+            "-".to_string()
+        } else {
+            coverage.covered().to_string()
+        };
+        let _ = writeln!(
+            markdown,
+            "| {} | {} | {covered} | {} | {min} | {} |",
+            coverage.kind,
+            coverage.total,
+            format_percentage(coverage.percentage()),
+            coverage.downstream_kinds.join(", ")
+        );
+    }
+    let _ = writeln!(
+        markdown,
+        "\n**Overall coverage:** {}",
+        format_percentage(report.overall())
+    );
+
+    for (heading, gaps) in [
+        (
+            "Uncovered requirements",
+            rows.iter()
+                .flat_map(|row| &row.coverage.uncovered)
+                .collect::<Vec<_>>(),
+        ),
+        (
+            "Orphan requirements",
+            rows.iter().flat_map(|row| &row.coverage.orphans).collect(),
+        ),
+    ] {
+        if gaps.is_empty() {
+            continue;
+        }
+        let _ = writeln!(markdown, "\n## {heading}\n");
+        for hrid in gaps {
+            let _ = writeln!(
+                markdown,
+                "- **{}**: {}",
+                hrid.display(digits),
+                title(directory, hrid)
+            );
+        }
+    }
+    markdown
+}
+
+fn coverage_json(
+    directory: &Directory,
+    report: &CoverageReport,
+    rows: &[Row<'_>],
+) -> serde_json::Value {
+    use serde_json::json;
+
+    let digits = directory.config().digits();
+    let by_kind: BTreeMap<&str, serde_json::Value> = rows
+        .iter()
+        .map(|row| {
+            let coverage = row.coverage;
+            let value = json!({
+                "total": coverage.total,
+                "covered": (!coverage.is_leaf()).then(|| coverage.covered()),
+                "coverage": coverage.percentage(),
+                "min_coverage": row.min,
+                "passed": row.passed(),
+                "downstream_kinds": coverage.downstream_kinds,
+            });
+            (coverage.kind.as_str(), value)
+        })
+        .collect();
+
+    let gap = |hrid: &Hrid, kind: &str, gap_type: &str| {
+        json!({
+            "hrid": hrid.display(digits).to_string(),
+            "kind": kind,
+            "title": title(directory, hrid),
+            "type": gap_type,
+        })
+    };
+    let gaps: Vec<_> = rows
+        .iter()
+        .flat_map(|row| {
+            let kind = row.coverage.kind.as_str();
+            let uncovered = row
+                .coverage
+                .uncovered
+                .iter()
+                .map(move |h| (h, kind, "uncovered"));
+            let orphans = row
+                .coverage
+                .orphans
+                .iter()
+                .map(move |h| (h, kind, "orphan"));
+            uncovered.chain(orphans)
+        })
+        .map(|(hrid, kind, gap_type)| gap(hrid, kind, gap_type))
+        .collect();
+
+    json!({
+        "overall_coverage": report.overall(),
+        "passed": rows.iter().all(Row::passed),
+        "by_kind": by_kind,
+        "gaps": gaps,
+    })
+}
+
+fn render_csv(rows: &[Row<'_>]) -> String {
+    let mut csv = String::from(
+        "kind,total,covered,uncovered,orphans,coverage,min_coverage,passed,downstream_kinds\n",
+    );
+    for row in rows {
+        let coverage = row.coverage;
+        let covered = if coverage.is_leaf() {
+            String::new()
+        } else {
+            coverage.covered().to_string()
+        };
+        let _ = writeln!(
+            csv,
+            "{},{},{},{},{},{},{},{},{}",
+            coverage.kind,
+            coverage.total,
+            covered,
+            coverage.uncovered.len(),
+            coverage.orphans.len(),
+            coverage
+                .percentage()
+                .map_or_else(String::new, |p| format!("{p:.1}")),
+            row.min.map_or_else(String::new, |min| min.to_string()),
+            row.passed(),
+            coverage.downstream_kinds.join(" ")
+        );
+    }
+    csv
+}
+
+#[cfg(test)]
+mod tests {
+    use requiem_core::Directory;
+    use tempfile::tempdir;
+
+    use super::{parse_threshold, render_csv, Command, OutputFormat};
+
+    #[test]
+    fn thresholds_prefer_kind_specific_values() {
+        let tmp = tempdir().unwrap();
+        let mut directory = Directory::new(tmp.path().to_path_buf()).unwrap();
+        let usr = directory
+            .add_requirement("USR", "# Covered".to_string())
+            .unwrap();
+        directory
+            .add_requirement("USR", "# Uncovered".to_string())
+            .unwrap();
+        let sys = directory
+            .add_requirement("SYS", "# System".to_string())
+            .unwrap();
+        directory.link_requirement(sys.hrid(), usr.hrid()).unwrap();
+
+        let command = Command {
+            min: vec![
+                parse_threshold("USR=50").unwrap(),
+                parse_threshold("90").unwrap(),
+            ],
+            output: OutputFormat::Csv,
+        };
+        let report = directory.coverage();
+        let rows = command.rows(&directory, &report);
+
+        assert_eq!(
+            render_csv(&rows),
+            "kind,total,covered,uncovered,orphans,coverage,min_coverage,passed,downstream_kinds\n\
+             SYS,1,,0,0,,90,true,\n\
+             USR,2,1,1,0,50.0,50,true,SYS\n"
+        );
+        assert!(parse_threshold("101").is_err());
+        assert!(parse_threshold("usr=95%").is_ok_and(|t| t.kind.as_deref() == Some("USR")));
+    }
+}
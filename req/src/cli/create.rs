@@ -5,7 +5,7 @@ use std::path::PathBuf;
 use requiem_core::{Directory, Hrid};
 use tracing::instrument;
 
-use crate::cli::parse_hrid;
+use crate::cli::{parse_attribute, parse_hrid};
 
 #[derive(Debug, clap::Parser)]
 pub struct Command {
@@ -31,6 +31,10 @@ pub struct Command {
     /// The body text of the requirement.
     #[clap(long, short)]
     body: Option<String>,
+
+    /// Custom attributes to set, as NAME=VALUE (repeatable).
+    #[clap(long = "attr", value_name = "NAME=VALUE", value_parser = parse_attribute)]
+    attributes: Vec<(String, String)>,
 }
 
 impl Command {
@@ -77,6 +81,9 @@ impl Command {
             // 'add_requirement' method to avoid unnecessary IO.
             directory.link_requirement(requirement.hrid(), parent)?;
         }
+        for (name, value) in &self.attributes {
+            directory.set_attribute(requirement.hrid(), name, Some(value))?;
+        }
         directory.flush()?;
 
         println!("Added requirement {}", requirement.hrid().display(digits));
@@ -108,6 +115,7 @@ mod tests {
             parent: Vec::new(),
             title: Some("Namespaced Requirement".to_string()),
             body: Some("test body".to_string()),
+            attributes: Vec::new(),
         };
 
         create
@@ -145,6 +153,7 @@ mod tests {
             parent: Vec::new(),
             title: None,
             body: None,
+            attributes: Vec::new(),
         };
 
         create
@@ -174,6 +183,7 @@ mod tests {
             parent: vec![parent.hrid().clone()],
             title: Some("Child".to_string()),
             body: Some("body text".to_string()),
+            attributes: Vec::new(),
         };
 
         create
@@ -190,4 +200,40 @@ mod tests {
         assert_eq!(child.title, "Child");
         assert_eq!(child.body, "body text");
     }
+
+    #[test]
+    fn create_run_sets_attributes_and_rejects_invalid_values() {
+        let tmp = tempdir().unwrap();
+        let root = tmp.path().to_path_buf();
+        std::fs::create_dir_all(root.join(".req")).unwrap();
+        std::fs::write(
+            root.join(".req/config.toml"),
+            "_version = \"1\"\n\n[attributes.priority]\ntype = \"enum\"\nvalues = [\"low\", \
+             \"high\"]\n",
+        )
+        .unwrap();
+
+        let create = |priority: &str| Command {
+            kind: "USR".to_string(),
+            parent: Vec::new(),
+            title: Some("Prioritised".to_string()),
+            body: None,
+            attributes: vec![("priority".to_string(), priority.to_string())],
+        };
+
+        create("urgent")
+            .run(root.clone())
+            .expect_err("undeclared enum value should be rejected");
+        create("high")
+            .run(root.clone())
+            .expect("create command should succeed");
+
+        let directory = Directory::new(root).expect("failed to load directory");
+        assert_eq!(directory.requirements().count(), 1);
+        let created = find_with_kind(&directory, "USR");
+        assert_eq!(
+            created.attributes.get("priority").map(ToString::to_string),
+            Some("high".to_string())
+        );
+    }
 }
@@ -0,0 +1,122 @@
+//! The `req diff` command: semantic requirement changes since a git revision.
+
+use std::{fmt::Write, path::PathBuf};
+
+use requiem_core::{domain::BaselineDiff, Directory};
+
+use crate::cli::baseline::{format_links, print_diff};
+
+#[derive(Debug, clap::Parser)]
+pub struct Command {
+    /// The git revision to compare against (e.g. main, HEAD~1, v2.0)
+    revision: String,
+
+    /// Output format
+    #[arg(long, value_enum, default_value = "table")]
+    output: OutputFormat,
+}
+
+#[derive(Debug, Clone, Copy, Default, clap::ValueEnum)]
+enum OutputFormat {
+    #[default]
+    Table,
+    Json,
+    /// Markdown table, e.g. for pull request comments
+    Markdown,
+}
+
+impl Command {
+    pub fn run(self, root: PathBuf) -> anyhow::Result<()> {
+        let directory = Directory::new(root)?;
+        let before = directory.baseline_at_revision(&self.revision)?;
+        let diff = before.diff(&directory.baseline("working tree".to_string()));
+
+        match self.output {
+            OutputFormat::Table => {
+                println!("Changes since {}:", self.revision);
+                print_diff(&diff);
+            }
+            OutputFormat::Json => println!("{}", serde_json::to_string_pretty(&diff)?),
+            OutputFormat::Markdown => print!("{}", render_markdown(&self.revision, &diff)),
+        }
+        Ok(())
+    }
+}
+
+fn render_markdown(revision: &str, diff: &BaselineDiff) -> String {
+    if diff.is_empty() {
+        return format!("No requirement changes since `{revision}`.\n");
+    }
+
+    let mut rows = Vec::new();
+    for requirement in &diff.added {
+        rows.push(("Added", &requirement.hrid, escape(&requirement.title)));
+    }
+    for requirement in &diff.removed {
+        rows.push(("Removed", &requirement.hrid, escape(&requirement.title)));
+    }
+    for requirement in &diff.modified {
+        rows.push(("Modified", &requirement.hrid, escape(&requirement.title)));
+    }
+    for renamed in &diff.renamed {
+        rows.push(("Renamed", &renamed.to, format!("was {}", renamed.from)));
+    }
+    for relinked in &diff.relinked {
+        rows.push(("Re-linked", &relinked.hrid, format_links(relinked)));
+    }
+
+    let mut markdown = format!(
+        "### Requirement changes since `{revision}`\n\n| Change | Requirement | Details \
+         |\n|---|---|---|\n"
+    );
+    for (change, hrid, details) in rows {
+        let _ = writeln!(markdown, "| {change} | {hrid} | {details} |");
+    }
+    markdown
+}
+
+/// Escapes text for a markdown table cell.
+fn escape(text: &str) -> String {
+    text.replace('|', "\\|")
+}
+
+#[cfg(test)]
+mod tests {
+    use requiem_core::domain::{
+        baseline::{BaselineRequirement, Renamed},
+        BaselineDiff,
+    };
+    use uuid::Uuid;
+
+    use super::render_markdown;
+
+    #[test]
+    fn markdown_lists_one_row_per_change() {
+        let diff = BaselineDiff {
+            added: vec![BaselineRequirement {
+                uuid: Uuid::nil(),
+                hrid: "USR-002".to_string(),
+                title: "Export | import".to_string(),
+                fingerprint: String::new(),
+                parents: Vec::new(),
+            }],
+            renamed: vec![Renamed {
+                uuid: Uuid::nil(),
+                from: "USR-001".to_string(),
+                to: "USR-010".to_string(),
+            }],
+            ..BaselineDiff::default()
+        };
+
+        assert_eq!(
+            render_markdown("main", &diff),
+            "### Requirement changes since `main`\n\n| Change | Requirement | Details \
+             |\n|---|---|---|\n| Added | USR-002 | Export \\| import |\n| Renamed | USR-010 | \
+             was USR-001 |\n"
+        );
+        assert_eq!(
+            render_markdown("main", &BaselineDiff::default()),
+            "No requirement changes since `main`.\n"
+        );
+    }
+}
@@ -18,6 +18,13 @@ use requiem_core::Directory;
 
 use crate::cli::terminal::Colorize;
 
+mod graph;
+mod html;
+mod json;
+mod matrix;
+mod reqif;
+mod sphinx_needs;
+
 /// Marker opening the generated region of a `SUMMARY.md`.
 const START_MARKER: &str = "<!-- requiem:summary:start -->";
 /// Marker closing the generated region of a `SUMMARY.md`.
@@ -31,12 +38,61 @@ pub enum Command {
     /// `<!-- requiem:summary:end -->` markers; content outside the markers
     /// is left untouched.
     Summary(Summary),
+
+    /// Write a traceability matrix between two sets of kinds
+    ///
+    /// Cells mark direct links (X) and, with --transitive, traces through
+    /// intermediate requirements (T); a trailing ! marks traces through a
+    /// suspect link.
+    Matrix(matrix::Matrix),
+
+    /// Write the trace graph as Graphviz DOT or Mermaid
+    ///
+    /// Requirements are clustered by namespace, with an edge from each child
+    /// to its parents. Suspect links are coloured, and requirements and links
+    /// on a cycle are marked in red.
+    Graph(graph::Graph),
+
+    /// Write the requirements as a ReqIF 1.2 document
+    ///
+    /// Each kind becomes a SPEC-OBJECT-TYPE and a SPECIFICATION, each
+    /// requirement a SPEC-OBJECT identified by its UUID, and each parent link
+    /// a SPEC-RELATION from child to parent.
+    Reqif(reqif::Reqif),
+
+    /// Write the whole repository as a JSON document
+    ///
+    /// The document holds the configuration and every requirement with its
+    /// frontmatter and parent links, and `req import json` restores it to
+    /// identical files. Use --schema to write its JSON Schema instead.
+    Json(json::Json),
+
+    /// Write the requirements as a static HTML site
+    ///
+    /// The site has a page per requirement with its rendered body, parents,
+    /// and children, a page per kind, a graph of every link, and a search
+    /// box. Suspect links are flagged throughout. It needs no web server:
+    /// open index.html in a browser.
+    Html(html::Html),
+
+    /// Write the requirements as a sphinx-needs needs.json
+    ///
+    /// Each requirement becomes a need with its HRID as the ID, its kind
+    /// (lowercase) as the type, and its parents as links, for Sphinx
+    /// projects to read with the needimport directive.
+    SphinxNeeds(sphinx_needs::SphinxNeeds),
 }
 
 impl Command {
     pub fn run(self, root: PathBuf) -> anyhow::Result<()> {
         match self {
             Self::Summary(command) => command.run(root),
+            Self::Matrix(command) => command.run(root),
+            Self::Graph(command) => command.run(root),
+            Self::Reqif(command) => command.run(root),
+            Self::Json(command) => command.run(root),
+            Self::Html(command) => command.run(root),
+            Self::SphinxNeeds(command) => command.run(root),
         }
     }
 }
@@ -0,0 +1,521 @@
+//! `req export graph`: the trace graph as Graphviz DOT or Mermaid.
+//!
+//! Requirements are drawn as nodes, grouped into a cluster per namespace
+//! (nested for multi-segment namespaces), with an edge from each child to
+//! its parents so that parents are drawn above their children. Suspect links
+//! are coloured, and requirements and links on a cycle are marked in red.
+
+use std::{
+    collections::{BTreeSet, HashMap, HashSet, VecDeque},
+    fmt::Write,
+    fs,
+    path::PathBuf,
+};
+
+use anyhow::{bail, Context};
+use requiem_core::{Directory, Hrid};
+
+use crate::cli::{parse_hrid, terminal::Colorize};
+
+/// The colour of suspect links.
+const SUSPECT: &str = "#e8590c";
+/// The colour of requirements and links on a cycle.
+const CYCLE: &str = "#c92a2a";
+
+#[derive(Debug, clap::Parser)]
+pub struct Graph {
+    /// Draw only these requirements and their descendants
+    #[arg(value_name = "HRID", value_parser = parse_hrid)]
+    roots: Vec<Hrid>,
+
+    /// Follow at most N levels of children from the given requirements
+    /// (0 = unlimited)
+    #[arg(long, value_name = "N", requires = "roots")]
+    depth: Option<usize>,
+
+    /// Draw only requirements of these kinds (comma-separated,
+    /// case-insensitive)
+    #[arg(long, value_delimiter = ',', value_name = "KIND")]
+    kind: Vec<String>,
+
+    /// Draw only requirements in these namespaces (comma-separated,
+    /// case-insensitive)
+    #[arg(long, value_delimiter = ',', value_name = "NS")]
+    namespace: Vec<String>,
+
+    /// Output format
+    #[arg(long, value_enum, default_value = "dot")]
+    format: Format,
+
+    /// Write the graph to a file instead of standard output
+    #[arg(long)]
+    file: Option<PathBuf>,
+}
+
+#[derive(Debug, Clone, Copy, Default, clap::ValueEnum)]
+enum Format {
+    #[default]
+    Dot,
+    Mermaid,
+}
+
+impl Graph {
+    pub fn run(self, root: PathBuf) -> anyhow::Result<()> {
+        let directory = Directory::new(root)?;
+        let diagram = Diagram::build(&directory, &self)?;
+
+        let rendered = match self.format {
+            Format::Dot => diagram.to_dot(),
+            Format::Mermaid => diagram.to_mermaid(),
+        };
+
+        match &self.file {
+            None => print!("{rendered}"),
+            Some(file) => {
+                fs::write(file, rendered)
+                    .with_context(|| format!("failed to write {}", file.display()))?;
+                println!(
+                    "{}",
+                    format!(
+                        "✅ Wrote {} ({} requirements, {} links)",
+                        file.display(),
+                        diagram.nodes.len(),
+                        diagram.edges.len()
+                    )
+                    .success()
+                );
+            }
+        }
+        Ok(())
+    }
+}
+
+/// A requirement in the graph.
+#[derive(Debug)]
+struct Node {
+    hrid: String,
+    title: String,
+    namespace: Vec<String>,
+    /// Whether the requirement is on a cycle.
+    cycle: bool,
+}
+
+/// A link from a child to a parent, by their indices in the nodes.
+#[derive(Debug)]
+struct Edge {
+    child: usize,
+    parent: usize,
+    link_type: Option<String>,
+    suspect: bool,
+    /// Whether the link is on a cycle.
+    cycle: bool,
+}
+
+/// The requirements and links to draw.
+#[derive(Debug)]
+struct Diagram {
+    nodes: Vec<Node>,
+    edges: Vec<Edge>,
+}
+
+impl Diagram {
+    fn build(directory: &Directory, filters: &Graph) -> anyhow::Result<Self> {
+        let digits = directory.config().digits();
+
+        // With roots, only their descendants (to the depth limit) are drawn.
+        let reachable: Option<BTreeSet<Hrid>> = if filters.roots.is_empty() {
+            None
+        } else {
+            let limit = filters.depth.filter(|&depth| depth > 0);
+            let mut reachable = BTreeSet::new();
+            let mut queue = VecDeque::new();
+            for root in &filters.roots {
+                if directory.find_by_hrid(root).is_none() {
+                    bail!("requirement {} not found", root.display(digits));
+                }
+                queue.push_back((root.clone(), 0));
+            }
+            while let Some((hrid, depth)) = queue.pop_front() {
+                if !reachable.insert(hrid.clone()) || limit.is_some_and(|limit| depth >= limit) {
+                    continue;
+                }
+                for child in directory.children_of(&hrid) {
+                    queue.push_back((child, depth + 1));
+                }
+            }
+            Some(reachable)
+        };
+
+        let included = |hrid: &Hrid| {
+            let namespace = hrid.namespace();
+            reachable
+                .as_ref()
+                .is_none_or(|reachable| reachable.contains(hrid))
+                && (filters.kind.is_empty()
+                    || filters
+                        .kind
+                        .iter()
+                        .any(|kind| kind.eq_ignore_ascii_case(hrid.kind())))
+                && (filters.namespace.is_empty()
+                    || filters.namespace.iter().any(|wanted| {
+                        namespace
+                            .iter()
+                            .any(|segment| segment.eq_ignore_ascii_case(wanted))
+                    }))
+        };
+
+        let mut views: Vec<_> = directory
+            .requirements()
+            .filter(|view| included(view.hrid))
+            .collect();
+        views.sort_by(|a, b| a.hrid.cmp(b.hrid));
+        let indices: HashMap<_, _> = views
+            .iter()
+            .enumerate()
+            .map(|(index, view)| (*view.uuid, index))
+            .collect();
+
+        let suspect: HashSet<_> = directory
+            .suspect_links()
+            .into_iter()
+            .map(|link| (link.child_uuid, link.parent_uuid))
+            .collect();
+        // Cycles are paths from child to parent, ending where they start.
+        let mut cycle_links = BTreeSet::new();
+        for cycle in directory.detect_cycles() {
+            for pair in cycle.windows(2) {
+                cycle_links.insert((pair[0].clone(), pair[1].clone()));
+            }
+        }
+
+        let nodes = views
+            .iter()
+            .map(|view| Node {
+                hrid: view.hrid.display(digits).to_string(),
+                title: view.title.trim().to_string(),
+                namespace: view
+                    .hrid
+                    .namespace()
+                    .into_iter()
+                    .map(String::from)
+                    .collect(),
+                cycle: cycle_links
+                    .iter()
+                    .any(|(child, parent)| child == view.hrid || parent == view.hrid),
+            })
+            .collect();
+
+        let mut edges = Vec::new();
+        for (child, view) in views.iter().enumerate() {
+            for (uuid, parent) in &view.parents {
+                let Some(&parent_index) = indices.get(uuid) else {
+                    continue;
+                };
+                edges.push(Edge {
+                    child,
+                    parent: parent_index,
+                    link_type: parent.link_type.clone(),
+                    suspect: suspect.contains(&(*view.uuid, *uuid)),
+                    cycle: cycle_links
+                        .contains(&(view.hrid.clone(), views[parent_index].hrid.clone())),
+                });
+            }
+        }
+        edges.sort_by_key(|edge| (edge.child, edge.parent));
+
+        Ok(Self { nodes, edges })
+    }
+
+    fn to_dot(&self) -> String {
+        let mut dot = String::from(
+            "digraph requirements {\n  rankdir=BT;\n  node [shape=box, style=\"rounded,filled\", \
+             fillcolor=\"#f6f8fa\", fontname=\"Helvetica\"];\n  edge [color=\"#57606a\", \
+             fontname=\"Helvetica\", fontsize=10];\n",
+        );
+        self.clusters(&mut dot, &self.all(), 0, &|out, node, indent| {
+            let node = &self.nodes[node];
+            let _ = write!(
+                out,
+                "{indent}{} [label={}",
+                dot_id(&node.hrid),
+                dot_id(&format!("{}\n{}", node.hrid, node.title))
+            );
+            if node.cycle {
+                let _ = write!(out, ", color=\"{CYCLE}\", penwidth=2");
+            }
+            out.push_str("];\n");
+        });
+
+        for edge in &self.edges {
+            let mut attributes = Vec::new();
+            if let Some(link_type) = &edge.link_type {
+                attributes.push(format!("label={}", dot_id(link_type)));
+            }
+            if edge.cycle {
+                attributes.push(format!("color=\"{CYCLE}\", penwidth=2"));
+            } else if edge.suspect {
+                attributes.push(format!("color=\"{SUSPECT}\", penwidth=2"));
+            }
+            if edge.suspect {
+                attributes.push("style=dashed, tooltip=\"suspect\"".to_string());
+            }
+            let _ = write!(
+                dot,
+                "  {} -> {}",
+                dot_id(&self.nodes[edge.child].hrid),
+                dot_id(&self.nodes[edge.parent].hrid)
+            );
+            if !attributes.is_empty() {
+                let _ = write!(dot, " [{}]", attributes.join(", "));
+            }
+            dot.push_str(";\n");
+        }
+        dot.push_str("}\n");
+        dot
+    }
+
+    fn to_mermaid(&self) -> String {
+        let mut mermaid = String::from("flowchart BT\n");
+        self.clusters(&mut mermaid, &self.all(), 0, &|out, node, indent| {
+            let node = &self.nodes[node];
+            let _ = writeln!(
+                out,
+                "{indent}{}[\"{}\"]",
+                mermaid_id(&node.hrid),
+                mermaid_text(&format!("{}: {}", node.hrid, node.title))
+            );
+        });
+
+        // Links are styled by their position in the diagram.
+        let mut styles: Vec<(String, Vec<usize>)> = Vec::new();
+        for (index, edge) in self.edges.iter().enumerate() {
+            let arrow = edge.link_type.as_ref().map_or_else(
+                || "-->".to_string(),
+                |link_type| format!("-->|{}|", mermaid_text(link_type)),
+            );
+            let _ = writeln!(
+                mermaid,
+                "  {} {arrow} {}",
+                mermaid_id(&self.nodes[edge.child].hrid),
+                mermaid_id(&self.nodes[edge.parent].hrid)
+            );
+
+            let style = match (edge.cycle, edge.suspect) {
+                (false, false) => continue,
+                (true, false) => format!("stroke:{CYCLE},stroke-width:3px"),
+                (true, true) => format!("stroke:{CYCLE},stroke-width:3px,stroke-dasharray:5 3"),
+                (false, true) => format!("stroke:{SUSPECT},stroke-width:2px,stroke-dasharray:5 3"),
+            };
+            match styles.iter_mut().find(|(existing, _)| *existing == style) {
+                Some((_, indices)) => indices.push(index),
+                None => styles.push((style, vec![index])),
+            }
+        }
+        for (style, indices) in styles {
+            let indices: Vec<String> = indices.iter().map(ToString::to_string).collect();
+            let _ = writeln!(mermaid, "  linkStyle {} {style}", indices.join(","));
+        }
+
+        let cycle: Vec<String> = self
+            .nodes
+            .iter()
+            .filter(|node| node.cycle)
+            .map(|node| mermaid_id(&node.hrid))
+            .collect();
+        if !cycle.is_empty() {
+            let _ = writeln!(
+                mermaid,
+                "  classDef cycle stroke:{CYCLE},stroke-width:3px\n  class {} cycle",
+                cycle.join(",")
+            );
+        }
+        mermaid
+    }
+
+    fn all(&self) -> Vec<usize> {
+        (0..self.nodes.len()).collect()
+    }
+
+    /// Writes `nodes`, which share the first `depth` namespace segments,
+    /// nesting a cluster for each further segment. Works for both formats,
+    /// which differ only in how a cluster opens and closes.
+    fn clusters(
+        &self,
+        out: &mut String,
+        nodes: &[usize],
+        depth: usize,
+        write_node: &dyn Fn(&mut String, usize, &str),
+    ) {
+        let indent = "  ".repeat(depth + 1);
+        let mut groups: Vec<(&str, Vec<usize>)> = Vec::new();
+        for &node in nodes {
+            match self.nodes[node].namespace.get(depth) {
+                None => write_node(out, node, &indent),
+                Some(segment) => match groups.iter_mut().find(|(name, _)| name == segment) {
+                    Some((_, members)) => members.push(node),
+                    None => groups.push((segment, vec![node])),
+                },
+            }
+        }
+
+        let dot = out.starts_with("digraph");
+        for (segment, members) in groups {
+            let path = self.nodes[members[0]].namespace[..=depth].join("-");
+            if dot {
+                let _ = writeln!(
+                    out,
+                    "{indent}subgraph {} {{\n{indent}  label={};",
+                    dot_id(&format!("cluster_{path}")),
+                    dot_id(segment)
+                );
+            } else {
+                let _ = writeln!(
+                    out,
+                    "{indent}subgraph {}[\"{}\"]",
+                    mermaid_id(&format!("ns-{path}")),
+                    mermaid_text(segment)
+                );
+            }
+            self.clusters(out, &members, depth + 1, write_node);
+            let _ = writeln!(out, "{indent}{}", if dot { "}" } else { "end" });
+        }
+    }
+}
+
+/// Quotes a DOT identifier or label.
+fn dot_id(text: &str) -> String {
+    format!(
+        "\"{}\"",
+        text.replace('\\', "\\\\")
+            .replace('"', "\\\"")
+            .replace('\n', "\\n")
+    )
+}
+
+/// A Mermaid node identifier for an HRID. Hyphens are replaced, since `-`
+/// starts Mermaid's link syntax.
+fn mermaid_id(hrid: &str) -> String {
+    hrid.replace('-', "_")
+}
+
+/// Escapes text for a quoted Mermaid label, using Mermaid's entity codes
+/// rather than HTML so the diagram can be embedded in a page as is.
+fn mermaid_text(text: &str) -> String {
+    text.replace('#', "#35;")
+        .replace('"', "#quot;")
+        .replace('<', "#lt;")
+        .replace('>', "#gt;")
+        .replace('|', "#124;")
+}
+
+#[cfg(test)]
+mod tests {
+    use requiem_core::{Directory, Hrid};
+    use tempfile::tempdir;
+
+    use super::{Diagram, Format, Graph};
+
+    fn graph(roots: &[&str], depth: Option<usize>, kind: &[&str]) -> Graph {
+        Graph {
+            roots: roots
+                .iter()
+                .map(|hrid| Hrid::try_from(*hrid).unwrap())
+                .collect(),
+            depth,
+            kind: kind.iter().map(ToString::to_string).collect(),
+            namespace: Vec::new(),
+            format: Format::Dot,
+            file: None,
+        }
+    }
+
+    #[test]
+    fn graph_clusters_namespaces_and_marks_suspect_links_and_cycles() {
+        let tmp = tempdir().unwrap();
+        let mut directory = Directory::new(tmp.path().to_path_buf()).unwrap();
+        let usr = directory
+            .add_requirement("USR", "# Log \"in\"".to_string())
+            .unwrap();
+        let sys = directory
+            .add_requirement_with_namespace(
+                vec!["AUTH".to_string()],
+                "SYS",
+                "# Sessions".to_string(),
+            )
+            .unwrap();
+        let tst = directory
+            .add_requirement_with_namespace(
+                vec!["AUTH".to_string()],
+                "TST",
+                "# Session test".to_string(),
+            )
+            .unwrap();
+        let other = directory
+            .add_requirement("SYS", "# Audit".to_string())
+            .unwrap();
+        directory.link_requirement(sys.hrid(), usr.hrid()).unwrap();
+        directory.link_requirement(tst.hrid(), sys.hrid()).unwrap();
+        directory
+            .link_requirement(other.hrid(), tst.hrid())
+            .unwrap();
+        directory
+            .update_requirement(sys.hrid(), None, Some("Changed".to_string()), None)
+            .unwrap();
+
+        let diagram = Diagram::build(&directory, &graph(&[], None, &[])).unwrap();
+        assert_eq!(
+            diagram.to_dot(),
+            "digraph requirements {\n  rankdir=BT;\n  node [shape=box, style=\"rounded,filled\", \
+             fillcolor=\"#f6f8fa\", fontname=\"Helvetica\"];\n  edge [color=\"#57606a\", \
+             fontname=\"Helvetica\", fontsize=10];\n  \"SYS-001\" [label=\"SYS-001\\nAudit\"];\n  \
+             \"USR-001\" [label=\"USR-001\\nLog \\\"in\\\"\"];\n  subgraph \"cluster_AUTH\" {\n    \
+             label=\"AUTH\";\n    \"AUTH-SYS-001\" [label=\"AUTH-SYS-001\\nSessions\"];\n    \
+             \"AUTH-TST-001\" [label=\"AUTH-TST-001\\nSession test\"];\n  }\n  \"SYS-001\" -> \
+             \"AUTH-TST-001\";\n  \"AUTH-SYS-001\" -> \"USR-001\";\n  \"AUTH-TST-001\" -> \
+             \"AUTH-SYS-001\" [color=\"#e8590c\", penwidth=2, style=dashed, \
+             tooltip=\"suspect\"];\n}\n"
+        );
+        assert_eq!(
+            diagram.to_mermaid(),
+            "flowchart BT\n  SYS_001[\"SYS-001: Audit\"]\n  USR_001[\"USR-001: Log \
+             #quot;in#quot;\"]\n  subgraph ns_AUTH[\"AUTH\"]\n    AUTH_SYS_001[\"AUTH-SYS-001: \
+             Sessions\"]\n    AUTH_TST_001[\"AUTH-TST-001: Session test\"]\n  end\n  SYS_001 --> \
+             AUTH_TST_001\n  AUTH_SYS_001 --> USR_001\n  AUTH_TST_001 --> AUTH_SYS_001\n  \
+             linkStyle 2 stroke:#e8590c,stroke-width:2px,stroke-dasharray:5 3\n"
+        );
+
+        // Roots limit the graph to their descendants, and kinds filter it.
+        let subtree = Diagram::build(&directory, &graph(&["AUTH-SYS-001"], Some(1), &[])).unwrap();
+        let hrids: Vec<_> = subtree
+            .nodes
+            .iter()
+            .map(|node| node.hrid.as_str())
+            .collect();
+        assert_eq!(hrids, ["AUTH-SYS-001", "AUTH-TST-001"]);
+        let kinds = Diagram::build(&directory, &graph(&[], None, &["sys"])).unwrap();
+        assert_eq!(kinds.nodes.len(), 2);
+        assert!(kinds.edges.is_empty());
+        assert!(Diagram::build(&directory, &graph(&["USR-002"], None, &[])).is_err());
+
+        // Close a cycle by hand, since linking refuses to.
+        let path = tmp.path().join("USR-001.md");
+        directory.flush().unwrap();
+        let contents = std::fs::read_to_string(&path).unwrap();
+        let parent = format!(
+            "parents:\n- uuid: {}\n  fingerprint: x\n  hrid: SYS-001\n",
+            other.uuid()
+        );
+        std::fs::write(
+            &path,
+            contents.replacen("---\n", &format!("---\n{parent}"), 1),
+        )
+        .unwrap();
+        let directory = Directory::new(tmp.path().to_path_buf()).unwrap();
+        let cyclic = Diagram::build(&directory, &graph(&[], None, &[])).unwrap();
+        assert!(cyclic.nodes.iter().all(|node| node.cycle));
+        assert!(cyclic.to_mermaid().ends_with(
+            "  classDef cycle stroke:#c92a2a,stroke-width:3px\n  class \
+             SYS_001,USR_001,AUTH_SYS_001,AUTH_TST_001 cycle\n"
+        ));
+    }
+}
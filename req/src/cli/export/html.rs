@@ -0,0 +1,198 @@
+//! `req export html`: the requirements as a static site that can be browsed
+//! offline.
+
+use std::{
+    fmt::Write as _,
+    fs, io,
+    path::{Component, PathBuf},
+};
+
+use anyhow::{bail, Context};
+use requiem_core::{storage::html, Directory};
+
+use crate::cli::terminal::Colorize;
+
+#[derive(Debug, clap::Parser)]
+pub struct Html {
+    /// The directory to write the site to (created if missing)
+    #[arg(value_name = "OUTDIR")]
+    outdir: PathBuf,
+
+    /// Don't print a summary
+    #[arg(short, long)]
+    quiet: bool,
+}
+
+/// The file listing what the last export wrote, so that re-exporting only
+/// removes files it wrote itself.
+const MANIFEST: &str = ".req-site";
+
+impl Html {
+    pub fn run(self, root: PathBuf) -> anyhow::Result<()> {
+        let directory = Directory::new(root)?;
+        let files = html::export(&directory);
+        let previous = self.previous_export()?;
+
+        for (path, contents) in &files {
+            let path = self.outdir.join(path);
+            if let Some(parent) = path.parent() {
+                fs::create_dir_all(parent)
+                    .with_context(|| format!("failed to create {}", parent.display()))?;
+            }
+            fs::write(&path, contents)
+                .with_context(|| format!("failed to write {}", path.display()))?;
+        }
+
+        // Remove the pages of requirements and kinds that no longer exist, so
+        // re-exporting into the same directory doesn't leave them behind.
+        for stale in previous.iter().filter(|path| !files.contains_key(*path)) {
+            let path = self.outdir.join(stale);
+            match fs::remove_file(&path) {
+                Err(error) if error.kind() != io::ErrorKind::NotFound => {
+                    return Err(error)
+                        .with_context(|| format!("failed to remove {}", path.display()));
+                }
+                _ => {}
+            }
+        }
+
+        let mut manifest = String::new();
+        for path in files.keys() {
+            let _ = writeln!(manifest, "{}", path.display());
+        }
+        let path = self.outdir.join(MANIFEST);
+        fs::write(&path, manifest)
+            .with_context(|| format!("failed to write {}", path.display()))?;
+
+        if !self.quiet {
+            println!(
+                "{}",
+                format!(
+                    "✅ Wrote {} requirement pages to {}",
+                    directory.requirements().count(),
+                    self.outdir.display()
+                )
+                .success()
+            );
+        }
+        Ok(())
+    }
+
+    /// The files written by the last export to the output directory.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the output directory has files but no manifest,
+    /// since they weren't written by an export and could be overwritten.
+    fn previous_export(&self) -> anyhow::Result<Vec<PathBuf>> {
+        let manifest = match fs::read_to_string(self.outdir.join(MANIFEST)) {
+            Ok(manifest) => manifest,
+            Err(error) if error.kind() == io::ErrorKind::NotFound => {
+                let empty =
+                    fs::read_dir(&self.outdir).map_or(true, |mut entries| entries.next().is_none());
+                if !empty {
+                    bail!(
+                        "{} is not empty, and wasn't written by `req export html`; choose an \
+                         empty or new directory",
+                        self.outdir.display()
+                    );
+                }
+                return Ok(Vec::new());
+            }
+            Err(error) => {
+                return Err(error).with_context(|| {
+                    format!("failed to read {}", self.outdir.join(MANIFEST).display())
+                });
+            }
+        };
+        // Only paths inside the output directory are ever removed.
+        Ok(manifest
+            .lines()
+            .map(PathBuf::from)
+            .filter(|path| {
+                path.components()
+                    .all(|component| matches!(component, Component::Normal(_)))
+            })
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use requiem_core::Directory;
+    use tempfile::tempdir;
+
+    use super::Html;
+
+    #[test]
+    fn html_run_writes_the_site_and_removes_stale_pages() {
+        let tmp = tempdir().unwrap();
+        let root = tmp.path().join("requirements");
+        let outdir = tmp.path().join("site");
+        let mut directory = Directory::new(root.clone()).unwrap();
+        let usr = directory
+            .add_requirement("USR", "# Log in".to_string())
+            .unwrap();
+        let sys = directory
+            .add_requirement("SYS", "# Sessions".to_string())
+            .unwrap();
+        directory.link_requirement(sys.hrid(), usr.hrid()).unwrap();
+        directory.flush().unwrap();
+
+        let command = || Html {
+            outdir: outdir.clone(),
+            quiet: true,
+        };
+        command().run(root.clone()).unwrap();
+        for page in [
+            "index.html",
+            "graph.html",
+            "search-index.js",
+            "kinds/SYS.html",
+            "requirements/USR-001.html",
+            "requirements/SYS-001.html",
+        ] {
+            assert!(outdir.join(page).is_file(), "{page} was not written");
+        }
+
+        let mut directory = Directory::new(root.clone()).unwrap();
+        directory.delete_requirement(sys.hrid()).unwrap();
+        directory.flush().unwrap();
+        command().run(root).unwrap();
+        assert!(outdir.join("requirements/USR-001.html").is_file());
+        assert!(!outdir.join("requirements/SYS-001.html").exists());
+        assert!(!outdir.join("kinds/SYS.html").exists());
+    }
+
+    #[test]
+    fn html_run_keeps_files_it_did_not_write() {
+        let tmp = tempdir().unwrap();
+        let root = tmp.path().join("requirements");
+        let outdir = tmp.path().join("site");
+        let mut directory = Directory::new(root.clone()).unwrap();
+        directory
+            .add_requirement("USR", "# Log in".to_string())
+            .unwrap();
+        directory.flush().unwrap();
+        let command = || Html {
+            outdir: outdir.clone(),
+            quiet: true,
+        };
+
+        // A directory of other files is refused.
+        std::fs::create_dir_all(&outdir).unwrap();
+        std::fs::write(outdir.join("index.html"), "hand-written").unwrap();
+        assert!(command().run(root.clone()).is_err());
+        assert_eq!(
+            std::fs::read_to_string(outdir.join("index.html")).unwrap(),
+            "hand-written"
+        );
+
+        // Files added to an exported site are kept by the next export.
+        std::fs::remove_file(outdir.join("index.html")).unwrap();
+        command().run(root.clone()).unwrap();
+        std::fs::write(outdir.join("requirements/notes.html"), "hand-written").unwrap();
+        command().run(root).unwrap();
+        assert!(outdir.join("requirements/notes.html").is_file());
+    }
+}
@@ -0,0 +1,46 @@
+//! `req export json`: the whole repository as a single JSON document, which
+//! `req import json` restores.
+
+use std::{fs, path::PathBuf};
+
+use anyhow::Context;
+use requiem_core::{storage::json, Directory};
+
+use crate::cli::terminal::Colorize;
+
+#[derive(Debug, clap::Parser)]
+pub struct Json {
+    /// The file to write (standard output if omitted)
+    #[arg(value_name = "FILE")]
+    file: Option<PathBuf>,
+
+    /// Write the JSON Schema of the document instead
+    #[arg(long)]
+    schema: bool,
+}
+
+impl Json {
+    pub fn run(self, root: PathBuf) -> anyhow::Result<()> {
+        let (document, summary) = if self.schema {
+            (json::SCHEMA.to_string(), "the JSON Schema".to_string())
+        } else {
+            let directory = Directory::new(root)?;
+            let count = directory.requirements().count();
+            let document = json::export(&directory).context("failed to read the templates")?;
+            (document, format!("{count} requirements"))
+        };
+
+        match &self.file {
+            None => print!("{document}"),
+            Some(file) => {
+                fs::write(file, document)
+                    .with_context(|| format!("failed to write {}", file.display()))?;
+                println!(
+                    "{}",
+                    format!("✅ Wrote {} ({summary})", file.display()).success()
+                );
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,365 @@
+//! `req export matrix`: a traceability matrix between two sets of kinds.
+//!
+//! Each cell records whether the row and column requirements are traced to
+//! each other, in either direction: directly by a link, or (with
+//! `--transitive`) through intermediate requirements. Traces through a
+//! suspect link are marked, since the trace may no longer hold.
+
+use std::{
+    collections::{BTreeSet, HashSet},
+    fmt::Write,
+    fs,
+    path::PathBuf,
+};
+
+use anyhow::Context;
+use requiem_core::{
+    storage::{csv, html},
+    Directory, Hrid,
+};
+
+use crate::cli::terminal::Colorize;
+
+#[derive(Debug, clap::Parser)]
+pub struct Matrix {
+    /// Kinds of the row requirements (e.g. USR); comma-separated
+    #[arg(long, required = true, value_delimiter = ',')]
+    rows: Vec<String>,
+
+    /// Kinds of the column requirements (e.g. SYS,TST); comma-separated
+    #[arg(long, required = true, value_delimiter = ',')]
+    cols: Vec<String>,
+
+    /// Include traces through intermediate requirements
+    #[arg(long)]
+    transitive: bool,
+
+    /// Output format
+    #[arg(long, value_enum, default_value = "markdown")]
+    format: Format,
+
+    /// Write the matrix to a file instead of standard output
+    #[arg(long)]
+    file: Option<PathBuf>,
+}
+
+#[derive(Debug, Clone, Copy, Default, clap::ValueEnum)]
+enum Format {
+    Csv,
+    #[default]
+    Markdown,
+    Html,
+}
+
+impl Matrix {
+    pub fn run(self, root: PathBuf) -> anyhow::Result<()> {
+        let directory = Directory::new(root)?;
+        let table = Table::build(&directory, &self.rows, &self.cols, self.transitive);
+
+        let rendered = match self.format {
+            Format::Csv => table.to_csv(),
+            Format::Markdown => table.to_markdown(),
+            Format::Html => table.to_html(),
+        };
+
+        match &self.file {
+            None => print!("{rendered}"),
+            Some(file) => {
+                fs::write(file, rendered)
+                    .with_context(|| format!("failed to write {}", file.display()))?;
+                println!(
+                    "{}",
+                    format!(
+                        "✅ Wrote {} ({} rows × {} columns)",
+                        file.display(),
+                        table.rows.len(),
+                        table.cols.len()
+                    )
+                    .success()
+                );
+            }
+        }
+        Ok(())
+    }
+}
+
+/// How a row and column requirement are traced to each other.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Cell {
+    /// Whether they are linked directly, rather than through intermediate
+    /// requirements.
+    direct: bool,
+    /// Whether the trace passes through a suspect link.
+    suspect: bool,
+}
+
+impl Cell {
+    const fn mark(self) -> &'static str {
+        match (self.direct, self.suspect) {
+            (true, false) => "X",
+            (true, true) => "X!",
+            (false, false) => "T",
+            (false, true) => "T!",
+        }
+    }
+
+    const fn class(self) -> &'static str {
+        match (self.direct, self.suspect) {
+            (_, true) => "suspect",
+            (true, false) => "direct",
+            (false, false) => "transitive",
+        }
+    }
+}
+
+const LEGEND: &str = "X = direct link, T = transitive trace, ! = trace through a suspect link";
+
+/// A row or column heading.
+#[derive(Debug)]
+struct Heading {
+    hrid: String,
+    title: String,
+}
+
+/// A rendered-ready traceability matrix.
+#[derive(Debug)]
+struct Table {
+    rows: Vec<Heading>,
+    cols: Vec<Heading>,
+    cells: Vec<Vec<Option<Cell>>>,
+}
+
+/// A requirement with its transitive relatives, for finding traces.
+struct Node {
+    hrid: Hrid,
+    ancestors: BTreeSet<Hrid>,
+    descendants: BTreeSet<Hrid>,
+}
+
+impl Table {
+    fn build(directory: &Directory, rows: &[String], cols: &[String], transitive: bool) -> Self {
+        let digits = directory.config().digits();
+        let nodes = |kinds: &[String]| {
+            let kinds: HashSet<String> = kinds.iter().map(|k| k.trim().to_uppercase()).collect();
+            let mut hrids: Vec<Hrid> = directory
+                .requirements()
+                .filter(|view| kinds.contains(view.hrid.kind()))
+                .map(|view| view.hrid.clone())
+                .collect();
+            hrids.sort();
+            hrids
+                .into_iter()
+                .map(|hrid| Node {
+                    ancestors: directory.ancestors_of(&hrid).into_iter().collect(),
+                    descendants: directory.descendants_of(&hrid).into_iter().collect(),
+                    hrid,
+                })
+                .collect::<Vec<_>>()
+        };
+        let row_nodes = nodes(rows);
+        let col_nodes = nodes(cols);
+
+        let suspect: BTreeSet<(Hrid, Hrid)> = directory
+            .suspect_links()
+            .into_iter()
+            .map(|link| (link.child_hrid, link.parent_hrid))
+            .collect();
+        let linked = |child: &Hrid, parent: &Hrid| {
+            directory.find_by_hrid(child).is_some_and(|view| {
+                view.parents.iter().any(|(uuid, _)| {
+                    directory
+                        .find_by_uuid(*uuid)
+                        .is_some_and(|p| p.hrid == parent)
+                })
+            })
+        };
+
+        // A trace from `upper` down to `lower`.
+        let trace = |upper: &Node, lower: &Node| -> Option<Cell> {
+            if linked(&lower.hrid, &upper.hrid) {
+                return Some(Cell {
+                    direct: true,
+                    suspect: suspect.contains(&(lower.hrid.clone(), upper.hrid.clone())),
+                });
+            }
+            if !transitive || !upper.descendants.contains(&lower.hrid) {
+                return None;
+            }
+            let suspect = suspect.iter().any(|(child, parent)| {
+                (parent == &upper.hrid || upper.descendants.contains(parent))
+                    && (child == &lower.hrid || lower.ancestors.contains(child))
+            });
+            Some(Cell {
+                direct: false,
+                suspect,
+            })
+        };
+
+        let cells = row_nodes
+            .iter()
+            .map(|row| {
+                col_nodes
+                    .iter()
+                    .map(|col| trace(row, col).or_else(|| trace(col, row)))
+                    .collect()
+            })
+            .collect();
+
+        let heading = |node: Node| Heading {
+            title: directory
+                .find_by_hrid(&node.hrid)
+                .map(|view| view.title.trim().to_string())
+                .unwrap_or_default(),
+            hrid: node.hrid.display(digits).to_string(),
+        };
+        Self {
+            rows: row_nodes.into_iter().map(heading).collect(),
+            cols: col_nodes.into_iter().map(heading).collect(),
+            cells,
+        }
+    }
+
+    fn to_csv(&self) -> String {
+        let mut csv = String::from("requirement,title");
+        for col in &self.cols {
+            csv.push(',');
+            csv.push_str(&csv::escape(&col.hrid));
+        }
+        csv.push('\n');
+        for (row, cells) in self.rows.iter().zip(&self.cells) {
+            csv.push_str(&csv::escape(&row.hrid));
+            csv.push(',');
+            csv.push_str(&csv::escape(&row.title));
+            for cell in cells {
+                csv.push(',');
+                csv.push_str(cell.map_or("", Cell::mark));
+            }
+            csv.push('\n');
+        }
+        csv
+    }
+
+    fn to_markdown(&self) -> String {
+        let mut markdown = String::from("| Requirement |");
+        for col in &self.cols {
+            let _ = write!(markdown, " {} |", col.hrid);
+        }
+        markdown.push_str("\n|---|");
+        markdown.push_str(&":-:|".repeat(self.cols.len()));
+        markdown.push('\n');
+        for (row, cells) in self.rows.iter().zip(&self.cells) {
+            let label = if row.title.is_empty() {
+                row.hrid.clone()
+            } else {
+                format!("{} {}", row.hrid, row.title.replace('|', "\\|"))
+            };
+            let _ = write!(markdown, "| {label} |");
+            for cell in cells {
+                let _ = write!(markdown, " {} |", cell.map_or("", Cell::mark));
+            }
+            markdown.push('\n');
+        }
+        let _ = writeln!(markdown, "\n{LEGEND}");
+        markdown
+    }
+
+    fn to_html(&self) -> String {
+        let mut html = String::from(
+            "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>Traceability \
+             matrix</title>\n<style>\ntable { border-collapse: collapse; font-family: sans-serif; \
+             }\nth, td { border: 1px solid #ccc; padding: 4px 8px; }\ntd { text-align: center; \
+             }\nth.row { text-align: left; font-weight: normal; }\ntd.direct { background: \
+             #c8e6c9; }\ntd.transitive { background: #e3f2fd; }\ntd.suspect { background: \
+             #ffe0b2; font-weight: bold; }\n</style>\n</head>\n<body>\n<table>\n<thead>\n<tr><th>Requirement</th>",
+        );
+        for col in &self.cols {
+            let _ = write!(
+                html,
+                "<th title=\"{}\">{}</th>",
+                html::escape(&col.title),
+                html::escape(&col.hrid)
+            );
+        }
+        html.push_str("</tr>\n</thead>\n<tbody>\n");
+        for (row, cells) in self.rows.iter().zip(&self.cells) {
+            let _ = write!(
+                html,
+                "<tr><th class=\"row\"><strong>{}</strong> {}</th>",
+                html::escape(&row.hrid),
+                html::escape(&row.title)
+            );
+            for cell in cells {
+                match cell {
+                    Some(cell) => {
+                        let _ = write!(html, "<td class=\"{}\">{}</td>", cell.class(), cell.mark());
+                    }
+                    None => html.push_str("<td></td>"),
+                }
+            }
+            html.push_str("</tr>\n");
+        }
+        let _ = write!(
+            html,
+            "</tbody>\n</table>\n<p>{}</p>\n</body>\n</html>\n",
+            html::escape(LEGEND)
+        );
+        html
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use requiem_core::Directory;
+    use tempfile::tempdir;
+
+    use super::Table;
+
+    #[test]
+    fn matrix_marks_direct_transitive_and_suspect_traces() {
+        let tmp = tempdir().unwrap();
+        let mut directory = Directory::new(tmp.path().to_path_buf()).unwrap();
+        let usr1 = directory
+            .add_requirement("USR", "# Login, securely".to_string())
+            .unwrap();
+        let usr2 = directory
+            .add_requirement("USR", "# Logout".to_string())
+            .unwrap();
+        let sys = directory
+            .add_requirement("SYS", "# Auth".to_string())
+            .unwrap();
+        let tst1 = directory
+            .add_requirement("TST", "# Auth test".to_string())
+            .unwrap();
+        let tst2 = directory
+            .add_requirement("TST", "# Logout test".to_string())
+            .unwrap();
+        directory.link_requirement(sys.hrid(), usr1.hrid()).unwrap();
+        directory.link_requirement(tst1.hrid(), sys.hrid()).unwrap();
+        directory
+            .link_requirement(tst2.hrid(), usr2.hrid())
+            .unwrap();
+        directory
+            .update_requirement(usr2.hrid(), None, Some("Changed".to_string()), None)
+            .unwrap();
+
+        let rows = ["usr".to_string()];
+        let cols = ["TST".to_string()];
+        let direct_only = Table::build(&directory, &rows, &cols, false);
+        assert_eq!(
+            direct_only.to_csv(),
+            "requirement,title,TST-001,TST-002\nUSR-001,\"Login, securely\",,\nUSR-002,Logout,,X!\n"
+        );
+
+        let transitive = Table::build(&directory, &rows, &cols, true);
+        assert_eq!(
+            transitive.to_csv(),
+            "requirement,title,TST-001,TST-002\nUSR-001,\"Login, securely\",T,\nUSR-002,Logout,,X!\n"
+        );
+
+        // Rows below columns trace upwards.
+        let upwards = Table::build(&directory, &cols, &rows, true);
+        assert!(upwards.to_markdown().starts_with(
+            "| Requirement | USR-001 | USR-002 |\n|---|:-:|:-:|\n| TST-001 Auth test | T |  |\n"
+        ));
+    }
+}
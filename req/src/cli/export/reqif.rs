@@ -0,0 +1,72 @@
+//! `req export reqif`: the requirements as a ReqIF 1.2 document, for tools
+//! such as DOORS and Polarion.
+
+use std::{fs, path::PathBuf};
+
+use anyhow::Context;
+use chrono::Utc;
+use requiem_core::{storage::reqif, Directory};
+
+use crate::cli::terminal::Colorize;
+
+#[derive(Debug, clap::Parser)]
+pub struct Reqif {
+    /// The file to write (standard output if omitted)
+    #[arg(value_name = "FILE")]
+    file: Option<PathBuf>,
+}
+
+impl Reqif {
+    pub fn run(self, root: PathBuf) -> anyhow::Result<()> {
+        let directory = Directory::new(root)?;
+        let document = reqif::export(&directory, Utc::now());
+
+        match &self.file {
+            None => print!("{document}"),
+            Some(file) => {
+                fs::write(file, document)
+                    .with_context(|| format!("failed to write {}", file.display()))?;
+                println!(
+                    "{}",
+                    format!(
+                        "✅ Wrote {} ({} requirements)",
+                        file.display(),
+                        directory.requirements().count()
+                    )
+                    .success()
+                );
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use requiem_core::Directory;
+    use tempfile::tempdir;
+
+    use super::Reqif;
+
+    #[test]
+    fn reqif_run_writes_the_document() {
+        let tmp = tempdir().unwrap();
+        let root = tmp.path().join("reqs");
+        let mut directory = Directory::new(root.clone()).unwrap();
+        let usr = directory
+            .add_requirement("USR", "# Log in".to_string())
+            .unwrap();
+        directory.flush().unwrap();
+
+        let file = tmp.path().join("out.reqif");
+        Reqif {
+            file: Some(file.clone()),
+        }
+        .run(root)
+        .unwrap();
+
+        let document = std::fs::read_to_string(file).unwrap();
+        assert!(document.contains(&format!("IDENTIFIER=\"_{}\"", usr.uuid())));
+        assert!(document.contains("THE-VALUE=\"USR-001\""));
+    }
+}
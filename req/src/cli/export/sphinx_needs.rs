@@ -0,0 +1,88 @@
+//! `req export sphinx-needs`: the requirements as a sphinx-needs
+//! `needs.json`, for Sphinx projects to read with `needimport`.
+
+use std::{fs, path::PathBuf};
+
+use anyhow::Context;
+use chrono::Utc;
+use requiem_core::{storage::sphinx_needs, Directory};
+
+use crate::cli::terminal::Colorize;
+
+#[derive(Debug, clap::Parser)]
+pub struct SphinxNeeds {
+    /// The file to write (standard output if omitted)
+    #[arg(value_name = "FILE")]
+    file: Option<PathBuf>,
+
+    /// The project name recorded in the file (defaults to the name of the
+    /// requirements root)
+    #[arg(long)]
+    project: Option<String>,
+}
+
+impl SphinxNeeds {
+    pub fn run(self, root: PathBuf) -> anyhow::Result<()> {
+        let project = self.project.unwrap_or_else(|| {
+            root.canonicalize()
+                .ok()
+                .and_then(|root| {
+                    root.file_name()
+                        .map(|name| name.to_string_lossy().into_owned())
+                })
+                .unwrap_or_else(|| "requirements".to_string())
+        });
+        let directory = Directory::new(root)?;
+        let document = sphinx_needs::export(&directory, &project, Utc::now());
+
+        match &self.file {
+            None => print!("{document}"),
+            Some(file) => {
+                fs::write(file, document)
+                    .with_context(|| format!("failed to write {}", file.display()))?;
+                println!(
+                    "{}",
+                    format!(
+                        "✅ Wrote {} ({} needs)",
+                        file.display(),
+                        directory.requirements().count()
+                    )
+                    .success()
+                );
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use requiem_core::Directory;
+    use tempfile::tempdir;
+
+    use super::SphinxNeeds;
+
+    #[test]
+    fn sphinx_needs_run_names_the_project_after_the_root() {
+        let tmp = tempdir().unwrap();
+        let root = tmp.path().join("specs");
+        let mut directory = Directory::new(root.clone()).unwrap();
+        directory
+            .add_requirement("USR", "# Log in".to_string())
+            .unwrap();
+        directory.flush().unwrap();
+
+        let file = tmp.path().join("needs.json");
+        SphinxNeeds {
+            file: Some(file.clone()),
+            project: None,
+        }
+        .run(root)
+        .unwrap();
+
+        let needs: serde_json::Value =
+            serde_json::from_str(&std::fs::read_to_string(file).unwrap()).unwrap();
+        assert_eq!(needs["project"], "specs");
+        assert_eq!(needs["versions"][""]["needs"]["USR-001"]["type"], "usr");
+    }
+}
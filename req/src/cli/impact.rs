@@ -0,0 +1,269 @@
+//! The `req impact` command: which requirements a change would make suspect.
+
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt::Write,
+    path::PathBuf,
+};
+
+use requiem_core::{
+    domain::{requirement::ContentRef, ImpactedRequirement},
+    Directory, Hrid, Requirement,
+};
+use tracing::instrument;
+
+use crate::cli::{parse_hrid, terminal::Colorize};
+
+#[derive(Debug, clap::Parser)]
+pub struct Command {
+    /// The requirement to change (read from the file when using
+    /// `--if-changed`)
+    #[arg(value_parser = parse_hrid, required_unless_present = "if_changed")]
+    hrid: Option<Hrid>,
+
+    /// Preview the impact of an edited requirement file, such as an
+    /// uncommitted working copy
+    ///
+    /// Only children whose stored fingerprints don't match the edited content
+    /// are impacted, so edits the fingerprint ignores have no impact.
+    #[arg(long, value_name = "FILE")]
+    if_changed: Option<PathBuf>,
+
+    /// Output format
+    #[arg(long, value_enum, default_value = "table")]
+    output: OutputFormat,
+}
+
+#[derive(Debug, Clone, Copy, Default, clap::ValueEnum)]
+enum OutputFormat {
+    #[default]
+    Table,
+    Json,
+    /// Graphviz DOT graph of the impacted links
+    Dot,
+}
+
+impl Command {
+    #[instrument(level = "debug", skip(self))]
+    pub fn run(self, root: PathBuf) -> anyhow::Result<()> {
+        let directory = Directory::new(root)?;
+        let digits = directory.config().digits();
+
+        let edited = self
+            .if_changed
+            .as_deref()
+            .map(Requirement::load_from_path)
+            .transpose()?;
+        let hrid = match (&self.hrid, &edited) {
+            (Some(hrid), _) => hrid.clone(),
+            (None, Some(edited)) => directory
+                .find_by_uuid(edited.uuid())
+                .map_or_else(|| edited.hrid().clone(), |view| view.hrid.clone()),
+            (None, None) => unreachable!("clap requires HRID or --if-changed"),
+        };
+        let Some(view) = directory.find_by_hrid(&hrid) else {
+            anyhow::bail!("Requirement {} not found", hrid.display(digits));
+        };
+        if let Some(edited) = &edited {
+            anyhow::ensure!(
+                edited.uuid() == *view.uuid,
+                "{} is not a version of {} (UUIDs differ)",
+                self.if_changed.unwrap_or_default().display(),
+                hrid.display(digits)
+            );
+        }
+
+        let content = edited.as_ref().map(|edited| ContentRef {
+            title: edited.title(),
+            body: edited.body(),
+            tags: edited.tags(),
+        });
+        let impact = directory.impact_of(&hrid, content);
+
+        match self.output {
+            OutputFormat::Table => print_table(&directory, &hrid, &impact, digits),
+            OutputFormat::Json => {
+                let json = impact_json(&directory, &hrid, &impact, digits);
+                println!("{}", serde_json::to_string_pretty(&json)?);
+            }
+            OutputFormat::Dot => print!("{}", render_dot(&directory, &hrid, &impact, digits)),
+        }
+        Ok(())
+    }
+}
+
+fn title(directory: &Directory, hrid: &Hrid) -> String {
+    directory
+        .find_by_hrid(hrid)
+        .map(|view| view.title.to_string())
+        .unwrap_or_default()
+}
+
+/// Groups impacted requirements by depth, then kind.
+fn by_depth_and_kind(
+    impact: &[ImpactedRequirement],
+) -> BTreeMap<usize, BTreeMap<&str, Vec<&ImpactedRequirement>>> {
+    let mut groups: BTreeMap<usize, BTreeMap<&str, Vec<&ImpactedRequirement>>> = BTreeMap::new();
+    for impacted in impact {
+        groups
+            .entry(impacted.depth)
+            .or_default()
+            .entry(impacted.hrid.kind())
+            .or_default()
+            .push(impacted);
+    }
+    groups
+}
+
+fn print_table(directory: &Directory, hrid: &Hrid, impact: &[ImpactedRequirement], digits: usize) {
+    let changed = hrid.display(digits);
+    if impact.is_empty() {
+        println!(
+            "{}",
+            format!("✅ Changing {changed} would not make any links suspect").success()
+        );
+        return;
+    }
+
+    println!(
+        "Changing {changed} ({}) would make {} requirement(s) suspect",
+        title(directory, hrid),
+        impact.len()
+    );
+    for (depth, kinds) in by_depth_and_kind(impact) {
+        let count: usize = kinds.values().map(Vec::len).sum();
+        let note = if depth == 1 {
+            "links become suspect immediately"
+        } else {
+            "suspect once the level above is updated"
+        };
+        println!();
+        println!(
+            "{} {}",
+            format!("Depth {depth} ({count}):").info(),
+            note.dim()
+        );
+        for (kind, requirements) in kinds {
+            println!("  {kind} ({})", requirements.len());
+            for impacted in requirements {
+                println!(
+                    "    {}  {}",
+                    impacted.hrid.display(digits),
+                    title(directory, &impacted.hrid).dim()
+                );
+            }
+        }
+    }
+}
+
+fn impact_json(
+    directory: &Directory,
+    hrid: &Hrid,
+    impact: &[ImpactedRequirement],
+    digits: usize,
+) -> serde_json::Value {
+    use serde_json::json;
+
+    let mut by_kind: BTreeMap<&str, usize> = BTreeMap::new();
+    for impacted in impact {
+        *by_kind.entry(impacted.hrid.kind()).or_default() += 1;
+    }
+
+    let depths: Vec<_> = by_depth_and_kind(impact)
+        .into_iter()
+        .map(|(depth, kinds)| {
+            let requirements: Vec<_> = kinds
+                .into_values()
+                .flatten()
+                .map(|impacted| {
+                    json!({
+                        "hrid": impacted.hrid.display(digits).to_string(),
+                        "title": title(directory, &impacted.hrid),
+                        "kind": impacted.hrid.kind(),
+                    })
+                })
+                .collect();
+            json!({
+                "depth": depth,
+                "count": requirements.len(),
+                "requirements": requirements,
+            })
+        })
+        .collect();
+
+    json!({
+        "requirement": hrid.display(digits).to_string(),
+        "total": impact.len(),
+        "by_kind": by_kind,
+        "depths": depths,
+    })
+}
+
+/// Renders the changed requirement and the links to it that would become
+/// suspect, directly or transitively, as a DOT graph.
+fn render_dot(
+    directory: &Directory,
+    hrid: &Hrid,
+    impact: &[ImpactedRequirement],
+    digits: usize,
+) -> String {
+    let mut nodes: BTreeSet<&Hrid> = impact.iter().map(|impacted| &impacted.hrid).collect();
+    nodes.insert(hrid);
+
+    let mut dot = String::from("digraph impact {\n    rankdir=BT;\n    node [shape=box];\n");
+    let _ = writeln!(
+        dot,
+        "    \"{}\" [style=filled, fillcolor=orange];",
+        hrid.display(digits)
+    );
+    for impacted in impact {
+        let Some(view) = directory.find_by_hrid(&impacted.hrid) else {
+            continue;
+        };
+        for (parent_uuid, _) in &view.parents {
+            let Some(parent) = directory.find_by_uuid(*parent_uuid) else {
+                continue;
+            };
+            if nodes.contains(parent.hrid) {
+                let _ = writeln!(
+                    dot,
+                    "    \"{}\" -> \"{}\";",
+                    impacted.hrid.display(digits),
+                    parent.hrid.display(digits)
+                );
+            }
+        }
+    }
+    dot.push_str("}\n");
+    dot
+}
+
+#[cfg(test)]
+mod tests {
+    use requiem_core::Directory;
+    use tempfile::tempdir;
+
+    use super::render_dot;
+
+    #[test]
+    fn dot_graph_links_impacted_requirements() {
+        let tmp = tempdir().unwrap();
+        let mut directory = Directory::new(tmp.path().to_path_buf()).unwrap();
+        let usr = directory
+            .add_requirement("USR", "# User".to_string())
+            .unwrap();
+        let sys = directory
+            .add_requirement("SYS", "# System".to_string())
+            .unwrap();
+        let unrelated = directory
+            .add_requirement("SYS", "# Unrelated".to_string())
+            .unwrap();
+        directory.link_requirement(sys.hrid(), usr.hrid()).unwrap();
+
+        let impact = directory.impact_of(usr.hrid(), None);
+        assert_eq!(impact.len(), 1);
+        let dot = render_dot(&directory, usr.hrid(), &impact, 3);
+        assert!(dot.contains("\"SYS-001\" -> \"USR-001\";"));
+        assert!(!dot.contains(&unrelated.hrid().display(3).to_string()));
+    }
+}
@@ -0,0 +1,128 @@
+//! The `req import` commands: create and update requirements from documents
+//! written by other tools.
+//!
+//! Imported requirements remember where they came from, so importing a later
+//! version of the same document updates them in place rather than creating
+//! duplicates. `req import json` instead restores a whole repository written
+//! by `req export json`.
+
+use std::path::PathBuf;
+
+use requiem_core::{
+    storage::directory::{Import, ImportChange},
+    Directory,
+};
+
+use crate::cli::terminal::Colorize;
+
+mod csv;
+mod doorstop;
+mod json;
+mod reqif;
+
+#[derive(Debug, clap::Parser)]
+pub enum Command {
+    /// Import the requirements in a ReqIF document
+    ///
+    /// Each SPEC-OBJECT becomes a requirement of the given kind, remembering
+    /// its ReqIF identifier; each SPEC-RELATION becomes a parent link.
+    /// Re-importing updates previously imported requirements in place, so
+    /// changed requirements make links from their children suspect.
+    Reqif(reqif::Reqif),
+
+    /// Migrate the requirements in a Doorstop project
+    ///
+    /// Each document becomes a kind named after its prefix, and each item a
+    /// requirement remembering its UID, linked to the items it links to.
+    /// Item fields with no equivalent are reported rather than imported.
+    Doorstop(doorstop::Doorstop),
+
+    /// Create requirements in bulk from a CSV or TSV spreadsheet
+    ///
+    /// Each row becomes a requirement with the HRID in its HRID column, or
+    /// the next HRID of the kind in its kind column. Parents are linked
+    /// once every row is created. If any row is invalid, nothing is
+    /// imported.
+    Csv(csv::Csv),
+
+    /// Restore a repository from a document written by `req export json`
+    ///
+    /// The configuration and every requirement are written exactly as they
+    /// were exported, keeping UUIDs, paths, and link fingerprints. The
+    /// directory must not already have requirements.
+    Json(json::Json),
+}
+
+impl Command {
+    pub fn run(self, root: PathBuf) -> anyhow::Result<()> {
+        match self {
+            Self::Reqif(command) => command.run(root),
+            Self::Doorstop(command) => command.run(root),
+            Self::Csv(command) => command.run(root),
+            Self::Json(command) => command.run(root),
+        }
+    }
+}
+
+/// Splits a `--namespace` value such as `ACME-BRAKES` into its segments.
+fn namespace_segments(namespace: Option<&str>) -> Vec<String> {
+    namespace
+        .into_iter()
+        .flat_map(|namespace| namespace.split('-'))
+        .map(|segment| segment.trim().to_uppercase())
+        .filter(|segment| !segment.is_empty())
+        .collect()
+}
+
+fn print_import(directory: &Directory, import: &Import, dry_run: bool) {
+    let digits = directory.config().digits();
+    for entry in &import.entries {
+        let hrid = entry.hrid.display(digits);
+        let line = match entry.change {
+            ImportChange::Created => format!("{hrid} + created ({})", entry.identifier).success(),
+            ImportChange::Updated => format!("{hrid} ~ updated ({})", entry.identifier).info(),
+            ImportChange::Unchanged => format!("{hrid} · unchanged ({})", entry.identifier).dim(),
+        };
+        println!("{line}");
+    }
+
+    if !import.missing.is_empty() || !import.unresolved.is_empty() {
+        println!();
+    }
+    for (child, parent) in &import.unresolved {
+        println!(
+            "{}",
+            format!(
+                "⚠️  {child} links to {parent}, which was not imported, so the link was not made"
+            )
+            .warning()
+        );
+    }
+    for hrid in &import.missing {
+        println!(
+            "{}",
+            format!(
+                "⚠️  {} is no longer in the document, so was left as it is",
+                hrid.display(digits)
+            )
+            .warning()
+        );
+    }
+
+    println!(
+        "\nSummary: {} created, {} updated, {} unchanged, {} missing",
+        import.count(ImportChange::Created),
+        import.count(ImportChange::Updated),
+        import.count(ImportChange::Unchanged),
+        import.missing.len()
+    );
+    if import.count(ImportChange::Updated) > 0 {
+        println!(
+            "{}",
+            "Run 'req review' to investigate links made suspect by updated requirements.".dim()
+        );
+    }
+    if dry_run {
+        println!("{}", "Dry run: no files were changed".dim());
+    }
+}
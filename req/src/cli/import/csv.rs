@@ -0,0 +1,213 @@
+//! `req import csv`: create requirements in bulk from a CSV or TSV
+//! spreadsheet.
+
+use std::path::PathBuf;
+
+use anyhow::{bail, Context};
+use requiem_core::{
+    storage::{
+        csv::{self, Columns, CsvError},
+        directory::RowError,
+    },
+    Directory,
+};
+use tracing::instrument;
+
+use crate::cli::terminal::Colorize;
+
+#[derive(Debug, clap::Parser)]
+pub struct Csv {
+    /// The spreadsheet to import
+    #[arg(value_name = "FILE")]
+    file: PathBuf,
+
+    /// The field delimiter: a single character, or 'tab' (defaults to tab
+    /// for .tsv files and ',' otherwise)
+    #[arg(long, value_parser = parse_delimiter)]
+    delimiter: Option<char>,
+
+    /// The column holding each requirement's HRID [default: HRID]
+    #[arg(long, value_name = "COLUMN")]
+    hrid_column: Option<String>,
+
+    /// The column holding the kind of requirements without an HRID, with an
+    /// optional namespace (e.g., CORE-SYS) [default: Kind]
+    #[arg(long, value_name = "COLUMN")]
+    kind_column: Option<String>,
+
+    /// The column holding each requirement's title [default: Title]
+    #[arg(long, value_name = "COLUMN")]
+    title_column: Option<String>,
+
+    /// The column holding each requirement's body [default: Body]
+    #[arg(long, value_name = "COLUMN")]
+    body_column: Option<String>,
+
+    /// The column holding each requirement's tags, separated by commas or
+    /// semicolons [default: Tags]
+    #[arg(long, value_name = "COLUMN")]
+    tags_column: Option<String>,
+
+    /// The column holding the HRIDs of each requirement's parents, separated
+    /// by commas or semicolons [default: Parents]
+    #[arg(long, value_name = "COLUMN")]
+    parents_column: Option<String>,
+
+    /// Check the spreadsheet and show what would be created without making
+    /// changes
+    #[arg(long)]
+    dry_run: bool,
+
+    /// Suppress output
+    #[arg(long, short)]
+    quiet: bool,
+}
+
+fn parse_delimiter(value: &str) -> Result<char, String> {
+    match value {
+        "tab" | "\\t" | "\t" => Ok('\t'),
+        _ => {
+            let mut chars = value.chars();
+            match (chars.next(), chars.next()) {
+                (Some(c), None) => Ok(c),
+                _ => Err("expected a single character, or 'tab'".to_string()),
+            }
+        }
+    }
+}
+
+impl Csv {
+    #[instrument]
+    pub fn run(self, root: PathBuf) -> anyhow::Result<()> {
+        let text = std::fs::read_to_string(&self.file)
+            .with_context(|| format!("failed to read {}", self.file.display()))?;
+        let delimiter = self.delimiter.unwrap_or_else(|| {
+            let tsv = self
+                .file
+                .extension()
+                .is_some_and(|extension| extension.eq_ignore_ascii_case("tsv"));
+            if tsv {
+                '\t'
+            } else {
+                ','
+            }
+        });
+        let columns = Columns {
+            hrid: self.hrid_column,
+            kind: self.kind_column,
+            title: self.title_column,
+            body: self.body_column,
+            tags: self.tags_column,
+            parents: self.parents_column,
+        };
+
+        let requirements = match csv::read_requirements(&text, delimiter, &columns) {
+            Ok(requirements) => requirements,
+            Err(CsvError::InvalidRows(errors)) => invalid_rows(&self.file, &errors)?,
+            Err(error) => {
+                return Err(error)
+                    .with_context(|| format!("failed to read {}", self.file.display()))
+            }
+        };
+
+        let mut directory = Directory::new(root)?;
+        let created = match directory.add_requirements(&requirements) {
+            Ok(created) => created,
+            Err(errors) => invalid_rows(&self.file, &errors)?,
+        };
+        if !self.dry_run {
+            directory.flush()?;
+        }
+
+        if !self.quiet {
+            let digits = directory.config().digits();
+            for (hrid, requirement) in created.iter().zip(&requirements) {
+                println!(
+                    "{}",
+                    format!("{} + created  {}", hrid.display(digits), requirement.title).success()
+                );
+            }
+            println!(
+                "\nSummary: {} created, {} parent links",
+                created.len(),
+                requirements
+                    .iter()
+                    .map(|requirement| requirement.parents.len())
+                    .sum::<usize>()
+            );
+            if self.dry_run {
+                println!("{}", "Dry run: no files were changed".dim());
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Fails with every invalid row, so they can all be fixed at once.
+fn invalid_rows<T>(file: &std::path::Path, errors: &[RowError]) -> anyhow::Result<T> {
+    let rows = errors
+        .iter()
+        .map(|error| format!("  {error}"))
+        .collect::<Vec<_>>()
+        .join("\n");
+    bail!(
+        "{} has {} invalid rows, so nothing was imported:\n{rows}",
+        file.display(),
+        errors.len()
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use requiem_core::{Directory, Hrid};
+    use tempfile::tempdir;
+
+    use super::Csv;
+
+    fn command(file: std::path::PathBuf) -> Csv {
+        Csv {
+            file,
+            delimiter: None,
+            hrid_column: None,
+            kind_column: None,
+            title_column: None,
+            body_column: None,
+            tags_column: None,
+            parents_column: None,
+            dry_run: false,
+            quiet: true,
+        }
+    }
+
+    #[test]
+    fn csv_run_links_rows_or_imports_nothing() {
+        let tmp = tempdir().unwrap();
+        let root = tmp.path().join("reqs");
+        let file = tmp.path().join("draft.tsv");
+
+        std::fs::write(
+            &file,
+            "Kind\tHRID\tTitle\tParents\nSYS\t\tAuth\tUSR-001\n\tUSR-001\tLog in\t\n\tUSR-001\tDuplicate\t\n",
+        )
+        .unwrap();
+        let error = command(file.clone()).run(root.clone()).unwrap_err();
+        assert!(error.to_string().contains("line 4: USR-001 is repeated"));
+        assert_eq!(
+            Directory::new(root.clone()).unwrap().requirements().count(),
+            0
+        );
+
+        std::fs::write(
+            &file,
+            "Kind\tHRID\tTitle\tParents\nSYS\t\tAuth\tUSR-001\n\tUSR-001\tLog in\t\n",
+        )
+        .unwrap();
+        command(file).run(root.clone()).unwrap();
+        let directory = Directory::new(root).unwrap();
+        let sys = Hrid::try_from("SYS-001").unwrap();
+        assert_eq!(
+            directory.ancestors_of(&sys),
+            [Hrid::try_from("USR-001").unwrap()]
+        );
+    }
+}
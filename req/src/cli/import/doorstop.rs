@@ -0,0 +1,121 @@
+//! `req import doorstop`: migrate the requirements in a Doorstop project.
+
+use std::path::PathBuf;
+
+use anyhow::Context;
+use requiem_core::{
+    storage::doorstop::{self, DoorstopProject},
+    Directory,
+};
+use tracing::instrument;
+
+use super::{namespace_segments, print_import};
+use crate::cli::terminal::Colorize;
+
+#[derive(Debug, clap::Parser)]
+pub struct Doorstop {
+    /// The root of the Doorstop project
+    #[arg(value_name = "PATH")]
+    path: PathBuf,
+
+    /// The namespace to create requirements in, with segments separated by
+    /// '-' (e.g., LEGACY)
+    #[arg(long)]
+    namespace: Option<String>,
+
+    /// Show what would be imported, and what can't be, without making
+    /// changes
+    #[arg(long)]
+    dry_run: bool,
+
+    /// Suppress output
+    #[arg(long, short)]
+    quiet: bool,
+}
+
+impl Doorstop {
+    #[instrument]
+    pub fn run(self, root: PathBuf) -> anyhow::Result<()> {
+        let namespace = namespace_segments(self.namespace.as_deref());
+        let project = doorstop::read_project(&self.path, &namespace)
+            .with_context(|| format!("failed to read {}", self.path.display()))?;
+
+        let mut directory = Directory::new(root)?;
+        let import = directory.import_requirements(doorstop::SOURCE, &project.requirements)?;
+        if !self.dry_run {
+            directory.flush()?;
+        }
+
+        if !self.quiet {
+            println!(
+                "Found {} items in {} documents ({})\n",
+                project.requirements.len(),
+                project.documents.len(),
+                project.documents.join(", ")
+            );
+            print_unmapped(&project);
+            print_import(&directory, &import, self.dry_run);
+        }
+        Ok(())
+    }
+}
+
+fn print_unmapped(project: &DoorstopProject) {
+    if project.unmapped.is_empty() {
+        return;
+    }
+    println!("Not mapped:");
+    for (location, description) in &project.unmapped {
+        println!("{}", format!("⚠️  {location}: {description}").warning());
+    }
+    println!();
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use requiem_core::{Directory, Hrid};
+    use tempfile::tempdir;
+
+    use super::Doorstop;
+
+    #[test]
+    fn doorstop_run_links_documents_by_uid() {
+        let tmp = tempdir().unwrap();
+        let project = tmp.path().join("project");
+        fs::create_dir_all(project.join("sys/llr")).unwrap();
+        fs::write(
+            project.join("sys/.doorstop.yml"),
+            "settings:\n  prefix: SYS\n",
+        )
+        .unwrap();
+        fs::write(project.join("sys/SYS001.yml"), "level: 1\ntext: Brake.\n").unwrap();
+        fs::write(
+            project.join("sys/llr/.doorstop.yml"),
+            "settings:\n  prefix: LLR\n  parent: SYS\n",
+        )
+        .unwrap();
+        fs::write(
+            project.join("sys/llr/LLR001.yml"),
+            "level: 1\nlinks:\n- SYS001: 0bcd\ntext: Brake hard.\n",
+        )
+        .unwrap();
+
+        let root = tmp.path().join("reqs");
+        Doorstop {
+            path: project,
+            namespace: None,
+            dry_run: false,
+            quiet: true,
+        }
+        .run(root.clone())
+        .unwrap();
+
+        let directory = Directory::new(root).unwrap();
+        let llr = Hrid::try_from("LLR-001").unwrap();
+        let sys = Hrid::try_from("SYS-001").unwrap();
+        assert_eq!(directory.ancestors_of(&llr), [sys]);
+        assert!(directory.suspect_links().is_empty());
+    }
+}
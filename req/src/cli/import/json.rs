@@ -0,0 +1,89 @@
+//! `req import json`: rebuild a repository from a document written by
+//! `req export json`.
+
+use std::path::PathBuf;
+
+use anyhow::Context;
+use requiem_core::{storage::json, Directory};
+use tracing::instrument;
+
+use crate::cli::terminal::Colorize;
+
+#[derive(Debug, clap::Parser)]
+pub struct Json {
+    /// The document to restore
+    #[arg(value_name = "FILE")]
+    file: PathBuf,
+
+    /// Suppress output
+    #[arg(long, short)]
+    quiet: bool,
+}
+
+impl Json {
+    #[instrument]
+    pub fn run(self, root: PathBuf) -> anyhow::Result<()> {
+        let document = std::fs::read_to_string(&self.file)
+            .with_context(|| format!("failed to read {}", self.file.display()))?;
+        let repository = json::parse(&document)
+            .with_context(|| format!("failed to parse {}", self.file.display()))?;
+        let count = repository.requirements.len();
+
+        let mut directory = Directory::new(root)?;
+        directory.restore(repository.config, repository.requirements)?;
+        directory.flush()?;
+        json::write_templates(directory.root(), &repository.templates)
+            .context("failed to write the templates")?;
+
+        if !self.quiet {
+            println!(
+                "{}",
+                format!(
+                    "✅ Restored {count} requirements into {}",
+                    directory.root().display()
+                )
+                .success()
+            );
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use requiem_core::{storage::json, Directory};
+    use tempfile::tempdir;
+
+    use super::Json;
+
+    #[test]
+    fn json_run_restores_into_an_empty_directory_only() {
+        let tmp = tempdir().unwrap();
+        let original = tmp.path().join("original");
+        let mut directory = Directory::new(original).unwrap();
+        let usr = directory
+            .add_requirement("USR", "# Log in".to_string())
+            .unwrap();
+        let sys = directory
+            .add_requirement("SYS", "# Sessions".to_string())
+            .unwrap();
+        directory.link_requirement(sys.hrid(), usr.hrid()).unwrap();
+        let file = tmp.path().join("export.json");
+        std::fs::write(&file, json::export(&directory).unwrap()).unwrap();
+
+        let restored = tmp.path().join("restored");
+        let command = || Json {
+            file: file.clone(),
+            quiet: true,
+        };
+        command().run(restored.clone()).unwrap();
+
+        let directory = Directory::new(restored.clone()).unwrap();
+        let view = directory.find_by_hrid(sys.hrid()).unwrap();
+        assert_eq!(*view.uuid, sys.uuid());
+        assert_eq!(directory.children_of(usr.hrid()), [sys.hrid().clone()]);
+
+        let error = command().run(restored).unwrap_err();
+        assert!(error.to_string().contains("already has requirements"));
+    }
+}
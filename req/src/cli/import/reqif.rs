@@ -0,0 +1,153 @@
+//! `req import reqif`: create and update requirements from a ReqIF document.
+
+use std::path::PathBuf;
+
+use anyhow::Context;
+use requiem_core::{storage::reqif, Directory};
+use tracing::instrument;
+
+use super::{namespace_segments, print_import};
+
+#[derive(Debug, clap::Parser)]
+pub struct Reqif {
+    /// The ReqIF document to import
+    #[arg(value_name = "FILE")]
+    file: PathBuf,
+
+    /// The kind of requirement to create (e.g., CUS)
+    #[arg(long)]
+    kind: String,
+
+    /// The namespace to create requirements in, with segments separated by
+    /// '-' (e.g., ACME-BRAKES)
+    #[arg(long)]
+    namespace: Option<String>,
+
+    /// Show what would be imported without making changes
+    #[arg(long)]
+    dry_run: bool,
+
+    /// Suppress output
+    #[arg(long, short)]
+    quiet: bool,
+}
+
+impl Reqif {
+    #[instrument]
+    pub fn run(self, root: PathBuf) -> anyhow::Result<()> {
+        let document = std::fs::read_to_string(&self.file)
+            .with_context(|| format!("failed to read {}", self.file.display()))?;
+        let kind = self.kind.trim().to_uppercase();
+        let namespace = namespace_segments(self.namespace.as_deref());
+        let document = reqif::parse(&document, &kind, &namespace)
+            .with_context(|| format!("failed to parse {}", self.file.display()))?;
+        let requirements = document.requirements;
+
+        let mut directory = Directory::new(root)?;
+        let import = directory.import_requirements(&document.source, &requirements)?;
+        if !self.dry_run {
+            directory.flush()?;
+        }
+
+        if !self.quiet {
+            println!("Found {} requirements\n", requirements.len());
+            print_import(&directory, &import, self.dry_run);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use requiem_core::{Directory, Hrid};
+    use tempfile::tempdir;
+
+    use super::Reqif;
+
+    fn document(header: &str, text: &str) -> String {
+        format!(
+            r#"<REQ-IF xmlns="http://www.omg.org/spec/ReqIF/20110401/reqif.xsd">
+  <THE-HEADER><REQ-IF-HEADER IDENTIFIER="{header}"/></THE-HEADER>
+  <CORE-CONTENT><REQ-IF-CONTENT>
+    <SPEC-TYPES>
+      <SPEC-OBJECT-TYPE IDENTIFIER="type"><SPEC-ATTRIBUTES>
+        <ATTRIBUTE-DEFINITION-STRING IDENTIFIER="text" LONG-NAME="ReqIF.Text"/>
+      </SPEC-ATTRIBUTES></SPEC-OBJECT-TYPE>
+    </SPEC-TYPES>
+    <SPEC-OBJECTS>
+      <SPEC-OBJECT IDENTIFIER="_brakes" LONG-NAME="Brakes"><VALUES>
+        <ATTRIBUTE-VALUE-STRING THE-VALUE="{text}">
+          <DEFINITION><ATTRIBUTE-DEFINITION-STRING-REF>text</ATTRIBUTE-DEFINITION-STRING-REF></DEFINITION>
+        </ATTRIBUTE-VALUE-STRING>
+      </VALUES></SPEC-OBJECT>
+    </SPEC-OBJECTS>
+  </REQ-IF-CONTENT></CORE-CONTENT>
+</REQ-IF>"#
+        )
+    }
+
+    #[test]
+    fn reqif_run_creates_then_updates_in_place() {
+        let tmp = tempdir().unwrap();
+        let root = tmp.path().join("reqs");
+        let file = tmp.path().join("customer.reqif");
+        let command = |dry_run| Reqif {
+            file: file.clone(),
+            kind: "cus".to_string(),
+            namespace: Some("acme".to_string()),
+            dry_run,
+            quiet: true,
+        };
+        let body = || {
+            let directory = Directory::new(root.clone()).unwrap();
+            let hrid = Hrid::try_from("ACME-CUS-001").unwrap();
+            let requirement = directory.requirement_by_hrid(&hrid)?;
+            assert_eq!(requirement.origin().unwrap().identifier, "_brakes");
+            Some(requirement.body().to_string())
+        };
+
+        std::fs::write(&file, document("_acme", "Stop quickly.")).unwrap();
+        command(true).run(root.clone()).unwrap();
+        assert_eq!(body(), None);
+        command(false).run(root.clone()).unwrap();
+        assert_eq!(body().as_deref(), Some("Stop quickly."));
+
+        std::fs::write(&file, document("_acme", "Stop very quickly.")).unwrap();
+        command(false).run(root.clone()).unwrap();
+        assert_eq!(body().as_deref(), Some("Stop very quickly."));
+        assert_eq!(Directory::new(root).unwrap().requirements().count(), 1);
+    }
+
+    #[test]
+    fn reqif_run_keeps_documents_apart() {
+        let tmp = tempdir().unwrap();
+        let root = tmp.path().join("reqs");
+        let import = |name: &str, header: &str, namespace: &str| {
+            let file = tmp.path().join(name);
+            std::fs::write(&file, document(header, namespace)).unwrap();
+            Reqif {
+                file,
+                kind: "cus".to_string(),
+                namespace: Some(namespace.to_string()),
+                dry_run: false,
+                quiet: true,
+            }
+            .run(root.clone())
+            .unwrap();
+        };
+
+        // Both documents use the same object identifier.
+        import("acme.reqif", "_acme", "acme");
+        import("globex.reqif", "_globex", "globex");
+        import("acme.reqif", "_acme", "acme");
+
+        let directory = Directory::new(root).unwrap();
+        for (hrid, body) in [("ACME-CUS-001", "acme"), ("GLOBEX-CUS-001", "globex")] {
+            let requirement = directory
+                .requirement_by_hrid(&Hrid::try_from(hrid).unwrap())
+                .unwrap();
+            assert_eq!(requirement.body(), body);
+        }
+        assert_eq!(directory.requirements().count(), 2);
+    }
+}
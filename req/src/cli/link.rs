@@ -16,6 +16,10 @@ pub struct Command {
     /// The human-readable ID of the parent document
     #[clap(value_parser = parse_hrid)]
     parent: Hrid,
+
+    /// The kind of relationship (e.g. `satisfies`, `verifies`)
+    #[arg(long = "type", value_name = "TYPE")]
+    link_type: Option<String>,
 }
 
 impl Command {
@@ -25,13 +29,19 @@ impl Command {
         let digits = directory.config().digits();
         let child = &self.child;
         let parent = &self.parent;
+        let view =
+            directory.link_requirement_with_type(child, parent, self.link_type.as_deref())?;
+        let suffix = view
+            .parents
+            .iter()
+            .find(|(_, info)| info.hrid == *parent)
+            .and_then(|(_, info)| info.link_type.as_deref())
+            .map_or_else(String::new, |link_type| format!(" ({link_type})"));
         let msg = format!(
-            "Linked {} to {}",
+            "Linked {} to {}{suffix}",
             child.display(digits),
             parent.display(digits)
         );
-
-        directory.link_requirement(&self.child, &self.parent)?;
         directory.flush()?;
 
         println!("{msg}");
@@ -73,6 +83,7 @@ mod tests {
         let link = Command {
             child: child.hrid().clone(),
             parent: parent.hrid().clone(),
+            link_type: None,
         };
 
         link.run(root.clone()).expect("link command should succeed");
@@ -84,4 +95,38 @@ mod tests {
             .iter()
             .any(|(_uuid, info)| info.hrid == *parent.hrid()));
     }
+
+    #[test]
+    fn link_run_records_link_type() {
+        let tmp = tempdir().unwrap();
+        let root = tmp.path().to_path_buf();
+
+        let mut directory = Directory::new(root.clone()).expect("failed to load directory");
+        let parent = directory
+            .add_requirement("USR", "# Parent".to_string())
+            .unwrap();
+        let child = directory
+            .add_requirement("TST", "# Child".to_string())
+            .unwrap();
+        directory
+            .flush()
+            .expect("failed to flush initial requirements");
+
+        let link = Command {
+            child: child.hrid().clone(),
+            parent: parent.hrid().clone(),
+            link_type: Some("Verifies".to_string()),
+        };
+
+        link.run(root.clone()).expect("link command should succeed");
+
+        let directory = Directory::new(root).expect("failed to load directory");
+        let reloaded_child = collect_child(&directory, "TST");
+        let (_uuid, info) = reloaded_child
+            .parents
+            .iter()
+            .find(|(_uuid, info)| info.hrid == *parent.hrid())
+            .expect("child should be linked to parent");
+        assert_eq!(info.link_type.as_deref(), Some("verifies"));
+    }
 }
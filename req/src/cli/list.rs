@@ -15,7 +15,7 @@ use requiem_core::{Directory, Hrid};
 use tracing::instrument;
 use uuid::Uuid;
 
-use super::parse_hrid;
+use super::{parse_attribute, parse_hrid};
 
 mod entry;
 mod filter;
@@ -42,7 +42,10 @@ pub struct List {
     targets: Vec<Hrid>,
 
     /// Columns to display (comma-separated).
-    #[arg(long, value_delimiter = ',', value_name = "COL")]
+    ///
+    /// Use `attr:NAME` for a single custom attribute, or `attributes` for all
+    /// of them.
+    #[arg(long, value_delimiter = ',', value_name = "COL", value_parser = ListColumn::parse)]
     columns: Vec<ListColumn>,
 
     /// Sort field (default: hrid).
@@ -69,6 +72,22 @@ pub struct List {
     #[arg(long, value_delimiter = ',', value_name = "TAG")]
     tag: Vec<String>,
 
+    /// Filter by lifecycle status (comma-separated, case-insensitive).
+    #[arg(long, value_delimiter = ',', value_name = "STATUS")]
+    status: Vec<String>,
+
+    /// Filter by custom attribute value (repeatable, case-insensitive).
+    ///
+    /// Different attributes must all match; repeating an attribute matches
+    /// any of its values.
+    #[arg(long = "attr", value_name = "NAME=VALUE", value_parser = parse_attribute)]
+    attributes: Vec<(String, String)>,
+
+    /// Follow only links of these types in relationship views
+    /// (comma-separated, case-insensitive).
+    #[arg(long, value_delimiter = ',', value_name = "TYPE")]
+    link_type: Vec<String>,
+
     /// Show only requirements without parents.
     #[arg(long)]
     orphans: bool,
@@ -173,9 +192,10 @@ impl List {
             let entry = entries[idx].clone();
             for parent in &entry.parents {
                 if let Some(parent_idx) = index_by_uuid.get(&parent.uuid) {
-                    entries[*parent_idx]
-                        .children
-                        .push(LinkRef::new(entry.uuid, entry.hrid.clone()));
+                    entries[*parent_idx].children.push(
+                        LinkRef::new(entry.uuid, entry.hrid.clone())
+                            .with_link_type(parent.link_type.clone()),
+                    );
                 }
             }
         }
@@ -290,7 +310,7 @@ impl List {
             View::Context => {
                 let base = produce_base_rows(entries, filters, &target_indices);
                 let depth = resolve_depth(self.depth, 1);
-                augment_with_context(entries, index_by_uuid, base, depth)
+                augment_with_context(entries, index_by_uuid, filters, base, depth)
             }
         }
     }
@@ -327,9 +347,13 @@ impl fmt::Display for OutputFormat {
 /// Shared entry/row fixtures used by the submodule tests.
 #[cfg(test)]
 mod fixtures {
-    use std::{collections::HashMap, path::PathBuf};
+    use std::{
+        collections::{BTreeMap, HashMap},
+        path::PathBuf,
+    };
 
     use chrono::{Duration, TimeZone, Utc};
+    use requiem_core::domain::AttributeValue;
     use uuid::Uuid;
 
     use super::{
@@ -360,6 +384,12 @@ mod fixtures {
                 hrid: Hrid::try_from("SYS-001").unwrap(),
                 title: Some("Root".to_string()),
                 tags: vec!["core".to_string()],
+                status: None,
+                verification: None,
+                attributes: BTreeMap::from([(
+                    "priority".to_string(),
+                    AttributeValue::Text("high".to_string()),
+                )]),
                 created: base_time,
                 content: "# Root requirement\nPrimary".to_string(),
                 parents: Vec::new(),
@@ -372,6 +402,9 @@ mod fixtures {
                 hrid: Hrid::try_from("SYS-002").unwrap(),
                 title: Some("Child".to_string()),
                 tags: Vec::new(),
+                status: None,
+                verification: None,
+                attributes: BTreeMap::new(),
                 created: base_time + Duration::days(1),
                 content: "## Child details\nImplements root".to_string(),
                 parents: vec![LinkRef::new(root_uuid, root.hrid.clone())],
@@ -396,6 +429,9 @@ mod fixtures {
                 },
                 title: Some("Login".to_string()),
                 tags: vec!["Security".to_string(), "UI".to_string()],
+                status: None,
+                verification: None,
+                attributes: BTreeMap::new(),
                 created: base_time + Duration::days(2),
                 content: "Implements login".to_string(),
                 parents: vec![LinkRef::new(child_uuid, child.hrid.clone())],
@@ -466,6 +502,9 @@ mod fixtures {
             kinds: Vec::new(),
             namespaces: Vec::new(),
             tags: Vec::new(),
+            statuses: Vec::new(),
+            attributes: Vec::new(),
+            link_types: Vec::new(),
             orphans: false,
             leaves: false,
             contains: None,
@@ -497,6 +536,9 @@ mod tests {
             kind: Vec::new(),
             namespace: Vec::new(),
             tag: Vec::new(),
+            status: Vec::new(),
+            attributes: Vec::new(),
+            link_type: Vec::new(),
             orphans: false,
             leaves: false,
             contains: None,
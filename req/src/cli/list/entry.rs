@@ -1,9 +1,15 @@
 //! Requirement snapshots used by the list command.
 
-use std::path::PathBuf;
+use std::{
+    collections::{BTreeMap, HashMap},
+    path::PathBuf,
+};
 
 use chrono::{DateTime, Utc};
-use requiem_core::{Directory, Hrid, RequirementView};
+use requiem_core::{
+    domain::{AttributeValue, Verification},
+    Directory, Hrid, RequirementView,
+};
 use uuid::Uuid;
 
 /// Parsed requirement snapshot used for listing.
@@ -13,6 +19,10 @@ pub(super) struct Entry {
     pub(super) hrid: Hrid,
     pub(super) title: Option<String>,
     pub(super) tags: Vec<String>,
+    pub(super) status: Option<String>,
+    /// Rolled-up test verification, or `None` if no tests verify it.
+    pub(super) verification: Option<Verification>,
+    pub(super) attributes: BTreeMap<String, AttributeValue>,
     pub(super) created: DateTime<Utc>,
     pub(super) content: String,
     pub(super) parents: Vec<LinkRef>,
@@ -25,20 +35,36 @@ pub(super) struct Entry {
 pub(super) struct LinkRef {
     pub(super) uuid: Uuid,
     pub(super) hrid: Hrid,
+    pub(super) link_type: Option<String>,
 }
 
 impl LinkRef {
     pub(super) const fn new(uuid: Uuid, hrid: Hrid) -> Self {
-        Self { uuid, hrid }
+        Self {
+            uuid,
+            hrid,
+            link_type: None,
+        }
+    }
+
+    pub(super) fn with_link_type(mut self, link_type: Option<String>) -> Self {
+        self.link_type = link_type;
+        self
     }
 }
 
 pub(super) fn collect_entries(directory: &Directory) -> Vec<Entry> {
     let mut entries = Vec::new();
     let digits = directory.config().digits();
+    let verification = directory.verification();
 
     for requirement in directory.requirements() {
-        entries.push(entry_from_requirement(directory, &requirement, digits));
+        entries.push(entry_from_requirement(
+            directory,
+            &requirement,
+            &verification,
+            digits,
+        ));
     }
 
     entries
@@ -47,12 +73,15 @@ pub(super) fn collect_entries(directory: &Directory) -> Vec<Entry> {
 fn entry_from_requirement(
     directory: &Directory,
     requirement: &RequirementView,
+    verification: &HashMap<Uuid, Verification>,
     digits: usize,
 ) -> Entry {
     let parents = requirement
         .parents
         .iter()
-        .map(|(uuid, parent)| LinkRef::new(*uuid, parent.hrid.clone()))
+        .map(|(uuid, parent)| {
+            LinkRef::new(*uuid, parent.hrid.clone()).with_link_type(parent.link_type.clone())
+        })
         .collect::<Vec<_>>();
 
     let tags = requirement.tags.iter().cloned().collect::<Vec<_>>();
@@ -66,6 +95,9 @@ fn entry_from_requirement(
         hrid: requirement.hrid.clone(),
         title: Some(requirement.title.to_string()),
         tags,
+        status: requirement.status.map(str::to_string),
+        verification: verification.get(requirement.uuid).copied(),
+        attributes: requirement.attributes.clone(),
         created: *requirement.created,
         content: format!(
             "# {} {}\n\n{}",
@@ -3,7 +3,10 @@
 use anyhow::Context;
 use regex::Regex;
 
-use super::{entry::Entry, List};
+use super::{
+    entry::{Entry, LinkRef},
+    List,
+};
 
 /// Compiled filters derived from the command-line arguments.
 #[derive(Debug, Clone)]
@@ -11,6 +14,10 @@ pub(super) struct Filters {
     pub(super) kinds: Vec<String>,
     pub(super) namespaces: Vec<String>,
     pub(super) tags: Vec<String>,
+    pub(super) statuses: Vec<String>,
+    /// `(name, value)` pairs; values are lowercase.
+    pub(super) attributes: Vec<(String, String)>,
+    pub(super) link_types: Vec<String>,
     pub(super) orphans: bool,
     pub(super) leaves: bool,
     pub(super) contains: Option<String>,
@@ -44,6 +51,21 @@ impl Filters {
                 .map(String::as_str)
                 .map(str::to_ascii_lowercase)
                 .collect(),
+            statuses: cmd
+                .status
+                .iter()
+                .map(|s| s.trim().to_ascii_lowercase())
+                .collect(),
+            attributes: cmd
+                .attributes
+                .iter()
+                .map(|(name, value)| (name.clone(), value.to_ascii_lowercase()))
+                .collect(),
+            link_types: cmd
+                .link_type
+                .iter()
+                .map(|t| t.trim().to_ascii_lowercase())
+                .collect(),
             orphans: cmd.orphans,
             leaves: cmd.leaves,
             contains: cmd.contains.as_deref().map(str::to_ascii_lowercase),
@@ -55,6 +77,9 @@ impl Filters {
         !self.kinds.is_empty()
             || !self.namespaces.is_empty()
             || !self.tags.is_empty()
+            || !self.statuses.is_empty()
+            || !self.attributes.is_empty()
+            || !self.link_types.is_empty()
             || self.orphans
             || self.leaves
             || self.contains.is_some()
@@ -77,6 +102,20 @@ impl Filters {
         if !self.tags.is_empty() {
             parts.push(format!("tag: {}", self.tags.join(", ")));
         }
+        if !self.statuses.is_empty() {
+            parts.push(format!("status: {}", self.statuses.join(", ")));
+        }
+        if !self.attributes.is_empty() {
+            let pairs: Vec<_> = self
+                .attributes
+                .iter()
+                .map(|(name, value)| format!("{name}={value}"))
+                .collect();
+            parts.push(format!("attr: {}", pairs.join(", ")));
+        }
+        if !self.link_types.is_empty() {
+            parts.push(format!("link-type: {}", self.link_types.join(", ")));
+        }
         if self.orphans {
             parts.push("orphans".to_string());
         }
@@ -92,6 +131,17 @@ impl Filters {
         parts.join(", ")
     }
 
+    /// Whether relationship views should follow the given link.
+    ///
+    /// Untyped links are excluded once any link-type filter is active.
+    pub(super) fn follows(&self, link: &LinkRef) -> bool {
+        self.link_types.is_empty()
+            || link
+                .link_type
+                .as_ref()
+                .is_some_and(|link_type| self.link_types.contains(link_type))
+    }
+
     pub(super) fn matches(&self, entry: &Entry) -> bool {
         if !self.kinds.is_empty() {
             let kind = entry.hrid.kind().to_ascii_lowercase();
@@ -132,6 +182,31 @@ impl Filters {
             }
         }
 
+        if !self.statuses.is_empty() {
+            let status = entry
+                .status
+                .as_deref()
+                .map(str::to_ascii_lowercase)
+                .unwrap_or_default();
+            if !self.statuses.iter().any(|s| s == &status) {
+                return false;
+            }
+        }
+
+        for (name, _) in &self.attributes {
+            let value = entry
+                .attributes
+                .get(name)
+                .map(|value| value.to_string().to_ascii_lowercase());
+            if !self
+                .attributes
+                .iter()
+                .any(|(n, v)| n == name && value.as_ref() == Some(v))
+            {
+                return false;
+            }
+        }
+
         if self.orphans && !entry.parents.is_empty() {
             return false;
         }
@@ -181,6 +256,9 @@ mod tests {
             kinds: vec!["usr".to_string()],
             namespaces: vec!["auth".to_string()],
             tags: vec!["security".to_string()],
+            statuses: Vec::new(),
+            attributes: Vec::new(),
+            link_types: Vec::new(),
             orphans: false,
             leaves: false,
             contains: Some("login".to_string()),
@@ -201,5 +279,25 @@ mod tests {
         let mut filters_with_tags = build_filters();
         filters_with_tags.tags = vec!["missing".to_string()];
         assert!(!filters_with_tags.matches(leaf));
+
+        let mut filters_with_status = build_filters();
+        filters_with_status.statuses = vec!["approved".to_string()];
+        assert!(!filters_with_status.matches(leaf));
+        let mut approved_leaf = leaf.clone();
+        approved_leaf.status = Some("Approved".to_string());
+        assert!(filters_with_status.matches(&approved_leaf));
+
+        let root = &fixtures.entries()[fixtures.root_index()];
+        let mut by_attribute = super::super::fixtures::empty_filters();
+        by_attribute.attributes = vec![
+            ("priority".to_string(), "low".to_string()),
+            ("priority".to_string(), "high".to_string()),
+        ];
+        assert!(by_attribute.matches(root));
+        assert!(!by_attribute.matches(leaf));
+        by_attribute
+            .attributes
+            .push(("owner".to_string(), "alice".to_string()));
+        assert!(!by_attribute.matches(root));
     }
 }
@@ -1,9 +1,12 @@
 //! Rendering of list rows as table, JSON, CSV, or tree output.
 
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 
 use anyhow::Context;
-use clap::ValueEnum;
+use requiem_core::{
+    domain::{AttributeValue, Verification},
+    storage::csv,
+};
 use serde::Serialize;
 
 use super::{
@@ -14,7 +17,7 @@ use super::{
 };
 
 /// Available table columns.
-#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash, Default, ValueEnum)]
+#[derive(Clone, Debug, Eq, PartialEq, Hash, Default)]
 pub(super) enum ListColumn {
     #[default]
     Hrid,
@@ -24,26 +27,83 @@ pub(super) enum ListColumn {
     Parents,
     Children,
     Tags,
+    Status,
+    /// Rolled-up test verification.
+    Verification,
+    /// Every custom attribute, as `name=value` pairs.
+    Attributes,
     Path,
     Created,
+    /// A single custom attribute, selected with `attr:NAME`.
+    Attribute(String),
 }
 
 impl ListColumn {
-    const fn header(self) -> &'static str {
+    const NAMES: [&'static str; 12] = [
+        "hrid",
+        "title",
+        "kind",
+        "namespace",
+        "parents",
+        "children",
+        "tags",
+        "status",
+        "verification",
+        "attributes",
+        "path",
+        "created",
+    ];
+
+    /// Parse a column name from the command line (case-insensitive).
+    pub(super) fn parse(s: &str) -> Result<Self, String> {
+        if let Some(name) = s.strip_prefix("attr:") {
+            return if name.is_empty() {
+                Err("attribute column needs a name, e.g. attr:priority".to_string())
+            } else {
+                Ok(Self::Attribute(name.to_string()))
+            };
+        }
+        Ok(match s.to_ascii_lowercase().as_str() {
+            "hrid" => Self::Hrid,
+            "title" => Self::Title,
+            "kind" => Self::Kind,
+            "namespace" => Self::Namespace,
+            "parents" => Self::Parents,
+            "children" => Self::Children,
+            "tags" => Self::Tags,
+            "status" => Self::Status,
+            "verification" => Self::Verification,
+            "attributes" => Self::Attributes,
+            "path" => Self::Path,
+            "created" => Self::Created,
+            _ => {
+                return Err(format!(
+                    "invalid column '{s}' (possible values: {}, attr:NAME)",
+                    Self::NAMES.join(", ")
+                ));
+            }
+        })
+    }
+
+    fn header(&self) -> String {
         match self {
-            Self::Hrid => "HRID",
-            Self::Title => "Title",
-            Self::Kind => "Kind",
-            Self::Namespace => "Namespace",
-            Self::Parents => "Parents",
-            Self::Children => "Children",
-            Self::Tags => "Tags",
-            Self::Path => "Path",
-            Self::Created => "Created",
+            Self::Hrid => "HRID".to_string(),
+            Self::Title => "Title".to_string(),
+            Self::Kind => "Kind".to_string(),
+            Self::Namespace => "Namespace".to_string(),
+            Self::Parents => "Parents".to_string(),
+            Self::Children => "Children".to_string(),
+            Self::Tags => "Tags".to_string(),
+            Self::Status => "Status".to_string(),
+            Self::Verification => "Verification".to_string(),
+            Self::Attributes => "Attributes".to_string(),
+            Self::Path => "Path".to_string(),
+            Self::Created => "Created".to_string(),
+            Self::Attribute(name) => name.clone(),
         }
     }
 
-    fn value(self, entry: &Entry, digits: usize) -> String {
+    fn value(&self, entry: &Entry, digits: usize) -> String {
         match self {
             Self::Hrid => entry.hrid.display(digits).to_string(),
             Self::Title => entry.title.clone().unwrap_or_default(),
@@ -62,8 +122,24 @@ impl ListColumn {
                 .collect::<Vec<_>>()
                 .join(", "),
             Self::Tags => entry.tags.join(", "),
+            Self::Status => entry.status.clone().unwrap_or_default(),
+            Self::Verification => entry
+                .verification
+                .map(|verification| verification.to_string())
+                .unwrap_or_default(),
+            Self::Attributes => entry
+                .attributes
+                .iter()
+                .map(|(name, value)| format!("{name}={value}"))
+                .collect::<Vec<_>>()
+                .join(", "),
             Self::Path => entry.path.display().to_string(),
             Self::Created => entry.created.to_rfc3339(),
+            Self::Attribute(name) => entry
+                .attributes
+                .get(name)
+                .map(ToString::to_string)
+                .unwrap_or_default(),
         }
     }
 }
@@ -85,6 +161,12 @@ struct SerializableRow<'a> {
     #[serde(skip_serializing_if = "Option::is_none")]
     tags: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
+    status: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    verification: Option<&'static str>,
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    attributes: BTreeMap<&'a str, &'a AttributeValue>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     path: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     created: Option<String>,
@@ -254,10 +336,7 @@ fn render_table(
     let mut data: Vec<Vec<String>> = Vec::new();
 
     if !quiet {
-        headers = selected_columns
-            .iter()
-            .map(|column| column.header().to_string())
-            .collect();
+        headers = selected_columns.iter().map(ListColumn::header).collect();
     }
 
     for row in rows {
@@ -392,6 +471,9 @@ fn render_json(
             ListColumn::Parents,
             ListColumn::Children,
             ListColumn::Tags,
+            ListColumn::Status,
+            ListColumn::Verification,
+            ListColumn::Attributes,
             ListColumn::Path,
             ListColumn::Created,
         ]
@@ -435,7 +517,7 @@ fn render_csv(
     if !quiet {
         let header_line = selected_columns
             .iter()
-            .map(|column| csv_escape(column.header()))
+            .map(|column| csv::escape(&column.header()))
             .collect::<Vec<_>>()
             .join(",");
         println!("{header_line}");
@@ -446,7 +528,7 @@ fn render_csv(
         let mut values = Vec::new();
 
         for column in &selected_columns {
-            let value = csv_escape(&column.value(entry, digits));
+            let value = csv::escape(&column.value(entry, digits));
             values.push(value);
         }
 
@@ -484,6 +566,9 @@ fn build_serializable_row<'a>(
         parents: None,
         children: None,
         tags: None,
+        status: None,
+        verification: None,
+        attributes: BTreeMap::new(),
         path: None,
         created: None,
     };
@@ -530,6 +615,21 @@ fn build_serializable_row<'a>(
                     row.tags = Some(entry.tags.join(", "));
                 }
             }
+            ListColumn::Status => {
+                row.status = entry.status.as_deref();
+            }
+            ListColumn::Verification => {
+                row.verification = entry.verification.map(Verification::as_str);
+            }
+            ListColumn::Attributes => {
+                row.attributes
+                    .extend(entry.attributes.iter().map(|(k, v)| (k.as_str(), v)));
+            }
+            ListColumn::Attribute(name) => {
+                if let Some((name, value)) = entry.attributes.get_key_value(name) {
+                    row.attributes.insert(name.as_str(), value);
+                }
+            }
             ListColumn::Path => {
                 row.path = Some(entry.path.display().to_string());
             }
@@ -542,15 +642,6 @@ fn build_serializable_row<'a>(
     row
 }
 
-fn csv_escape(value: &str) -> String {
-    if value.contains(',') || value.contains('"') || value.contains('\n') || value.contains('\r') {
-        let escaped = value.replace('"', "\"\"");
-        format!("\"{escaped}\"")
-    } else {
-        value.to_string()
-    }
-}
-
 #[cfg(test)]
 mod tests {
     use super::{
@@ -558,6 +649,24 @@ mod tests {
         *,
     };
 
+    #[test]
+    fn list_column_parse_accepts_names_and_attributes() {
+        assert_eq!(ListColumn::parse("Title"), Ok(ListColumn::Title));
+        assert_eq!(
+            ListColumn::parse("attr:priority"),
+            Ok(ListColumn::Attribute("priority".to_string()))
+        );
+        assert!(ListColumn::parse("attr:").is_err());
+        assert!(ListColumn::parse("bogus").is_err());
+
+        let fixtures = SampleEntries::new();
+        let root = &fixtures.entries()[fixtures.root_index()];
+        let priority = ListColumn::Attribute("priority".to_string());
+        assert_eq!(priority.header(), "priority");
+        assert_eq!(priority.value(root, 3), "high");
+        assert_eq!(ListColumn::Attributes.value(root, 3), "priority=high");
+    }
+
     #[test]
     fn render_rows_supports_all_formats() {
         let fixtures = SampleEntries::new();
@@ -695,11 +804,4 @@ mod tests {
         assert_eq!(row.path.as_deref(), Some("system/auth/USR-007.md"));
         assert!(row.created.is_some());
     }
-
-    #[test]
-    fn csv_escape_quotes_and_commas() {
-        assert_eq!(csv_escape("simple"), "simple");
-        assert_eq!(csv_escape("needs,comma"), "\"needs,comma\"");
-        assert_eq!(csv_escape("quote\"here"), "\"quote\"\"here\"");
-    }
 }
@@ -79,6 +79,7 @@ pub(super) fn produce_direction_rows(
         target_indices.iter().copied(),
         entries,
         index_by_uuid,
+        filters,
         limit,
         direction,
     );
@@ -126,7 +127,11 @@ pub(super) fn produce_tree_rows(
         }
 
         // Recursively visit children
-        for child in &entries[index].children {
+        for child in entries[index]
+            .children
+            .iter()
+            .filter(|link| filters.follows(link))
+        {
             if let Some(&child_idx) = index_by_uuid.get(&child.uuid) {
                 if filters.matches(&entries[child_idx]) || depth + 1 < limit {
                     dfs(
@@ -182,6 +187,7 @@ pub(super) fn produce_tree_rows(
 pub(super) fn augment_with_context(
     entries: &[Entry],
     index_by_uuid: &HashMap<Uuid, usize>,
+    filters: &Filters,
     mut rows: Vec<Row>,
     depth: usize,
 ) -> Vec<Row> {
@@ -195,8 +201,22 @@ pub(super) fn augment_with_context(
 
     for row in &rows {
         let seeds = std::iter::once(row.index);
-        let parents = traverse(seeds.clone(), entries, index_by_uuid, depth, Direction::Up);
-        let children = traverse(seeds, entries, index_by_uuid, depth, Direction::Down);
+        let parents = traverse(
+            seeds.clone(),
+            entries,
+            index_by_uuid,
+            filters,
+            depth,
+            Direction::Up,
+        );
+        let children = traverse(
+            seeds,
+            entries,
+            index_by_uuid,
+            filters,
+            depth,
+            Direction::Down,
+        );
 
         for rel_row in parents.into_iter().chain(children) {
             if existing.contains(&rel_row.index) {
@@ -220,6 +240,7 @@ fn traverse<I>(
     seeds: I,
     entries: &[Entry],
     index_by_uuid: &HashMap<Uuid, usize>,
+    filters: &Filters,
     depth_limit: usize,
     direction: Direction,
 ) -> Vec<Row>
@@ -232,14 +253,14 @@ where
     for seed in seeds {
         match direction {
             Direction::Up => {
-                for parent in &entries[seed].parents {
+                for parent in entries[seed].parents.iter().filter(|l| filters.follows(l)) {
                     if let Some(&idx) = index_by_uuid.get(&parent.uuid) {
                         queue.push_back((idx, 1));
                     }
                 }
             }
             Direction::Down => {
-                for child in &entries[seed].children {
+                for child in entries[seed].children.iter().filter(|l| filters.follows(l)) {
                     if let Some(&idx) = index_by_uuid.get(&child.uuid) {
                         queue.push_back((idx, 1));
                     }
@@ -270,14 +291,18 @@ where
 
         match direction {
             Direction::Up => {
-                for parent in &entries[index].parents {
+                for parent in entries[index].parents.iter().filter(|l| filters.follows(l)) {
                     if let Some(&parent_idx) = index_by_uuid.get(&parent.uuid) {
                         queue.push_back((parent_idx, depth + 1));
                     }
                 }
             }
             Direction::Down => {
-                for child in &entries[index].children {
+                for child in entries[index]
+                    .children
+                    .iter()
+                    .filter(|l| filters.follows(l))
+                {
                     if let Some(&child_idx) = index_by_uuid.get(&child.uuid) {
                         queue.push_back((child_idx, depth + 1));
                     }
@@ -426,6 +451,44 @@ mod tests {
         }));
     }
 
+    #[test]
+    fn produce_direction_rows_follows_only_matching_link_types() {
+        let fixtures = SampleEntries::new();
+        let mut entries = fixtures.entries().to_vec();
+        // Type the root → child link; leave child → leaf untyped.
+        for link in &mut entries[fixtures.root_index()].children {
+            link.link_type = Some("satisfies".to_string());
+        }
+        for link in &mut entries[fixtures.child_index()].parents {
+            link.link_type = Some("satisfies".to_string());
+        }
+
+        let mut filters = empty_filters();
+        filters.link_types = vec!["satisfies".to_string()];
+
+        let children = produce_direction_rows(
+            View::Descendants,
+            &entries,
+            fixtures.index_map(),
+            &filters,
+            &[fixtures.root_index()],
+            None,
+        );
+        assert_eq!(children.len(), 1);
+        assert_eq!(children[0].index, fixtures.child_index());
+
+        filters.link_types = vec!["verifies".to_string()];
+        let parents = produce_direction_rows(
+            View::Ancestors,
+            &entries,
+            fixtures.index_map(),
+            &filters,
+            &[fixtures.child_index()],
+            None,
+        );
+        assert!(parents.is_empty());
+    }
+
     #[test]
     fn produce_tree_rows_and_context() {
         let fixtures = SampleEntries::new();
@@ -443,6 +506,7 @@ mod tests {
         rows = augment_with_context(
             fixtures.entries(),
             fixtures.index_map(),
+            &filters,
             vec![row(fixtures.child_index(), Direction::None, 0)],
             2,
         );
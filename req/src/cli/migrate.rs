@@ -0,0 +1,119 @@
+//! The `req migrate` command: upgrade files to the current format version.
+
+use std::path::PathBuf;
+
+use requiem_core::{storage::directory::OutdatedFile, Directory};
+use tracing::instrument;
+
+use crate::cli::terminal::Colorize;
+
+#[derive(Debug, clap::Parser)]
+pub struct Command {
+    /// Check for outdated files without making changes (exits with code 2 if
+    /// any are found)
+    #[arg(long)]
+    check: bool,
+
+    /// Show what would be changed without making changes
+    #[arg(long)]
+    dry_run: bool,
+
+    /// Suppress output
+    #[arg(long, short)]
+    quiet: bool,
+}
+
+impl Command {
+    #[instrument]
+    pub fn run(self, root: PathBuf) -> anyhow::Result<()> {
+        let mut directory = Directory::new(root)?;
+        let outdated = directory.outdated_files();
+
+        if outdated.is_empty() {
+            if !self.quiet {
+                println!("{}", "✅ All files use the current format.".success());
+            }
+            return Ok(());
+        }
+
+        if self.check {
+            if !self.quiet {
+                println!(
+                    "{}",
+                    format!("⚠️  {} files use an older format", outdated.len()).warning()
+                );
+                print_files(&directory, &outdated);
+            }
+            std::process::exit(2);
+        }
+
+        if self.dry_run {
+            if !self.quiet {
+                println!("Would migrate {} files:", outdated.len());
+                print_files(&directory, &outdated);
+            }
+            return Ok(());
+        }
+
+        let migrated = directory.migrate();
+        directory.flush()?;
+
+        if !self.quiet {
+            println!(
+                "{}",
+                format!("✅ Migrated {} files", migrated.len()).success()
+            );
+            print_files(&directory, &migrated);
+        }
+        Ok(())
+    }
+}
+
+fn print_files(directory: &Directory, files: &[OutdatedFile]) {
+    for file in files {
+        let path = file
+            .path
+            .strip_prefix(directory.root())
+            .unwrap_or(&file.path);
+        println!(
+            "  • {} (v{} → v{})",
+            path.display(),
+            file.version,
+            file.latest
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use requiem_core::Directory;
+    use tempfile::tempdir;
+
+    use super::Command;
+
+    #[test]
+    fn migrate_run_upgrades_files_unless_dry_run() {
+        let tmp = tempdir().unwrap();
+        let root = tmp.path().to_path_buf();
+        let path = root.join("USR-001.md");
+        let v1 = "---\n_version: '1'\nuuid: 12b3f5c5-b1a8-4aa8-a882-20ff1c2aab53\ncreated: \
+                  2025-07-14T07:15:00Z\n---\n# USR-001 Title\n";
+        std::fs::write(&path, v1).unwrap();
+
+        let command = |dry_run| Command {
+            check: false,
+            dry_run,
+            quiet: true,
+        };
+
+        command(true).run(root.clone()).unwrap();
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), v1);
+
+        command(false).run(root.clone()).unwrap();
+        assert_eq!(
+            std::fs::read_to_string(&path).unwrap(),
+            v1.replace("_version: '1'", "_version: '2'")
+        );
+        assert!(Directory::new(root).unwrap().outdated_files().is_empty());
+    }
+}
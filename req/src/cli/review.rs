@@ -33,6 +33,11 @@ pub struct Command {
     #[arg(long, conflicts_with = "accept")]
     detail: bool,
 
+    /// Show what changed in each parent since the link was reviewed,
+    /// recovered from git history (implies --detail for table output)
+    #[arg(long, conflicts_with = "accept", conflicts_with = "quiet")]
+    diff: bool,
+
     /// Output format (table, json, ndjson)
     #[arg(
         long,
@@ -68,6 +73,10 @@ pub struct Command {
     #[arg(long)]
     kind: Option<String>,
 
+    /// Filter by link type (e.g. `verifies`)
+    #[arg(long = "type", value_name = "TYPE")]
+    link_type: Option<String>,
+
     /// Group output by field (parent, child, none)
     #[arg(long, value_name = "FIELD", conflicts_with = "accept")]
     group_by: Option<GroupBy>,
@@ -105,7 +114,11 @@ impl Command {
 
         // Handle empty results
         if suspect_links.is_empty() {
-            if self.child.is_some() || self.parent.is_some() || self.kind.is_some() {
+            if self.child.is_some()
+                || self.parent.is_some()
+                || self.kind.is_some()
+                || self.link_type.is_some()
+            {
                 println!("No suspect links matched the specified filters.");
                 return Ok(());
             }
@@ -133,10 +146,10 @@ impl Command {
 
         match self.format {
             SuspectFormat::Json => {
-                Self::output_json(&suspect_links, &directory, digits)?;
+                self.output_json(&suspect_links, &directory, digits)?;
             }
             SuspectFormat::Ndjson => {
-                Self::output_ndjson(&suspect_links, &directory, digits)?;
+                self.output_ndjson(&suspect_links, &directory, digits)?;
             }
             SuspectFormat::Table => {
                 self.output_table(&suspect_links, &directory, digits);
@@ -147,7 +160,8 @@ impl Command {
         std::process::exit(2);
     }
 
-    /// Apply the `--child`, `--parent`, and `--kind` filters in place.
+    /// Apply the `--child`, `--parent`, `--kind`, and `--type` filters in
+    /// place.
     fn apply_filters(&self, suspect_links: &mut Vec<SuspectLink>) {
         if let Some(ref child_filter) = self.child {
             suspect_links.retain(|link| &link.child_hrid == child_filter);
@@ -160,6 +174,11 @@ impl Command {
             let kind_upper = kind_filter.to_uppercase();
             suspect_links.retain(|link| link.child_hrid.kind() == kind_upper);
         }
+        if let Some(ref type_filter) = self.link_type {
+            // Link types are stored lowercase
+            let type_lower = type_filter.trim().to_lowercase();
+            suspect_links.retain(|link| link.link_type.as_deref() == Some(type_lower.as_str()));
+        }
     }
 }
 
@@ -5,14 +5,20 @@ use requiem_core::{Directory, SuspectLink};
 use super::{display_path, Command, GroupBy};
 use crate::cli::terminal::Colorize;
 
-/// Serialize one suspect link for the JSON and NDJSON formats.
-fn link_json(link: &SuspectLink, directory: &Directory, digits: usize) -> serde_json::Value {
+/// Serialize one suspect link for the JSON and NDJSON formats, with the
+/// changes to its parent if `diff` is set.
+fn link_json(
+    link: &SuspectLink,
+    directory: &Directory,
+    digits: usize,
+    diff: bool,
+) -> serde_json::Value {
     use serde_json::json;
 
     let child_req = directory.requirement_by_hrid(&link.child_hrid);
     let parent_req = directory.requirement_by_hrid(&link.parent_hrid);
 
-    json!({
+    let mut json = json!({
         "child": {
             "hrid": link.child_hrid.display(digits).to_string(),
             "title": child_req.map(|r| r.title().to_string()),
@@ -25,10 +31,64 @@ fn link_json(link: &SuspectLink, directory: &Directory, digits: usize) -> serde_
             "path": display_path(directory, &link.parent_hrid),
             "kind": link.parent_hrid.kind(),
         },
+        "link_type": &link.link_type,
         "status": "fingerprint drift",
         "stored_fingerprint": &link.stored_fingerprint,
         "current_fingerprint": &link.current_fingerprint,
-    })
+    });
+    if diff {
+        match directory.parent_changes(link) {
+            Ok(changes) => {
+                json["parent_changes"] = changes.map_or(
+                    serde_json::Value::Null,
+                    |changes| json!({ "commit": changes.commit, "diff": changes.diff }),
+                );
+            }
+            Err(error) => json["parent_changes_error"] = json!(error.to_string()),
+        }
+    }
+    json
+}
+
+/// Print the unified diff of a link's parent since the link was reviewed.
+fn print_parent_changes(link: &SuspectLink, directory: &Directory) {
+    let changes = match directory.parent_changes(link) {
+        Ok(Some(changes)) => changes,
+        Ok(None) => {
+            println!(
+                "  CHANGES: {}",
+                "unavailable (no committed version of the parent matches the stored fingerprint)"
+                    .dim()
+            );
+            return;
+        }
+        Err(error) => {
+            println!(
+                "  CHANGES: {}",
+                format!("unavailable (cannot search git history: {error})").warning()
+            );
+            return;
+        }
+    };
+
+    println!(
+        "  CHANGES: since commit {}",
+        &changes.commit[..changes.commit.len().min(12)]
+    );
+    for line in changes.diff.lines() {
+        let colored = if line.starts_with("+++") || line.starts_with("---") {
+            line.dim()
+        } else if line.starts_with('+') {
+            line.success()
+        } else if line.starts_with('-') {
+            line.warning()
+        } else if line.starts_with("@@") {
+            line.info()
+        } else {
+            line.to_string()
+        };
+        println!("    {colored}");
+    }
 }
 
 impl Command {
@@ -90,6 +150,7 @@ impl Command {
     }
 
     pub(super) fn output_json(
+        &self,
         suspect_links: &[SuspectLink],
         directory: &Directory,
         digits: usize,
@@ -116,7 +177,7 @@ impl Command {
 
         let links: Vec<_> = suspect_links
             .iter()
-            .map(|link| link_json(link, directory, digits))
+            .map(|link| link_json(link, directory, digits, self.diff))
             .collect();
 
         let output = json!({
@@ -134,12 +195,13 @@ impl Command {
     }
 
     pub(super) fn output_ndjson(
+        &self,
         suspect_links: &[SuspectLink],
         directory: &Directory,
         digits: usize,
     ) -> anyhow::Result<()> {
         for link in suspect_links {
-            let obj = link_json(link, directory, digits);
+            let obj = link_json(link, directory, digits, self.diff);
             println!("{}", serde_json::to_string(&obj)?);
         }
         Ok(())
@@ -151,7 +213,7 @@ impl Command {
         directory: &Directory,
         digits: usize,
     ) {
-        if self.detail {
+        if self.detail || self.diff {
             // Detailed block format
             for (i, link) in suspect_links.iter().enumerate() {
                 if i > 0 {
@@ -194,6 +256,10 @@ impl Command {
                 println!("  STORED:  {}", link.stored_fingerprint);
                 println!("  CURRENT: {}", link.current_fingerprint);
                 println!();
+                if self.diff {
+                    print_parent_changes(link, directory);
+                    println!();
+                }
                 println!("  ACTIONS:");
                 println!(
                     "    req review --accept --child {} --parent {}",
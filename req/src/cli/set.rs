@@ -0,0 +1,116 @@
+//! The `req set` command: set or clear custom attributes on a requirement.
+
+use std::path::PathBuf;
+
+use requiem_core::{Directory, Hrid};
+use tracing::instrument;
+
+use crate::cli::{parse_attribute, parse_hrid, terminal::Colorize};
+
+#[derive(Debug, clap::Parser)]
+#[command(arg_required_else_help = true)]
+pub struct Command {
+    /// The human-readable ID of the requirement
+    #[clap(value_parser = parse_hrid)]
+    hrid: Hrid,
+
+    /// Attributes to set, as NAME=VALUE (must be declared in config.toml)
+    #[clap(value_name = "NAME=VALUE", value_parser = parse_attribute)]
+    attributes: Vec<(String, String)>,
+
+    /// Attributes to remove
+    #[arg(long, value_name = "NAME")]
+    unset: Vec<String>,
+}
+
+impl Command {
+    #[instrument]
+    pub fn run(self, root: PathBuf) -> anyhow::Result<()> {
+        let mut directory = Directory::new(root)?;
+        let digits = directory.config().digits();
+        let hrid = self.hrid.display(digits);
+
+        let mut messages = Vec::new();
+        for (name, value) in &self.attributes {
+            directory.set_attribute(&self.hrid, name, Some(value))?;
+            messages.push(format!("✅ {hrid}: {name} = {value}"));
+        }
+        for name in &self.unset {
+            if directory.set_attribute(&self.hrid, name, None)?.is_some() {
+                messages.push(format!("✅ {hrid}: {name} removed"));
+            }
+        }
+        directory.flush()?;
+
+        if messages.is_empty() {
+            println!("{}", format!("No attributes changed on {hrid}").dim());
+        }
+        for message in messages {
+            println!("{}", message.success());
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use requiem_core::{domain::AttributeValue, Directory};
+    use tempfile::tempdir;
+
+    use super::Command;
+
+    #[test]
+    fn set_run_updates_and_removes_attributes() {
+        let tmp = tempdir().unwrap();
+        let root = tmp.path().to_path_buf();
+        std::fs::create_dir_all(root.join(".req")).unwrap();
+        std::fs::write(
+            root.join(".req/config.toml"),
+            "_version = \"1\"\n\n[attributes.risk]\ntype = \"integer\"\n\n[attributes.owner]\ntype \
+             = \"string\"\n",
+        )
+        .unwrap();
+
+        let mut directory = Directory::new(root.clone()).expect("failed to load directory");
+        let requirement = directory
+            .add_requirement("USR", "# Requirement".to_string())
+            .unwrap();
+        directory.flush().unwrap();
+
+        Command {
+            hrid: requirement.hrid().clone(),
+            attributes: vec![
+                ("risk".to_string(), "4".to_string()),
+                ("owner".to_string(), "alice".to_string()),
+            ],
+            unset: Vec::new(),
+        }
+        .run(root.clone())
+        .expect("set should succeed");
+
+        Command {
+            hrid: requirement.hrid().clone(),
+            attributes: Vec::new(),
+            unset: vec!["owner".to_string()],
+        }
+        .run(root.clone())
+        .expect("unset should succeed");
+
+        let error = Command {
+            hrid: requirement.hrid().clone(),
+            attributes: vec![("risk".to_string(), "high".to_string())],
+            unset: Vec::new(),
+        }
+        .run(root.clone())
+        .unwrap_err();
+        assert!(error.to_string().contains("not an integer"));
+
+        let directory = Directory::new(root).unwrap();
+        let view = directory.find_by_hrid(requirement.hrid()).unwrap();
+        assert_eq!(
+            view.attributes.iter().collect::<Vec<_>>(),
+            [(&"risk".to_string(), &AttributeValue::Integer(4))]
+        );
+    }
+}
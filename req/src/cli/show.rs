@@ -83,6 +83,18 @@ impl Show {
         }
         println!("  UUID:      {}", req.uuid);
         println!("  Created:   {}", req.created);
+        if let Some(status) = req.status {
+            println!("  Status:    {status}");
+        }
+        if let Some(test) = req.test {
+            println!("  Test:      {}", test.location);
+            if let Some(result) = &test.result {
+                println!("  Result:    {} ({})", result.outcome, result.timestamp);
+            }
+        }
+        if let Some(verification) = directory.verification().get(req.uuid) {
+            println!("  Verified:  {verification}");
+        }
 
         // File path
         if let Some(path) = directory.path_for(req.hrid) {
@@ -97,6 +109,14 @@ impl Show {
             }
         }
 
+        // Custom attributes
+        if !req.attributes.is_empty() {
+            println!("\n{}", "Attributes".dim());
+            for (name, value) in req.attributes {
+                println!("  {name}: {value}");
+            }
+        }
+
         // Parents
         if !req.parents.is_empty() {
             println!("\n{}", "Parents".dim());
@@ -108,8 +128,12 @@ impl Show {
                 });
 
                 let indicator = if is_suspect { " ⚠️" } else { "" };
+                let link_type = parent_info
+                    .link_type
+                    .as_deref()
+                    .map_or_else(String::new, |t| format!(" [{t}]"));
                 println!(
-                    "  • {} ({}){indicator}",
+                    "  • {} ({}){link_type}{indicator}",
                     parent_info.hrid.display(digits),
                     parent_uuid
                 );
@@ -148,7 +172,8 @@ impl Show {
                 json!({
                     "uuid": uuid.to_string(),
                     "hrid": info.hrid.display(digits).to_string(),
-                    "fingerprint": info.fingerprint
+                    "fingerprint": info.fingerprint,
+                    "link_type": info.link_type
                 })
             })
             .collect();
@@ -180,6 +205,10 @@ impl Show {
             "created": req.created.to_rfc3339(),
             "title": req.title,
             "tags": req.tags,
+            "status": req.status,
+            "attributes": req.attributes,
+            "test": req.test,
+            "verification": directory.verification().get(req.uuid).map(|v| v.as_str()),
             "parents": parents,
             "children": children,
             "path": path,
@@ -215,12 +244,32 @@ impl Show {
         }
         println!("| UUID | `{}` |", req.uuid);
         println!("| Created | {} |", req.created);
+        if let Some(status) = req.status {
+            println!("| Status | {status} |");
+        }
+        if let Some(test) = req.test {
+            println!("| Test | `{}` |", test.location);
+            if let Some(result) = &test.result {
+                println!("| Result | {} ({}) |", result.outcome, result.timestamp);
+            }
+        }
+        if let Some(verification) = directory.verification().get(req.uuid) {
+            println!("| Verified | {verification} |");
+        }
+        for (name, value) in req.attributes {
+            println!("| {name} | {value} |");
+        }
 
         if !req.parents.is_empty() {
             println!("\n## Parents\n");
             for (_uuid, info) in &req.parents {
                 let hrid_display = info.hrid.display(directory.config().digits());
-                println!("- [{hrid_display}]({hrid_display}.md)");
+                match &info.link_type {
+                    Some(link_type) => {
+                        println!("- [{hrid_display}]({hrid_display}.md) ({link_type})");
+                    }
+                    None => println!("- [{hrid_display}]({hrid_display}.md)"),
+                }
             }
         }
 
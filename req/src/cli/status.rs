@@ -1,16 +1,27 @@
-//! The `req status` command: requirement counts and suspect-link totals.
+//! The `req status` command: requirement counts, suspect-link totals, and
+//! test verification.
+//!
+//! The `set` subcommand changes a requirement's lifecycle status.
 
 use std::{collections::BTreeMap, path::PathBuf, process};
 
 use clap::Parser;
-use requiem_core::Directory;
+use requiem_core::{domain::Verification, Directory};
 use tracing::instrument;
 
 use super::terminal::{is_narrow, Colorize};
 
+mod set;
+
 #[derive(Debug, Parser, Default)]
-#[command(about = "Show requirement counts and suspect link totals")]
+#[command(
+    about = "Show requirement counts and suspect link totals",
+    args_conflicts_with_subcommands = true
+)]
 pub struct Command {
+    #[command(subcommand)]
+    action: Option<Action>,
+
     /// Output format (table, json)
     #[arg(long, value_name = "FORMAT", default_value = "table")]
     output: OutputFormat,
@@ -20,6 +31,42 @@ pub struct Command {
     quiet: bool,
 }
 
+#[derive(Debug, clap::Subcommand)]
+enum Action {
+    /// Change the lifecycle status of a requirement
+    Set(set::Command),
+}
+
+/// How many requirements (other than tests) are verified by their tests.
+#[derive(Debug, Default)]
+struct VerificationCounts {
+    tests: usize,
+    passed: usize,
+    incomplete: usize,
+    failed: usize,
+    unverified: usize,
+}
+
+impl VerificationCounts {
+    fn of(directory: &Directory) -> Self {
+        let rollup = directory.verification();
+        let mut counts = Self::default();
+        for requirement in directory.requirements() {
+            if requirement.test.is_some() {
+                counts.tests += 1;
+                continue;
+            }
+            match rollup.get(requirement.uuid) {
+                Some(Verification::Passed) => counts.passed += 1,
+                Some(Verification::Incomplete) => counts.incomplete += 1,
+                Some(Verification::Failed) => counts.failed += 1,
+                None => counts.unverified += 1,
+            }
+        }
+        counts
+    }
+}
+
 #[derive(Debug, Clone, Copy, Default, clap::ValueEnum)]
 enum OutputFormat {
     #[default]
@@ -30,6 +77,10 @@ enum OutputFormat {
 impl Command {
     #[instrument(level = "debug", skip(self))]
     pub fn run(self, root: PathBuf) -> anyhow::Result<()> {
+        if let Some(Action::Set(command)) = self.action {
+            return command.run(root);
+        }
+
         let directory = Directory::new(root)?;
 
         let mut counts: BTreeMap<String, usize> = BTreeMap::new();
@@ -45,6 +96,8 @@ impl Command {
         // Check for path issues (files not at canonical locations)
         let path_issues = directory.check_path_drift().len();
 
+        let verification = VerificationCounts::of(&directory);
+
         // Check if we have an empty repository
         if total == 0 {
             println!("No requirements found yet. Create one with 'req create'.");
@@ -53,13 +106,13 @@ impl Command {
 
         match self.output {
             OutputFormat::Json => {
-                Self::output_json(&counts, total, suspect_count, path_issues)?;
+                Self::output_json(&counts, total, suspect_count, path_issues, &verification)?;
             }
             OutputFormat::Table => {
                 if self.quiet {
                     Self::output_quiet(&counts, total, suspect_count, path_issues);
                 } else {
-                    Self::output_table(&counts, total, suspect_count, path_issues);
+                    Self::output_table(&counts, total, suspect_count, path_issues, &verification);
                 }
             }
         }
@@ -77,6 +130,7 @@ impl Command {
         total: usize,
         suspect_count: usize,
         path_issues: usize,
+        verification: &VerificationCounts,
     ) -> anyhow::Result<()> {
         use serde_json::json;
 
@@ -98,7 +152,14 @@ impl Command {
                 "delta": 0  // TODO: implement git delta
             },
             "suspect_links": suspect_count,
-            "path_issues": path_issues
+            "path_issues": path_issues,
+            "verification": {
+                "tests": verification.tests,
+                "passed": verification.passed,
+                "incomplete": verification.incomplete,
+                "failed": verification.failed,
+                "unverified": verification.unverified
+            }
         });
 
         println!("{}", serde_json::to_string_pretty(&output)?);
@@ -119,6 +180,7 @@ impl Command {
         total: usize,
         suspect_count: usize,
         path_issues: usize,
+        verification: &VerificationCounts,
     ) {
         let narrow = is_narrow();
 
@@ -170,6 +232,40 @@ impl Command {
                 println!("{}", "Run 'req diagnose paths' to see path details.".dim());
             }
         }
+
+        if verification.tests > 0 {
+            Self::output_verification(verification);
+        }
+    }
+
+    fn output_verification(verification: &VerificationCounts) {
+        println!();
+        println!("Verification ({} tests):", verification.tests);
+        println!(
+            "  Passed:      {}",
+            verification.passed.to_string().success()
+        );
+        println!("  Incomplete:  {}", verification.incomplete);
+        if verification.failed == 0 {
+            println!("  Failed:      {} ✅", "0".success());
+        } else {
+            println!(
+                "  Failed:      {} ⚠️",
+                verification.failed.to_string().warning()
+            );
+        }
+        println!(
+            "  Unverified:  {}",
+            verification.unverified.to_string().dim()
+        );
+
+        if verification.failed > 0 || verification.incomplete > 0 {
+            println!();
+            println!(
+                "{}",
+                "Run 'req list --columns hrid,title,verification' to see which requirements.".dim()
+            );
+        }
     }
 }
 
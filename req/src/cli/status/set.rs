@@ -0,0 +1,74 @@
+//! The `req status set` subcommand: change a requirement's lifecycle status.
+
+use std::path::PathBuf;
+
+use requiem_core::{Directory, Hrid};
+use tracing::instrument;
+
+use crate::cli::{parse_hrid, terminal::Colorize};
+
+#[derive(Debug, clap::Parser)]
+pub struct Command {
+    /// The human-readable ID of the requirement
+    #[clap(value_parser = parse_hrid)]
+    hrid: Hrid,
+
+    /// The new status (e.g. draft, approved)
+    status: String,
+}
+
+impl Command {
+    #[instrument]
+    pub fn run(self, root: PathBuf) -> anyhow::Result<()> {
+        let mut directory = Directory::new(root)?;
+        let digits = directory.config().digits();
+
+        let previous = directory.set_status(&self.hrid, &self.status)?;
+        directory.flush()?;
+
+        let status = self.status.trim().to_lowercase();
+        let hrid = self.hrid.display(digits);
+        match previous {
+            Some(previous) if previous == status => {
+                println!("{}", format!("{hrid} is already {status}").dim());
+            }
+            Some(previous) => {
+                println!("{}", format!("✅ {hrid}: {previous} → {status}").success());
+            }
+            None => println!("{}", format!("✅ {hrid}: {status}").success()),
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use requiem_core::Directory;
+    use tempfile::tempdir;
+
+    use super::Command;
+
+    #[test]
+    fn set_run_updates_status() {
+        let tmp = tempdir().unwrap();
+        let root = tmp.path().to_path_buf();
+
+        let mut directory = Directory::new(root.clone()).expect("failed to load directory");
+        let requirement = directory
+            .add_requirement("USR", "# Requirement".to_string())
+            .unwrap();
+        directory.flush().unwrap();
+
+        Command {
+            hrid: requirement.hrid().clone(),
+            status: "Approved".to_string(),
+        }
+        .run(root.clone())
+        .expect("status set should succeed without a lifecycle");
+
+        let directory = Directory::new(root).unwrap();
+        let view = directory.find_by_hrid(requirement.hrid()).unwrap();
+        assert_eq!(view.status, Some("approved"));
+    }
+}
@@ -53,6 +53,12 @@ enum CheckType {
     Links,
     /// Check parent fingerprints match current content
     Suspect,
+    /// Check approved requirements do not depend on draft parents
+    Status,
+    /// Check custom attributes against the schema in config.toml
+    Attributes,
+    /// Check links against the per-kind link rules in config.toml
+    Rules,
     /// Run all checks
     All,
 }
@@ -70,12 +76,20 @@ struct ValidationResult {
     paths: Vec<PathIssue>,
     links: Vec<LinkIssue>,
     suspect: Vec<SuspectIssue>,
+    status: Vec<StatusIssue>,
+    attributes: Vec<AttributeIssue>,
+    rules: Vec<RuleIssue>,
 }
 
 impl ValidationResult {
     /// Count the total number of issues across all categories.
     fn count_total_issues(&self) -> usize {
-        self.paths.len() + self.links.len() + self.suspect.len()
+        self.paths.len()
+            + self.links.len()
+            + self.suspect.len()
+            + self.status.len()
+            + self.attributes.len()
+            + self.rules.len()
     }
 
     /// Count only the fixable issues (paths + stale HRIDs).
@@ -88,7 +102,8 @@ impl ValidationResult {
                 .count()
     }
 
-    /// Count unfixable issues (structure + broken refs + cycles + suspect).
+    /// Count unfixable issues (structure + broken refs + cycles + suspect +
+    /// status + attributes + rules).
     fn count_unfixable_issues(&self) -> usize {
         self.count_total_issues() - self.count_fixable_issues()
     }
@@ -122,6 +137,30 @@ struct SuspectIssue {
     parent: String,
 }
 
+/// An approved child linked to a draft parent.
+#[derive(Debug)]
+struct StatusIssue {
+    child: String,
+    parent: String,
+}
+
+/// A custom attribute that violates the schema.
+#[derive(Debug)]
+struct AttributeIssue {
+    hrid: String,
+    attribute: String,
+    problem: String,
+}
+
+/// A requirement that breaks the link rules of its kind.
+#[derive(Debug)]
+struct RuleIssue {
+    hrid: String,
+    /// The offending parent, for rules about a single link.
+    parent: Option<String>,
+    problem: String,
+}
+
 impl Validate {
     #[instrument(level = "debug", skip(self))]
     pub fn run(self, root: PathBuf) -> anyhow::Result<()> {
@@ -134,6 +173,9 @@ impl Validate {
                 CheckType::Paths,
                 CheckType::Links,
                 CheckType::Suspect,
+                CheckType::Status,
+                CheckType::Attributes,
+                CheckType::Rules,
             ]
         } else {
             self.check.as_slice()
@@ -156,6 +198,21 @@ impl Validate {
             } else {
                 vec![]
             },
+            status: if checks.contains(&CheckType::Status) {
+                check::status(&directory)
+            } else {
+                vec![]
+            },
+            attributes: if checks.contains(&CheckType::Attributes) {
+                check::attributes(&directory)
+            } else {
+                vec![]
+            },
+            rules: if checks.contains(&CheckType::Rules) {
+                check::rules(&directory)
+            } else {
+                vec![]
+            },
         };
 
         // Output results
@@ -2,7 +2,7 @@
 
 use requiem_core::Directory;
 
-use super::{LinkIssue, PathIssue, SuspectIssue};
+use super::{AttributeIssue, LinkIssue, PathIssue, RuleIssue, StatusIssue, SuspectIssue};
 
 /// Find files that are not at their canonical locations.
 pub(super) fn paths(directory: &Directory) -> Vec<PathIssue> {
@@ -71,3 +71,46 @@ pub(super) fn suspect(directory: &Directory) -> Vec<SuspectIssue> {
 
     issues
 }
+
+/// Find approved requirements that link to draft parents.
+pub(super) fn status(directory: &Directory) -> Vec<StatusIssue> {
+    let digits = directory.config().digits();
+    directory
+        .status_conflicts()
+        .into_iter()
+        .map(|conflict| StatusIssue {
+            child: conflict.child_hrid.display(digits).to_string(),
+            parent: conflict.parent_hrid.display(digits).to_string(),
+        })
+        .collect()
+}
+
+/// Find custom attributes that are missing, undeclared, or mistyped.
+pub(super) fn attributes(directory: &Directory) -> Vec<AttributeIssue> {
+    let digits = directory.config().digits();
+    directory
+        .attribute_issues()
+        .into_iter()
+        .map(|issue| AttributeIssue {
+            hrid: issue.hrid.display(digits).to_string(),
+            attribute: issue.attribute,
+            problem: issue.problem.to_string(),
+        })
+        .collect()
+}
+
+/// Find requirements that break the link rules of their kind.
+pub(super) fn rules(directory: &Directory) -> Vec<RuleIssue> {
+    let digits = directory.config().digits();
+    directory
+        .link_rule_violations()
+        .into_iter()
+        .map(|violation| RuleIssue {
+            hrid: violation.hrid.display(digits).to_string(),
+            parent: violation
+                .parent
+                .map(|parent| parent.display(digits).to_string()),
+            problem: violation.error.to_string(),
+        })
+        .collect()
+}
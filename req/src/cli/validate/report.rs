@@ -67,6 +67,23 @@ impl Validate {
             );
         }
 
+        // Status
+        if result.status.is_empty() {
+            println!("✓ Status:     No approved requirements with draft parents");
+        } else {
+            println!(
+                "{}",
+                format!(
+                    "✗ Status:     {} approved requirement link(s) to draft parents",
+                    result.status.len()
+                )
+                .warning()
+            );
+        }
+
+        print_attribute_issues(result);
+        print_rule_issues(result);
+
         // Summary
         let total_issues = result.count_total_issues();
         if total_issues == 0 {
@@ -146,6 +163,19 @@ impl Validate {
             })
             .collect();
 
+        let status_issues: Vec<_> = result
+            .status
+            .iter()
+            .map(|issue| {
+                json!({
+                    "type": "approved_child_of_draft",
+                    "child": issue.child,
+                    "parent": issue.parent,
+                    "fixable": false
+                })
+            })
+            .collect();
+
         let total_issues = result.count_total_issues();
         let fixable_issues = result.count_fixable_issues();
 
@@ -155,7 +185,10 @@ impl Validate {
                 "structure": [],
                 "paths": path_issues,
                 "links": link_issues,
-                "suspect": suspect_issues
+                "suspect": suspect_issues,
+                "status": status_issues,
+                "attributes": attribute_issues_json(result),
+                "rules": rule_issues_json(result)
             },
             "summary": {
                 "total_issues": total_issues,
@@ -174,6 +207,81 @@ impl Validate {
     }
 }
 
+fn attribute_issues_json(result: &ValidationResult) -> Vec<serde_json::Value> {
+    result
+        .attributes
+        .iter()
+        .map(|issue| {
+            serde_json::json!({
+                "type": "attribute",
+                "hrid": issue.hrid,
+                "attribute": issue.attribute,
+                "problem": issue.problem,
+                "fixable": false
+            })
+        })
+        .collect()
+}
+
+fn rule_issues_json(result: &ValidationResult) -> Vec<serde_json::Value> {
+    result
+        .rules
+        .iter()
+        .map(|issue| {
+            serde_json::json!({
+                "type": "link_rule",
+                "hrid": issue.hrid,
+                "parent": issue.parent,
+                "problem": issue.problem,
+                "fixable": false
+            })
+        })
+        .collect()
+}
+
+fn print_attribute_issues(result: &ValidationResult) {
+    if result.attributes.is_empty() {
+        println!("✓ Attributes: All custom attributes match the schema");
+        return;
+    }
+    println!(
+        "{}",
+        format!(
+            "✗ Attributes: {} attribute issue(s)",
+            result.attributes.len()
+        )
+        .warning()
+    );
+    for issue in &result.attributes {
+        println!(
+            "{}",
+            format!("    {} {}: {}", issue.hrid, issue.attribute, issue.problem).dim()
+        );
+    }
+}
+
+fn print_rule_issues(result: &ValidationResult) {
+    if result.rules.is_empty() {
+        println!("✓ Rules:      All links follow the kind link rules");
+        return;
+    }
+    println!(
+        "{}",
+        format!(
+            "✗ Rules:      {} link rule violation(s)",
+            result.rules.len()
+        )
+        .warning()
+    );
+    for issue in &result.rules {
+        let link = issue.parent.as_ref().map_or_else(
+            || issue.hrid.clone(),
+            |parent| format!("{} → {parent}", issue.hrid),
+        );
+        println!("{}", format!("    {link}: {}", issue.problem).dim());
+    }
+}
+
 fn categorize_link_issues(
     links: &[LinkIssue],
 ) -> (Vec<&LinkIssue>, Vec<&LinkIssue>, Vec<&LinkIssue>) {
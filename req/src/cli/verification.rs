@@ -0,0 +1,35 @@
+//! The `req tests` commands: keep test requirements in step with the
+//! annotated tests in source code, and record their results.
+
+use std::path::PathBuf;
+
+mod import;
+mod sync;
+
+#[derive(Debug, clap::Parser)]
+pub enum Command {
+    /// Create, update, and retire test requirements from annotated tests
+    ///
+    /// Scans the configured source files for tests annotated with the
+    /// requirements they verify (`// verifies: SYS-001` or
+    /// `#[requirement("SYS-001")]`), and links a test requirement for each to
+    /// those requirements.
+    Sync(sync::Command),
+
+    /// Record test results against test requirements
+    ///
+    /// Reads a JUnit XML report, or the JSON lines written by `cargo test --
+    /// -Z unstable-options --format json`, and records whether each test
+    /// passed, failed, or was skipped on its test requirement. Results roll
+    /// up to show how well the requirements above are verified.
+    Import(import::Command),
+}
+
+impl Command {
+    pub fn run(self, root: PathBuf) -> anyhow::Result<()> {
+        match self {
+            Self::Sync(command) => command.run(root),
+            Self::Import(command) => command.run(root),
+        }
+    }
+}
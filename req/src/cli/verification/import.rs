@@ -0,0 +1,159 @@
+//! `req tests import`: record test results against test requirements.
+
+use std::{io::Read, path::PathBuf};
+
+use anyhow::Context;
+use chrono::Utc;
+use requiem_core::{
+    domain::TestOutcome,
+    storage::{directory::TestImport, test_report::parse_test_report},
+    Directory,
+};
+use tracing::instrument;
+
+use crate::cli::terminal::Colorize;
+
+#[derive(Debug, clap::Parser)]
+pub struct Command {
+    /// The test report: JUnit XML, or the JSON lines written by `cargo test
+    /// -- -Z unstable-options --format json` (use `-` to read standard input)
+    #[arg(value_name = "FILE")]
+    report: PathBuf,
+
+    /// Show what would be recorded without making changes
+    #[arg(long)]
+    dry_run: bool,
+
+    /// Suppress output
+    #[arg(long, short)]
+    quiet: bool,
+}
+
+impl Command {
+    #[instrument]
+    pub fn run(self, root: PathBuf) -> anyhow::Result<()> {
+        let report = if self.report.as_os_str() == "-" {
+            let mut report = String::new();
+            std::io::stdin()
+                .read_to_string(&mut report)
+                .context("failed to read test report from standard input")?;
+            report
+        } else {
+            std::fs::read_to_string(&self.report)
+                .with_context(|| format!("failed to read {}", self.report.display()))?
+        };
+        let runs = parse_test_report(&report)
+            .with_context(|| format!("failed to parse {}", self.report.display()))?;
+
+        let mut directory = Directory::new(root)?;
+        let import = directory.import_test_results(&runs, Utc::now());
+        if !self.dry_run {
+            directory.flush()?;
+        }
+
+        if !self.quiet {
+            println!("Found {} test results\n", runs.len());
+            print_import(&directory, &import, self.dry_run);
+        }
+        Ok(())
+    }
+}
+
+fn print_import(directory: &Directory, import: &TestImport, dry_run: bool) {
+    let digits = directory.config().digits();
+    for (hrid, outcome) in &import.recorded {
+        let hrid = hrid.display(digits);
+        let line = match outcome {
+            TestOutcome::Passed => format!("{hrid} ✓ passed").success(),
+            TestOutcome::Failed => format!("{hrid} ✗ failed").warning(),
+            TestOutcome::Skipped => format!("{hrid} - skipped").info(),
+        };
+        println!("{line}");
+    }
+    for hrid in &import.not_run {
+        println!("{}", format!("{} · not run", hrid.display(digits)).dim());
+    }
+
+    if !import.ambiguous.is_empty() {
+        println!();
+    }
+    for name in &import.ambiguous {
+        println!(
+            "{}",
+            format!("⚠️  {name} matches more than one test requirement, so was not recorded")
+                .warning()
+        );
+    }
+
+    println!(
+        "\nSummary: {} passed, {} failed, {} skipped, {} not run",
+        import.count(TestOutcome::Passed),
+        import.count(TestOutcome::Failed),
+        import.count(TestOutcome::Skipped),
+        import.not_run.len()
+    );
+    if !import.unmatched.is_empty() {
+        println!(
+            "{}",
+            format!(
+                "Results matching no test requirement: {}",
+                import.unmatched.len()
+            )
+            .dim()
+        );
+    }
+    if dry_run {
+        println!("{}", "Dry run: no files were changed".dim());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use requiem_core::{domain::TestOutcome, Directory};
+    use tempfile::tempdir;
+
+    use super::Command;
+
+    #[test]
+    fn import_run_records_results_unless_dry_run() {
+        let tmp = tempdir().unwrap();
+        let root = tmp.path().join("reqs");
+        let mut directory = Directory::new(root.clone()).unwrap();
+        let sys = directory
+            .add_requirement("SYS", "# Auth".to_string())
+            .unwrap();
+        directory
+            .sync_tests(&[requiem_core::storage::test_scan::DiscoveredTest {
+                location: "../src/auth.rs::logs_in".to_string(),
+                title: "Logs in".to_string(),
+                description: String::new(),
+                verifies: vec![sys.hrid().clone()],
+            }])
+            .unwrap();
+        directory.flush().unwrap();
+        let report = tmp.path().join("results.json");
+        std::fs::write(
+            &report,
+            "{ \"type\": \"test\", \"name\": \"auth::tests::logs_in\", \"event\": \"failed\" }\n",
+        )
+        .unwrap();
+
+        let command = |dry_run| Command {
+            report: report.clone(),
+            dry_run,
+            quiet: true,
+        };
+        let outcome = || {
+            let directory = Directory::new(root.clone()).unwrap();
+            directory
+                .requirements()
+                .find_map(|view| Some(view.test?.result.as_ref()?.outcome))
+        };
+
+        command(true).run(root.clone()).unwrap();
+        assert_eq!(outcome(), None);
+
+        command(false).run(root.clone()).unwrap();
+        assert_eq!(outcome(), Some(TestOutcome::Failed));
+    }
+}
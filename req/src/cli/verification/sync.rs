@@ -0,0 +1,160 @@
+//! `req tests sync`: generate test requirements from annotated tests.
+
+use std::path::PathBuf;
+
+use requiem_core::{
+    storage::{
+        directory::{TestChange, TestSync},
+        test_scan::scan_tests,
+    },
+    Directory,
+};
+use tracing::instrument;
+
+use crate::cli::terminal::Colorize;
+
+#[derive(Debug, clap::Parser)]
+pub struct Command {
+    /// Glob patterns of the source files to scan, relative to the
+    /// requirements directory (defaults to `sources` under `[tests]` in
+    /// config.toml)
+    #[arg(long = "source", value_name = "GLOB")]
+    sources: Vec<String>,
+
+    /// Show what would be changed without making changes
+    #[arg(long)]
+    dry_run: bool,
+
+    /// Suppress output
+    #[arg(long, short)]
+    quiet: bool,
+}
+
+impl Command {
+    #[instrument]
+    pub fn run(self, root: PathBuf) -> anyhow::Result<()> {
+        let mut directory = Directory::new(root)?;
+        let sources = if self.sources.is_empty() {
+            directory.config().tests().sources.clone()
+        } else {
+            self.sources.clone()
+        };
+        if sources.is_empty() {
+            anyhow::bail!(
+                "No test sources configured: set `sources` under [tests] in .req/config.toml, or \
+                 pass --source"
+            );
+        }
+
+        let tests = scan_tests(directory.root(), &sources)?;
+        let sync = directory.sync_tests(&tests)?;
+        if !self.dry_run {
+            directory.flush()?;
+        }
+
+        if !self.quiet {
+            println!("Found {} annotated tests\n", tests.len());
+            print_sync(&directory, &sync, self.dry_run);
+        }
+        Ok(())
+    }
+}
+
+fn print_sync(directory: &Directory, sync: &TestSync, dry_run: bool) {
+    let digits = directory.config().digits();
+    for entry in &sync.entries {
+        let hrid = entry.hrid.display(digits);
+        let location = format!("({})", entry.location).dim();
+        let line = match entry.change {
+            TestChange::Created => format!("{hrid} + created").success(),
+            TestChange::Updated => format!("{hrid} ~ updated").info(),
+            TestChange::Unchanged => format!("{hrid} ✓ unchanged").dim(),
+            TestChange::Retired => format!("{hrid} - retired").warning(),
+        };
+        println!("{line} {location}");
+    }
+
+    if !sync.unresolved.is_empty() || !sync.not_retired.is_empty() {
+        println!();
+    }
+    for (location, hrid) in &sync.unresolved {
+        println!(
+            "{}",
+            format!(
+                "⚠️  {location} verifies {}, which doesn't exist",
+                hrid.display(digits)
+            )
+            .warning()
+        );
+    }
+    for hrid in &sync.not_retired {
+        println!(
+            "{}",
+            format!(
+                "⚠️  {} was not retired: other requirements link to it",
+                hrid.display(digits)
+            )
+            .warning()
+        );
+    }
+
+    println!(
+        "\nSummary: {} created, {} updated, {} retired, {} unchanged",
+        sync.count(TestChange::Created),
+        sync.count(TestChange::Updated),
+        sync.count(TestChange::Retired),
+        sync.count(TestChange::Unchanged)
+    );
+    if dry_run {
+        println!("{}", "Dry run: no files were changed".dim());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use requiem_core::Directory;
+    use tempfile::tempdir;
+
+    use super::Command;
+
+    #[test]
+    fn sync_run_writes_test_requirements_unless_dry_run() {
+        let tmp = tempdir().unwrap();
+        let root = tmp.path().join("reqs");
+        let mut directory = Directory::new(root.clone()).unwrap();
+        let sys = directory
+            .add_requirement("SYS", "# Auth".to_string())
+            .unwrap();
+        directory.flush().unwrap();
+        std::fs::create_dir_all(tmp.path().join("src")).unwrap();
+        std::fs::write(
+            tmp.path().join("src/auth.rs"),
+            "// verifies: SYS-001\n#[test]\nfn logs_in() {}\n",
+        )
+        .unwrap();
+
+        let command = |dry_run| Command {
+            sources: vec!["../src/**/*.rs".to_string()],
+            dry_run,
+            quiet: true,
+        };
+        command(true).run(root.clone()).unwrap();
+        assert_eq!(
+            Directory::new(root.clone()).unwrap().requirements().count(),
+            1
+        );
+
+        command(false).run(root.clone()).unwrap();
+        let directory = Directory::new(root).unwrap();
+        let test = directory
+            .requirements()
+            .find(|view| view.test.is_some())
+            .expect("test requirement should be written");
+        assert_eq!(test.test.unwrap().location, "../src/auth.rs::logs_in");
+        assert_eq!(test.title, "logs_in");
+        assert_eq!(
+            directory.descendants_of(sys.hrid()),
+            std::slice::from_ref(test.hrid)
+        );
+    }
+}